@@ -0,0 +1,42 @@
+use std::{env, process};
+
+use intents_models::simulator::{AuctioneerSimulator, Scenario, SimulatorConfig};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8900";
+
+/// Runs auctions of the scenario file passed as the first argument on the address passed as
+/// the second one (`127.0.0.1:8900` by default), and prints the report.
+/// Exits with an error unless every intent was fulfilled without protocol violations
+#[tokio::main]
+async fn main() {
+    intents_models::log::init_tracing(false);
+    match run().await {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("auctioneer_simulator error: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+async fn run() -> Result<bool, String> {
+    let mut args = env::args().skip(1);
+    let scenario_path = args
+        .next()
+        .ok_or("usage: auctioneer_simulator <scenario.json> [address]")?;
+    let address = args.next().unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let scenario = Scenario::load(&scenario_path).map_err(|err| format!("{err:?}"))?;
+    let simulator = AuctioneerSimulator::bind(address, SimulatorConfig::default(), scenario)
+        .await
+        .map_err(|err| format!("{err:?}"))?;
+    let url = simulator.url().map_err(|err| format!("{err:?}"))?;
+    println!("Waiting for solvers on {url}");
+
+    let report = simulator.run().await.map_err(|err| format!("{err:?}"))?;
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|err| format!("failed to serialize report: {err}"))?;
+    println!("{json}");
+    Ok(report.is_success())
+}
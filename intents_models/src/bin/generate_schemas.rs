@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+use std::{env, fs, process};
+
+use intents_models::models::schema::wire_model_schemas;
+
+/// Writes `<Model>.schema.json` of every root wire model into the directory passed as
+/// the first argument (`schemas` by default)
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("generate_schemas error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let dir = PathBuf::from(env::args().nth(1).unwrap_or_else(|| "schemas".to_string()));
+    fs::create_dir_all(&dir).map_err(|err| format!("failed to create {}: {err}", dir.display()))?;
+
+    for (name, schema) in wire_model_schemas() {
+        let path = dir.join(format!("{name}.schema.json"));
+        let json = serde_json::to_string_pretty(&schema)
+            .map_err(|err| format!("failed to serialize {name} schema: {err}"))?;
+        fs::write(&path, json + "\n")
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
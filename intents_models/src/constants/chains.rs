@@ -31,9 +31,45 @@ pub const WRAPPED_NATIVE_TOKEN_SOLANA_ADDRESS: &str = "So11111111111111111111111
 
 pub const NATIVE_TOKEN_SUI_ADDRESS: &str = "0x2::sui::SUI";
 
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// `0x` followed by 40 hex characters
+pub fn is_valid_evm_address(address: &str) -> bool {
+    address
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Base58 encoded 32 bytes public key
+pub fn is_valid_solana_address(address: &str) -> bool {
+    (32..=44).contains(&address.len()) && address.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+/// `0x` followed by up to 64 hex characters (short form addresses like `0x2` are allowed)
+pub fn is_valid_sui_address(address: &str) -> bool {
+    address.strip_prefix("0x").is_some_and(|hex| {
+        (1..=64).contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// Sui coin type in `address::module::Name` format, e.g. `0x2::sui::SUI`
+pub fn is_valid_sui_coin_type(coin_type: &str) -> bool {
+    let mut parts = coin_type.splitn(3, "::");
+    let (Some(address), Some(module), Some(name)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let is_identifier = |value: &str| {
+        value.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    is_valid_sui_address(address) && is_identifier(module) && is_identifier(name)
+}
+
 pub const WRAPPED_NATIVE_TOKEN_HYPE_ADDRESS: &str = "0x5555555555555555555555555555555555555555";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr, EnumIter, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema_repr))]
 #[repr(u32)]
 pub enum ChainId {
     Ethereum = 1,
@@ -50,6 +86,7 @@ pub enum ChainId {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ChainType {
     EVM,
     Solana,
@@ -145,6 +182,24 @@ impl ChainId {
         }
     }
 
+    /// Checks wallet address format of the chain
+    pub fn is_valid_address(self, address: &str) -> bool {
+        match self.to_chain_type() {
+            ChainType::EVM => is_valid_evm_address(address),
+            ChainType::Solana => is_valid_solana_address(address),
+            ChainType::Sui => is_valid_sui_address(address),
+        }
+    }
+
+    /// Checks token address format of the chain
+    pub fn is_valid_token_address(self, address: &str) -> bool {
+        match self.to_chain_type() {
+            ChainType::EVM => is_valid_evm_address(address),
+            ChainType::Solana => is_valid_solana_address(address),
+            ChainType::Sui => is_valid_sui_coin_type(address),
+        }
+    }
+
     pub fn wrapped_native_token_address(self) -> String {
         match self {
             ChainId::Solana => WRAPPED_NATIVE_TOKEN_SOLANA_ADDRESS.to_string(),
@@ -208,6 +263,29 @@ mod tests {
         assert!(!is_native_token_evm_address(""));
     }
 
+    #[test]
+    fn test_address_formats() {
+        assert!(ChainId::Base.is_valid_address(EVM_NULL_ADDRESS));
+        assert!(ChainId::Base.is_valid_token_address("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"));
+        assert!(!ChainId::Base.is_valid_address("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA0291"));
+        assert!(!ChainId::Base.is_valid_address("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913aa"));
+        assert!(!ChainId::Base.is_valid_address("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA0291g"));
+
+        assert!(ChainId::Solana.is_valid_token_address(NATIVE_TOKEN_SOLANA_ADDRESS));
+        assert!(ChainId::Solana.is_valid_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"));
+        // `0` and `l` are not part of the base58 alphabet
+        assert!(!ChainId::Solana.is_valid_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt10"));
+        assert!(!ChainId::Solana.is_valid_address(EVM_NULL_ADDRESS));
+
+        assert!(ChainId::Sui.is_valid_address(
+            "0x5f7a2c4d8a1e0d52b8e3f1c55c0c7e0bd3e7b0a6f9f7c9a8a6d5e4c3b2a19080"
+        ));
+        assert!(ChainId::Sui.is_valid_token_address(NATIVE_TOKEN_SUI_ADDRESS));
+        assert!(!ChainId::Sui.is_valid_token_address("0x2::sui"));
+        assert!(!ChainId::Sui.is_valid_token_address("0x2::sui::1SUI"));
+        assert!(!ChainId::Sui.is_valid_address(NATIVE_TOKEN_SOLANA_ADDRESS));
+    }
+
     #[test]
     fn test_supported_chains() {
         let chains = ChainId::supported_chains();
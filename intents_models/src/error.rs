@@ -29,6 +29,9 @@ pub enum Error {
     #[error("Nats error: {0}")]
     NatsError(String),
 
+    #[error("WebSocket error: {0}")]
+    WebSocketError(String),
+
     #[error("Too large request body: {0}")]
     TooLargeRequestBody(String),
 
@@ -3,4 +3,7 @@ pub mod error;
 pub mod log;
 pub mod models;
 pub mod network;
+pub mod simulator;
 pub mod slack;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
@@ -3,11 +3,14 @@ use std::ops::Deref;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod types;
 pub mod ws_messages;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DisplayU128(#[serde_as(as = "PickFirst<(DisplayFromStr, _)>")] pub u128);
 
 impl DisplayU128 {
@@ -0,0 +1,200 @@
+//! JSON Schema of wire models shared with frontend, auctioneer and solvers
+//!
+//! Available with `schema` feature. Every public model derives `schemars::JsonSchema`,
+//! the list below only contains root models: everything they reference is included
+//! into their `$defs`. Run `cargo run -p intents_models --features schema --bin generate_schemas`
+//! to write the schemas to disk.
+//!
+//! TypeScript definitions are out of scope of this crate: TypeScript clients generate them from
+//! the written schemas with their own tooling (e.g. `json-schema-to-typescript`).
+
+use crate::models::types::order::UserOrders;
+use crate::models::types::user_types::IntentRequest;
+use crate::models::ws_messages::api_response::ApiResponse;
+use crate::models::ws_messages::auctioneer_message::WsAuctioneerMessageInner;
+use crate::models::ws_messages::solver_message::WsSolverMessage;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+/// JSON Schema of `T` with all referenced models in `$defs`
+pub fn schema_for<T: JsonSchema>() -> Schema {
+    SchemaGenerator::default().into_root_schema_for::<T>()
+}
+
+/// Schemas of root wire models by model name.
+///
+/// Auctioneer messages are sent as `ApiResponse` with `WsAuctioneerMessageInner` in `data` field
+pub fn wire_model_schemas() -> Vec<(String, Schema)> {
+    fn named<T: JsonSchema>() -> (String, Schema) {
+        (T::schema_name().into_owned(), schema_for::<T>())
+    }
+
+    vec![
+        named::<IntentRequest>(),
+        named::<UserOrders>(),
+        named::<WsSolverMessage>(),
+        named::<WsAuctioneerMessageInner>(),
+        named::<ApiResponse>(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{
+        USER, cross_chain_dca_order, single_chain_limit_order_with_extra_transfer,
+    };
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+
+    fn validator<T: JsonSchema>() -> jsonschema::Validator {
+        let schema = schema_for::<T>();
+        jsonschema::validator_for(schema.as_value()).expect("Generated schema must be valid")
+    }
+
+    /// Payload must match the schema, be accepted by serde, and serialize back to a matching payload
+    fn assert_round_trip<T: JsonSchema + Serialize + DeserializeOwned>(payload: Value) {
+        let validator = validator::<T>();
+        if let Err(err) = validator.validate(&payload) {
+            panic!(
+                "Payload doesn't match {} schema: {err}\n{payload}",
+                T::schema_name()
+            );
+        }
+        let value: T = serde_json::from_value(payload.clone()).expect("Payload must deserialize");
+        let serialized = serde_json::to_value(&value).expect("Model must serialize");
+        if let Err(err) = validator.validate(&serialized) {
+            panic!(
+                "Serialized {} doesn't match its schema: {err}\n{serialized}",
+                T::schema_name()
+            );
+        }
+    }
+
+    fn assert_rejected<T: JsonSchema>(payload: Value) {
+        assert!(
+            !validator::<T>().is_valid(&payload),
+            "Payload must not match {} schema: {payload}",
+            T::schema_name()
+        );
+    }
+
+    #[test]
+    fn test_wire_model_schemas() {
+        let schemas = wire_model_schemas();
+        let names: Vec<_> = schemas.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "IntentRequest",
+                "UserOrders",
+                "WsSolverMessage",
+                "WsAuctioneerMessageInner",
+                "ApiResponse"
+            ]
+        );
+        for (name, schema) in schemas {
+            assert!(
+                jsonschema::meta::is_valid(schema.as_value()),
+                "{name} schema is not a valid JSON Schema"
+            );
+        }
+    }
+
+    #[test]
+    fn test_intent_request_round_trip() {
+        assert_round_trip::<IntentRequest>(single_chain_limit_order_with_extra_transfer());
+        assert_round_trip::<IntentRequest>(cross_chain_dca_order());
+
+        // u128 values are accepted both as strings and as numbers
+        let mut payload = single_chain_limit_order_with_extra_transfer();
+        payload["genericData"]["amountIn"] = json!(1000000000000000000u128);
+        assert_round_trip::<IntentRequest>(payload);
+
+        let mut payload = single_chain_limit_order_with_extra_transfer();
+        payload["type"] = json!("SingleChainMarketOrder");
+        assert_rejected::<IntentRequest>(payload);
+
+        let mut payload = single_chain_limit_order_with_extra_transfer();
+        payload["genericData"]["chainId"] = json!(12345);
+        assert_rejected::<IntentRequest>(payload);
+
+        let mut payload = cross_chain_dca_order();
+        payload["genericData"]
+            .as_object_mut()
+            .expect("Object")
+            .remove("totalIntervals");
+        assert_rejected::<IntentRequest>(payload);
+    }
+
+    #[test]
+    fn test_ws_messages_round_trip() {
+        assert_round_trip::<WsSolverMessage>(json!({
+            "Register": {
+                "protocol_version": 1,
+                "order_types": ["SingleChainLimitOrder"],
+                "chains": [8453, 7565164],
+                "capabilities": ["StopLoss"],
+            }
+        }));
+        assert_round_trip::<WsSolverMessage>(json!({
+            "Participate": {
+                "Single": {
+                    "intent_id": "intent",
+                    "order_type": "SingleChainLimitOrder",
+                    "solver_address": USER,
+                    "amount_out": 3000000000u64,
+                }
+            }
+        }));
+        assert_round_trip::<WsSolverMessage>(json!({
+            "GetStartPermissions": ["intent", "CrossChainDCAOrder"]
+        }));
+        assert_rejected::<WsSolverMessage>(json!({ "Participate": { "Single": {} } }));
+
+        let auction_request = json!({
+            "intent_id": "intent",
+            "intent": single_chain_limit_order_with_extra_transfer(),
+            "execution_terms": {
+                "type": "SingleChain",
+                "protocol_fee_transfer": {
+                    "token": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+                    "receiver": "0x4444444444444444444444444444444444444444",
+                    "amount": "1000",
+                },
+                "solver_execution_duration": 60,
+                "order_type_specific_data": { "type": "Limit" },
+            },
+        });
+        assert_round_trip::<WsAuctioneerMessageInner>(auction_request.clone());
+        assert_round_trip::<WsAuctioneerMessageInner>(json!({
+            "solver_id": "solver",
+            "status": "registered",
+            "pending_auction_results": [{
+                "intent_id": "intent",
+                "amount_out": "3000000000",
+                "solver_start_permission": null,
+            }],
+            "unfinished_orders": [auction_request.clone()],
+        }));
+
+        assert_round_trip::<ApiResponse>(json!({
+            "success": true,
+            "code": 200,
+            "data": auction_request,
+        }));
+        assert_round_trip::<ApiResponse>(json!({
+            "success": false,
+            "code": 400,
+            "error": "Bad request",
+        }));
+        assert_rejected::<ApiResponse>(json!({ "success": true }));
+    }
+
+    #[test]
+    fn test_user_orders_round_trip() {
+        assert_round_trip::<UserOrders>(
+            serde_json::to_value(UserOrders::default()).expect("Must serialize"),
+        );
+    }
+}
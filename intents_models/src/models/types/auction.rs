@@ -0,0 +1,412 @@
+use crate::error::{Error, ModelResult};
+use crate::models::types::solver_types::{ExecutionTerms, SolverStartPermission};
+use crate::models::ws_messages::auctioneer_message::{AuctionRequest, AuctionResult};
+use crate::models::ws_messages::solver_message::ParticipateAuction;
+use error_stack::report;
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+/// Rule picking the winner among bids with equal `amount_out`
+pub enum TieBreak {
+    /// The earliest bid wins
+    #[default]
+    EarliestBid,
+    /// Bid of the solver with the highest reputation wins, the earliest one among equal
+    /// reputations. Solvers missing in the map have zero reputation
+    Reputation(HashMap<String, u64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuctionBid {
+    pub solver_id: String,
+    pub participate: ParticipateAuction,
+    /// Timestamp of bid arrival in milliseconds
+    pub received_at: u64,
+    /// Arrival order in the book, breaks ties of bids received at the same time
+    sequence: u64,
+}
+
+impl AuctionBid {
+    pub fn intent_id(&self) -> &str {
+        participate_intent_id(&self.participate)
+    }
+
+    pub fn amount_out(&self) -> u128 {
+        match &self.participate {
+            ParticipateAuction::Single(bid) => bid.amount_out,
+            ParticipateAuction::Multi(bid) => bid.amount_out,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Bids of a closed auction, the best one first
+pub struct ClosedAuction {
+    pub intent_id: String,
+    pub bids: Vec<AuctionBid>,
+}
+
+impl ClosedAuction {
+    pub fn winner(&self) -> Option<&AuctionBid> {
+        self.bids.first()
+    }
+
+    /// Auction result for every bidder with the winning `amount_out`.
+    /// Only the winner's result carries `permission`
+    pub fn results(&self, permission: &SolverStartPermission) -> Vec<(String, AuctionResult)> {
+        let Some(winner) = self.winner() else {
+            return vec![];
+        };
+        self.bids
+            .iter()
+            .map(|bid| {
+                let result = AuctionResult {
+                    intent_id: self.intent_id.clone(),
+                    amount_out: winner.amount_out(),
+                    solver_start_permission: (bid.solver_id == winner.solver_id)
+                        .then(|| permission.clone()),
+                };
+                (bid.solver_id.clone(), result)
+            })
+            .collect()
+    }
+}
+
+struct OpenAuction {
+    request: AuctionRequest,
+    /// One bid per solver, in order of arrival
+    bids: Vec<AuctionBid>,
+}
+
+#[derive(Default)]
+/// Bids of open auctions, checked against intent constraints.
+///
+/// The bid with the highest `amount_out` wins, ties are broken by `TieBreak`
+pub struct AuctionBook {
+    tie_break: TieBreak,
+    auctions: HashMap<String, OpenAuction>,
+    next_sequence: u64,
+}
+
+impl AuctionBook {
+    pub fn new(tie_break: TieBreak) -> Self {
+        Self {
+            tie_break,
+            auctions: HashMap::new(),
+            next_sequence: 0,
+        }
+    }
+
+    /// Starts accepting bids for the requested intent
+    pub fn open(&mut self, request: AuctionRequest) -> ModelResult<()> {
+        if self.auctions.contains_key(&request.intent_id) {
+            return Err(report!(Error::LogicError(format!(
+                "Auction for intent {} is already open",
+                request.intent_id
+            ))));
+        }
+        self.auctions.insert(
+            request.intent_id.clone(),
+            OpenAuction {
+                request,
+                bids: vec![],
+            },
+        );
+        Ok(())
+    }
+
+    pub fn is_open(&self, intent_id: &str) -> bool {
+        self.auctions.contains_key(intent_id)
+    }
+
+    /// Bids of an open auction in order of arrival
+    pub fn bids(&self, intent_id: &str) -> &[AuctionBid] {
+        self.auctions
+            .get(intent_id)
+            .map(|auction| auction.bids.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Adds a bid to an open auction, replacing the previous bid of the solver.
+    ///
+    /// Rejects bids not matching the intent type, bids below intent `amount_out_min`, and cross
+    /// chain bids swapping into less than `min_stablecoins_amount` of execution terms
+    pub fn submit(
+        &mut self,
+        solver_id: &str,
+        participate: ParticipateAuction,
+        received_at: u64,
+    ) -> ModelResult<()> {
+        let intent_id = participate_intent_id(&participate);
+        let auction = self.auctions.get_mut(intent_id).ok_or_else(|| {
+            report!(Error::ValidationError)
+                .attach_printable(format!("Auction for intent {intent_id} is not open"))
+        })?;
+        check_bid(&auction.request, &participate)?;
+
+        auction.bids.retain(|bid| bid.solver_id != solver_id);
+        auction.bids.push(AuctionBid {
+            solver_id: solver_id.to_string(),
+            participate,
+            received_at,
+            sequence: self.next_sequence,
+        });
+        self.next_sequence += 1;
+        Ok(())
+    }
+
+    /// Stops accepting bids and ranks them, the winner first
+    pub fn close(&mut self, intent_id: &str) -> ModelResult<ClosedAuction> {
+        let auction = self.auctions.remove(intent_id).ok_or_else(|| {
+            report!(Error::LogicError(format!(
+                "Auction for intent {intent_id} is not open"
+            )))
+        })?;
+        let mut bids = auction.bids;
+        bids.sort_by(|a, b| self.rank(a, b));
+        Ok(ClosedAuction {
+            intent_id: intent_id.to_string(),
+            bids,
+        })
+    }
+
+    /// `Ordering::Less` if `a` beats `b`
+    fn rank(&self, a: &AuctionBid, b: &AuctionBid) -> Ordering {
+        let by_amount = b.amount_out().cmp(&a.amount_out());
+        let by_reputation = match &self.tie_break {
+            TieBreak::EarliestBid => Ordering::Equal,
+            TieBreak::Reputation(reputations) => {
+                let reputation = |bid: &AuctionBid| {
+                    Reverse(reputations.get(&bid.solver_id).copied().unwrap_or(0))
+                };
+                reputation(a).cmp(&reputation(b))
+            }
+        };
+        by_amount
+            .then(by_reputation)
+            .then(a.received_at.cmp(&b.received_at))
+            .then(a.sequence.cmp(&b.sequence))
+    }
+}
+
+fn participate_intent_id(participate: &ParticipateAuction) -> &str {
+    match participate {
+        ParticipateAuction::Single(bid) => &bid.intent_id,
+        ParticipateAuction::Multi(bid) => &bid.intent_id,
+    }
+}
+
+fn check_bid(request: &AuctionRequest, participate: &ParticipateAuction) -> ModelResult<()> {
+    let intent_id = &request.intent_id;
+    let intent_order_type = request.intent.get_order_type();
+    let (order_type, amount_out) = match (participate, &request.execution_terms) {
+        (ParticipateAuction::Single(bid), ExecutionTerms::SingleChain(_)) => {
+            (bid.order_type, bid.amount_out)
+        }
+        (ParticipateAuction::Multi(bid), ExecutionTerms::CrossChain(terms)) => {
+            if bid.will_swap && !terms.allow_swap {
+                return Err(report!(Error::ValidationError).attach_printable(format!(
+                    "Swap to stablecoins is not allowed for intent {intent_id}"
+                )));
+            }
+            if bid.will_swap && bid.stablecoins_amount < terms.min_stablecoins_amount {
+                return Err(report!(Error::ValidationError).attach_printable(format!(
+                    "Bid stablecoins amount {} is below minimum {} of intent {intent_id}",
+                    bid.stablecoins_amount, terms.min_stablecoins_amount
+                )));
+            }
+            (bid.order_type, bid.amount_out)
+        }
+        _ => {
+            return Err(report!(Error::ValidationError).attach_printable(format!(
+                "Bid kind doesn't match {intent_order_type} intent {intent_id}"
+            )));
+        }
+    };
+    if order_type != intent_order_type {
+        return Err(report!(Error::ValidationError).attach_printable(format!(
+            "Bid for {order_type} doesn't match {intent_order_type} intent {intent_id}"
+        )));
+    }
+    let amount_out_min = request.intent.get_amount_out_min();
+    if amount_out < amount_out_min {
+        return Err(report!(Error::ValidationError).attach_printable(format!(
+            "Bid amount out {amount_out} is below minimum {amount_out_min} of intent {intent_id}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::types::order::OrderType;
+    use crate::models::ws_messages::solver_message::{
+        CrossChainAuctionParticipate, SingleChainAuctionParticipate,
+    };
+    use crate::test_fixtures::{
+        USER, auction_request, cross_chain_auction_request, single_chain_auction_request,
+    };
+
+    fn single_chain_request(intent_id: &str) -> AuctionRequest {
+        auction_request(single_chain_auction_request(intent_id))
+    }
+
+    fn cross_chain_request(intent_id: &str, allow_swap: bool) -> AuctionRequest {
+        auction_request(cross_chain_auction_request(intent_id, allow_swap))
+    }
+
+    fn single_chain_bid(intent_id: &str, amount_out: u128) -> ParticipateAuction {
+        ParticipateAuction::Single(SingleChainAuctionParticipate {
+            intent_id: intent_id.to_string(),
+            order_type: OrderType::SingleChainLimitOrder,
+            solver_address: USER.to_string(),
+            amount_out,
+        })
+    }
+
+    fn cross_chain_bid(
+        intent_id: &str,
+        amount_out: u128,
+        will_swap: bool,
+        stablecoins_amount: u128,
+    ) -> ParticipateAuction {
+        ParticipateAuction::Multi(CrossChainAuctionParticipate {
+            intent_id: intent_id.to_string(),
+            order_type: OrderType::CrossChainLimitOrder,
+            amount_out,
+            will_swap,
+            stablecoins_amount,
+            src_chain_solver_address: USER.to_string(),
+            dest_chain_solver_address: USER.to_string(),
+        })
+    }
+
+    fn ranking(closed: &ClosedAuction) -> Vec<&str> {
+        closed
+            .bids
+            .iter()
+            .map(|bid| bid.solver_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_bid_constraints() {
+        let mut book = AuctionBook::default();
+        book.open(single_chain_request("single")).unwrap();
+        book.open(cross_chain_request("cross", false)).unwrap();
+        book.open(cross_chain_request("swap", true)).unwrap();
+        assert!(book.open(single_chain_request("single")).is_err());
+
+        // Unknown auction
+        assert!(
+            book.submit("a", single_chain_bid("other", 3_000_000_000), 0)
+                .is_err()
+        );
+        // Below amount_out_min
+        assert!(
+            book.submit("a", single_chain_bid("single", 2_999_999_999), 0)
+                .is_err()
+        );
+        // Bid kind doesn't match the intent
+        assert!(
+            book.submit("a", single_chain_bid("cross", 99_000_000), 0)
+                .is_err()
+        );
+        assert!(
+            book.submit("a", cross_chain_bid("single", 3_000_000_000, false, 0), 0)
+                .is_err()
+        );
+        // Swap isn't allowed
+        assert!(
+            book.submit(
+                "a",
+                cross_chain_bid("cross", 99_000_000, true, 99_000_000),
+                0
+            )
+            .is_err()
+        );
+        // Insufficient stablecoins after swap
+        assert!(
+            book.submit(
+                "a",
+                cross_chain_bid("swap", 99_000_000, true, 98_999_999),
+                0
+            )
+            .is_err()
+        );
+
+        book.submit("a", single_chain_bid("single", 3_000_000_000), 0)
+            .unwrap();
+        book.submit("a", cross_chain_bid("cross", 98_000_000, false, 0), 0)
+            .unwrap();
+        book.submit(
+            "a",
+            cross_chain_bid("swap", 99_000_000, true, 99_000_000),
+            0,
+        )
+        .unwrap();
+        for intent_id in ["single", "cross", "swap"] {
+            assert_eq!(book.bids(intent_id).len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_winner_selection() {
+        let mut book = AuctionBook::default();
+        book.open(single_chain_request("intent")).unwrap();
+        book.submit("a", single_chain_bid("intent", 3_100_000_000), 10)
+            .unwrap();
+        book.submit("b", single_chain_bid("intent", 3_200_000_000), 20)
+            .unwrap();
+        book.submit("c", single_chain_bid("intent", 3_200_000_000), 15)
+            .unwrap();
+        // Replaced bid is ranked by its new arrival time
+        book.submit("a", single_chain_bid("intent", 3_200_000_000), 30)
+            .unwrap();
+        assert_eq!(book.bids("intent").len(), 3);
+
+        let closed = book.close("intent").unwrap();
+        assert_eq!(ranking(&closed), ["c", "b", "a"]);
+        assert_eq!(
+            closed.winner().map(AuctionBid::amount_out),
+            Some(3_200_000_000)
+        );
+        assert!(!book.is_open("intent"));
+        assert!(book.close("intent").is_err());
+        assert!(
+            book.submit("a", single_chain_bid("intent", 3_300_000_000), 40)
+                .is_err()
+        );
+
+        // Bids received at the same time are ranked by arrival order
+        book.open(single_chain_request("intent")).unwrap();
+        book.submit("b", single_chain_bid("intent", 3_000_000_000), 0)
+            .unwrap();
+        book.submit("a", single_chain_bid("intent", 3_000_000_000), 0)
+            .unwrap();
+        assert_eq!(ranking(&book.close("intent").unwrap()), ["b", "a"]);
+
+        book.open(single_chain_request("empty")).unwrap();
+        assert!(book.close("empty").unwrap().winner().is_none());
+    }
+
+    #[test]
+    fn test_reputation_tie_break() {
+        let reputations = HashMap::from([("a".to_string(), 5), ("b".to_string(), 10)]);
+        let mut book = AuctionBook::new(TieBreak::Reputation(reputations));
+        book.open(single_chain_request("intent")).unwrap();
+        book.submit("c", single_chain_bid("intent", 3_000_000_000), 0)
+            .unwrap();
+        book.submit("a", single_chain_bid("intent", 3_000_000_000), 1)
+            .unwrap();
+        book.submit("d", single_chain_bid("intent", 3_100_000_000), 2)
+            .unwrap();
+        book.submit("b", single_chain_bid("intent", 3_000_000_000), 3)
+            .unwrap();
+
+        let closed = book.close("intent").unwrap();
+        assert_eq!(ranking(&closed), ["d", "b", "a", "c"]);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::error::{Error, ModelResult};
+use crate::models::types::common::DcaSchedule;
+use crate::models::types::validation::{ValidationIssue, ValidationIssueCode, check_issues};
 use error_stack::report;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
@@ -7,6 +9,7 @@ use std::time::UNIX_EPOCH;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Common limit order data to trigger "take profit" or "stop loss" execution
 pub struct CommonDcaOrderData {
@@ -21,7 +24,8 @@ pub struct CommonDcaOrderData {
     pub interval_duration: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Common values of DCA order state
 pub struct CommonDcaOrderState {
@@ -44,11 +48,7 @@ impl CommonDcaOrderData {
 
     /// Calculate interval index at specific timestamp
     pub fn get_interval_index(&self, timestamp: u32) -> u32 {
-        if timestamp < self.start_time {
-            0
-        } else {
-            (timestamp - self.start_time) / self.interval_duration + 1
-        }
+        self.get_schedule().get_interval_index(timestamp as u64)
     }
 
     /// Calculate timestamp of next DCA interval start
@@ -57,6 +57,11 @@ impl CommonDcaOrderData {
         self.start_time + current_interval_index * self.interval_duration
     }
 
+    /// Clock independent schedule of the order
+    pub fn get_schedule(&self) -> DcaSchedule {
+        DcaSchedule::new(self)
+    }
+
     pub fn check_current_dca_interval_can_be_fulfilled(
         &self,
         dca_state: &CommonDcaOrderState,
@@ -87,26 +92,49 @@ impl CommonDcaOrderData {
         Ok(())
     }
 
+    /// Timestamp (in seconds) when the last DCA interval starts
+    pub fn get_last_interval_start_timestamp(&self) -> u64 {
+        self.start_time as u64
+            + self.total_intervals.saturating_sub(1) as u64 * self.interval_duration as u64
+    }
+
     /// Validates common DCA order data
     pub fn validate(&self, min_interval_duration: u32) -> ModelResult<()> {
+        check_issues(self.validation_issues(min_interval_duration))
+    }
+
+    /// Issues of common DCA order data, with field paths relative to `genericData`
+    pub fn validation_issues(&self, min_interval_duration: u32) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+
         if self.amount_in_per_interval == 0 {
-            return Err(report!(Error::ValidationError)
-                .attach_printable("Zero amount_in_per_interval".to_string()));
+            issues.push(ValidationIssue::new(
+                "amountInPerInterval",
+                ValidationIssueCode::ZeroAmount,
+                "Zero amount_in_per_interval",
+            ));
         }
 
         if self.interval_duration < min_interval_duration {
-            return Err(report!(Error::ValidationError).attach_printable(format!(
-                "DCA interval duration ({}) is below minimum ({min_interval_duration})",
-                self.interval_duration
-            )));
+            issues.push(ValidationIssue::new(
+                "intervalDuration",
+                ValidationIssueCode::IntervalTooShort,
+                format!(
+                    "DCA interval duration ({}) is below minimum ({min_interval_duration})",
+                    self.interval_duration
+                ),
+            ));
         }
 
         if self.total_intervals < 2 {
-            return Err(report!(Error::ValidationError)
-                .attach_printable("Invalid total number of DCA intervals".to_string()));
+            issues.push(ValidationIssue::new(
+                "totalIntervals",
+                ValidationIssueCode::InvalidTotalIntervals,
+                "Invalid total number of DCA intervals",
+            ));
         }
 
-        Ok(())
+        issues
     }
 }
 
@@ -201,5 +229,21 @@ mod tests {
         dca_data.total_intervals = 2;
         let res = dca_data.validate(30);
         assert!(res.is_ok());
+
+        dca_data.amount_in_per_interval = 0;
+        dca_data.total_intervals = 1;
+        let codes: Vec<_> = dca_data
+            .validation_issues(31)
+            .into_iter()
+            .map(|issue| issue.code)
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                ValidationIssueCode::ZeroAmount,
+                ValidationIssueCode::IntervalTooShort,
+                ValidationIssueCode::InvalidTotalIntervals
+            ]
+        );
     }
 }
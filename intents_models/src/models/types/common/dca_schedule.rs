@@ -0,0 +1,417 @@
+use crate::constants::chains::ChainId;
+use crate::models::types::common::{CommonDcaOrderData, CommonDcaOrderState};
+use crate::models::types::order::DcaIntervalExecutionSearchRequest;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// DCA order timing. All calculations take current time explicitly, in SECONDS
+pub struct DcaSchedule {
+    pub start_time: u64,
+    pub interval_duration: u64,
+    pub total_intervals: u32,
+    pub amount_in_per_interval: u128,
+    /// Order deadline. Intervals starting at or after it can't be executed
+    pub deadline: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// Time window of a DCA interval: `[start, end)`
+pub struct DcaIntervalWindow {
+    /// Interval number, starting from 1
+    pub interval_number: u32,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// DCA order progress at some point in time
+pub struct DcaProgress {
+    /// Interval at the moment, None before order start or after the last interval
+    pub current_interval: Option<u32>,
+    pub executed_intervals: u32,
+    /// Intervals that can't be executed anymore because the order deadline cuts them off
+    pub missed_intervals: u32,
+    /// Intervals that can still be executed. Skipped intervals roll over to the following ones
+    pub remaining_intervals: u32,
+    /// Tokens IN spent on executed intervals
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_spent: u128,
+    /// Tokens IN to be spent on remaining intervals
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_remaining: u128,
+    /// Earliest time the last remaining interval can be executed, possibly after the nominal
+    /// last interval. None if no intervals remain
+    pub projected_completion_time: Option<u64>,
+}
+
+impl DcaSchedule {
+    pub fn new(dca_order_data: &CommonDcaOrderData) -> Self {
+        Self {
+            start_time: dca_order_data.start_time as u64,
+            interval_duration: dca_order_data.interval_duration as u64,
+            total_intervals: dca_order_data.total_intervals,
+            amount_in_per_interval: dca_order_data.amount_in_per_interval,
+            deadline: None,
+        }
+    }
+
+    pub fn with_deadline(mut self, deadline: u64) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Interval number at `timestamp`: 0 before order start, 1 for the first interval.
+    /// May exceed `total_intervals` after the last interval ends
+    pub fn get_interval_index(&self, timestamp: u64) -> u32 {
+        if timestamp < self.start_time {
+            0
+        } else {
+            let index = (timestamp - self.start_time)
+                .checked_div(self.interval_duration)
+                .unwrap_or_default()
+                + 1;
+            u32::try_from(index).unwrap_or(u32::MAX)
+        }
+    }
+
+    /// Window of interval `interval_number`, None if the order has no such interval
+    pub fn get_interval_window(&self, interval_number: u32) -> Option<DcaIntervalWindow> {
+        if interval_number == 0 || interval_number > self.total_intervals {
+            return None;
+        }
+        let start = self.start_time + (interval_number as u64 - 1) * self.interval_duration;
+        Some(DcaIntervalWindow {
+            interval_number,
+            start,
+            end: start + self.interval_duration,
+        })
+    }
+
+    /// Windows of every interval of the order
+    pub fn get_intervals(&self) -> Vec<DcaIntervalWindow> {
+        (1..=self.total_intervals)
+            .filter_map(|interval_number| self.get_interval_window(interval_number))
+            .collect()
+    }
+
+    /// Interval in progress at `now`
+    pub fn get_current_interval(&self, now: u64) -> Option<DcaIntervalWindow> {
+        self.get_interval_window(self.get_interval_index(now))
+    }
+
+    /// Start of the interval following the one in progress at `now`
+    pub fn get_next_interval_start_timestamp(&self, now: u64) -> u64 {
+        self.start_time + self.get_interval_index(now) as u64 * self.interval_duration
+    }
+
+    /// Window solver is allowed to execute interval `interval_number` in:
+    /// the interval window cut by the order deadline.
+    /// Skipped intervals roll over, so intervals after the nominal last one are allowed
+    /// while they start before the deadline
+    pub fn get_permission_window(&self, interval_number: u32) -> Option<DcaIntervalWindow> {
+        if interval_number == 0 {
+            return None;
+        }
+        let start = self.interval_start(interval_number);
+        let mut window = DcaIntervalWindow {
+            interval_number,
+            start,
+            end: start.saturating_add(self.interval_duration),
+        };
+        if let Some(deadline) = self.deadline {
+            if window.start >= deadline {
+                return None;
+            }
+            window.end = window.end.min(deadline);
+        }
+        Some(window)
+    }
+
+    /// Request to search for execution of interval `interval_number` within its permission window
+    pub fn get_execution_search_request(
+        &self,
+        chain_id: ChainId,
+        order_id: String,
+        interval_number: u32,
+    ) -> Option<DcaIntervalExecutionSearchRequest> {
+        let window = self.get_permission_window(interval_number)?;
+        Some(DcaIntervalExecutionSearchRequest {
+            chain_id,
+            order_id,
+            interval_number,
+            permission_start_timestamp: window.start,
+            permission_end_timestamp: window.end,
+        })
+    }
+
+    /// Progress of the order with `dca_state` at `now`.
+    ///
+    /// Like `CommonDcaOrderData::check_current_dca_interval_can_be_fulfilled`, any interval after
+    /// the last executed one can be executed while fewer than `total_intervals` were executed,
+    /// so skipped intervals roll over instead of being lost. Only the deadline makes them missed.
+    /// None if token amounts overflow
+    pub fn get_progress(&self, dca_state: &CommonDcaOrderState, now: u64) -> Option<DcaProgress> {
+        let executed_intervals = dca_state.total_executed_intervals.min(self.total_intervals);
+        let pending_intervals = self.total_intervals - executed_intervals;
+
+        // First interval that may still be executed, one interval per remaining execution from there
+        let first_open_interval = self
+            .get_interval_index(now)
+            .max(dca_state.last_executed_interval_index.saturating_add(1))
+            .max(1);
+        let first_open_start = self.interval_start(first_open_interval);
+        let remaining_intervals = match self.deadline {
+            None => pending_intervals,
+            Some(deadline) if first_open_start >= deadline => 0,
+            Some(deadline) => {
+                let opening_before_deadline = (deadline - 1 - first_open_start)
+                    .checked_div(self.interval_duration)
+                    .map(|intervals| intervals.saturating_add(1))
+                    .unwrap_or(u64::MAX);
+                pending_intervals.min(u32::try_from(opening_before_deadline).unwrap_or(u32::MAX))
+            }
+        };
+        let missed_intervals = pending_intervals - remaining_intervals;
+
+        Some(DcaProgress {
+            current_interval: self
+                .get_current_interval(now)
+                .map(|window| window.interval_number),
+            executed_intervals,
+            missed_intervals,
+            remaining_intervals,
+            amount_spent: self
+                .amount_in_per_interval
+                .checked_mul(executed_intervals as u128)?,
+            amount_remaining: self
+                .amount_in_per_interval
+                .checked_mul(remaining_intervals as u128)?,
+            projected_completion_time: (remaining_intervals > 0).then(|| {
+                self.interval_start(first_open_interval.saturating_add(remaining_intervals - 1))
+                    .max(now)
+            }),
+        })
+    }
+
+    /// Start of interval `interval_number`, including intervals after the nominal last one
+    fn interval_start(&self, interval_number: u32) -> u64 {
+        self.start_time.saturating_add(
+            (interval_number.saturating_sub(1) as u64).saturating_mul(self.interval_duration),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> DcaSchedule {
+        DcaSchedule::new(&CommonDcaOrderData {
+            start_time: 1000,
+            amount_in_per_interval: 200,
+            total_intervals: 5,
+            interval_duration: 100,
+        })
+    }
+
+    fn dca_state(
+        total_executed_intervals: u32,
+        last_executed_interval_index: u32,
+    ) -> CommonDcaOrderState {
+        CommonDcaOrderState {
+            total_executed_intervals,
+            last_executed_interval_index,
+        }
+    }
+
+    #[test]
+    fn test_interval_windows() {
+        let schedule = schedule();
+        assert_eq!(schedule.get_interval_index(999), 0);
+        assert_eq!(schedule.get_interval_index(1000), 1);
+        assert_eq!(schedule.get_interval_index(1099), 1);
+        assert_eq!(schedule.get_interval_index(1100), 2);
+        assert_eq!(schedule.get_interval_index(1500), 6);
+
+        let intervals = schedule.get_intervals();
+        assert_eq!(intervals.len(), 5);
+        assert_eq!(
+            intervals[4],
+            DcaIntervalWindow {
+                interval_number: 5,
+                start: 1400,
+                end: 1500,
+            }
+        );
+        assert_eq!(schedule.get_interval_window(0), None);
+        assert_eq!(schedule.get_interval_window(6), None);
+        assert_eq!(schedule.get_current_interval(999), None);
+        assert_eq!(schedule.get_current_interval(1500), None);
+        assert_eq!(schedule.get_next_interval_start_timestamp(999), 1000);
+        assert_eq!(schedule.get_next_interval_start_timestamp(1150), 1200);
+    }
+
+    #[test]
+    fn test_permission_windows() {
+        let schedule = schedule().with_deadline(1350);
+        assert_eq!(
+            schedule.get_permission_window(3),
+            Some(DcaIntervalWindow {
+                interval_number: 3,
+                start: 1200,
+                end: 1300,
+            })
+        );
+        // Cut by deadline
+        assert_eq!(
+            schedule.get_permission_window(4),
+            Some(DcaIntervalWindow {
+                interval_number: 4,
+                start: 1300,
+                end: 1350,
+            })
+        );
+        // Starts after deadline
+        assert_eq!(schedule.get_permission_window(5), None);
+
+        let request = schedule
+            .get_execution_search_request(ChainId::Base, "order".to_string(), 4)
+            .expect("Interval 4 can be executed");
+        assert_eq!(request.interval_number, 4);
+        assert_eq!(request.permission_start_timestamp, 1300);
+        assert_eq!(request.permission_end_timestamp, 1350);
+    }
+
+    #[test]
+    fn test_rolled_over_permission_windows() {
+        // Intervals after the nominal last one, bounded by the deadline only
+        let schedule = schedule().with_deadline(1650);
+        assert_eq!(
+            schedule.get_permission_window(7),
+            Some(DcaIntervalWindow {
+                interval_number: 7,
+                start: 1600,
+                end: 1650,
+            })
+        );
+        assert_eq!(schedule.get_permission_window(8), None);
+        assert_eq!(schedule.get_permission_window(0), None);
+        let request = schedule
+            .get_execution_search_request(ChainId::Base, "order".to_string(), 6)
+            .expect("Rolled over interval can be executed");
+        assert_eq!(request.permission_start_timestamp, 1500);
+        assert_eq!(request.permission_end_timestamp, 1600);
+    }
+
+    #[test]
+    fn test_progress_rolled_over_interval_has_permission_window() {
+        let schedule = schedule();
+        let progress = schedule
+            .get_progress(&dca_state(1, 1), 1250)
+            .expect("Amounts fit u128");
+        let last_interval = schedule.get_interval_index(
+            progress
+                .projected_completion_time
+                .expect("Intervals remain"),
+        );
+        assert_eq!(last_interval, 6);
+        assert!(schedule.get_permission_window(last_interval).is_some());
+    }
+
+    #[test]
+    fn test_progress_amount_overflow() {
+        let mut schedule = schedule();
+        schedule.amount_in_per_interval = u128::MAX;
+        assert_eq!(schedule.get_progress(&dca_state(1, 1), 1250), None);
+    }
+
+    #[test]
+    fn test_progress() {
+        let schedule = schedule();
+
+        // Before start
+        let progress = schedule
+            .get_progress(&dca_state(0, 0), 500)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.current_interval, None);
+        assert_eq!(progress.remaining_intervals, 5);
+        assert_eq!(progress.amount_remaining, 1000);
+        assert_eq!(progress.projected_completion_time, Some(1400));
+
+        // Interval 3: interval 1 executed, interval 2 skipped and rolled over past the last one
+        let progress = schedule
+            .get_progress(&dca_state(1, 1), 1250)
+            .expect("Amounts fit u128");
+        assert_eq!(
+            progress,
+            DcaProgress {
+                current_interval: Some(3),
+                executed_intervals: 1,
+                missed_intervals: 0,
+                remaining_intervals: 4,
+                amount_spent: 200,
+                amount_remaining: 800,
+                projected_completion_time: Some(1500),
+            }
+        );
+
+        // Current interval already executed
+        let progress = schedule
+            .get_progress(&dca_state(2, 3), 1250)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.missed_intervals, 0);
+        assert_eq!(progress.remaining_intervals, 3);
+        assert_eq!(progress.projected_completion_time, Some(1500));
+
+        // Deadline cuts the intervals starting at or after it
+        let progress = schedule
+            .with_deadline(1450)
+            .get_progress(&dca_state(1, 1), 1250)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.missed_intervals, 1);
+        assert_eq!(progress.remaining_intervals, 3);
+        assert_eq!(progress.projected_completion_time, Some(1400));
+
+        let progress = schedule
+            .with_deadline(1300)
+            .get_progress(&dca_state(2, 3), 1250)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.missed_intervals, 3);
+        assert_eq!(progress.remaining_intervals, 0);
+        assert_eq!(progress.projected_completion_time, None);
+
+        // All intervals executed
+        let progress = schedule
+            .get_progress(&dca_state(5, 5), 1450)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.missed_intervals, 0);
+        assert_eq!(progress.remaining_intervals, 0);
+        assert_eq!(progress.amount_spent, 1000);
+        assert_eq!(progress.projected_completion_time, None);
+
+        // After the last interval, unexecuted intervals can still be executed without a deadline
+        let progress = schedule
+            .get_progress(&dca_state(3, 4), 2000)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.current_interval, None);
+        assert_eq!(progress.missed_intervals, 0);
+        assert_eq!(progress.remaining_intervals, 2);
+        assert_eq!(progress.projected_completion_time, Some(2100));
+
+        // ...unless the deadline passed
+        let progress = schedule
+            .with_deadline(1500)
+            .get_progress(&dca_state(3, 4), 2000)
+            .expect("Amounts fit u128");
+        assert_eq!(progress.missed_intervals, 2);
+        assert_eq!(progress.remaining_intervals, 0);
+    }
+}
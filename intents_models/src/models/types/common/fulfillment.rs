@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Call mode for EVM external calls
 pub enum EvmCallMode {
     /// Approve tokens to call target and call it
@@ -1,11 +1,13 @@
 use crate::error::{Error, ModelResult};
 use crate::models::types::common::StopLossType;
+use crate::models::types::validation::{ValidationIssue, ValidationIssueCode, check_issues};
 use error_stack::report;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Common limit order data to trigger "take profit" or "stop loss" execution
 pub struct CommonLimitOrderData {
@@ -14,7 +16,7 @@ pub struct CommonLimitOrderData {
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub take_profit_min_out: Option<u128>,
-    /// Stop loss type
+    /// Stop loss type. Missing type means `StopLossType::Fixed`
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_loss_type: Option<StopLossType>,
     /// Initial requested trigger price of token IN/token OUT to trigger stop loss
@@ -46,6 +48,11 @@ impl CommonLimitOrderData {
         }
     }
 
+    /// Stop loss type to apply, `StopLossType::Fixed` if it was not specified
+    pub fn get_stop_loss_type(&self) -> StopLossType {
+        self.stop_loss_type.unwrap_or(StopLossType::Fixed)
+    }
+
     pub fn check_order_can_be_fulfilled(&self) -> ModelResult<()> {
         // If no "stop loss" is requested order can be fulfilled
         if self.stop_loss_trigger_price.is_none()
@@ -68,16 +75,41 @@ impl CommonLimitOrderData {
 
     /// Validates common limit order data
     pub fn validate(&self, amount_out_min: u128) -> ModelResult<()> {
+        check_issues(self.validation_issues(amount_out_min))
+    }
+
+    /// Issues of common limit order data, with field paths relative to `genericData`
+    pub fn validation_issues(&self, amount_out_min: u128) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+
         if let (None, Some(take_profit_min_out)) =
             (&self.stop_loss_trigger_price, self.take_profit_min_out)
             && amount_out_min != take_profit_min_out
         {
-            return Err(report!(Error::ValidationError).attach_printable(
-                "If 'stop loss' is not required, take_profit_min_out must be omitted or equal to amount_out_min"
+            issues.push(ValidationIssue::new(
+                "takeProfitMinOut",
+                ValidationIssueCode::InvalidTakeProfit,
+                "If 'stop loss' is not required, take_profit_min_out must be omitted or equal to amount_out_min",
             ));
         }
 
-        Ok(())
+        // Missing stop loss type is `StopLossType::Fixed`
+        match (self.stop_loss_type, self.stop_loss_trigger_price) {
+            (None, None) => {}
+            (_, Some(trigger_price)) if trigger_price.is_finite() && trigger_price > 0.0 => {}
+            (_, Some(_)) => issues.push(ValidationIssue::new(
+                "stopLossTriggerPrice",
+                ValidationIssueCode::InvalidStopLoss,
+                "Stop loss trigger price must be a positive number",
+            )),
+            (Some(_), None) => issues.push(ValidationIssue::new(
+                "stopLossTriggerPrice",
+                ValidationIssueCode::InvalidStopLoss,
+                "Stop loss trigger price is required with stop loss type",
+            )),
+        }
+
+        issues
     }
 }
 
@@ -146,6 +178,24 @@ mod tests {
         limit_order_data.take_profit_min_out = Some(1000);
         let valid = limit_order_data.validate(100);
         assert!(valid.is_ok());
+
+        // Trigger price without stop loss type is a fixed stop loss
+        limit_order_data.stop_loss_type = None;
+        assert!(limit_order_data.validate(100).is_ok());
+        assert_eq!(limit_order_data.get_stop_loss_type(), StopLossType::Fixed);
+
+        // Stop loss type without trigger price
+        limit_order_data.stop_loss_type = Some(StopLossType::Fixed);
+        limit_order_data.stop_loss_trigger_price = None;
+        let issues = limit_order_data.validation_issues(1000);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "stopLossTriggerPrice");
+        assert_eq!(issues[0].code, ValidationIssueCode::InvalidStopLoss);
+
+        limit_order_data.stop_loss_type = Some(StopLossType::TrailingPercent);
+        limit_order_data.stop_loss_trigger_price = Some(-1.0);
+        let valid = limit_order_data.validate(100);
+        assert!(valid.is_err());
     }
 
     #[test]
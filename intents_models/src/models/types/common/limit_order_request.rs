@@ -5,6 +5,7 @@ use crate::models::types::common::StopLossType;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Common limit order data to trigger "take profit" or "stop loss" execution
 pub struct CommonLimitOrderUserRequestData {
@@ -1,4 +1,5 @@
 mod dca_order;
+mod dca_schedule;
 mod fulfillment;
 mod limit_order;
 mod limit_order_request;
@@ -9,6 +10,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 use std::{fmt, str::FromStr};
 
 pub use dca_order::*;
+pub use dca_schedule::*;
 pub use fulfillment::*;
 pub use limit_order::*;
 pub use limit_order_request::*;
@@ -17,7 +19,8 @@ pub use user_response::*;
 use crate::error::Error;
 
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Transfer details
 pub struct TransferDetails {
     /// Address of token to send
@@ -31,6 +34,7 @@ pub struct TransferDetails {
 
 #[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StopLossType {
     /// Fixed stop loss based on the current `token_in / token_out` price ratio.
@@ -3,6 +3,7 @@ use serde_with::serde_as;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// DCA interval execution data, provided to user on request
 pub struct DcaIntervalExecutionResponse {
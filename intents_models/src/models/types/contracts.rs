@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ContractsAddresses {
     pub evm: HashMap<u32, EvmContractsAddresses>,
@@ -12,6 +13,7 @@ pub struct ContractsAddresses {
 // ================================= EVM =================================
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmContractsAddresses {
     pub single_chain: SingleChainEvmContracts,
@@ -20,6 +22,7 @@ pub struct EvmContractsAddresses {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SingleChainEvmContracts {
     pub guard_limit: String,
@@ -28,6 +31,7 @@ pub struct SingleChainEvmContracts {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CrossChainEvmContracts {
     pub guard_limit: String,
@@ -39,6 +43,7 @@ pub struct CrossChainEvmContracts {
 
 // ================================ SOLANA ================================
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SolanaContractsAddresses {
     pub single_chain: SingleChainSolanaContracts,
@@ -46,6 +51,7 @@ pub struct SolanaContractsAddresses {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SingleChainSolanaContracts {
     pub guard_program_id: String,
@@ -54,6 +60,7 @@ pub struct SingleChainSolanaContracts {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CrossChainSolanaContracts {
     pub guard_program_id: String,
@@ -66,6 +73,7 @@ pub struct CrossChainSolanaContracts {
 
 // ================================= SUI =================================
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SuiContractsAddresses {
     pub package_id: String,
@@ -3,6 +3,7 @@ use serde_with::{DisplayFromStr, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected common on chain cross-chain order data about current on chain order state
 pub struct CrossChainOnChainOrderData {
     /// `true` - At least one execution has started
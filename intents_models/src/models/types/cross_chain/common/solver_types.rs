@@ -11,6 +11,7 @@ use serde_with::{DisplayFromStr, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Data, used by Solver to start cross chain order execution
 pub struct CrossChainSolverStartPermission {
@@ -45,6 +46,7 @@ pub struct CrossChainSolverStartPermission {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Chain-specific data, used by Solver to start cross chain order execution
 pub enum CrossChainSolverStartOrderData {
     /// EVM-based chain data (e.g., Ethereum, Binance Smart Chain)
@@ -56,6 +58,7 @@ pub enum CrossChainSolverStartOrderData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Destination-chain-specific data, used by Solver to fulfill order on destination chain
 pub enum CrossChainSolverFulfillmentData {
     /// EVM-based chain data (e.g., Ethereum, Binance Smart Chain)
@@ -103,6 +106,7 @@ impl CrossChainSolverStartOrderData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Sui-specific chain data, used by Solver to start cross chain order execution
 pub struct CrossChainStartOrderSuiData {
     /// Package ID, that should be interacted with
@@ -125,6 +129,7 @@ pub struct CrossChainStartOrderSuiData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Terms of execution of cross-chain intent, provided to Solver, used for bidding estimations and execution
 pub struct CrossChainExecutionTerms {
     /// Amount of collateral for as solver to lock
@@ -160,6 +165,7 @@ pub struct CrossChainExecutionTerms {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Auctioneer data collected after checking cross-chain order execution
 pub struct DestChainFulfillmentDetails {
     /// Actually received main amount OUT
@@ -178,6 +184,7 @@ pub struct DestChainFulfillmentDetails {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Auctioneer data collected after checking cross-chain extra transfer execution
 pub struct TransferFulfillmentDetails {
     /// `true` - transaction was signed by the Solver
@@ -188,6 +195,7 @@ pub struct TransferFulfillmentDetails {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Auctioneer data collected after checking cross-chain extra transfer execution.
 /// Contains inconsistencies of expected/received tokens amounts
 pub struct AmountInconsistency {
@@ -211,6 +219,7 @@ pub struct AmountInconsistency {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Success confirmation, provided to Solver after successful order execution.
 /// Allows Solver to claim tokens in source chain
@@ -228,6 +237,7 @@ pub struct CrossChainSolverSuccessConfirmation {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Enum for the chain-specific data of success confirmation, provided to Solver after successful
 /// cross-chain order execution. Allows Solver to claim tokens in source chain
 pub enum SolverSuccessConfirmationData {
@@ -240,6 +250,7 @@ pub enum SolverSuccessConfirmationData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// EVM-specific data of success confirmation, provided to Solver after successful cross-chain order execution
 pub struct SuccessConfirmationEVMData {
@@ -252,6 +263,7 @@ pub struct SuccessConfirmationEVMData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum EvmSuccessConfirmationOrderTypeData {
     CrossChainLimit(EvmSuccessConfirmationCrossChainLimitOrderData),
@@ -259,6 +271,7 @@ pub enum EvmSuccessConfirmationOrderTypeData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Sui-specific data of success confirmation, provided to Solver after successful cross-chain order execution
 pub struct SuccessConfirmationSuiData {
     /// Package ID, that should be interacted with
@@ -276,6 +289,7 @@ pub struct SuccessConfirmationSuiData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Solana-specific data of success confirmation, provided to Solver after successful cross-chain order execution
 pub struct SuccessConfirmationSolanaData {
     /// Program ID, that should be interacted with
@@ -1,4 +1,4 @@
-use crate::constants::chains::ChainId;
+use crate::constants::chains::{ChainId, ChainType};
 use crate::error::{Error, ModelResult};
 use crate::models::types::common::TransferDetails;
 use crate::models::types::user_types::{EVMData, SuiData};
@@ -9,6 +9,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Common data for all cross chain orders
 pub struct CrossChainGenericData {
@@ -52,6 +53,7 @@ impl CrossChainGenericData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Chain-specific data cross chain orders
 pub enum CrossChainChainSpecificData {
     /// EVM-based chain data (e.g., Ethereum, Binance Smart Chain)
@@ -63,6 +65,7 @@ pub enum CrossChainChainSpecificData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Solana-specific data cross chain orders
 pub struct CrossChainSolanaData {
@@ -71,6 +74,14 @@ pub struct CrossChainSolanaData {
 }
 
 impl CrossChainChainSpecificData {
+    pub fn chain_type(&self) -> ChainType {
+        match self {
+            CrossChainChainSpecificData::EVM(_) => ChainType::EVM,
+            CrossChainChainSpecificData::Sui(_) => ChainType::Sui,
+            CrossChainChainSpecificData::Solana(_) => ChainType::Solana,
+        }
+    }
+
     pub fn try_get_evm(&self) -> ModelResult<&EVMData> {
         match self {
             CrossChainChainSpecificData::EVM(evm_data) => Ok(evm_data),
@@ -5,6 +5,7 @@ use serde_with::serde_as;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CrossChainOnChainDcaOrderData {
     #[serde(flatten)]
     pub common_data: CrossChainOnChainOrderData,
@@ -16,6 +17,7 @@ pub struct CrossChainOnChainDcaOrderData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ExecutionStart {
     TimestampSeconds(u32),
     IntervalIndex(u32),
@@ -14,6 +14,7 @@ use serde_with::{DisplayFromStr, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Permission, granted to Solver to start cross-chain DCA order execution
 pub struct CrossChainDcaOrderSolverStartPermission {
@@ -31,6 +32,7 @@ pub struct CrossChainDcaOrderSolverStartPermission {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Cross chain DCA order data required for execution start
 pub struct StartEvmCrossChainDcaOrderData {
     /// Order info struct
@@ -41,6 +43,7 @@ pub struct StartEvmCrossChainDcaOrderData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmCrossChainDcaOrderInfo {
     pub user: String,
@@ -97,6 +100,7 @@ impl TryFrom<(&CrossChainDcaOrderGenericData, &EVMData)> for EvmCrossChainDcaOrd
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmCrossChainDcaSolverPermission {
     pub solver: String,
@@ -120,6 +124,7 @@ pub struct EvmCrossChainDcaSolverPermission {
 /******************************************************************************/
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EvmSuccessConfirmationCrossChainDcaOrderData {
     /// Order info that should be passed to contract
     pub order_info: EvmCrossChainDcaOrderInfo,
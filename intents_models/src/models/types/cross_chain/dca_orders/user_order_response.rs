@@ -6,6 +6,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Cross chain DCA order data, provided to user on request
 pub struct CrossChainUserDcaOrderResponse {
@@ -6,13 +6,14 @@ use crate::models::types::cross_chain::{
     CrossChainGenericData,
 };
 use crate::models::types::user_types::IntentRequest;
+use crate::models::types::validation::check_execution_details_hash;
 use error_stack::{ResultExt, report};
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
-use sha2::Digest;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Cross chain dca order intent request, received from the user
 pub struct CrossChainDcaOrderUserIntentRequest {
@@ -26,6 +27,7 @@ pub struct CrossChainDcaOrderUserIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold generic data related to the cross chain dca order intent
 pub struct CrossChainDcaOrderGenericRequestData {
@@ -71,6 +73,7 @@ impl From<CrossChainDcaOrderGenericData> for CrossChainDcaOrderGenericRequestDat
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold execution details of cross chain DCA order, provided by the user
 pub struct CrossChainDcaOrderExecutionDetails {
@@ -108,20 +111,16 @@ impl TryFrom<CrossChainDcaOrderUserIntentRequest> for IntentRequest {
             common_dca_order_data,
         } = generic_data;
 
-        let mut hasher = sha2::Sha256::new();
-        hasher.update(&execution_details);
-        let result = hasher.finalize();
-        let computed_execution_details_hash = format!("0x{result:x}");
-
-        if !computed_execution_details_hash.eq_ignore_ascii_case(&execution_details_hash) {
+        if let Some(issue) =
+            check_execution_details_hash(&execution_details, &execution_details_hash)
+        {
             tracing::error!(
-                "genericData.executionDetailsHash {} doesn't match with executionDetails ({}) SHA-256 hash {}",
+                "genericData.executionDetailsHash {} doesn't match with executionDetails ({}): {}",
                 &execution_details_hash,
                 &execution_details,
-                &computed_execution_details_hash
+                &issue.message
             );
-            return Err(report!(Error::ValidationError)
-                .attach_printable("Execution details hash does not match the provided hash."));
+            return Err(report!(Error::ValidationError).attach_printable(issue.message));
         }
 
         let execution_details: CrossChainDcaOrderExecutionDetails =
@@ -5,6 +5,7 @@ use serde_with::serde_as;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Cross chain DCA order intent structure
 pub struct CrossChainDcaOrderIntentRequest {
@@ -16,6 +17,7 @@ pub struct CrossChainDcaOrderIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold generic data related to cross chain DCA order intent
 pub struct CrossChainDcaOrderGenericData {
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Requested EVM fulfillment data
 pub struct EvmCrossChainFulfillmentData {
     /// Destination chain guard address
@@ -14,6 +15,7 @@ pub struct EvmCrossChainFulfillmentData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Requested EVM fulfillment data
 pub enum EvmCrossChainRequestedFulfillment {
     SimpleFulfillment(SimpleEvmRequestedFulfillment),
@@ -33,6 +35,7 @@ impl EvmCrossChainRequestedFulfillment {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Requested fulfillment data (without external call)
 pub struct SimpleEvmRequestedFulfillment {
@@ -54,6 +57,7 @@ pub struct SimpleEvmRequestedFulfillment {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Requested fulfillment data (with external call)
 pub struct EvmRequestedFulfillmentWithExternalCall {
@@ -2,6 +2,7 @@ use crate::models::types::cross_chain::CrossChainOnChainOrderData;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected on chain cross-chain limit order data about current on chain order state
 pub struct CrossChainOnChainLimitOrderData {
     #[serde(flatten)]
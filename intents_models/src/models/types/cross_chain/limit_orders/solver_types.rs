@@ -14,6 +14,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 /*********************************************************************/
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Cross chain limit order data required for execution start
 pub struct StartEvmCrossChainLimitOrderData {
     /// Order info struct
@@ -24,6 +25,7 @@ pub struct StartEvmCrossChainLimitOrderData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Permission, granted to Solver to start cross-chain limit order execution
 pub struct CrossChainLimitOrderSolverStartPermission {
@@ -35,6 +37,7 @@ pub struct CrossChainLimitOrderSolverStartPermission {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmCrossChainLimitOrderInfo {
     #[serde_as(as = "PickFirst<(DisplayFromStr, _)>")]
@@ -83,6 +86,7 @@ impl TryFrom<(&CrossChainLimitOrderGenericData, &EVMData)> for EvmCrossChainLimi
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmCrossChainLimitSolverPermission {
     pub solver: String,
@@ -103,6 +107,7 @@ pub struct EvmCrossChainLimitSolverPermission {
 /******************************************************************************/
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EvmSuccessConfirmationCrossChainLimitOrderData {
     /// Order info that should be passed to contract
     pub order_info: EvmCrossChainLimitOrderInfo,
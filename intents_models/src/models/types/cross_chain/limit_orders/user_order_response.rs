@@ -6,6 +6,7 @@ use crate::models::types::order::OrderStatus;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Cross chain limit order data, provided to user on request
 pub struct CrossChainUserLimitOrderResponse {
@@ -8,13 +8,14 @@ use crate::models::types::cross_chain::{
     CrossChainLimitOrderIntentRequest,
 };
 use crate::models::types::user_types::IntentRequest;
+use crate::models::types::validation::check_execution_details_hash;
 use error_stack::{ResultExt, report};
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
-use sha2::Digest;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Cross chain limit order intent request, received from the user
 pub struct CrossChainLimitOrderUserIntentRequest {
@@ -28,6 +29,7 @@ pub struct CrossChainLimitOrderUserIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold generic data related to the cross chain limit order intent
 pub struct CrossChainLimitOrderGenericRequestData {
@@ -67,6 +69,7 @@ impl From<CrossChainLimitOrderGenericData> for CrossChainLimitOrderGenericReques
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold execution details of cross chain limit order, provided by the user
 pub struct CrossChainLimitOrderExecutionDetails {
@@ -92,21 +95,17 @@ impl TryFrom<CrossChainLimitOrderUserIntentRequest> for IntentRequest {
     type Error = error_stack::Report<Error>;
 
     fn try_from(value: CrossChainLimitOrderUserIntentRequest) -> Result<Self, Self::Error> {
-        let mut hasher = sha2::Sha256::new();
-        hasher.update(&value.execution_details);
-        let result = hasher.finalize();
-        let execution_details_hash = format!("0x{result:x}");
-
-        if !execution_details_hash.eq_ignore_ascii_case(&value.generic_data.execution_details_hash)
-        {
+        if let Some(issue) = check_execution_details_hash(
+            &value.execution_details,
+            &value.generic_data.execution_details_hash,
+        ) {
             tracing::error!(
-                "genericData.executionDetailsHash {} doesn't match with executionDetails ({}) SHA-256 hash {}",
+                "genericData.executionDetailsHash {} doesn't match with executionDetails ({}): {}",
                 &value.generic_data.execution_details_hash,
                 &value.execution_details,
-                &execution_details_hash
+                &issue.message
             );
-            return Err(report!(Error::ValidationError)
-                .attach_printable("Execution details hash does not match the provided hash."));
+            return Err(report!(Error::ValidationError).attach_printable(issue.message));
         }
 
         let execution_details: CrossChainLimitOrderExecutionDetails =
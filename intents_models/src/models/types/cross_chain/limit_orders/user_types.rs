@@ -5,6 +5,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Cross chain Limit order intent structure
 pub struct CrossChainLimitOrderIntentRequest {
@@ -16,6 +17,7 @@ pub struct CrossChainLimitOrderIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Generic data related to cross chain limit order intent
 pub struct CrossChainLimitOrderGenericData {
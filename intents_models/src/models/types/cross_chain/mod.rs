@@ -16,6 +16,7 @@ pub use order::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum CrossChainIntentRequest {
     CrossChainLimitOrder(CrossChainLimitOrderIntentRequest),
@@ -86,6 +87,7 @@ impl CrossChainIntentRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum CrossChainGenericDataEnum {
     Limit(CrossChainLimitOrderGenericData),
@@ -108,6 +110,7 @@ impl CrossChainGenericDataEnum {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum CrossChainSolverStartPermissionEnum {
     Limit(CrossChainLimitOrderSolverStartPermission),
@@ -202,6 +205,7 @@ impl CrossChainSolverStartPermissionEnum {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected on chain order data about current on chain order state
 pub enum CrossChainOnChainOrderDataEnum {
     CrossChainLimitOrder(CrossChainOnChainLimitOrderData),
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// List of transaction hashes, provided by the Solver to Auctioneer after fulfillment of cross chain order
 pub struct FulfillmentTxHashes {
     /// Transaction hash of main order fulfillment
@@ -0,0 +1,279 @@
+//! Minimal EIP-712 encoding helpers and ECDSA signer recovery
+//!
+//! Only the value types used by intent typed data are supported. Every helper returns
+//! the 32 bytes "encodeData" representation of a single value.
+
+use crate::error::{Error, ModelResult};
+use error_stack::{ResultExt, report};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+pub type Word = [u8; 32];
+
+pub fn keccak256(data: impl AsRef<[u8]>) -> Word {
+    Keccak256::digest(data).into()
+}
+
+/// `typeHash` of a type from its `encodeType` string
+pub fn type_hash(encoded_type: &str) -> Word {
+    keccak256(encoded_type.as_bytes())
+}
+
+/// `hashStruct` of already encoded members, `type_hash` first
+pub fn hash_struct(type_hash: Word, members: &[Word]) -> Word {
+    let mut hasher = Keccak256::new();
+    hasher.update(type_hash);
+    for member in members {
+        hasher.update(member);
+    }
+    hasher.finalize().into()
+}
+
+/// Encoded array: hash of the concatenated encoded elements
+pub fn hash_array(elements: &[Word]) -> Word {
+    keccak256(elements.concat())
+}
+
+pub fn encode_string(value: &str) -> Word {
+    keccak256(value.as_bytes())
+}
+
+pub fn encode_uint(value: u128) -> Word {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Parses decimal or `0x` prefixed hex `uint256`
+pub fn encode_uint256(value: &str) -> ModelResult<Word> {
+    let mut word = [0u8; 32];
+    if let Some(hex_value) = value.strip_prefix("0x") {
+        if hex_value.is_empty() || hex_value.len() > 64 {
+            return Err(report!(Error::ParseError)
+                .attach_printable(format!("Invalid uint256 hex value: {value}")));
+        }
+        let padded = format!("{hex_value:0>64}");
+        hex::decode_to_slice(&padded, &mut word)
+            .change_context(Error::ParseError)
+            .attach_printable(format!("Invalid uint256 hex value: {value}"))?;
+        return Ok(word);
+    }
+
+    if value.is_empty() {
+        return Err(report!(Error::ParseError).attach_printable("Empty uint256 value"));
+    }
+    for c in value.chars() {
+        let digit = c.to_digit(10).ok_or_else(|| {
+            report!(Error::ParseError).attach_printable(format!("Invalid uint256 value: {value}"))
+        })?;
+        // word = word * 10 + digit
+        let mut carry = digit;
+        for byte in word.iter_mut().rev() {
+            let product = *byte as u32 * 10 + carry;
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry != 0 {
+            return Err(
+                report!(Error::ParseError).attach_printable(format!("uint256 overflow: {value}"))
+            );
+        }
+    }
+    Ok(word)
+}
+
+pub fn encode_address(address: &str) -> ModelResult<Word> {
+    let mut word = [0u8; 32];
+    let hex_address = address
+        .strip_prefix("0x")
+        .filter(|hex_address| hex_address.len() == 40)
+        .ok_or_else(|| {
+            report!(Error::ParseError).attach_printable(format!("Invalid EVM address: {address}"))
+        })?;
+    hex::decode_to_slice(hex_address, &mut word[12..])
+        .change_context(Error::ParseError)
+        .attach_printable(format!("Invalid EVM address: {address}"))?;
+    Ok(word)
+}
+
+pub fn encode_bytes32(value: &str) -> ModelResult<Word> {
+    let mut word = [0u8; 32];
+    let hex_value = value.strip_prefix("0x").unwrap_or(value);
+    hex::decode_to_slice(hex_value, &mut word)
+        .change_context(Error::ParseError)
+        .attach_printable(format!("Invalid bytes32 value: {value}"))?;
+    Ok(word)
+}
+
+/// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`
+pub fn typed_data_digest(domain_separator: Word, struct_hash: Word) -> Word {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    hasher.finalize().into()
+}
+
+/// Recovers lowercase `0x` address of `digest` signer.
+///
+/// Accepts 65 bytes `r ‖ s ‖ v` signatures (`v` is 0, 1, 27 or 28)
+/// and 64 bytes EIP-2098 compact signatures
+pub fn recover_signer(digest: Word, signature: &str) -> ModelResult<String> {
+    let bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+        .change_context(Error::ValidationError)
+        .attach_printable("Signature is not valid hex")?;
+
+    let (r, s, y_parity) = match bytes.len() {
+        65 => {
+            let y_parity = match bytes[64] {
+                0 | 27 => false,
+                1 | 28 => true,
+                v => {
+                    return Err(report!(Error::ValidationError)
+                        .attach_printable(format!("Invalid signature v value: {v}")));
+                }
+            };
+            let mut s = [0u8; 32];
+            s.copy_from_slice(&bytes[32..64]);
+            (&bytes[..32], s, y_parity)
+        }
+        64 => {
+            let mut s = [0u8; 32];
+            s.copy_from_slice(&bytes[32..64]);
+            let y_parity = s[0] & 0x80 != 0;
+            s[0] &= 0x7f;
+            (&bytes[..32], s, y_parity)
+        }
+        len => {
+            return Err(report!(Error::ValidationError)
+                .attach_printable(format!("Invalid signature length: {len} bytes")));
+        }
+    };
+
+    let mut rs = [0u8; 64];
+    rs[..32].copy_from_slice(r);
+    rs[32..].copy_from_slice(&s);
+    let mut signature = Signature::from_slice(&rs)
+        .change_context(Error::ValidationError)
+        .attach_printable("Invalid signature")?;
+    let mut recovery_id = RecoveryId::new(y_parity, false);
+    // `ecrecover` accepts high S signatures, `k256` only works with normalized ones
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::new(!y_parity, false);
+    }
+
+    let verifying_key = VerifyingKey::recover_from_prehash(&digest, &signature, recovery_id)
+        .change_context(Error::ValidationError)
+        .attach_printable("Failed to recover signer")?;
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = keccak256(&public_key.as_bytes()[1..]);
+    Ok(format!("0x{}", hex::encode(&hash[12..])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from EIP-712 specification
+    const PERSON_TYPE: &str = "Person(string name,address wallet)";
+    const MAIL_TYPE: &str =
+        "Mail(Person from,Person to,string contents)Person(string name,address wallet)";
+
+    fn hash_person(name: &str, wallet: &str) -> Word {
+        hash_struct(
+            type_hash(PERSON_TYPE),
+            &[
+                encode_string(name),
+                encode_address(wallet).expect("Valid address"),
+            ],
+        )
+    }
+
+    fn mail_digest() -> Word {
+        let domain_separator = hash_struct(
+            type_hash(
+                "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            ),
+            &[
+                encode_string("Ether Mail"),
+                encode_string("1"),
+                encode_uint(1),
+                encode_address("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC")
+                    .expect("Valid address"),
+            ],
+        );
+        let struct_hash = hash_struct(
+            type_hash(MAIL_TYPE),
+            &[
+                hash_person("Cow", "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+                hash_person("Bob", "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+                encode_string("Hello, Bob!"),
+            ],
+        );
+        typed_data_digest(domain_separator, struct_hash)
+    }
+
+    #[test]
+    fn test_eip712_mail_example() {
+        assert_eq!(
+            hex::encode(mail_digest()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+
+        let signature = "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+                         07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c";
+        let signer = recover_signer(mail_digest(), signature).expect("Must recover");
+        assert_eq!(signer, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826");
+    }
+
+    #[test]
+    fn test_recover_signer_signature_formats() {
+        let digest = mail_digest();
+        let r = "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d";
+        let s = "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562";
+        let expected = "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826";
+
+        // v as recovery id
+        let signature = format!("0x{r}{s}01");
+        assert_eq!(
+            recover_signer(digest, &signature).expect("Must recover"),
+            expected
+        );
+
+        // EIP-2098 compact signature: y parity in the highest bit of s
+        let compact_s = format!("87{}", &s[2..]);
+        let signature = format!("0x{r}{compact_s}");
+        assert_eq!(
+            recover_signer(digest, &signature).expect("Must recover"),
+            expected
+        );
+
+        // Wrong y parity recovers another address
+        let signature = format!("0x{r}{s}1b");
+        assert_ne!(
+            recover_signer(digest, &signature).expect("Must recover"),
+            expected
+        );
+
+        assert!(recover_signer(digest, &format!("0x{r}{s}1d")).is_err());
+        assert!(recover_signer(digest, &format!("0x{r}")).is_err());
+        assert!(recover_signer(digest, "0xzz").is_err());
+    }
+
+    #[test]
+    fn test_encode_uint256() {
+        assert_eq!(encode_uint256("1000").expect("Valid"), encode_uint(1000));
+        assert_eq!(encode_uint256("0x3e8").expect("Valid"), encode_uint(1000));
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(encode_uint256(max).expect("Valid"), [0xff; 32]);
+        assert!(
+            encode_uint256(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            )
+            .is_err()
+        );
+        assert!(encode_uint256("12a").is_err());
+        assert!(encode_uint256("").is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use crate::constants::chains::ChainId;
+use crate::error::{Error, ModelResult};
+use crate::models::types::user_types::IntentRequest;
+use error_stack::report;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use std::collections::HashMap;
+
+/// Decimals of USD amounts and prices: 1 USD is `10^8`
+pub const USD_DECIMALS: u32 = 8;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// USD price of a whole token, with `USD_DECIMALS` decimals
+pub struct TokenPrice {
+    pub decimals: u8,
+    #[serde_as(as = "DisplayFromStr")]
+    pub usd_price: u128,
+}
+
+impl TokenPrice {
+    pub fn new(decimals: u8, usd_price: u128) -> Self {
+        Self {
+            decimals,
+            usd_price,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct FeeSchedule {
+    /// Protocol fee in basis points of the trade notional
+    pub fee_bps: u32,
+    /// Minimum protocol fee in USD, with `USD_DECIMALS` decimals
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_fee_usd: u128,
+    /// Collateral of cross chain orders in basis points of the trade notional
+    pub collateral_bps: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// Fee schedules by source chain of the intent
+pub struct FeeConfig {
+    pub default_schedule: FeeSchedule,
+    #[serde(default)]
+    pub chain_overrides: HashMap<ChainId, FeeSchedule>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// Protocol fee and solver collateral of an intent execution, in collateral token units
+pub struct ProtocolFees {
+    /// Value of the traded tokens IN in USD, with `USD_DECIMALS` decimals
+    #[serde_as(as = "DisplayFromStr")]
+    pub notional_usd: u128,
+    #[serde_as(as = "DisplayFromStr")]
+    pub protocol_fee: u128,
+    /// Zero for single chain intents, which are executed atomically
+    #[serde_as(as = "DisplayFromStr")]
+    pub collateral_amount: u128,
+}
+
+impl FeeConfig {
+    pub fn new(default_schedule: FeeSchedule) -> Self {
+        Self {
+            default_schedule,
+            chain_overrides: HashMap::new(),
+        }
+    }
+
+    pub fn with_chain_override(mut self, chain_id: ChainId, schedule: FeeSchedule) -> Self {
+        self.chain_overrides.insert(chain_id, schedule);
+        self
+    }
+
+    /// Schedule applied to intents with `chain_id` source chain
+    pub fn schedule(&self, chain_id: ChainId) -> &FeeSchedule {
+        self.chain_overrides
+            .get(&chain_id)
+            .unwrap_or(&self.default_schedule)
+    }
+
+    /// Protocol fee and collateral of a single execution of `intent`, i.e. of one interval
+    /// for DCA intents.
+    ///
+    /// All amounts are integers, so every party gets identical numbers. Notional is rounded
+    /// down to the smallest USD unit, fee and collateral are rounded up
+    pub fn calculate(
+        &self,
+        intent: &IntentRequest,
+        token_in_price: &TokenPrice,
+        collateral_token_price: &TokenPrice,
+    ) -> ModelResult<ProtocolFees> {
+        let schedule = self.schedule(intent.get_src_chain());
+        let notional_usd = mul_div(
+            intent.get_trade_amount_in(),
+            token_in_price.usd_price,
+            decimals_multiplier(token_in_price.decimals)?,
+            Rounding::Down,
+        )?;
+
+        let fee_usd = bps_of(notional_usd, schedule.fee_bps)?.max(schedule.min_fee_usd);
+        let protocol_fee = usd_to_token(fee_usd, collateral_token_price)?;
+
+        let collateral_amount = match intent {
+            IntentRequest::CrossChainLimitOrder(_) | IntentRequest::CrossChainDcaOrder(_) => {
+                let collateral_usd = bps_of(notional_usd, schedule.collateral_bps)?;
+                usd_to_token(collateral_usd, collateral_token_price)?
+            }
+            IntentRequest::SingleChainLimitOrder(_) | IntentRequest::SingleChainDcaOrder(_) => 0,
+        };
+
+        Ok(ProtocolFees {
+            notional_usd,
+            protocol_fee,
+            collateral_amount,
+        })
+    }
+}
+
+fn bps_of(amount: u128, bps: u32) -> ModelResult<u128> {
+    mul_div(amount, bps as u128, BPS_DENOMINATOR, Rounding::Up)
+}
+
+fn usd_to_token(usd_amount: u128, price: &TokenPrice) -> ModelResult<u128> {
+    if price.usd_price == 0 {
+        return Err(report!(Error::LogicError(
+            "Token price must be positive".to_string()
+        )));
+    }
+    mul_div(
+        usd_amount,
+        decimals_multiplier(price.decimals)?,
+        price.usd_price,
+        Rounding::Up,
+    )
+}
+
+fn decimals_multiplier(decimals: u8) -> ModelResult<u128> {
+    10u128.checked_pow(decimals as u32).ok_or_else(|| {
+        report!(Error::LogicError(format!(
+            "Token decimals {decimals} are out of range"
+        )))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rounding {
+    Down,
+    Up,
+}
+
+/// `a * b / denominator` without intermediate overflow
+fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> ModelResult<u128> {
+    if denominator == 0 {
+        return Err(report!(Error::LogicError("Division by zero".to_string())));
+    }
+    let (high, low) = full_mul(a, b);
+    // Quotient doesn't fit into u128
+    if high >= denominator {
+        return Err(report!(Error::LogicError(format!(
+            "Overflow of {a} * {b} / {denominator}"
+        ))));
+    }
+
+    // Long division of 256 bit product, `remainder` stays below `denominator`
+    let mut quotient = 0u128;
+    let mut remainder = high;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+
+    match rounding {
+        Rounding::Up if remainder != 0 => quotient.checked_add(1).ok_or_else(|| {
+            report!(Error::LogicError(format!(
+                "Overflow of {a} * {b} / {denominator}"
+            )))
+        }),
+        _ => Ok(quotient),
+    }
+}
+
+/// 256 bit product as `(high, low)` halves
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (middle << 64) | (low_low & MASK);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{cross_chain_limit_order, intent_request, single_chain_limit_order};
+    use serde_json::json;
+
+    const USD: u128 = 100_000_000;
+
+    fn eth_price() -> TokenPrice {
+        TokenPrice::new(18, 3_000 * USD)
+    }
+
+    fn usdc_price() -> TokenPrice {
+        TokenPrice::new(6, USD)
+    }
+
+    fn schedule(fee_bps: u32, min_fee_usd: u128, collateral_bps: u32) -> FeeSchedule {
+        FeeSchedule {
+            fee_bps,
+            min_fee_usd,
+            collateral_bps,
+        }
+    }
+
+    /// Swap of `amount_in` WETH on Base
+    fn single_chain_intent(amount_in: &str) -> IntentRequest {
+        let mut intent = single_chain_limit_order();
+        intent["genericData"]["amountIn"] = json!(amount_in);
+        intent_request(intent)
+    }
+
+    /// Transfer of 100 USDC from Ethereum to Base
+    fn cross_chain_intent() -> IntentRequest {
+        intent_request(cross_chain_limit_order())
+    }
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(mul_div(7, 3, 2, Rounding::Down).unwrap(), 10);
+        assert_eq!(mul_div(7, 3, 2, Rounding::Up).unwrap(), 11);
+        assert_eq!(mul_div(6, 3, 2, Rounding::Up).unwrap(), 9);
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Down).unwrap(),
+            u128::MAX
+        );
+        // Intermediate product doesn't fit into u128
+        assert_eq!(
+            mul_div(
+                10u128.pow(30),
+                10u128.pow(20),
+                10u128.pow(18),
+                Rounding::Down
+            )
+            .unwrap(),
+            10u128.pow(32)
+        );
+        assert_eq!(
+            mul_div(u128::MAX, 3, 4, Rounding::Down).unwrap(),
+            u128::MAX / 4 * 3 + 2
+        );
+        assert!(mul_div(u128::MAX, 2, 1, Rounding::Down).is_err());
+        assert!(mul_div(u128::MAX, 1, 1, Rounding::Up).is_ok());
+        assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn test_single_chain_fees() {
+        let config = FeeConfig::new(schedule(5, 0, 100));
+
+        // 1 WETH = $3000, 5 bps fee = $1.5
+        let intent = single_chain_intent("1000000000000000000");
+        let fees = config
+            .calculate(&intent, &eth_price(), &usdc_price())
+            .unwrap();
+        assert_eq!(
+            fees,
+            ProtocolFees {
+                notional_usd: 3_000 * USD,
+                protocol_fee: 1_500_000,
+                collateral_amount: 0,
+            }
+        );
+
+        // Fee is rounded up to the smallest units of the collateral token
+        let intent = single_chain_intent("1");
+        let fees = config
+            .calculate(&intent, &eth_price(), &usdc_price())
+            .unwrap();
+        assert_eq!(fees.notional_usd, 0);
+        assert_eq!(fees.protocol_fee, 0);
+        let intent = single_chain_intent("10000000");
+        let fees = config
+            .calculate(&intent, &eth_price(), &usdc_price())
+            .unwrap();
+        assert_eq!(fees.notional_usd, 3);
+        assert_eq!(fees.protocol_fee, 1);
+
+        // Minimum fee of $2
+        let config = FeeConfig::new(schedule(5, 2 * USD, 100));
+        let intent = single_chain_intent("1000000000000000000");
+        let fees = config
+            .calculate(&intent, &eth_price(), &usdc_price())
+            .unwrap();
+        assert_eq!(fees.protocol_fee, 2_000_000);
+
+        // Fee in WETH
+        let fees = config
+            .calculate(&intent, &eth_price(), &eth_price())
+            .unwrap();
+        assert_eq!(fees.protocol_fee, 666_666_666_666_667);
+
+        assert!(
+            config
+                .calculate(&intent, &eth_price(), &TokenPrice::new(6, 0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cross_chain_fees() {
+        let config = FeeConfig::new(schedule(5, 0, 100))
+            .with_chain_override(ChainId::Ethereum, schedule(10, USD, 200));
+        assert_eq!(config.schedule(ChainId::Base).fee_bps, 5);
+
+        // 100 USDC from Ethereum: $1 minimum fee and 2% collateral
+        let fees = config
+            .calculate(&cross_chain_intent(), &usdc_price(), &usdc_price())
+            .unwrap();
+        assert_eq!(
+            fees,
+            ProtocolFees {
+                notional_usd: 100 * USD,
+                protocol_fee: 1_000_000,
+                collateral_amount: 2_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fee_config_serde() {
+        let config = FeeConfig::new(schedule(5, USD, 100))
+            .with_chain_override(ChainId::Ethereum, schedule(10, 2 * USD, 200));
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["chainOverrides"]["1"]["minFeeUsd"], json!("200000000"));
+        let deserialized: FeeConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, config);
+    }
+}
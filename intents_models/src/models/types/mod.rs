@@ -1,9 +1,14 @@
+pub mod auction;
 pub mod common;
 pub mod contracts;
 pub mod cross_chain;
+pub mod eip712;
+pub mod fees;
 pub mod order;
+pub mod permit2;
 pub mod single_chain;
 pub mod solver_types;
 pub mod user_request_types;
 pub mod user_types;
 pub mod utils;
+pub mod validation;
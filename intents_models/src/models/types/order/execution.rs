@@ -2,6 +2,7 @@ use crate::constants::chains::ChainId;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Enum that has all possible variants of execution search requests
 pub enum ExecutionSearchRequest {
@@ -17,6 +18,7 @@ impl ExecutionSearchRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Request to search fo DCA interval execution
 pub struct DcaIntervalExecutionSearchRequest {
     /// Chain ID where order interval execution should be fulfilled
@@ -32,6 +34,7 @@ pub struct DcaIntervalExecutionSearchRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected order execution data
 pub struct OrderExecutionData {
     /// Chain ID where execution was fulfilled
@@ -47,6 +50,7 @@ pub struct OrderExecutionData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Fulfillment data for a specific order type
 pub enum OrderTypeFulfillmentData {
@@ -57,6 +61,7 @@ pub enum OrderTypeFulfillmentData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// DCA order fulfillment details.
 pub struct DcaOrderFulfillmentData {
     /// Fulfilled interval number
@@ -0,0 +1,300 @@
+use crate::error::{Error, ModelResult};
+use crate::models::types::common::CommonDcaOrderState;
+use crate::models::types::order::{OrderStatus, OrderType};
+use error_stack::report;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "payload")]
+/// Events moving an order through its lifecycle
+pub enum OrderEvent {
+    /// Auction got a winner bid
+    BidReceived,
+    /// Auction ended without any bids
+    AuctionClosedWithoutBids,
+    /// Limit order execution was confirmed on chain
+    ExecutionConfirmed,
+    /// DCA interval execution was confirmed on chain
+    IntervalConfirmed {
+        /// Total number of DCA order intervals
+        total_intervals: u32,
+        /// DCA order state including the confirmed interval
+        dca_state: CommonDcaOrderState,
+    },
+    /// User cancelled the order
+    Cancelled,
+    /// Order deadline passed
+    DeadlinePassed,
+}
+
+impl fmt::Display for OrderEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            OrderEvent::BidReceived => "BidReceived",
+            OrderEvent::AuctionClosedWithoutBids => "AuctionClosedWithoutBids",
+            OrderEvent::ExecutionConfirmed => "ExecutionConfirmed",
+            OrderEvent::IntervalConfirmed { .. } => "IntervalConfirmed",
+            OrderEvent::Cancelled => "Cancelled",
+            OrderEvent::DeadlinePassed => "DeadlinePassed",
+        };
+        write!(f, "{value}")
+    }
+}
+
+impl OrderStatus {
+    /// No further transitions are possible
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Fulfilled | OrderStatus::Cancelled | OrderStatus::Outdated
+        )
+    }
+
+    /// Status of an order of `order_type` after `event`, or an error if `event` is not legal
+    /// in current status or for that order type.
+    ///
+    /// Lifecycle:
+    /// - `Auction` / `NoBids` / `DcaIntervalFulfilled` wait for a bid, `NoBids` after an auction without bids.
+    ///   Only DCA orders can be in `DcaIntervalFulfilled` status
+    /// - `Executing` waits for on chain confirmation. Limit orders are confirmed with `ExecutionConfirmed`.
+    ///   DCA orders are confirmed per interval with `IntervalConfirmed`, moving the order to
+    ///   `DcaIntervalFulfilled`, or to `Fulfilled` once all intervals are executed
+    /// - Orders can be cancelled while waiting for a bid, and become `Outdated` in any non-terminal status
+    pub fn transition(self, order_type: OrderType, event: OrderEvent) -> ModelResult<OrderStatus> {
+        let is_dca = matches!(
+            order_type,
+            OrderType::CrossChainDCAOrder | OrderType::SingleChainDCAOrder
+        );
+        let invalid_transition = || {
+            report!(Error::LogicError(format!(
+                "Invalid order status transition: {event} in {self} status of {order_type}"
+            )))
+        };
+        let next_status = match (self, &event) {
+            (OrderStatus::DcaIntervalFulfilled, _) if !is_dca => return Err(invalid_transition()),
+            (
+                OrderStatus::Auction | OrderStatus::NoBids | OrderStatus::DcaIntervalFulfilled,
+                OrderEvent::BidReceived,
+            ) => OrderStatus::Executing,
+            (
+                OrderStatus::Auction | OrderStatus::NoBids | OrderStatus::DcaIntervalFulfilled,
+                OrderEvent::AuctionClosedWithoutBids,
+            ) => OrderStatus::NoBids,
+            (
+                OrderStatus::Auction | OrderStatus::NoBids | OrderStatus::DcaIntervalFulfilled,
+                OrderEvent::Cancelled,
+            ) => OrderStatus::Cancelled,
+            (OrderStatus::Executing, OrderEvent::ExecutionConfirmed) if !is_dca => {
+                OrderStatus::Fulfilled
+            }
+            (
+                OrderStatus::Executing,
+                OrderEvent::IntervalConfirmed {
+                    total_intervals,
+                    dca_state,
+                },
+            ) if is_dca => {
+                validate_confirmed_interval(*total_intervals, dca_state)?;
+                if dca_state.total_executed_intervals == *total_intervals {
+                    OrderStatus::Fulfilled
+                } else {
+                    OrderStatus::DcaIntervalFulfilled
+                }
+            }
+            (status, OrderEvent::DeadlinePassed) if !status.is_terminal() => OrderStatus::Outdated,
+            _ => return Err(invalid_transition()),
+        };
+        Ok(next_status)
+    }
+}
+
+fn validate_confirmed_interval(
+    total_intervals: u32,
+    dca_state: &CommonDcaOrderState,
+) -> ModelResult<()> {
+    if dca_state.total_executed_intervals == 0 {
+        return Err(report!(Error::LogicError(
+            "Confirmed DCA interval is missing in DCA order state".to_string()
+        )));
+    }
+    if dca_state.total_executed_intervals > total_intervals {
+        return Err(report!(Error::LogicError(format!(
+            "DCA order executed {} intervals out of {total_intervals}",
+            dca_state.total_executed_intervals
+        ))));
+    }
+    // Interval indexes start from 1, every executed interval has a distinct index
+    if dca_state.last_executed_interval_index < dca_state.total_executed_intervals {
+        return Err(report!(Error::LogicError(format!(
+            "DCA order executed {} intervals, but last executed interval index is {}",
+            dca_state.total_executed_intervals, dca_state.last_executed_interval_index
+        ))));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    const DCA: OrderType = OrderType::SingleChainDCAOrder;
+
+    const STATUSES: [OrderStatus; 7] = [
+        OrderStatus::Auction,
+        OrderStatus::NoBids,
+        OrderStatus::Executing,
+        OrderStatus::DcaIntervalFulfilled,
+        OrderStatus::Fulfilled,
+        OrderStatus::Cancelled,
+        OrderStatus::Outdated,
+    ];
+
+    fn interval_confirmed(
+        total_intervals: u32,
+        total_executed_intervals: u32,
+        last_executed_interval_index: u32,
+    ) -> OrderEvent {
+        OrderEvent::IntervalConfirmed {
+            total_intervals,
+            dca_state: CommonDcaOrderState {
+                total_executed_intervals,
+                last_executed_interval_index,
+            },
+        }
+    }
+
+    fn events() -> Vec<OrderEvent> {
+        vec![
+            OrderEvent::BidReceived,
+            OrderEvent::AuctionClosedWithoutBids,
+            OrderEvent::ExecutionConfirmed,
+            interval_confirmed(10, 3, 5),
+            OrderEvent::Cancelled,
+            OrderEvent::DeadlinePassed,
+        ]
+    }
+
+    /// Expected status after each of `events()`, `None` for illegal transitions
+    fn expected_transitions(
+        status: OrderStatus,
+        order_type: OrderType,
+    ) -> [Option<OrderStatus>; 6] {
+        use OrderStatus::*;
+        let is_dca = matches!(
+            order_type,
+            OrderType::CrossChainDCAOrder | OrderType::SingleChainDCAOrder
+        );
+        match status {
+            // Limit orders never get to `DcaIntervalFulfilled`
+            DcaIntervalFulfilled if !is_dca => [None; 6],
+            Auction | NoBids | DcaIntervalFulfilled => [
+                Some(Executing),
+                Some(NoBids),
+                None,
+                None,
+                Some(Cancelled),
+                Some(Outdated),
+            ],
+            // Limit orders can only be confirmed at once, DCA orders only per interval
+            Executing if is_dca => [
+                None,
+                None,
+                None,
+                Some(DcaIntervalFulfilled),
+                None,
+                Some(Outdated),
+            ],
+            Executing => [None, None, Some(Fulfilled), None, None, Some(Outdated)],
+            Fulfilled | Cancelled | Outdated => [None; 6],
+        }
+    }
+
+    #[test]
+    fn test_all_transitions() {
+        for order_type in OrderType::iter() {
+            for status in STATUSES {
+                let expected_transitions = expected_transitions(status, order_type);
+                for (event, expected) in events().into_iter().zip(expected_transitions) {
+                    let res = status.transition(order_type, event.clone());
+                    match expected {
+                        Some(expected) => assert_eq!(
+                            res.expect("Transition must be legal"),
+                            expected,
+                            "{event} in {status} status of {order_type}"
+                        ),
+                        None => assert!(
+                            res.is_err(),
+                            "{event} must be illegal in {status} status of {order_type}"
+                        ),
+                    }
+                }
+                // Limit orders never get to `DcaIntervalFulfilled`, it is not terminal either
+                let unreachable = status == OrderStatus::DcaIntervalFulfilled
+                    && !matches!(
+                        order_type,
+                        OrderType::CrossChainDCAOrder | OrderType::SingleChainDCAOrder
+                    );
+                if !unreachable {
+                    assert_eq!(status.is_terminal(), expected_transitions == [None; 6]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dca_interval_confirmation() {
+        // Last interval fulfills the order
+        assert_eq!(
+            OrderStatus::Executing
+                .transition(DCA, interval_confirmed(10, 10, 12))
+                .expect("Must be legal"),
+            OrderStatus::Fulfilled
+        );
+        assert_eq!(
+            OrderStatus::Executing
+                .transition(DCA, interval_confirmed(10, 1, 1))
+                .expect("Must be legal"),
+            OrderStatus::DcaIntervalFulfilled
+        );
+
+        // No executed intervals
+        assert!(
+            OrderStatus::Executing
+                .transition(DCA, interval_confirmed(10, 0, 0))
+                .is_err()
+        );
+        // More executed intervals than the order has
+        assert!(
+            OrderStatus::Executing
+                .transition(DCA, interval_confirmed(10, 11, 11))
+                .is_err()
+        );
+        // Executed intervals can't outnumber interval indexes
+        assert!(
+            OrderStatus::Executing
+                .transition(DCA, interval_confirmed(10, 3, 2))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dca_order_lifecycle() {
+        let mut status = OrderStatus::Auction;
+        for executed_intervals in 1..=3 {
+            status = status
+                .transition(DCA, OrderEvent::BidReceived)
+                .expect("Must be legal");
+            status = status
+                .transition(
+                    DCA,
+                    interval_confirmed(3, executed_intervals, executed_intervals),
+                )
+                .expect("Must be legal");
+        }
+        assert_eq!(status, OrderStatus::Fulfilled);
+        assert!(status.transition(DCA, OrderEvent::BidReceived).is_err());
+    }
+}
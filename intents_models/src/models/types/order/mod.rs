@@ -13,14 +13,18 @@ use error_stack::report;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use strum_macros::EnumIter;
 
 mod execution;
+mod lifecycle;
 mod order_data_request;
 
 pub use execution::*;
+pub use lifecycle::*;
 pub use order_data_request::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected on chain order data about current on chain order state
 pub enum OnChainOrderDataEnum {
     SingleChainLimitOrder(SingleChainOnChainLimitOrderData),
@@ -77,7 +81,8 @@ impl OnChainOrderDataEnum {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash, Copy, EnumIter)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum OrderType {
     CrossChainLimitOrder,
     CrossChainDCAOrder,
@@ -98,6 +103,7 @@ impl fmt::Display for OrderType {
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Represents the lifecycle status of an order from a domain perspective.
 pub enum OrderStatus {
     /// In auction stage, waiting for bids.
@@ -156,6 +162,7 @@ impl FromStr for OrderStatus {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// List of orders provided to user on request
 pub struct UserOrders {
@@ -166,6 +173,7 @@ pub struct UserOrders {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum UserOrderType {
     CrossChainLimitOrder(CrossChainUserLimitOrderResponse),
     CrossChainDCAOrder(CrossChainUserDcaOrderResponse),
@@ -15,6 +15,7 @@ use crate::models::types::user_types::IntentRequest;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Request for on chain order data
 pub struct OnChainOrderDataRequest {
     pub order_id: String,
@@ -24,6 +25,7 @@ pub struct OnChainOrderDataRequest {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Extra data required for on chain order data collection
 pub enum OnChainOrderDataRequestChainData {
     EVM { user_address: String, nonce: String },
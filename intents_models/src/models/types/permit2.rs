@@ -0,0 +1,624 @@
+//! Permit2 `PermitWitnessTransferFrom` typed data of EVM intents
+//!
+//! The witness structs mirror the order fields guard contracts commit to. Cross chain witnesses
+//! commit to the destination side of the order through `executionDetailsHash`, as destination chain
+//! addresses may be non-EVM.
+//!
+//! NOTE: guard contract sources are not part of this repository, and the witness type strings
+//! below are not verified against them yet. Test vectors only prove the EIP-712 encoding of these
+//! type strings, not parity with on-chain verification. Until a vector from a signed intent
+//! accepted on-chain is added, `verify_permit2_signature` must not be used to reject intents.
+//! In particular, limit order witnesses don't commit to `stopLossType`, `stopLossTriggerPrice`
+//! and `takeProfitMinOut`, which is to be confirmed against the guard contracts.
+
+use crate::constants::chains::{ChainId, ChainType};
+use crate::error::{Error, ModelResult};
+use crate::models::types::common::TransferDetails;
+use crate::models::types::contracts::ContractsAddresses;
+use crate::models::types::eip712::{
+    Word, encode_address, encode_bytes32, encode_string, encode_uint, encode_uint256, hash_array,
+    hash_struct, recover_signer, type_hash, typed_data_digest,
+};
+use crate::models::types::user_types::IntentRequest;
+use error_stack::report;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+
+pub const PERMIT2_DOMAIN_NAME: &str = "Permit2";
+pub const PERMIT2_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,uint256 chainId,address verifyingContract)";
+pub const TOKEN_PERMISSIONS_TYPE: &str = "TokenPermissions(address token,uint256 amount)";
+pub const EXTRA_TRANSFER_TYPE: &str =
+    "ExtraTransfer(address token,address receiver,uint256 amount)";
+pub const SINGLE_CHAIN_LIMIT_ORDER_TYPE: &str = "SingleChainLimitOrder(address user,address tokenIn,address tokenOut,uint256 amountIn,uint256 amountOutMin,address destinationAddress,ExtraTransfer[] extraTransfers,uint256 deadline)";
+pub const SINGLE_CHAIN_DCA_ORDER_TYPE: &str = "SingleChainDcaOrder(address user,address tokenIn,address tokenOut,uint256 amountInPerInterval,uint256 amountOutMin,uint32 startTime,uint32 totalIntervals,uint32 intervalDuration,address destinationAddress,ExtraTransfer[] extraTransfers,uint256 deadline)";
+pub const CROSS_CHAIN_LIMIT_ORDER_TYPE: &str = "CrossChainLimitOrder(address user,address tokenIn,uint256 amountIn,uint256 minStablecoinsAmount,uint256 destChainId,bytes32 executionDetailsHash,uint256 deadline)";
+pub const CROSS_CHAIN_DCA_ORDER_TYPE: &str = "CrossChainDcaOrder(address user,address tokenIn,uint256 amountInPerInterval,uint256 minStablecoinsAmount,uint32 startTime,uint32 totalIntervals,uint32 intervalDuration,uint256 destChainId,bytes32 executionDetailsHash,uint256 deadline)";
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// Permit2 `PermitWitnessTransferFrom` message together with its domain
+pub struct PermitWitnessTransferFrom {
+    /// Domain chain ID, the chain tokens IN are spent on
+    pub chain_id: u32,
+    /// Permit2 contract address, domain `verifyingContract`
+    pub permit2: String,
+    /// Permitted token
+    pub token: String,
+    /// Permitted amount
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount: u128,
+    /// Guard contract allowed to spend tokens
+    pub spender: String,
+    /// Permit2 nonce, decimal or hex
+    pub nonce: String,
+    /// Permit deadline, in SECONDS
+    pub deadline: u64,
+    pub witness: IntentWitness,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "payload")]
+/// Witness of each intent type
+pub enum IntentWitness {
+    SingleChainLimitOrder(SingleChainLimitOrderWitness),
+    SingleChainDcaOrder(SingleChainDcaOrderWitness),
+    CrossChainLimitOrder(CrossChainLimitOrderWitness),
+    CrossChainDcaOrder(CrossChainDcaOrderWitness),
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SingleChainLimitOrderWitness {
+    pub user: String,
+    pub token_in: String,
+    pub token_out: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_in: u128,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_out_min: u128,
+    pub destination_address: String,
+    pub extra_transfers: Vec<TransferDetails>,
+    pub deadline: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SingleChainDcaOrderWitness {
+    pub user: String,
+    pub token_in: String,
+    pub token_out: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_in_per_interval: u128,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_out_min: u128,
+    pub start_time: u32,
+    pub total_intervals: u32,
+    pub interval_duration: u32,
+    pub destination_address: String,
+    pub extra_transfers: Vec<TransferDetails>,
+    pub deadline: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CrossChainLimitOrderWitness {
+    pub user: String,
+    pub token_in: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_in: u128,
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_stablecoins_amount: u128,
+    pub dest_chain_id: ChainId,
+    pub execution_details_hash: String,
+    pub deadline: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CrossChainDcaOrderWitness {
+    pub user: String,
+    pub token_in: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_in_per_interval: u128,
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_stablecoins_amount: u128,
+    pub start_time: u32,
+    pub total_intervals: u32,
+    pub interval_duration: u32,
+    pub dest_chain_id: ChainId,
+    pub execution_details_hash: String,
+    pub deadline: u64,
+}
+
+fn hash_extra_transfers(extra_transfers: &[TransferDetails]) -> ModelResult<Word> {
+    let extra_transfer_type_hash = type_hash(EXTRA_TRANSFER_TYPE);
+    let hashes = extra_transfers
+        .iter()
+        .map(|transfer| {
+            Ok(hash_struct(
+                extra_transfer_type_hash,
+                &[
+                    encode_address(&transfer.token)?,
+                    encode_address(&transfer.receiver)?,
+                    encode_uint(transfer.amount),
+                ],
+            ))
+        })
+        .collect::<ModelResult<Vec<_>>>()?;
+    Ok(hash_array(&hashes))
+}
+
+impl IntentWitness {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            IntentWitness::SingleChainLimitOrder(_) => "SingleChainLimitOrder",
+            IntentWitness::SingleChainDcaOrder(_) => "SingleChainDcaOrder",
+            IntentWitness::CrossChainLimitOrder(_) => "CrossChainLimitOrder",
+            IntentWitness::CrossChainDcaOrder(_) => "CrossChainDcaOrder",
+        }
+    }
+
+    /// Definitions of the witness type and every type it references
+    fn type_definitions(&self) -> Vec<&'static str> {
+        match self {
+            IntentWitness::SingleChainLimitOrder(_) => {
+                vec![SINGLE_CHAIN_LIMIT_ORDER_TYPE, EXTRA_TRANSFER_TYPE]
+            }
+            IntentWitness::SingleChainDcaOrder(_) => {
+                vec![SINGLE_CHAIN_DCA_ORDER_TYPE, EXTRA_TRANSFER_TYPE]
+            }
+            IntentWitness::CrossChainLimitOrder(_) => vec![CROSS_CHAIN_LIMIT_ORDER_TYPE],
+            IntentWitness::CrossChainDcaOrder(_) => vec![CROSS_CHAIN_DCA_ORDER_TYPE],
+        }
+    }
+
+    /// `encodeType` of the witness
+    pub fn encode_type(&self) -> String {
+        let mut definitions = self.type_definitions();
+        let primary = definitions.remove(0);
+        definitions.sort_unstable();
+        std::iter::once(primary).chain(definitions).collect()
+    }
+
+    /// Permit2 `witnessTypeString`: `<Type> witness)` followed by every referenced type
+    /// definition, `TokenPermissions` included, in alphabetical order
+    pub fn witness_type_string(&self) -> String {
+        let mut definitions = self.type_definitions();
+        definitions.push(TOKEN_PERMISSIONS_TYPE);
+        definitions.sort_unstable();
+        format!("{} witness){}", self.type_name(), definitions.concat())
+    }
+
+    pub fn struct_hash(&self) -> ModelResult<Word> {
+        let type_hash = type_hash(&self.encode_type());
+        let hash = match self {
+            IntentWitness::SingleChainLimitOrder(witness) => hash_struct(
+                type_hash,
+                &[
+                    encode_address(&witness.user)?,
+                    encode_address(&witness.token_in)?,
+                    encode_address(&witness.token_out)?,
+                    encode_uint(witness.amount_in),
+                    encode_uint(witness.amount_out_min),
+                    encode_address(&witness.destination_address)?,
+                    hash_extra_transfers(&witness.extra_transfers)?,
+                    encode_uint(witness.deadline as u128),
+                ],
+            ),
+            IntentWitness::SingleChainDcaOrder(witness) => hash_struct(
+                type_hash,
+                &[
+                    encode_address(&witness.user)?,
+                    encode_address(&witness.token_in)?,
+                    encode_address(&witness.token_out)?,
+                    encode_uint(witness.amount_in_per_interval),
+                    encode_uint(witness.amount_out_min),
+                    encode_uint(witness.start_time as u128),
+                    encode_uint(witness.total_intervals as u128),
+                    encode_uint(witness.interval_duration as u128),
+                    encode_address(&witness.destination_address)?,
+                    hash_extra_transfers(&witness.extra_transfers)?,
+                    encode_uint(witness.deadline as u128),
+                ],
+            ),
+            IntentWitness::CrossChainLimitOrder(witness) => hash_struct(
+                type_hash,
+                &[
+                    encode_address(&witness.user)?,
+                    encode_address(&witness.token_in)?,
+                    encode_uint(witness.amount_in),
+                    encode_uint(witness.min_stablecoins_amount),
+                    encode_uint(witness.dest_chain_id as u128),
+                    encode_bytes32(&witness.execution_details_hash)?,
+                    encode_uint(witness.deadline as u128),
+                ],
+            ),
+            IntentWitness::CrossChainDcaOrder(witness) => hash_struct(
+                type_hash,
+                &[
+                    encode_address(&witness.user)?,
+                    encode_address(&witness.token_in)?,
+                    encode_uint(witness.amount_in_per_interval),
+                    encode_uint(witness.min_stablecoins_amount),
+                    encode_uint(witness.start_time as u128),
+                    encode_uint(witness.total_intervals as u128),
+                    encode_uint(witness.interval_duration as u128),
+                    encode_uint(witness.dest_chain_id as u128),
+                    encode_bytes32(&witness.execution_details_hash)?,
+                    encode_uint(witness.deadline as u128),
+                ],
+            ),
+        };
+        Ok(hash)
+    }
+}
+
+impl PermitWitnessTransferFrom {
+    pub fn domain_separator(&self) -> ModelResult<Word> {
+        Ok(hash_struct(
+            type_hash(PERMIT2_DOMAIN_TYPE),
+            &[
+                encode_string(PERMIT2_DOMAIN_NAME),
+                encode_uint(self.chain_id as u128),
+                encode_address(&self.permit2)?,
+            ],
+        ))
+    }
+
+    /// `encodeType` of `PermitWitnessTransferFrom` with the intent witness
+    pub fn encode_type(&self) -> String {
+        format!(
+            "PermitWitnessTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline,{}",
+            self.witness.witness_type_string()
+        )
+    }
+
+    pub fn struct_hash(&self) -> ModelResult<Word> {
+        let token_permissions = hash_struct(
+            type_hash(TOKEN_PERMISSIONS_TYPE),
+            &[encode_address(&self.token)?, encode_uint(self.amount)],
+        );
+        Ok(hash_struct(
+            type_hash(&self.encode_type()),
+            &[
+                token_permissions,
+                encode_address(&self.spender)?,
+                encode_uint256(&self.nonce)?,
+                encode_uint(self.deadline as u128),
+                self.witness.struct_hash()?,
+            ],
+        ))
+    }
+
+    /// EIP-712 digest signed by the user
+    pub fn digest(&self) -> ModelResult<Word> {
+        Ok(typed_data_digest(
+            self.domain_separator()?,
+            self.struct_hash()?,
+        ))
+    }
+
+    /// Recovers lowercase address of the signer of the permit
+    pub fn recover_signer(&self, signature: &str) -> ModelResult<String> {
+        recover_signer(self.digest()?, signature)
+    }
+}
+
+impl IntentRequest {
+    /// Builds Permit2 witness transfer the user signed for an EVM intent
+    pub fn get_permit2_witness_transfer(
+        &self,
+        contracts: &ContractsAddresses,
+    ) -> ModelResult<PermitWitnessTransferFrom> {
+        let src_chain = self.get_src_chain();
+        if src_chain.to_chain_type() != ChainType::EVM {
+            return Err(report!(Error::LogicError(format!(
+                "Permit2 is not supported on {src_chain}"
+            ))));
+        }
+        let evm_contracts = contracts.evm.get(&(src_chain as u32)).ok_or_else(|| {
+            report!(Error::LogicError(format!(
+                "No EVM contracts configured for {src_chain}"
+            )))
+        })?;
+
+        let (spender, nonce, witness) = match self {
+            IntentRequest::SingleChainLimitOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                let common_data = &generic_data.common_data;
+                (
+                    &evm_contracts.single_chain.guard_limit,
+                    &intent.chain_specific_data.try_get_evm()?.nonce,
+                    IntentWitness::SingleChainLimitOrder(SingleChainLimitOrderWitness {
+                        user: common_data.user.clone(),
+                        token_in: common_data.token_in.clone(),
+                        token_out: common_data.token_out.clone(),
+                        amount_in: generic_data.amount_in,
+                        amount_out_min: common_data.amount_out_min,
+                        destination_address: common_data.destination_address.clone(),
+                        extra_transfers: common_data.extra_transfers.clone().unwrap_or_default(),
+                        deadline: common_data.deadline,
+                    }),
+                )
+            }
+            IntentRequest::SingleChainDcaOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                let common_data = &generic_data.common_data;
+                let dca_data = &generic_data.common_dca_order_data;
+                (
+                    &evm_contracts.single_chain.guard_dca,
+                    &intent.chain_specific_data.try_get_evm()?.nonce,
+                    IntentWitness::SingleChainDcaOrder(SingleChainDcaOrderWitness {
+                        user: common_data.user.clone(),
+                        token_in: common_data.token_in.clone(),
+                        token_out: common_data.token_out.clone(),
+                        amount_in_per_interval: dca_data.amount_in_per_interval,
+                        amount_out_min: common_data.amount_out_min,
+                        start_time: dca_data.start_time,
+                        total_intervals: dca_data.total_intervals,
+                        interval_duration: dca_data.interval_duration,
+                        destination_address: common_data.destination_address.clone(),
+                        extra_transfers: common_data.extra_transfers.clone().unwrap_or_default(),
+                        deadline: common_data.deadline,
+                    }),
+                )
+            }
+            IntentRequest::CrossChainLimitOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                let common_data = &generic_data.common_data;
+                (
+                    &evm_contracts.cross_chain.guard_limit,
+                    &intent.chain_specific_data.try_get_evm()?.nonce,
+                    IntentWitness::CrossChainLimitOrder(CrossChainLimitOrderWitness {
+                        user: common_data.user.clone(),
+                        token_in: common_data.token_in.clone(),
+                        amount_in: generic_data.amount_in,
+                        min_stablecoins_amount: common_data.min_stablecoins_amount,
+                        dest_chain_id: common_data.dest_chain_id,
+                        execution_details_hash: common_data.execution_details_hash.clone(),
+                        deadline: common_data.deadline,
+                    }),
+                )
+            }
+            IntentRequest::CrossChainDcaOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                let common_data = &generic_data.common_data;
+                let dca_data = &generic_data.common_dca_order_data;
+                (
+                    &evm_contracts.cross_chain.guard_dca,
+                    &intent.chain_specific_data.try_get_evm()?.nonce,
+                    IntentWitness::CrossChainDcaOrder(CrossChainDcaOrderWitness {
+                        user: common_data.user.clone(),
+                        token_in: common_data.token_in.clone(),
+                        amount_in_per_interval: dca_data.amount_in_per_interval,
+                        min_stablecoins_amount: common_data.min_stablecoins_amount,
+                        start_time: dca_data.start_time,
+                        total_intervals: dca_data.total_intervals,
+                        interval_duration: dca_data.interval_duration,
+                        dest_chain_id: common_data.dest_chain_id,
+                        execution_details_hash: common_data.execution_details_hash.clone(),
+                        deadline: common_data.deadline,
+                    }),
+                )
+            }
+        };
+
+        Ok(PermitWitnessTransferFrom {
+            chain_id: src_chain as u32,
+            permit2: evm_contracts.permit2.clone(),
+            token: self.get_token_in_address().to_string(),
+            // DCA orders permit spending of all intervals at once
+            amount: self.get_total_amount_in(),
+            spender: spender.clone(),
+            nonce: nonce.clone(),
+            deadline: self.get_deadline(),
+            witness,
+        })
+    }
+
+    /// Checks Permit2 signature of an EVM intent was made by the intent user.
+    /// See module docs: witness type strings are not verified against guard contracts yet
+    pub fn verify_permit2_signature(&self, contracts: &ContractsAddresses) -> ModelResult<()> {
+        let signature = match self {
+            IntentRequest::SingleChainLimitOrder(intent) => {
+                &intent.chain_specific_data.try_get_evm()?.signature
+            }
+            IntentRequest::SingleChainDcaOrder(intent) => {
+                &intent.chain_specific_data.try_get_evm()?.signature
+            }
+            IntentRequest::CrossChainLimitOrder(intent) => {
+                &intent.chain_specific_data.try_get_evm()?.signature
+            }
+            IntentRequest::CrossChainDcaOrder(intent) => {
+                &intent.chain_specific_data.try_get_evm()?.signature
+            }
+        };
+        let signer = self
+            .get_permit2_witness_transfer(contracts)?
+            .recover_signer(signature)?;
+        if !signer.eq_ignore_ascii_case(self.get_user_address()) {
+            return Err(report!(Error::ValidationError).attach_printable(format!(
+                "Permit2 signature was made by {signer}, not by order user {}",
+                self.get_user_address()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self as fixtures, USDC_BASE};
+    use serde_json::{Value, json};
+
+    // Vectors below were produced with an independent EIP-712 implementation (alloy `sol!` structs
+    // declared from the type strings of this module), signed by private key
+    // 0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318.
+    // They check the encoding only, not that guard contracts use the same type strings
+    const PERMIT2: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+    fn contracts() -> ContractsAddresses {
+        let evm_contracts = |guard_limit: &str, guard_dca: &str| {
+            json!({
+                "singleChain": {
+                    "guardLimit": guard_limit,
+                    "guardDca": guard_dca,
+                    "protocolFeeToken": USDC_BASE,
+                },
+                "crossChain": {
+                    "guardLimit": guard_limit,
+                    "guardDca": guard_dca,
+                    "collateralToken": USDC_BASE,
+                    "stablecoin": USDC_BASE,
+                    "destinationChainGuard": guard_limit,
+                },
+                "permit2": PERMIT2,
+            })
+        };
+        serde_json::from_value(json!({
+            "evm": {
+                "8453": evm_contracts(
+                    "0x3333333333333333333333333333333333333333",
+                    "0x3333333333333333333333333333333333333334",
+                ),
+                "1": evm_contracts(
+                    "0x5555555555555555555555555555555555555554",
+                    "0x5555555555555555555555555555555555555555",
+                ),
+            },
+            "solana": {
+                "singleChain": {
+                    "guardProgramId": "",
+                    "guardAccount": "",
+                    "protocolFeeToken": "",
+                },
+                "crossChain": {
+                    "guardProgramId": "",
+                    "guardAccount": "",
+                    "collateralTokenMint": "",
+                    "collateralTokenProgram": "",
+                    "stablecoinTokenMint": "",
+                    "stablecoinTokenProgram": "",
+                },
+            },
+            "sui": {
+                "packageId": "",
+                "guard": "",
+                "guardCollateralType": "",
+                "guardStablecoinType": "",
+            },
+        }))
+        .expect("Valid contracts config")
+    }
+
+    fn signed(mut intent: Value, signature: &str) -> IntentRequest {
+        intent["chainSpecificData"]["EVM"]["signature"] = json!(signature);
+        fixtures::intent_request(intent)
+    }
+
+    fn single_chain_limit_order() -> IntentRequest {
+        signed(
+            fixtures::single_chain_limit_order_with_extra_transfer(),
+            "0xf9b06014a6614cfc6e557e593cdfbca0aebc2961e4f2481388d834779cf46b3a\
+             41dcead0732f46aecaf10951effca495179bfde29bcedcb67e6672352239febd1b",
+        )
+    }
+
+    fn cross_chain_dca_order() -> IntentRequest {
+        signed(
+            fixtures::cross_chain_dca_order(),
+            "0x20b0a9afe24884ffbfe58c2c978f975fa5dff462f3ff1627239e02941bc1d14d\
+             295cb22ab550edaddd64436a994b5c4ff2081e900a7925832a49c84ebda277ef1c",
+        )
+    }
+
+    #[test]
+    fn test_single_chain_limit_order_permit() {
+        let intent = single_chain_limit_order();
+        let permit = intent
+            .get_permit2_witness_transfer(&contracts())
+            .expect("Must build permit");
+
+        assert_eq!(permit.chain_id, 8453);
+        assert_eq!(permit.spender, "0x3333333333333333333333333333333333333333");
+        assert_eq!(
+            permit.encode_type(),
+            "PermitWitnessTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline,SingleChainLimitOrder witness)\
+             ExtraTransfer(address token,address receiver,uint256 amount)\
+             SingleChainLimitOrder(address user,address tokenIn,address tokenOut,uint256 amountIn,uint256 amountOutMin,address destinationAddress,ExtraTransfer[] extraTransfers,uint256 deadline)\
+             TokenPermissions(address token,uint256 amount)"
+        );
+        assert_eq!(
+            hex::encode(permit.struct_hash().expect("Must hash")),
+            "1f759092ffefb5eefc2c930717de1c9e3a5192aedfffa0e7fe63c5e9a1a7e270"
+        );
+        assert_eq!(
+            hex::encode(permit.digest().expect("Must hash")),
+            "4275326f29e15818cbf92a8491e12173f95b0d360f72c2b10e77149b63098b92"
+        );
+        intent
+            .verify_permit2_signature(&contracts())
+            .expect("Signature must be valid");
+    }
+
+    #[test]
+    fn test_cross_chain_dca_order_permit() {
+        let intent = cross_chain_dca_order();
+        let permit = intent
+            .get_permit2_witness_transfer(&contracts())
+            .expect("Must build permit");
+
+        assert_eq!(permit.chain_id, 1);
+        assert_eq!(permit.spender, "0x5555555555555555555555555555555555555555");
+        // All intervals are permitted at once
+        assert_eq!(permit.amount, 1_000_000_000);
+        assert_eq!(
+            hex::encode(permit.struct_hash().expect("Must hash")),
+            "58d82c70378aba649eed6aaccfcb55e6a880bf4941633ea0230f04c4e82b5543"
+        );
+        assert_eq!(
+            hex::encode(permit.digest().expect("Must hash")),
+            "fa4caa881ae8d17abf25ef735134265ca944e3669571ba6762c0769ecc58ab89"
+        );
+        intent
+            .verify_permit2_signature(&contracts())
+            .expect("Signature must be valid");
+    }
+
+    #[test]
+    fn test_tampered_intent_signature_is_rejected() {
+        let IntentRequest::CrossChainDcaOrder(mut intent) = cross_chain_dca_order() else {
+            unreachable!()
+        };
+        intent.generic_data.common_data.min_stablecoins_amount = 1;
+        let res = IntentRequest::CrossChainDcaOrder(intent).verify_permit2_signature(&contracts());
+        assert_eq!(
+            res.expect_err("Signature must not match").current_context(),
+            &Error::ValidationError
+        );
+
+        // Permit2 is EVM only
+        let IntentRequest::CrossChainDcaOrder(mut intent) = cross_chain_dca_order() else {
+            unreachable!()
+        };
+        intent.generic_data.common_data.src_chain_id = ChainId::Solana;
+        assert!(
+            IntentRequest::CrossChainDcaOrder(intent)
+                .get_permit2_witness_transfer(&contracts())
+                .is_err()
+        );
+    }
+}
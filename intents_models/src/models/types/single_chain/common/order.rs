@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected common on chain single-chain order data about current on chain order state
 pub struct SingleChainOnChainOrderData {
     /// Is order still active?
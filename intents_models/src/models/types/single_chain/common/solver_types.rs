@@ -11,6 +11,7 @@ use serde_with::{DisplayFromStr, serde_as};
 /*********************************************************************/
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Permission, granted to Solver to start single chain order execution
 pub struct SingleChainSolverStartPermission {
@@ -28,6 +29,7 @@ pub struct SingleChainSolverStartPermission {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Chain-specific data of permission, granted to Solver to start single chain order execution
 pub enum SingleChainSolverStartOrderData {
     /// EVM-based chain data (e.g., Ethereum, Binance Smart Chain)
@@ -76,6 +78,7 @@ impl SingleChainSolverStartOrderData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Single chain order execution terms, provided to the Solver during auction
 pub struct SingleChainExecutionTerms {
     /// Address of protocol fee token, receiver and protocol fee amount
@@ -87,6 +90,7 @@ pub struct SingleChainExecutionTerms {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Set of common data to check single chain limit order execution
 pub struct SingleChainOrderExecutionDetails {
     pub chain_id: ChainId,
@@ -95,6 +99,7 @@ pub struct SingleChainOrderExecutionDetails {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Sui-specific chain data, used by Solver to start single chain order execution
 pub struct SingleChainStartOrderSuiData {
     /// Package ID, that should be interacted with
@@ -1,4 +1,4 @@
-use crate::constants::chains::ChainId;
+use crate::constants::chains::{ChainId, ChainType};
 use crate::error::{Error, ModelResult};
 use crate::models::types::common::TransferDetails;
 use crate::models::types::user_types::EVMData;
@@ -9,6 +9,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Common single chain order generic data
 pub struct SingleChainGenericData {
@@ -44,6 +45,7 @@ impl SingleChainGenericData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Chain-specific single chain order data
 pub enum SingleChainChainSpecificData {
     /// EVM-based chain data (e.g., Ethereum, Binance Smart Chain)
@@ -56,6 +58,7 @@ pub enum SingleChainChainSpecificData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Sui-specific single chain order data
 pub struct SingleChainSuiData {
@@ -68,6 +71,7 @@ pub struct SingleChainSuiData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Solana-specific single chain order data
 pub struct SingleChainSolanaData {
@@ -79,6 +83,14 @@ pub struct SingleChainSolanaData {
 }
 
 impl SingleChainChainSpecificData {
+    pub fn chain_type(&self) -> ChainType {
+        match self {
+            SingleChainChainSpecificData::EVM(_) => ChainType::EVM,
+            SingleChainChainSpecificData::Sui(_) => ChainType::Sui,
+            SingleChainChainSpecificData::Solana(_) => ChainType::Solana,
+        }
+    }
+
     pub fn try_get_evm(&self) -> ModelResult<&EVMData> {
         match self {
             SingleChainChainSpecificData::EVM(evm_data) => Ok(evm_data),
@@ -3,6 +3,7 @@ use crate::models::types::single_chain::SingleChainOnChainOrderData;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SingleChainOnChainDcaOrderData {
     #[serde(flatten)]
     pub common_data: SingleChainOnChainOrderData,
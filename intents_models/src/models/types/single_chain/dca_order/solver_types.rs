@@ -16,6 +16,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 /*********************************************************************/
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Single chain DCA order data required for execution start
 pub struct StartEvmSingleChainDcaOrderData {
     pub order_info: EvmSingleChainDcaOrderInfo,
@@ -24,6 +25,7 @@ pub struct StartEvmSingleChainDcaOrderData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Permission, granted to Solver to start single-chain DCA order execution
 pub struct SingleChainDcaOrderSolverStartPermission {
@@ -36,6 +38,7 @@ pub struct SingleChainDcaOrderSolverStartPermission {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Set of data to check single chain DCA order execution
 pub struct SingleChainDcaOrderExecutionDetails {
     #[serde(flatten)]
@@ -44,6 +47,7 @@ pub struct SingleChainDcaOrderExecutionDetails {
 }
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmSingleChainDcaOrderInfo {
     pub user: String,
@@ -124,6 +128,7 @@ impl TryFrom<(&SingleChainDcaOrderGenericData, &EVMData)> for EvmSingleChainDcaO
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmSingleChainDcaSolverPermission {
     pub solver: String,
@@ -9,6 +9,7 @@ use serde_with::serde_as;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Single chain dca order intent request, received from the user
 pub struct SingleChainDcaOrderUserIntentRequest {
@@ -20,6 +21,7 @@ pub struct SingleChainDcaOrderUserIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold generic data related to the single chain dca order intent
 pub struct SingleChainDcaOrderGenericRequestData {
@@ -6,6 +6,7 @@ use serde_with::serde_as;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Single chain DCA order intent structure
 pub struct SingleChainDcaOrderIntentRequest {
@@ -17,6 +18,7 @@ pub struct SingleChainDcaOrderIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Generic data related to the single-chain DCA order
 pub struct SingleChainDcaOrderGenericData {
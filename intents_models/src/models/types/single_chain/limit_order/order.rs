@@ -2,6 +2,7 @@ use crate::models::types::single_chain::SingleChainOnChainOrderData;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected on chain single-chain limit order data about current on chain order state
 pub struct SingleChainOnChainLimitOrderData {
     #[serde(flatten)]
@@ -17,6 +17,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 /*********************************************************************/
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Single chain limit order data required for execution start
 pub struct StartEvmSingleChainLimitOrderData {
     pub order_info: EvmSingleChainLimitOrderInfo,
@@ -25,6 +26,7 @@ pub struct StartEvmSingleChainLimitOrderData {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Permission, granted to Solver to start single-chain limit order execution
 pub struct SingleChainLimitOrderSolverStartPermission {
@@ -35,6 +37,7 @@ pub struct SingleChainLimitOrderSolverStartPermission {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Set of data to check single chain limit order execution
 pub struct SingleChainLimitOrderExecutionDetails {
     #[serde(flatten)]
@@ -43,6 +46,7 @@ pub struct SingleChainLimitOrderExecutionDetails {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmSingleChainLimitOrderInfo {
     pub user: String,
@@ -115,6 +119,7 @@ impl TryFrom<(&SingleChainLimitOrderGenericData, &EVMData)> for EvmSingleChainLi
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct EvmSingleChainLimitSolverPermission {
     pub solver: String,
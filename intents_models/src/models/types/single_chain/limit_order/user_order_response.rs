@@ -5,6 +5,7 @@ use crate::models::types::{order::OrderStatus, single_chain::SingleChainLimitOrd
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Single chain limit order data, provided to user on request
 pub struct SingleChainUserLimitOrderResponse {
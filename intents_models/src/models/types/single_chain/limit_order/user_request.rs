@@ -9,6 +9,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Single chain limit order intent request, received from the user
 pub struct SingleChainLimitOrderUserIntentRequest {
@@ -20,6 +21,7 @@ pub struct SingleChainLimitOrderUserIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// A structure to hold generic data related to the single chain limit order intent
 pub struct SingleChainLimitOrderGenericRequestData {
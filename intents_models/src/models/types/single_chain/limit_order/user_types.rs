@@ -5,6 +5,7 @@ use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Single chain Limit order intent structure, provided by the user
 pub struct SingleChainLimitOrderIntentRequest {
@@ -16,6 +17,7 @@ pub struct SingleChainLimitOrderIntentRequest {
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Generic data of single chain Limit order intent structure, provided by the user
 pub struct SingleChainLimitOrderGenericData {
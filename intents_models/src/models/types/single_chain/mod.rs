@@ -15,6 +15,7 @@ pub use limit_order::*;
 pub use solver_types::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum SingleChainIntentRequest {
     SingleChainLimitOrder(SingleChainLimitOrderIntentRequest),
@@ -94,6 +95,7 @@ impl SingleChainIntentRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Permission, granted to Solver single-chain order execution
 pub enum SingleChainSolverStartPermissionEnum {
@@ -145,6 +147,7 @@ impl SingleChainSolverStartPermissionEnum {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Collected on chain order data about current on chain order state
 pub enum SingleChainOnChainOrderDataEnum {
     SingleChainLimitOrder(SingleChainOnChainLimitOrderData),
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Set of data to check single chain order execution
 pub enum SingleChainSolverExecutionDetailsEnum {
@@ -24,6 +25,7 @@ impl SingleChainSolverExecutionDetailsEnum {
 
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Result data of checking single chain order execution
 pub struct SingleChainSolverSuccessConfirmation {
     /// Amount of main tokens OUT that were actually received by the user
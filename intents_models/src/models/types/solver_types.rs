@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 /*********************************************************************/
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Terms of execution of specific intent
 pub enum ExecutionTerms {
@@ -51,6 +52,7 @@ impl ExecutionTerms {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Data, used by Solver to start order execution
 pub enum SolverStartPermission {
@@ -61,6 +63,7 @@ pub enum SolverStartPermission {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Data, used by Solver to start order execution (sorted by chain number)
 pub enum SolverStartPermissionChainNumber {
@@ -281,6 +284,7 @@ impl SolverStartPermission {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// EVM-specific data for start order execution
 pub struct StartOrderEVMData {
@@ -295,6 +299,7 @@ pub struct StartOrderEVMData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Type-specific order data required for execution start
 pub enum StartEvmOrderTypeData {
@@ -324,6 +329,7 @@ impl StartEvmOrderTypeData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 /// Solana-specific data for start order execution
 pub struct StartOrderSolanaData {
     /// Program ID, that should be interacted with
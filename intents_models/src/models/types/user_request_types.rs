@@ -14,6 +14,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::{StringWithSeparator, formats::CommaSeparator, serde_as};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Intent request, received from the user, but not converted to `IntentRequest` enum yet
 /// Main purpose is to pass data which `IntentRequest` doesn't have (like `execution_details`)
@@ -102,6 +103,7 @@ impl UserRequestGenericData {
 
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserIntentsRequest {
     #[serde_as(as = "StringWithSeparator<CommaSeparator, String>")]
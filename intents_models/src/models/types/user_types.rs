@@ -1,4 +1,4 @@
-use crate::constants::chains::ChainId;
+use crate::constants::chains::{ChainId, ChainType};
 use crate::error::{Error, ModelResult};
 use crate::models::types::common::{CommonDcaOrderData, CommonLimitOrderData};
 use crate::models::types::cross_chain::CrossChainIntentRequest;
@@ -7,12 +7,17 @@ use crate::models::types::cross_chain::{CrossChainDcaOrderIntentRequest, CrossCh
 use crate::models::types::order::OrderType;
 use crate::models::types::single_chain::SingleChainLimitOrderIntentRequest;
 use crate::models::types::single_chain::{
-    SingleChainDcaOrderIntentRequest, SingleChainIntentRequest,
+    SingleChainDcaOrderIntentRequest, SingleChainGenericData, SingleChainIntentRequest,
+};
+use crate::models::types::validation::{
+    CommonOrderFields, ValidationContext, ValidationIssue, ValidationIssueCode,
+    check_execution_details_hash_format, check_min_stablecoins_amount,
 };
 use error_stack::report;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Main intent request struct.
 pub enum IntentRequest {
@@ -23,6 +28,7 @@ pub enum IntentRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 /// Main intent request struct. (sorted by chains number)
 pub enum IntentRequestChainsNum {
@@ -264,9 +270,161 @@ impl IntentRequest {
             }
         }
     }
+
+    /// Validates the whole request, collecting every issue found instead of stopping at the first one.
+    /// Empty result means the request is valid
+    pub fn validate(&self, ctx: ValidationContext) -> Vec<ValidationIssue> {
+        let mut issues = self.get_common_order_fields().validation_issues(&ctx);
+
+        let mut generic_data_issues = vec![];
+        match self {
+            IntentRequest::SingleChainLimitOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                generic_data_issues.extend(check_amount_in(generic_data.amount_in));
+                generic_data_issues.extend(
+                    generic_data
+                        .common_limit_order_data
+                        .validation_issues(generic_data.common_data.amount_out_min),
+                );
+            }
+            IntentRequest::SingleChainDcaOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                generic_data_issues.extend(dca_order_issues(
+                    &generic_data.common_dca_order_data,
+                    generic_data.common_data.deadline,
+                    &ctx,
+                ));
+            }
+            IntentRequest::CrossChainLimitOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                generic_data_issues.extend(check_amount_in(generic_data.amount_in));
+                generic_data_issues.extend(
+                    generic_data
+                        .common_limit_order_data
+                        .validation_issues(generic_data.common_data.amount_out_min),
+                );
+                generic_data_issues.extend(cross_chain_issues(&generic_data.common_data, &ctx));
+            }
+            IntentRequest::CrossChainDcaOrder(intent) => {
+                let generic_data = &intent.generic_data;
+                generic_data_issues.extend(dca_order_issues(
+                    &generic_data.common_dca_order_data,
+                    generic_data.common_data.deadline,
+                    &ctx,
+                ));
+                generic_data_issues.extend(cross_chain_issues(&generic_data.common_data, &ctx));
+            }
+        }
+
+        issues.extend(
+            generic_data_issues
+                .into_iter()
+                .map(|issue| issue.with_field_prefix("genericData")),
+        );
+        issues
+    }
+
+    fn get_common_order_fields(&self) -> CommonOrderFields<'_> {
+        match self {
+            IntentRequest::SingleChainLimitOrder(intent) => single_chain_order_fields(
+                &intent.generic_data.common_data,
+                intent.chain_specific_data.chain_type(),
+            ),
+            IntentRequest::SingleChainDcaOrder(intent) => single_chain_order_fields(
+                &intent.generic_data.common_data,
+                intent.chain_specific_data.chain_type(),
+            ),
+            IntentRequest::CrossChainLimitOrder(intent) => cross_chain_order_fields(
+                &intent.generic_data.common_data,
+                intent.chain_specific_data.chain_type(),
+            ),
+            IntentRequest::CrossChainDcaOrder(intent) => cross_chain_order_fields(
+                &intent.generic_data.common_data,
+                intent.chain_specific_data.chain_type(),
+            ),
+        }
+    }
+}
+
+fn single_chain_order_fields(
+    common_data: &SingleChainGenericData,
+    chain_specific_data_type: ChainType,
+) -> CommonOrderFields<'_> {
+    CommonOrderFields {
+        user: &common_data.user,
+        src_chain: common_data.chain_id,
+        token_in: &common_data.token_in,
+        dest_chain: common_data.chain_id,
+        token_out: &common_data.token_out,
+        amount_out_min: common_data.amount_out_min,
+        destination_address: &common_data.destination_address,
+        extra_transfers: &common_data.extra_transfers,
+        deadline: common_data.deadline,
+        chain_specific_data_type,
+    }
+}
+
+fn cross_chain_order_fields(
+    common_data: &CrossChainGenericData,
+    chain_specific_data_type: ChainType,
+) -> CommonOrderFields<'_> {
+    CommonOrderFields {
+        user: &common_data.user,
+        src_chain: common_data.src_chain_id,
+        token_in: &common_data.token_in,
+        dest_chain: common_data.dest_chain_id,
+        token_out: &common_data.token_out,
+        amount_out_min: common_data.amount_out_min,
+        destination_address: &common_data.destination_address,
+        extra_transfers: &common_data.extra_transfers,
+        deadline: common_data.deadline,
+        chain_specific_data_type,
+    }
+}
+
+fn check_amount_in(amount_in: u128) -> Option<ValidationIssue> {
+    (amount_in == 0).then(|| {
+        ValidationIssue::new(
+            "amountIn",
+            ValidationIssueCode::ZeroAmount,
+            "Zero amount_in",
+        )
+    })
+}
+
+fn dca_order_issues(
+    dca_order_data: &CommonDcaOrderData,
+    deadline: u64,
+    ctx: &ValidationContext,
+) -> Vec<ValidationIssue> {
+    let mut issues = dca_order_data.validation_issues(ctx.min_dca_interval_duration);
+    let last_interval_start = dca_order_data.get_last_interval_start_timestamp();
+    if last_interval_start >= deadline {
+        issues.push(ValidationIssue::new(
+            "totalIntervals",
+            ValidationIssueCode::ScheduleExceedsDeadline,
+            format!(
+                "Last DCA interval starts at {last_interval_start}, not before deadline {deadline}"
+            ),
+        ));
+    }
+    issues
+}
+
+fn cross_chain_issues(
+    common_data: &CrossChainGenericData,
+    ctx: &ValidationContext,
+) -> Vec<ValidationIssue> {
+    check_min_stablecoins_amount(common_data.min_stablecoins_amount, ctx)
+        .into_iter()
+        .chain(check_execution_details_hash_format(
+            &common_data.execution_details_hash,
+        ))
+        .collect()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// EVM-specific data of intent request
 pub struct EVMData {
@@ -277,6 +435,7 @@ pub struct EVMData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 /// Sui-specific data of intent request
 pub struct SuiData {
@@ -285,6 +444,7 @@ pub struct SuiData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct OrderCreatedResponse {
     /// Created intent ID
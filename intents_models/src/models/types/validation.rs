@@ -0,0 +1,601 @@
+use crate::constants::chains::{ChainId, ChainType};
+use crate::error::{Error, ModelResult};
+use crate::models::types::common::TransferDetails;
+use error_stack::report;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default minimum DCA interval duration, in seconds
+pub const DEFAULT_MIN_DCA_INTERVAL_DURATION: u32 = 60;
+/// Default maximum number of extra transfers per order
+pub const DEFAULT_MAX_EXTRA_TRANSFERS: usize = 10;
+
+/// Environment an intent request is validated against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationContext {
+    /// Current Unix timestamp, in SECONDS
+    pub now: u64,
+    /// Minimum allowed DCA interval duration, in seconds
+    pub min_dca_interval_duration: u32,
+    /// Maximum allowed number of extra transfers
+    pub max_extra_transfers: usize,
+    /// Value of a single trade `amount_in` expressed in stablecoin base units, if known.
+    /// Used to check `min_stablecoins_amount` of cross chain orders is reachable
+    pub amount_in_stablecoin_value: Option<u128>,
+}
+
+impl ValidationContext {
+    pub fn new(now: u64) -> Self {
+        Self {
+            now,
+            min_dca_interval_duration: DEFAULT_MIN_DCA_INTERVAL_DURATION,
+            max_extra_transfers: DEFAULT_MAX_EXTRA_TRANSFERS,
+            amount_in_stablecoin_value: None,
+        }
+    }
+
+    /// Context validating against the current system time
+    pub fn current() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("We don't live in the past")
+            .as_secs();
+        Self::new(now)
+    }
+
+    pub fn with_min_dca_interval_duration(mut self, min_dca_interval_duration: u32) -> Self {
+        self.min_dca_interval_duration = min_dca_interval_duration;
+        self
+    }
+
+    pub fn with_max_extra_transfers(mut self, max_extra_transfers: usize) -> Self {
+        self.max_extra_transfers = max_extra_transfers;
+        self
+    }
+
+    pub fn with_amount_in_stablecoin_value(mut self, amount_in_stablecoin_value: u128) -> Self {
+        self.amount_in_stablecoin_value = Some(amount_in_stablecoin_value);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// Machine-readable reason of a validation issue
+pub enum ValidationIssueCode {
+    /// Deadline is not in the future
+    DeadlinePassed,
+    /// Amount must be greater than zero
+    ZeroAmount,
+    /// Token IN and token OUT are the same token on the same chain
+    SameToken,
+    /// Address doesn't match address format of the chain
+    InvalidAddress,
+    /// More extra transfers than allowed
+    TooManyExtraTransfers,
+    /// Chain-specific data doesn't belong to the chain of the order
+    ChainSpecificDataMismatch,
+    /// `take_profit_min_out` is set while it has no effect
+    InvalidTakeProfit,
+    /// Stop loss type and trigger price must be set together, trigger price must be positive
+    InvalidStopLoss,
+    /// DCA interval duration is below allowed minimum
+    IntervalTooShort,
+    /// DCA order must have at least 2 intervals
+    InvalidTotalIntervals,
+    /// Some DCA intervals start after the order deadline
+    ScheduleExceedsDeadline,
+    /// `min_stablecoins_amount` can not be reached with `amount_in`
+    StablecoinsAmountTooHigh,
+    /// Execution details hash is malformed or doesn't match execution details
+    ExecutionDetailsHashMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+/// Single problem found in a request
+pub struct ValidationIssue {
+    /// Path of the invalid field in request JSON, e.g. `genericData.extraTransfers[0].receiver`
+    pub field: String,
+    pub code: ValidationIssueCode,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(
+        field: impl Into<String>,
+        code: ValidationIssueCode,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Prefixes field path, e.g. `takeProfitMinOut` -> `genericData.takeProfitMinOut`
+    pub fn with_field_prefix(mut self, prefix: &str) -> Self {
+        self.field = format!("{prefix}.{}", self.field);
+        self
+    }
+}
+
+/// Turns found issues into `Error::ValidationError` with every issue message attached
+pub fn check_issues(issues: Vec<ValidationIssue>) -> ModelResult<()> {
+    let mut issues = issues.into_iter();
+    let Some(first) = issues.next() else {
+        return Ok(());
+    };
+    let report = issues.fold(
+        report!(Error::ValidationError).attach_printable(first.message),
+        |report, issue| report.attach_printable(issue.message),
+    );
+    Err(report)
+}
+
+/// SHA-256 hash of `execution_details` JSON String (hex format)
+pub fn compute_execution_details_hash(execution_details: &str) -> String {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(execution_details);
+    format!("0x{:x}", hasher.finalize())
+}
+
+/// Checks `execution_details_hash` is the SHA-256 hash of `execution_details`
+pub fn check_execution_details_hash(
+    execution_details: &str,
+    execution_details_hash: &str,
+) -> Option<ValidationIssue> {
+    let computed_hash = compute_execution_details_hash(execution_details);
+    if computed_hash.eq_ignore_ascii_case(execution_details_hash) {
+        None
+    } else {
+        Some(ValidationIssue::new(
+            "genericData.executionDetailsHash",
+            ValidationIssueCode::ExecutionDetailsHashMismatch,
+            format!(
+                "Execution details hash does not match the provided hash. Expected {computed_hash}"
+            ),
+        ))
+    }
+}
+
+/// Issue if `execution_details_hash` is not a hex encoded SHA-256 hash
+pub(crate) fn check_execution_details_hash_format(
+    execution_details_hash: &str,
+) -> Option<ValidationIssue> {
+    let is_valid = execution_details_hash
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    (!is_valid).then(|| {
+        ValidationIssue::new(
+            "executionDetailsHash",
+            ValidationIssueCode::ExecutionDetailsHashMismatch,
+            "Execution details hash must be a hex encoded SHA-256 hash",
+        )
+    })
+}
+
+/// Order fields shared by all intent types, with the chains they live on
+pub(crate) struct CommonOrderFields<'a> {
+    pub user: &'a str,
+    pub src_chain: ChainId,
+    pub token_in: &'a str,
+    pub dest_chain: ChainId,
+    pub token_out: &'a str,
+    pub amount_out_min: u128,
+    pub destination_address: &'a str,
+    pub extra_transfers: &'a Option<Vec<TransferDetails>>,
+    pub deadline: u64,
+    pub chain_specific_data_type: ChainType,
+}
+
+impl CommonOrderFields<'_> {
+    /// Field paths are relative to `genericData`, except `chainSpecificData`
+    pub fn validation_issues(&self, ctx: &ValidationContext) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+
+        if self.deadline <= ctx.now {
+            issues.push(ValidationIssue::new(
+                "deadline",
+                ValidationIssueCode::DeadlinePassed,
+                format!(
+                    "Deadline {} is not after current time {}",
+                    self.deadline, ctx.now
+                ),
+            ));
+        }
+
+        if self.amount_out_min == 0 {
+            issues.push(ValidationIssue::new(
+                "amountOutMin",
+                ValidationIssueCode::ZeroAmount,
+                "Zero amount_out_min",
+            ));
+        }
+
+        let same_token = self.src_chain == self.dest_chain
+            && (self.token_in.eq_ignore_ascii_case(self.token_out)
+                || (self.src_chain.is_native_token(self.token_in)
+                    && self.src_chain.is_native_token(self.token_out)));
+        if same_token {
+            issues.push(ValidationIssue::new(
+                "tokenOut",
+                ValidationIssueCode::SameToken,
+                "Token IN and token OUT must be different",
+            ));
+        }
+
+        let mut check_address = |field: String, chain: ChainId, address: &str, is_token: bool| {
+            let is_valid = if is_token {
+                chain.is_valid_token_address(address)
+            } else {
+                chain.is_valid_address(address)
+            };
+            if !is_valid {
+                issues.push(ValidationIssue::new(
+                    field,
+                    ValidationIssueCode::InvalidAddress,
+                    format!("Invalid {chain} address: {address}"),
+                ));
+            }
+        };
+        check_address("user".to_string(), self.src_chain, self.user, false);
+        check_address("tokenIn".to_string(), self.src_chain, self.token_in, true);
+        check_address(
+            "tokenOut".to_string(),
+            self.dest_chain,
+            self.token_out,
+            true,
+        );
+        check_address(
+            "destinationAddress".to_string(),
+            self.dest_chain,
+            self.destination_address,
+            false,
+        );
+        let extra_transfers = self.extra_transfers.as_deref().unwrap_or_default();
+        for (i, transfer) in extra_transfers.iter().enumerate() {
+            let field = format!("extraTransfers[{i}]");
+            check_address(
+                format!("{field}.token"),
+                self.dest_chain,
+                &transfer.token,
+                true,
+            );
+            check_address(
+                format!("{field}.receiver"),
+                self.dest_chain,
+                &transfer.receiver,
+                false,
+            );
+        }
+
+        for (i, transfer) in extra_transfers.iter().enumerate() {
+            if transfer.amount == 0 {
+                issues.push(ValidationIssue::new(
+                    format!("extraTransfers[{i}].amount"),
+                    ValidationIssueCode::ZeroAmount,
+                    "Zero extra transfer amount",
+                ));
+            }
+        }
+        if extra_transfers.len() > ctx.max_extra_transfers {
+            issues.push(ValidationIssue::new(
+                "extraTransfers",
+                ValidationIssueCode::TooManyExtraTransfers,
+                format!(
+                    "{} extra transfers requested, at most {} allowed",
+                    extra_transfers.len(),
+                    ctx.max_extra_transfers
+                ),
+            ));
+        }
+
+        let mut issues: Vec<_> = issues
+            .into_iter()
+            .map(|issue| issue.with_field_prefix("genericData"))
+            .collect();
+
+        if self.chain_specific_data_type != self.src_chain.to_chain_type() {
+            issues.push(ValidationIssue::new(
+                "chainSpecificData",
+                ValidationIssueCode::ChainSpecificDataMismatch,
+                format!(
+                    "{} chain-specific data provided for {} order",
+                    self.chain_specific_data_type, self.src_chain
+                ),
+            ));
+        }
+
+        issues
+    }
+}
+
+/// Issues of `min_stablecoins_amount` of cross chain orders, with field paths relative to `genericData`
+pub(crate) fn check_min_stablecoins_amount(
+    min_stablecoins_amount: u128,
+    ctx: &ValidationContext,
+) -> Option<ValidationIssue> {
+    if min_stablecoins_amount == 0 {
+        return Some(ValidationIssue::new(
+            "minStablecoinsAmount",
+            ValidationIssueCode::ZeroAmount,
+            "Zero min_stablecoins_amount",
+        ));
+    }
+    match ctx.amount_in_stablecoin_value {
+        Some(amount_in_value) if min_stablecoins_amount > amount_in_value => {
+            Some(ValidationIssue::new(
+                "minStablecoinsAmount",
+                ValidationIssueCode::StablecoinsAmountTooHigh,
+                format!(
+                    "min_stablecoins_amount ({min_stablecoins_amount}) exceeds value of amount_in ({amount_in_value})"
+                ),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::types::common::{
+        CommonDcaOrderData, CommonDcaOrderState, CommonLimitOrderData,
+    };
+    use crate::models::types::cross_chain::{
+        CrossChainChainSpecificData, CrossChainDcaOrderGenericData,
+        CrossChainDcaOrderIntentRequest, CrossChainGenericData, CrossChainSolanaData,
+    };
+    use crate::models::types::single_chain::{
+        SingleChainChainSpecificData, SingleChainGenericData, SingleChainLimitOrderGenericData,
+        SingleChainLimitOrderIntentRequest, SingleChainSolanaData,
+    };
+    use crate::models::types::user_types::{EVMData, IntentRequest};
+
+    const NOW: u64 = 1_700_000_000;
+    const USER: &str = "0x1111111111111111111111111111111111111111";
+    const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+    const WETH_BASE: &str = "0x4200000000000000000000000000000000000006";
+    const SOLANA_USER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const USDC_SOLANA: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn evm_data() -> EVMData {
+        EVMData {
+            nonce: "1".to_string(),
+            signature: "0x".to_string(),
+        }
+    }
+
+    fn single_chain_limit_order() -> SingleChainLimitOrderIntentRequest {
+        SingleChainLimitOrderIntentRequest {
+            generic_data: SingleChainLimitOrderGenericData {
+                common_data: SingleChainGenericData {
+                    user: USER.to_string(),
+                    chain_id: ChainId::Base,
+                    token_in: WETH_BASE.to_string(),
+                    token_out: USDC_BASE.to_string(),
+                    amount_out_min: 3_000_000_000,
+                    destination_address: USER.to_string(),
+                    extra_transfers: None,
+                    deadline: NOW + 3600,
+                },
+                common_limit_order_data: CommonLimitOrderData {
+                    take_profit_min_out: None,
+                    stop_loss_type: None,
+                    stop_loss_trigger_price: None,
+                    stop_loss_triggered: false,
+                },
+                amount_in: 1_000_000_000_000_000_000,
+            },
+            chain_specific_data: SingleChainChainSpecificData::EVM(evm_data()),
+        }
+    }
+
+    fn cross_chain_dca_order() -> CrossChainDcaOrderIntentRequest {
+        CrossChainDcaOrderIntentRequest {
+            generic_data: CrossChainDcaOrderGenericData {
+                common_data: CrossChainGenericData {
+                    user: USER.to_string(),
+                    src_chain_id: ChainId::Base,
+                    token_in: WETH_BASE.to_string(),
+                    min_stablecoins_amount: 300_000_000,
+                    dest_chain_id: ChainId::Solana,
+                    token_out: USDC_SOLANA.to_string(),
+                    amount_out_min: 290_000_000,
+                    destination_address: SOLANA_USER.to_string(),
+                    extra_transfers: None,
+                    deadline: NOW + 10 * 3600,
+                    execution_details_hash: compute_execution_details_hash("{}"),
+                },
+                common_dca_order_data: CommonDcaOrderData {
+                    start_time: NOW as u32,
+                    amount_in_per_interval: 100_000_000_000_000_000,
+                    total_intervals: 10,
+                    interval_duration: 3600,
+                },
+                common_dca_state: CommonDcaOrderState {
+                    total_executed_intervals: 0,
+                    last_executed_interval_index: 0,
+                },
+                last_executed_interval_solver: None,
+            },
+            chain_specific_data: CrossChainChainSpecificData::EVM(evm_data()),
+        }
+    }
+
+    fn fields_and_codes(issues: Vec<ValidationIssue>) -> Vec<(String, ValidationIssueCode)> {
+        issues
+            .into_iter()
+            .map(|issue| (issue.field, issue.code))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_single_chain_limit_order() {
+        let intent = IntentRequest::SingleChainLimitOrder(single_chain_limit_order());
+        assert_eq!(intent.validate(ValidationContext::new(NOW)), vec![]);
+
+        let mut order = single_chain_limit_order();
+        order.generic_data.amount_in = 0;
+        order.generic_data.common_data.deadline = NOW;
+        order.generic_data.common_data.token_out = WETH_BASE.to_lowercase();
+        order.generic_data.common_data.destination_address = SOLANA_USER.to_string();
+        order.generic_data.common_data.extra_transfers = Some(vec![
+            TransferDetails {
+                token: USDC_BASE.to_string(),
+                receiver: USER.to_string(),
+                amount: 0,
+            },
+            TransferDetails {
+                token: USDC_BASE.to_string(),
+                receiver: USER.to_string(),
+                amount: 1,
+            },
+        ]);
+        order
+            .generic_data
+            .common_limit_order_data
+            .take_profit_min_out = Some(1);
+        order.chain_specific_data = SingleChainChainSpecificData::Solana(SingleChainSolanaData {
+            order_pubkey: SOLANA_USER.to_string(),
+            secret_number: 1,
+        });
+        let issues = IntentRequest::SingleChainLimitOrder(order)
+            .validate(ValidationContext::new(NOW).with_max_extra_transfers(1));
+
+        assert_eq!(
+            fields_and_codes(issues),
+            vec![
+                (
+                    "genericData.deadline".to_string(),
+                    ValidationIssueCode::DeadlinePassed
+                ),
+                (
+                    "genericData.tokenOut".to_string(),
+                    ValidationIssueCode::SameToken
+                ),
+                (
+                    "genericData.destinationAddress".to_string(),
+                    ValidationIssueCode::InvalidAddress
+                ),
+                (
+                    "genericData.extraTransfers[0].amount".to_string(),
+                    ValidationIssueCode::ZeroAmount
+                ),
+                (
+                    "genericData.extraTransfers".to_string(),
+                    ValidationIssueCode::TooManyExtraTransfers
+                ),
+                (
+                    "chainSpecificData".to_string(),
+                    ValidationIssueCode::ChainSpecificDataMismatch
+                ),
+                (
+                    "genericData.amountIn".to_string(),
+                    ValidationIssueCode::ZeroAmount
+                ),
+                (
+                    "genericData.takeProfitMinOut".to_string(),
+                    ValidationIssueCode::InvalidTakeProfit
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_cross_chain_dca_order() {
+        let intent = IntentRequest::CrossChainDcaOrder(cross_chain_dca_order());
+        let ctx = ValidationContext::new(NOW).with_amount_in_stablecoin_value(300_000_000);
+        assert_eq!(intent.validate(ctx.clone()), vec![]);
+
+        let mut order = cross_chain_dca_order();
+        order.generic_data.common_data.min_stablecoins_amount = 300_000_001;
+        order.generic_data.common_data.execution_details_hash = "0x1234".to_string();
+        order.generic_data.common_data.deadline = NOW + 9 * 3600;
+        order.generic_data.common_dca_order_data.interval_duration = 30;
+        let issues = IntentRequest::CrossChainDcaOrder(order).validate(ctx);
+
+        assert_eq!(
+            fields_and_codes(issues),
+            vec![
+                (
+                    "genericData.intervalDuration".to_string(),
+                    ValidationIssueCode::IntervalTooShort
+                ),
+                (
+                    "genericData.minStablecoinsAmount".to_string(),
+                    ValidationIssueCode::StablecoinsAmountTooHigh
+                ),
+                (
+                    "genericData.executionDetailsHash".to_string(),
+                    ValidationIssueCode::ExecutionDetailsHashMismatch
+                ),
+            ]
+        );
+
+        // Last interval starts exactly at the deadline
+        let mut order = cross_chain_dca_order();
+        order.generic_data.common_data.deadline = NOW + 9 * 3600;
+        order.chain_specific_data = CrossChainChainSpecificData::Solana(CrossChainSolanaData {
+            order_pubkey: SOLANA_USER.to_string(),
+        });
+        let issues = IntentRequest::CrossChainDcaOrder(order).validate(ValidationContext::new(NOW));
+        assert_eq!(
+            fields_and_codes(issues),
+            vec![
+                (
+                    "chainSpecificData".to_string(),
+                    ValidationIssueCode::ChainSpecificDataMismatch
+                ),
+                (
+                    "genericData.totalIntervals".to_string(),
+                    ValidationIssueCode::ScheduleExceedsDeadline
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_execution_details_hash() {
+        let execution_details = r#"{"destChainId":8453}"#;
+        let hash = compute_execution_details_hash(execution_details);
+        assert_eq!(hash.len(), 66);
+
+        assert!(check_execution_details_hash(execution_details, &hash).is_none());
+        assert!(check_execution_details_hash(execution_details, &hash.to_uppercase()).is_none());
+
+        let issue = check_execution_details_hash("{}", &hash).expect("Hash must not match");
+        assert_eq!(issue.field, "genericData.executionDetailsHash");
+        assert_eq!(
+            issue.code,
+            ValidationIssueCode::ExecutionDetailsHashMismatch
+        );
+
+        let err = check_issues(vec![issue]).expect_err("Issue must fail the check");
+        assert_eq!(err.current_context(), &Error::ValidationError);
+        assert!(check_issues(vec![]).is_ok());
+    }
+
+    #[test]
+    fn test_validation_issue_serialization() {
+        let issue = ValidationIssue::new(
+            "amountIn",
+            ValidationIssueCode::ZeroAmount,
+            "Zero amount_in",
+        )
+        .with_field_prefix("genericData");
+        assert_eq!(
+            serde_json::to_value(&issue).expect("Must serialize"),
+            serde_json::json!({
+                "field": "genericData.amountIn",
+                "code": "ZERO_AMOUNT",
+                "message": "Zero amount_in",
+            })
+        );
+    }
+}
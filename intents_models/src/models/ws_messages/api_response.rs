@@ -8,6 +8,7 @@ use serde_json::{Value, from_value};
 use std::ops::Deref;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ApiResponse {
     pub success: bool,
     pub code: u16,
@@ -165,6 +166,7 @@ mod tests {
                 status: "status_mock".to_string(),
                 pending_auction_results: vec![],
                 unfinished_orders: vec![],
+                negotiated_features: None,
             })
             .ok(),
             error: None,
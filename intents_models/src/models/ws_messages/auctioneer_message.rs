@@ -2,11 +2,13 @@ use crate::models::types::cross_chain::CrossChainSolverSuccessConfirmation;
 use crate::models::types::solver_types::{ExecutionTerms, SolverStartPermission};
 use crate::models::types::user_types::IntentRequest;
 use crate::models::ws_messages::api_response::ApiResponse;
+use crate::models::ws_messages::protocol::ProtocolFeatures;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use std::ops::Deref;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct WsAuctioneerMessage {
     inner: WsAuctioneerMessageInner,
 }
@@ -60,6 +62,7 @@ impl Deref for WsAuctioneerMessage {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum WsAuctioneerMessageInner {
     RegisterResponse(RegisterResponseData),
@@ -70,14 +73,19 @@ pub enum WsAuctioneerMessageInner {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct RegisterResponseData {
     pub solver_id: String,
     pub status: String,
     pub pending_auction_results: Vec<AuctionResult>,
     pub unfinished_orders: Vec<AuctionRequest>,
+    /// Features negotiated with the solver, missing for legacy solvers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negotiated_features: Option<ProtocolFeatures>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AuctionRequest {
     pub intent_id: String,
     pub intent: IntentRequest,
@@ -86,6 +94,7 @@ pub struct AuctionRequest {
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AuctionResult {
     pub intent_id: String,
     #[serde_as(as = "DisplayFromStr")]
@@ -94,6 +103,7 @@ pub struct AuctionResult {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AuctionEndData {
     pub intent_id: String,
     pub solver_success_confirmation: CrossChainSolverSuccessConfirmation,
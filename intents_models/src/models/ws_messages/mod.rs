@@ -1,34 +1,80 @@
 pub mod api_response;
 pub mod auctioneer_message;
 pub mod helpers;
+pub mod protocol;
 pub mod solver_message;
 
 use crate::{
     error::{Error, ModelResult},
-    models::ws_messages::{api_response::ApiResponse, solver_message::WsSolverMessage},
+    models::ws_messages::{
+        api_response::ApiResponse,
+        protocol::{
+            LEGACY_PROTOCOL_VERSION, ProtocolFeatures, WsAuctioneerEnvelope, WsSolverEnvelope,
+            is_envelope,
+        },
+        solver_message::WsSolverMessage,
+    },
 };
 use auctioneer_message::WsAuctioneerMessage;
 use error_stack::{ResultExt, report};
-use serde_json::{from_slice, to_vec};
+use serde_json::{Value, from_slice, from_value, to_vec};
 
+/// Decodes auctioneer message of any supported protocol version
 pub fn handle_ws_auctioneer_request_msg(bytes: &[u8]) -> ModelResult<WsAuctioneerMessage> {
-    match from_slice::<ApiResponse>(bytes) {
+    let value = from_slice::<Value>(bytes).map_err(|err| {
+        report!(Error::SerdeDeserialize(format!(
+            "Failed to deserialize into WsAuctioneerMessage: {err}"
+        )))
+    })?;
+    if is_envelope(&value) {
+        return decode_envelope::<WsAuctioneerEnvelope>(value)?.into_message();
+    }
+    match from_value::<ApiResponse>(value) {
         Ok(msg) => msg.try_into(),
         Err(err) => Err(report!(Error::SerdeDeserialize(format!(
             "Failed to deserialize into WsAuctioneerMessage: {err}"
         )))),
     }
 }
+
+/// Serializes solver message with legacy protocol
 pub fn serialize_solver_response_message(msg: WsSolverMessage) -> ModelResult<Vec<u8>> {
-    to_vec(&msg)
-        .change_context(Error::SerdeSerialize(
-            "Failed to serialize WsSolverMessage:".to_string(),
-        ))
+    encode_ws_solver_message(&msg, LEGACY_PROTOCOL_VERSION)
         .attach_printable_lazy(|| format!("Failed to serialize message: {msg:?}"))
 }
 
+/// Serializes solver message with protocol `version`
+pub fn encode_ws_solver_message(msg: &WsSolverMessage, version: u32) -> ModelResult<Vec<u8>> {
+    let bytes = if version == LEGACY_PROTOCOL_VERSION {
+        match msg {
+            // Legacy auctioneers expect registration without data
+            WsSolverMessage::Register(_) => to_vec("Register"),
+            _ => to_vec(msg),
+        }
+    } else {
+        to_vec(&WsSolverEnvelope::from_message(msg, version)?)
+    };
+    bytes.change_context(Error::SerdeSerialize(
+        "Failed to serialize WsSolverMessage:".to_string(),
+    ))
+}
+
+/// Decodes solver message of any supported protocol version
 pub fn handle_ws_solver_request_msg(bytes: &[u8]) -> ModelResult<WsSolverMessage> {
-    match from_slice::<WsSolverMessage>(bytes) {
+    let value = from_slice::<Value>(bytes).map_err(|err| {
+        report!(Error::SerdeDeserialize(
+            "Failed to deserialize into WsSolverMessage".to_string()
+        ))
+        .attach_printable(format!("Failed to deserialize message: {err}"))
+    })?;
+    if is_envelope(&value) {
+        return decode_envelope::<WsSolverEnvelope>(value)?.into_message();
+    }
+    // Legacy solvers register without data
+    if value.as_str() == Some("Register") {
+        return Ok(WsSolverMessage::Register(ProtocolFeatures::legacy()));
+    }
+    match from_value::<WsSolverMessage>(value) {
         Ok(msg) => Ok(msg),
         Err(err) => Err(report!(Error::SerdeDeserialize(
             "Failed to deserialize into WsSolverMessage".to_string()
@@ -37,13 +83,35 @@ pub fn handle_ws_solver_request_msg(bytes: &[u8]) -> ModelResult<WsSolverMessage
     }
 }
 
+/// Serializes auctioneer message with legacy protocol
 pub fn serialize_auctioneer_response_message(msg: WsAuctioneerMessage) -> ModelResult<Vec<u8>> {
-    let api_response = ApiResponse::from(msg.clone());
-    to_vec(&api_response).change_context(Error::SerdeSerialize(
+    encode_ws_auctioneer_message(&msg, LEGACY_PROTOCOL_VERSION)
+}
+
+/// Serializes auctioneer message with protocol `version`
+pub fn encode_ws_auctioneer_message(
+    msg: &WsAuctioneerMessage,
+    version: u32,
+) -> ModelResult<Vec<u8>> {
+    let bytes = if version == LEGACY_PROTOCOL_VERSION {
+        to_vec(&ApiResponse::from(msg.clone()))
+    } else {
+        to_vec(&WsAuctioneerEnvelope::from_message(msg, version)?)
+    };
+    bytes.change_context(Error::SerdeSerialize(
         "Failed to serialize WsAuctioneerMessage".to_string(),
     ))
 }
 
+fn decode_envelope<T: serde::de::DeserializeOwned>(value: Value) -> ModelResult<T> {
+    from_value(value).map_err(|err| {
+        report!(Error::SerdeDeserialize(
+            "Failed to deserialize message envelope".to_string()
+        ))
+        .attach_printable(format!("Failed to deserialize message: {err}"))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::models::ws_messages::auctioneer_message::{
@@ -63,6 +131,7 @@ mod tests {
                     status: "status_mock".to_string(),
                     pending_auction_results: vec![],
                     unfinished_orders: vec![],
+                    negotiated_features: None,
                 })
                 .unwrap(),
             ),
@@ -0,0 +1,609 @@
+//! Versioned solver WebSocket protocol
+//!
+//! Messages are sent as `{ "version", "kind", "payload" }` envelopes. `kind` selects the payload
+//! type, so adding fields to a payload can't make one message decode as another.
+//! Version 0 is the legacy protocol without envelope: bare `WsSolverMessage` from the solver
+//! and `ApiResponse` with untagged data from the auctioneer.
+
+use crate::constants::chains::ChainId;
+use crate::error::{Error, ModelResult};
+use crate::models::types::order::OrderType;
+use crate::models::ws_messages::api_response::ApiResponse;
+use crate::models::ws_messages::auctioneer_message::{
+    WsAuctioneerMessage, WsAuctioneerMessageInner,
+};
+use crate::models::ws_messages::solver_message::WsSolverMessage;
+use error_stack::{ResultExt, report};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value, to_value};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// Legacy protocol without envelope
+pub const LEGACY_PROTOCOL_VERSION: u32 = 0;
+/// Latest supported protocol version
+pub const WS_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SolverMessageKind {
+    Register,
+    Participate,
+    SolverDstChain,
+    GetStartPermissions,
+    SingleChainOrderFulfilled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AuctioneerMessageKind {
+    RegisterResponse,
+    AuctionRequest,
+    AuctionResult,
+    AuctionEnd,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+/// Versioned message envelope
+pub struct WsEnvelope<K> {
+    /// Protocol version the message is encoded with
+    pub version: u32,
+    pub kind: K,
+    pub payload: Value,
+}
+
+pub type WsSolverEnvelope = WsEnvelope<SolverMessageKind>;
+pub type WsAuctioneerEnvelope = WsEnvelope<AuctioneerMessageKind>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+/// Optional solver features
+pub enum SolverCapability {
+    /// Solver executes intents with extra transfers
+    ExtraTransfers,
+    /// Solver executes stop loss limit orders
+    StopLoss,
+    /// Solver accepts pending auction results and unfinished orders on reconnection
+    SessionResume,
+    /// Capability introduced in a later protocol version
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+/// Protocol features supported by one side of the connection, or negotiated by both
+pub struct ProtocolFeatures {
+    /// Highest supported protocol version
+    pub protocol_version: u32,
+    pub order_types: Vec<OrderType>,
+    pub chains: Vec<ChainId>,
+    #[serde(default)]
+    pub capabilities: Vec<SolverCapability>,
+}
+
+impl ProtocolFeatures {
+    pub fn new(order_types: Vec<OrderType>, chains: Vec<ChainId>) -> Self {
+        Self {
+            protocol_version: WS_PROTOCOL_VERSION,
+            order_types,
+            chains,
+            capabilities: vec![],
+        }
+    }
+
+    pub fn with_capabilities(mut self, capabilities: Vec<SolverCapability>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Features of legacy solvers, which register without any data:
+    /// all order types and chains, no optional capabilities
+    pub fn legacy() -> Self {
+        Self {
+            protocol_version: LEGACY_PROTOCOL_VERSION,
+            order_types: OrderType::iter().collect(),
+            chains: ChainId::iter().collect(),
+            capabilities: vec![],
+        }
+    }
+
+    /// Features supported by both `self` (auctioneer) and `solver`: the lowest protocol version,
+    /// common order types, chains and capabilities in `self` order
+    pub fn negotiate(&self, solver: &ProtocolFeatures) -> ModelResult<ProtocolFeatures> {
+        let order_types: Vec<_> = self
+            .order_types
+            .iter()
+            .filter(|order_type| solver.order_types.contains(order_type))
+            .copied()
+            .collect();
+        if order_types.is_empty() {
+            return Err(report!(Error::ValidationError)
+                .attach_printable("Solver supports none of the auctioneer order types"));
+        }
+        let chains: Vec<_> = self
+            .chains
+            .iter()
+            .filter(|chain| solver.chains.contains(chain))
+            .copied()
+            .collect();
+        if chains.is_empty() {
+            return Err(report!(Error::ValidationError)
+                .attach_printable("Solver supports none of the auctioneer chains"));
+        }
+        let capabilities = self
+            .capabilities
+            .iter()
+            .filter(|capability| {
+                **capability != SolverCapability::Unknown
+                    && solver.capabilities.contains(capability)
+            })
+            .copied()
+            .collect();
+
+        Ok(ProtocolFeatures {
+            protocol_version: self.protocol_version.min(solver.protocol_version),
+            order_types,
+            chains,
+            capabilities,
+        })
+    }
+
+    pub fn supports_order_type(&self, order_type: OrderType) -> bool {
+        self.order_types.contains(&order_type)
+    }
+
+    pub fn supports_chain(&self, chain: ChainId) -> bool {
+        self.chains.contains(&chain)
+    }
+
+    pub fn has_capability(&self, capability: SolverCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+impl fmt::Display for SolverMessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Display for AuctioneerMessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl WsSolverMessage {
+    pub fn kind(&self) -> SolverMessageKind {
+        match self {
+            WsSolverMessage::Register(_) => SolverMessageKind::Register,
+            WsSolverMessage::Participate(_) => SolverMessageKind::Participate,
+            WsSolverMessage::SolverDstChain(_) => SolverMessageKind::SolverDstChain,
+            WsSolverMessage::GetStartPermissions(..) => SolverMessageKind::GetStartPermissions,
+            WsSolverMessage::SingleChainOrderFulfilled(_) => {
+                SolverMessageKind::SingleChainOrderFulfilled
+            }
+        }
+    }
+}
+
+impl WsAuctioneerMessageInner {
+    pub fn kind(&self) -> AuctioneerMessageKind {
+        match self {
+            WsAuctioneerMessageInner::RegisterResponse(_) => {
+                AuctioneerMessageKind::RegisterResponse
+            }
+            WsAuctioneerMessageInner::AuctionRequest(_) => AuctioneerMessageKind::AuctionRequest,
+            WsAuctioneerMessageInner::AuctionResult(_) => AuctioneerMessageKind::AuctionResult,
+            WsAuctioneerMessageInner::AuctionEnd(_) => AuctioneerMessageKind::AuctionEnd,
+            WsAuctioneerMessageInner::ErrorMessage(_) => AuctioneerMessageKind::Error,
+        }
+    }
+}
+
+impl WsSolverEnvelope {
+    pub fn from_message(message: &WsSolverMessage, version: u32) -> ModelResult<Self> {
+        check_envelope_version(version)?;
+        let payload = match message {
+            WsSolverMessage::Register(features) => to_value(features),
+            WsSolverMessage::Participate(participate) => to_value(participate),
+            WsSolverMessage::SolverDstChain(data) => to_value(data),
+            WsSolverMessage::GetStartPermissions(intent_id, order_type) => {
+                to_value((intent_id, order_type))
+            }
+            WsSolverMessage::SingleChainOrderFulfilled(details) => to_value(details),
+        }
+        .change_context(Error::SerdeSerialize(format!(
+            "Failed to serialize {} payload",
+            message.kind()
+        )))?;
+        Ok(Self {
+            version,
+            kind: message.kind(),
+            payload,
+        })
+    }
+
+    pub fn into_message(self) -> ModelResult<WsSolverMessage> {
+        // Registration of a newer solver must be decodable to negotiate a common version
+        if self.kind != SolverMessageKind::Register {
+            check_envelope_version(self.version)?;
+        }
+        let kind = self.kind;
+        let payload = self.payload;
+        let message = match kind {
+            SolverMessageKind::Register => {
+                WsSolverMessage::Register(decode_payload(kind, payload)?)
+            }
+            SolverMessageKind::Participate => {
+                WsSolverMessage::Participate(decode_payload(kind, payload)?)
+            }
+            SolverMessageKind::SolverDstChain => {
+                WsSolverMessage::SolverDstChain(decode_payload(kind, payload)?)
+            }
+            SolverMessageKind::GetStartPermissions => {
+                let (intent_id, order_type) = decode_payload(kind, payload)?;
+                WsSolverMessage::GetStartPermissions(intent_id, order_type)
+            }
+            SolverMessageKind::SingleChainOrderFulfilled => {
+                WsSolverMessage::SingleChainOrderFulfilled(decode_payload(kind, payload)?)
+            }
+        };
+        Ok(message)
+    }
+}
+
+impl WsAuctioneerEnvelope {
+    pub fn from_message(message: &WsAuctioneerMessage, version: u32) -> ModelResult<Self> {
+        check_envelope_version(version)?;
+        let payload = match &**message {
+            WsAuctioneerMessageInner::RegisterResponse(data) => to_value(data),
+            WsAuctioneerMessageInner::AuctionRequest(request) => to_value(request),
+            WsAuctioneerMessageInner::AuctionResult(result) => to_value(result),
+            WsAuctioneerMessageInner::AuctionEnd(data) => to_value(data),
+            WsAuctioneerMessageInner::ErrorMessage(api_response) => to_value(api_response),
+        }
+        .change_context(Error::SerdeSerialize(format!(
+            "Failed to serialize {} payload",
+            message.kind()
+        )))?;
+        Ok(Self {
+            version,
+            kind: message.kind(),
+            payload,
+        })
+    }
+
+    pub fn into_message(self) -> ModelResult<WsAuctioneerMessage> {
+        check_envelope_version(self.version)?;
+        let kind = self.kind;
+        let payload = self.payload;
+        let inner = match kind {
+            AuctioneerMessageKind::RegisterResponse => {
+                WsAuctioneerMessageInner::RegisterResponse(decode_payload(kind, payload)?)
+            }
+            AuctioneerMessageKind::AuctionRequest => {
+                WsAuctioneerMessageInner::AuctionRequest(decode_payload(kind, payload)?)
+            }
+            AuctioneerMessageKind::AuctionResult => {
+                WsAuctioneerMessageInner::AuctionResult(decode_payload(kind, payload)?)
+            }
+            AuctioneerMessageKind::AuctionEnd => {
+                WsAuctioneerMessageInner::AuctionEnd(decode_payload(kind, payload)?)
+            }
+            AuctioneerMessageKind::Error => WsAuctioneerMessageInner::ErrorMessage(
+                decode_payload::<ApiResponse>(kind, payload)?,
+            ),
+        };
+        Ok(WsAuctioneerMessage::new(inner))
+    }
+}
+
+/// Whether `value` is a versioned envelope rather than a legacy message
+pub(crate) fn is_envelope(value: &Value) -> bool {
+    value.get("version").is_some() && value.get("kind").is_some()
+}
+
+fn check_envelope_version(version: u32) -> ModelResult<()> {
+    if version == LEGACY_PROTOCOL_VERSION || version > WS_PROTOCOL_VERSION {
+        return Err(report!(Error::ValidationError)
+            .attach_printable(format!("Unsupported protocol version: {version}")));
+    }
+    Ok(())
+}
+
+fn decode_payload<T: DeserializeOwned>(kind: impl fmt::Display, payload: Value) -> ModelResult<T> {
+    from_value(payload).map_err(|err| {
+        report!(Error::SerdeDeserialize(format!(
+            "Failed to deserialize {kind} payload"
+        )))
+        .attach_printable(format!("Failed to deserialize payload: {err}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ws_messages::auctioneer_message::RegisterResponseData;
+    use crate::models::ws_messages::solver_message::ParticipateAuction;
+    use crate::models::ws_messages::{
+        encode_ws_auctioneer_message, encode_ws_solver_message, handle_ws_auctioneer_request_msg,
+        handle_ws_solver_request_msg,
+    };
+
+    // Messages of protocol version 0, as sent by solvers and auctioneer before envelopes
+    const LEGACY_REGISTER: &str = r#""Register""#;
+    const LEGACY_PARTICIPATE: &str = r#"{"Participate":{"Single":{"intent_id":"intent","order_type":"SingleChainLimitOrder","solver_address":"0x2c7536E3605D9C16a7a3D7b1898e529396a65c23","amount_out":3000000000}}}"#;
+    const LEGACY_GET_START_PERMISSIONS: &str =
+        r#"{"GetStartPermissions":["intent","SingleChainDCAOrder"]}"#;
+    const LEGACY_REGISTER_RESPONSE: &str = r#"{"success":true,"code":200,"data":{"solver_id":"solver","status":"registered","pending_auction_results":[{"intent_id":"intent","amount_out":"3000000000","solver_start_permission":null}],"unfinished_orders":[]}}"#;
+    const LEGACY_ERROR: &str = r#"{"success":false,"code":401,"error":"Unauthorized"}"#;
+
+    fn solver_features() -> ProtocolFeatures {
+        ProtocolFeatures::new(
+            vec![
+                OrderType::SingleChainLimitOrder,
+                OrderType::CrossChainLimitOrder,
+            ],
+            vec![ChainId::Base, ChainId::Solana],
+        )
+        .with_capabilities(vec![
+            SolverCapability::SessionResume,
+            SolverCapability::StopLoss,
+        ])
+    }
+
+    fn auctioneer_features() -> ProtocolFeatures {
+        ProtocolFeatures::new(
+            vec![
+                OrderType::CrossChainLimitOrder,
+                OrderType::CrossChainDCAOrder,
+                OrderType::SingleChainLimitOrder,
+            ],
+            vec![ChainId::Ethereum, ChainId::Base, ChainId::Solana],
+        )
+        .with_capabilities(vec![
+            SolverCapability::ExtraTransfers,
+            SolverCapability::StopLoss,
+            SolverCapability::SessionResume,
+        ])
+    }
+
+    #[test]
+    fn test_decode_legacy_solver_messages() {
+        let message =
+            handle_ws_solver_request_msg(LEGACY_REGISTER.as_bytes()).expect("Must decode");
+        let WsSolverMessage::Register(features) = message else {
+            panic!("Expected Register, got {message:?}");
+        };
+        assert_eq!(features, ProtocolFeatures::legacy());
+        assert_eq!(features.protocol_version, LEGACY_PROTOCOL_VERSION);
+        assert!(features.supports_chain(ChainId::Sui));
+
+        let message =
+            handle_ws_solver_request_msg(LEGACY_PARTICIPATE.as_bytes()).expect("Must decode");
+        assert!(matches!(
+            message,
+            WsSolverMessage::Participate(ParticipateAuction::Single(ref participate))
+                if participate.amount_out == 3_000_000_000
+        ));
+
+        let message = handle_ws_solver_request_msg(LEGACY_GET_START_PERMISSIONS.as_bytes())
+            .expect("Must decode");
+        assert!(matches!(
+            message,
+            WsSolverMessage::GetStartPermissions(ref intent_id, OrderType::SingleChainDCAOrder)
+                if intent_id == "intent"
+        ));
+
+        // Legacy encoding is unchanged
+        let bytes = encode_ws_solver_message(
+            &WsSolverMessage::Register(solver_features()),
+            LEGACY_PROTOCOL_VERSION,
+        )
+        .expect("Must encode");
+        assert_eq!(bytes, LEGACY_REGISTER.as_bytes());
+        let bytes =
+            encode_ws_solver_message(&message, LEGACY_PROTOCOL_VERSION).expect("Must encode");
+        assert_eq!(bytes, LEGACY_GET_START_PERMISSIONS.as_bytes());
+    }
+
+    #[test]
+    fn test_decode_legacy_auctioneer_messages() {
+        let message = handle_ws_auctioneer_request_msg(LEGACY_REGISTER_RESPONSE.as_bytes())
+            .expect("Must decode");
+        let WsAuctioneerMessageInner::RegisterResponse(data) = message.inner() else {
+            panic!("Expected RegisterResponse");
+        };
+        assert_eq!(data.pending_auction_results.len(), 1);
+        assert_eq!(data.negotiated_features, None);
+
+        let message =
+            handle_ws_auctioneer_request_msg(LEGACY_ERROR.as_bytes()).expect("Must decode");
+        assert!(matches!(
+            *message,
+            WsAuctioneerMessageInner::ErrorMessage(ApiResponse { code: 401, .. })
+        ));
+    }
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let register = WsSolverMessage::Register(solver_features());
+        let bytes = encode_ws_solver_message(&register, WS_PROTOCOL_VERSION).expect("Must encode");
+        let envelope: Value = serde_json::from_slice(&bytes).expect("Valid JSON");
+        assert_eq!(envelope["version"], serde_json::json!(WS_PROTOCOL_VERSION));
+        assert_eq!(envelope["kind"], "Register");
+        assert_eq!(
+            envelope["payload"]["chains"],
+            serde_json::json!([8453, 7565164])
+        );
+        let WsSolverMessage::Register(features) =
+            handle_ws_solver_request_msg(&bytes).expect("Must decode")
+        else {
+            panic!("Expected Register");
+        };
+        assert_eq!(features, solver_features());
+
+        let message = handle_ws_solver_request_msg(LEGACY_GET_START_PERMISSIONS.as_bytes())
+            .expect("Must decode");
+        let bytes = encode_ws_solver_message(&message, WS_PROTOCOL_VERSION).expect("Must encode");
+        assert!(matches!(
+            handle_ws_solver_request_msg(&bytes).expect("Must decode"),
+            WsSolverMessage::GetStartPermissions(_, OrderType::SingleChainDCAOrder)
+        ));
+
+        let register_response = WsAuctioneerMessage::register_response(RegisterResponseData {
+            solver_id: "solver".to_string(),
+            status: "registered".to_string(),
+            pending_auction_results: vec![],
+            unfinished_orders: vec![],
+            negotiated_features: Some(
+                auctioneer_features()
+                    .negotiate(&solver_features())
+                    .expect("Must negotiate"),
+            ),
+        });
+        let bytes = encode_ws_auctioneer_message(&register_response, WS_PROTOCOL_VERSION)
+            .expect("Must encode");
+        let message = handle_ws_auctioneer_request_msg(&bytes).expect("Must decode");
+        let WsAuctioneerMessageInner::RegisterResponse(data) = message.inner() else {
+            panic!("Expected RegisterResponse");
+        };
+        assert!(data.negotiated_features.is_some());
+
+        let error = WsAuctioneerMessage::error(ApiResponse::bad_request("Bad request"));
+        let bytes = encode_ws_auctioneer_message(&error, WS_PROTOCOL_VERSION).expect("Must encode");
+        assert!(matches!(
+            *handle_ws_auctioneer_request_msg(&bytes).expect("Must decode"),
+            WsAuctioneerMessageInner::ErrorMessage(ApiResponse { code: 400, .. })
+        ));
+    }
+
+    #[test]
+    fn test_envelope_kind_selects_payload() {
+        // Register response payload can't be decoded as another message
+        let envelope = serde_json::json!({
+            "version": WS_PROTOCOL_VERSION,
+            "kind": "AuctionResult",
+            "payload": {
+                "solver_id": "solver",
+                "status": "registered",
+                "pending_auction_results": [],
+                "unfinished_orders": [],
+            },
+        });
+        assert!(handle_ws_auctioneer_request_msg(envelope.to_string().as_bytes()).is_err());
+
+        // Unknown payload fields are ignored
+        let envelope = serde_json::json!({
+            "version": WS_PROTOCOL_VERSION,
+            "kind": "AuctionResult",
+            "payload": {
+                "intent_id": "intent",
+                "amount_out": "100",
+                "solver_start_permission": null,
+                "new_field": true,
+            },
+        });
+        assert!(matches!(
+            *handle_ws_auctioneer_request_msg(envelope.to_string().as_bytes())
+                .expect("Must decode"),
+            WsAuctioneerMessageInner::AuctionResult(_)
+        ));
+    }
+
+    #[test]
+    fn test_envelope_versions() {
+        let envelope = |version: u32, kind: &str, payload: Value| {
+            serde_json::json!({ "version": version, "kind": kind, "payload": payload }).to_string()
+        };
+
+        // Newer solver registers with its own version and unknown capabilities
+        let register = envelope(
+            WS_PROTOCOL_VERSION + 1,
+            "Register",
+            serde_json::json!({
+                "protocol_version": WS_PROTOCOL_VERSION + 1,
+                "order_types": ["SingleChainLimitOrder"],
+                "chains": [8453],
+                "capabilities": ["StopLoss", "SomeFutureCapability"],
+            }),
+        );
+        let WsSolverMessage::Register(features) =
+            handle_ws_solver_request_msg(register.as_bytes()).expect("Must decode")
+        else {
+            panic!("Expected Register");
+        };
+        assert_eq!(
+            features.capabilities,
+            [SolverCapability::StopLoss, SolverCapability::Unknown]
+        );
+        let negotiated = auctioneer_features()
+            .negotiate(&features)
+            .expect("Must negotiate");
+        assert_eq!(negotiated.protocol_version, WS_PROTOCOL_VERSION);
+        assert_eq!(negotiated.capabilities, [SolverCapability::StopLoss]);
+
+        // Other messages must use a supported version
+        let participate = envelope(
+            WS_PROTOCOL_VERSION + 1,
+            "GetStartPermissions",
+            serde_json::json!(["intent", "SingleChainLimitOrder"]),
+        );
+        assert!(handle_ws_solver_request_msg(participate.as_bytes()).is_err());
+        let participate = envelope(
+            LEGACY_PROTOCOL_VERSION,
+            "GetStartPermissions",
+            serde_json::json!(["intent", "SingleChainLimitOrder"]),
+        );
+        assert!(handle_ws_solver_request_msg(participate.as_bytes()).is_err());
+        assert!(
+            encode_ws_solver_message(
+                &WsSolverMessage::GetStartPermissions(
+                    "intent".to_string(),
+                    OrderType::SingleChainLimitOrder
+                ),
+                WS_PROTOCOL_VERSION + 1
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let negotiated = auctioneer_features()
+            .negotiate(&solver_features())
+            .expect("Must negotiate");
+        assert_eq!(
+            negotiated,
+            ProtocolFeatures {
+                protocol_version: WS_PROTOCOL_VERSION,
+                order_types: vec![
+                    OrderType::CrossChainLimitOrder,
+                    OrderType::SingleChainLimitOrder
+                ],
+                chains: vec![ChainId::Base, ChainId::Solana],
+                capabilities: vec![SolverCapability::StopLoss, SolverCapability::SessionResume],
+            }
+        );
+
+        // Legacy solver gets legacy protocol and everything auctioneer supports except capabilities
+        let negotiated = auctioneer_features()
+            .negotiate(&ProtocolFeatures::legacy())
+            .expect("Must negotiate");
+        assert_eq!(negotiated.protocol_version, LEGACY_PROTOCOL_VERSION);
+        assert_eq!(negotiated.order_types, auctioneer_features().order_types);
+        assert!(negotiated.capabilities.is_empty());
+
+        let solver =
+            ProtocolFeatures::new(vec![OrderType::SingleChainDCAOrder], vec![ChainId::Base]);
+        assert!(auctioneer_features().negotiate(&solver).is_err());
+        let solver =
+            ProtocolFeatures::new(vec![OrderType::SingleChainLimitOrder], vec![ChainId::Sui]);
+        assert!(auctioneer_features().negotiate(&solver).is_err());
+    }
+}
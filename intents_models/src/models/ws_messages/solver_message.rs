@@ -1,11 +1,13 @@
 use crate::models::types::order::OrderTypeFulfillmentData;
 use crate::models::types::{order::OrderType, single_chain::SingleChainSolverExecutionDetailsEnum};
+use crate::models::ws_messages::protocol::ProtocolFeatures;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum WsSolverMessage {
-    /// Register solver
-    Register,
+    /// Register solver with supported protocol features
+    Register(ProtocolFeatures),
     /// Participate in auction (bid)
     Participate(ParticipateAuction),
     /// Request confirmation of cross chain order fulfillment
@@ -17,12 +19,14 @@ pub enum WsSolverMessage {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ParticipateAuction {
     Single(SingleChainAuctionParticipate),
     Multi(CrossChainAuctionParticipate),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SingleChainAuctionParticipate {
     pub intent_id: String,
     pub order_type: OrderType,
@@ -31,6 +35,7 @@ pub struct SingleChainAuctionParticipate {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CrossChainAuctionParticipate {
     pub intent_id: String,
     pub order_type: OrderType,
@@ -42,6 +47,7 @@ pub struct CrossChainAuctionParticipate {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SolverDstChainData {
     pub intent_id: String,
     /// Fulfillment data for a specific order type
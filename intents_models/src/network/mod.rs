@@ -2,6 +2,7 @@ pub mod client_rate_limit;
 pub mod http;
 pub mod nats;
 pub mod rate_limit;
+pub mod solver_client;
 
 use std::{num::NonZeroU32, time::Duration};
 
@@ -1,7 +1,7 @@
 use crate::error::{Error, ModelResult};
 use async_nats::{Client, ConnectOptions};
 use error_stack::ResultExt;
-use futures::stream::StreamExt;
+use futures::stream::{Stream, StreamExt};
 use serde::{Serialize, de::DeserializeOwned};
 use std::future::Future;
 use std::marker::PhantomData;
@@ -81,6 +81,69 @@ where
         ))
     }
 
+    /// Same connection and limits, for a different pair of message types
+    pub fn retyped<NewOut, NewIn>(&self) -> NatsManager<NewOut, NewIn> {
+        NatsManager {
+            client: self.client.clone(),
+            max_request_body_size: self.max_request_body_size,
+            max_json_depth: self.max_json_depth,
+            chunk_processing_interval: self.chunk_processing_interval,
+            max_concurrency: self.max_concurrency,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Fire-and-forget publish, without waiting for a reply
+    pub async fn publish(&self, subject: &'static str, msg: MsgOut) -> ModelResult<()> {
+        let data = serde_json::to_vec(&msg).change_context(Error::SerdeSerialize(
+            "Failed to serialize nats msg".to_string(),
+        ))?;
+
+        self.client
+            .publish(subject, data.into())
+            .await
+            .change_context(Error::NatsError("Failed to publish nats msg".to_string()))
+    }
+
+    /// Stream of messages published to `subject`. Messages that fail to parse are logged and skipped.
+    pub async fn subscribe(
+        &self,
+        subject: &'static str,
+    ) -> ModelResult<impl Stream<Item = MsgIn> + Send + 'static>
+    where
+        MsgIn: Send + 'static,
+    {
+        let subscriber = self
+            .client
+            .subscribe(subject)
+            .await
+            .change_context(Error::NatsError(
+                "Failed to subscribe to nats subject".to_string(),
+            ))?;
+
+        let max_request_body_size = self.max_request_body_size;
+        let max_json_depth = self.max_json_depth;
+        let chunk_processing_interval = self.chunk_processing_interval;
+
+        Ok(subscriber.filter_map(move |message| {
+            let parsed = validate_and_parse_json(
+                &message.payload,
+                max_request_body_size,
+                max_json_depth,
+                chunk_processing_interval,
+            );
+            async move {
+                match parsed {
+                    Ok(msg) => Some(msg),
+                    Err(e) => {
+                        tracing::error!("Failed to parse message: {}", e);
+                        None
+                    }
+                }
+            }
+        }))
+    }
+
     pub async fn subscribe_and_process<F, Fut>(
         self,
         subject: &'static str,
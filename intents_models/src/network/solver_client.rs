@@ -0,0 +1,874 @@
+//! Reference solver client for the auctioneer WebSocket
+//!
+//! `SolverClient::connect` registers the solver and spawns a task owning the connection.
+//! The task reconnects with exponential backoff, registers again and recovers pending
+//! auction results and unfinished orders from the register response.
+//! Messages sent while disconnected are delivered after re-registration.
+
+use crate::error::{Error, ModelResult};
+use crate::models::types::order::OrderType;
+use crate::models::types::single_chain::SingleChainSolverExecutionDetailsEnum;
+use crate::models::ws_messages::api_response::ApiResponse;
+use crate::models::ws_messages::auctioneer_message::{
+    AuctionEndData, AuctionRequest, AuctionResult, RegisterResponseData, WsAuctioneerMessageInner,
+};
+use crate::models::ws_messages::protocol::{ProtocolFeatures, WS_PROTOCOL_VERSION};
+use crate::models::ws_messages::solver_message::{
+    ParticipateAuction, SolverDstChainData, WsSolverMessage,
+};
+use crate::models::ws_messages::{encode_ws_solver_message, handle_ws_auctioneer_request_msg};
+use error_stack::{ResultExt, report};
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+const DEFAULT_REGISTER_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct SolverClientConfig {
+    /// Auctioneer WebSocket URL
+    pub url: String,
+    /// Extra handshake headers, e.g. solver credentials
+    pub headers: Vec<(String, String)>,
+    /// Features the solver registers with
+    pub features: ProtocolFeatures,
+    /// Protocol version of the register message, `LEGACY_PROTOCOL_VERSION` for legacy auctioneers
+    pub register_version: u32,
+    /// Delay before the first reconnection attempt, doubled after every failed attempt
+    pub reconnect_delay: Duration,
+    pub max_reconnect_delay: Duration,
+    /// Time to wait for register response
+    pub register_timeout: Duration,
+    /// Capacity of outgoing messages queue and of `auction_requests` and `errors` streams.
+    /// Messages to a full stream are dropped
+    pub channel_capacity: usize,
+}
+
+impl SolverClientConfig {
+    pub fn new(url: impl Into<String>, features: ProtocolFeatures) -> Self {
+        Self {
+            url: url.into(),
+            headers: vec![],
+            features,
+            register_version: WS_PROTOCOL_VERSION,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            max_reconnect_delay: DEFAULT_MAX_RECONNECT_DELAY,
+            register_timeout: DEFAULT_REGISTER_TIMEOUT,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_register_version(mut self, register_version: u32) -> Self {
+        self.register_version = register_version;
+        self
+    }
+
+    pub fn with_reconnect_delay(mut self, reconnect_delay: Duration, max_delay: Duration) -> Self {
+        self.reconnect_delay = reconnect_delay;
+        self.max_reconnect_delay = max_delay;
+        self
+    }
+
+    pub fn with_register_timeout(mut self, register_timeout: Duration) -> Self {
+        self.register_timeout = register_timeout;
+        self
+    }
+
+    pub fn with_channel_capacity(mut self, channel_capacity: usize) -> Self {
+        self.channel_capacity = channel_capacity;
+        self
+    }
+}
+
+/// Typed streams of auctioneer messages.
+///
+/// After every (re-)registration pending auction results and unfinished orders are delivered
+/// to `auction_results` and `auction_requests` again, so they may contain duplicates.
+/// `auction_requests` and `errors` are bounded by `channel_capacity`: while one of them is full,
+/// new messages for it are dropped with a warning. `auction_results` and `auction_ends` are
+/// unbounded, as results may carry start permissions which must never be lost
+pub struct SolverStreams {
+    pub auction_requests: mpsc::Receiver<AuctionRequest>,
+    pub auction_results: mpsc::UnboundedReceiver<AuctionResult>,
+    pub auction_ends: mpsc::UnboundedReceiver<AuctionEndData>,
+    /// Error messages sent by auctioneer
+    pub errors: mpsc::Receiver<ApiResponse>,
+}
+
+#[derive(Debug, Clone)]
+/// Handle to the solver connection. Connection is closed once every handle is dropped
+pub struct SolverClient {
+    messages: mpsc::Sender<WsSolverMessage>,
+    registration: watch::Receiver<Option<RegisterResponseData>>,
+}
+
+impl SolverClient {
+    /// Connects to the auctioneer and registers the solver
+    pub async fn connect(config: SolverClientConfig) -> ModelResult<(SolverClient, SolverStreams)> {
+        let (messages_tx, messages_rx) = mpsc::channel(config.channel_capacity);
+        let (registration_tx, registration_rx) = watch::channel(None);
+        let (senders, streams) = stream_channels(config.channel_capacity);
+
+        let mut connection = Connection {
+            config,
+            senders,
+            registration: registration_tx,
+            messages: messages_rx,
+            unsent: None,
+        };
+        let session = connection.connect_and_register().await?;
+        tokio::spawn(connection.run(session));
+
+        Ok((
+            SolverClient {
+                messages: messages_tx,
+                registration: registration_rx,
+            },
+            streams,
+        ))
+    }
+
+    /// Bid in auction
+    pub async fn participate(&self, participate: ParticipateAuction) -> ModelResult<()> {
+        self.send(WsSolverMessage::Participate(participate)).await
+    }
+
+    /// Request permission to start order execution. Permission is delivered as `AuctionResult`
+    pub async fn get_start_permissions(
+        &self,
+        intent_id: String,
+        order_type: OrderType,
+    ) -> ModelResult<()> {
+        self.send(WsSolverMessage::GetStartPermissions(intent_id, order_type))
+            .await
+    }
+
+    /// Request confirmation of cross chain order fulfillment on destination chain
+    pub async fn report_dst_chain_fulfillment(&self, data: SolverDstChainData) -> ModelResult<()> {
+        self.send(WsSolverMessage::SolverDstChain(data)).await
+    }
+
+    /// Inform about single chain order fulfillment
+    pub async fn report_single_chain_fulfillment(
+        &self,
+        execution_details: SingleChainSolverExecutionDetailsEnum,
+    ) -> ModelResult<()> {
+        self.send(WsSolverMessage::SingleChainOrderFulfilled(
+            execution_details,
+        ))
+        .await
+    }
+
+    /// Latest register response, None while reconnecting
+    pub fn registration(&self) -> Option<RegisterResponseData> {
+        self.registration.borrow().clone()
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.registration.borrow().is_some()
+    }
+
+    /// Features negotiated on the latest registration, None while reconnecting
+    pub fn negotiated_features(&self) -> Option<ProtocolFeatures> {
+        self.registration.borrow().as_ref().map(negotiated_features)
+    }
+
+    /// Waits until the solver is registered
+    pub async fn wait_registered(&self) -> ModelResult<RegisterResponseData> {
+        let mut registration = self.registration.clone();
+        let registration = registration
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| report!(Error::ModuleStopped("Solver client stopped".to_string())))?;
+        Ok(registration.clone().expect("Checked by wait_for"))
+    }
+
+    async fn send(&self, message: WsSolverMessage) -> ModelResult<()> {
+        self.messages
+            .send(message)
+            .await
+            .map_err(|_| report!(Error::ModuleStopped("Solver client stopped".to_string())))
+    }
+}
+
+/// Features negotiated on `registration`. Legacy auctioneers don't negotiate anything
+fn negotiated_features(registration: &RegisterResponseData) -> ProtocolFeatures {
+    registration
+        .negotiated_features
+        .clone()
+        .unwrap_or_else(ProtocolFeatures::legacy)
+}
+
+struct StreamSenders {
+    auction_requests: mpsc::Sender<AuctionRequest>,
+    auction_results: mpsc::UnboundedSender<AuctionResult>,
+    auction_ends: mpsc::UnboundedSender<AuctionEndData>,
+    errors: mpsc::Sender<ApiResponse>,
+}
+
+fn stream_channels(capacity: usize) -> (StreamSenders, SolverStreams) {
+    let (auction_requests_tx, auction_requests_rx) = mpsc::channel(capacity);
+    let (auction_results_tx, auction_results_rx) = mpsc::unbounded_channel();
+    let (auction_ends_tx, auction_ends_rx) = mpsc::unbounded_channel();
+    let (errors_tx, errors_rx) = mpsc::channel(capacity);
+    (
+        StreamSenders {
+            auction_requests: auction_requests_tx,
+            auction_results: auction_results_tx,
+            auction_ends: auction_ends_tx,
+            errors: errors_tx,
+        },
+        SolverStreams {
+            auction_requests: auction_requests_rx,
+            auction_results: auction_results_rx,
+            auction_ends: auction_ends_rx,
+            errors: errors_rx,
+        },
+    )
+}
+
+impl StreamSenders {
+    fn recover(&self, registration: &RegisterResponseData) {
+        for result in &registration.pending_auction_results {
+            let _ = self.auction_results.send(result.clone());
+        }
+        for request in &registration.unfinished_orders {
+            forward("auction_requests", &self.auction_requests, request.clone());
+        }
+    }
+
+    fn dispatch(&self, message: WsAuctioneerMessageInner) {
+        match message {
+            WsAuctioneerMessageInner::AuctionRequest(request) => {
+                forward("auction_requests", &self.auction_requests, request);
+            }
+            WsAuctioneerMessageInner::AuctionResult(result) => {
+                let _ = self.auction_results.send(result);
+            }
+            WsAuctioneerMessageInner::AuctionEnd(data) => {
+                let _ = self.auction_ends.send(data);
+            }
+            WsAuctioneerMessageInner::ErrorMessage(error) => {
+                forward("errors", &self.errors, error);
+            }
+            WsAuctioneerMessageInner::RegisterResponse(_) => {
+                tracing::warn!("Unexpected register response");
+            }
+        }
+    }
+}
+
+// Dropped streams are not an error: solver may not be interested in some messages.
+// Never waits for the solver: the connection task must keep answering pings even if some
+// bounded stream is not drained
+fn forward<T>(stream: &str, sender: &mpsc::Sender<T>, message: T) {
+    match sender.try_send(message) {
+        Ok(()) | Err(mpsc::error::TrySendError::Closed(_)) => {}
+        Err(mpsc::error::TrySendError::Full(_)) => {
+            tracing::warn!("Solver is not draining {stream} stream, dropping message");
+        }
+    }
+}
+
+/// Registered connection and protocol version to use on it
+struct Session {
+    stream: WsStream,
+    version: u32,
+}
+
+enum SessionEnd {
+    Disconnected,
+    Stopped,
+}
+
+struct Connection {
+    config: SolverClientConfig,
+    senders: StreamSenders,
+    registration: watch::Sender<Option<RegisterResponseData>>,
+    messages: mpsc::Receiver<WsSolverMessage>,
+    /// Message which failed to be sent because of disconnection
+    unsent: Option<WsSolverMessage>,
+}
+
+impl Connection {
+    async fn run(mut self, mut session: Session) {
+        loop {
+            match self.serve(session).await {
+                SessionEnd::Stopped => return,
+                SessionEnd::Disconnected => {
+                    self.registration.send_replace(None);
+                    tracing::warn!("Disconnected from auctioneer, reconnecting");
+                }
+            }
+
+            let mut delay = self.config.reconnect_delay;
+            session = loop {
+                tokio::time::sleep(delay).await;
+                if self.messages.is_closed() {
+                    return;
+                }
+                match self.connect_and_register().await {
+                    Ok(session) => break session,
+                    Err(err) => {
+                        tracing::warn!("Failed to reconnect to auctioneer: {err:?}");
+                        delay = (delay * 2).min(self.config.max_reconnect_delay);
+                    }
+                }
+            };
+        }
+    }
+
+    async fn connect_and_register(&mut self) -> ModelResult<Session> {
+        let mut request = self
+            .config
+            .url
+            .as_str()
+            .into_client_request()
+            .change_context(Error::WebSocketError("Invalid auctioneer URL".to_string()))?;
+        for (name, value) in &self.config.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).change_context(
+                Error::WebSocketError(format!("Invalid header name: {name}")),
+            )?;
+            let value = HeaderValue::from_str(value).change_context(Error::WebSocketError(
+                format!("Invalid {name} header value"),
+            ))?;
+            request.headers_mut().insert(name, value);
+        }
+        let (mut stream, _response) =
+            connect_async(request)
+                .await
+                .change_context(Error::WebSocketError(
+                    "Failed to connect to auctioneer".to_string(),
+                ))?;
+
+        let register = WsSolverMessage::Register(self.config.features.clone());
+        send_message(&mut stream, &register, self.config.register_version).await?;
+        let registration = tokio::time::timeout(
+            self.config.register_timeout,
+            self.wait_register_response(&mut stream),
+        )
+        .await
+        .map_err(|_| {
+            report!(Error::WebSocketError(
+                "Register response timed out".to_string()
+            ))
+        })??;
+
+        let version = negotiated_features(&registration).protocol_version;
+        self.senders.recover(&registration);
+        self.registration.send_replace(Some(registration));
+        Ok(Session { stream, version })
+    }
+
+    async fn wait_register_response(
+        &self,
+        stream: &mut WsStream,
+    ) -> ModelResult<RegisterResponseData> {
+        loop {
+            let bytes = match stream.next().await {
+                Some(Ok(Message::Text(text))) => text.into_bytes(),
+                Some(Ok(Message::Binary(bytes))) => bytes,
+                Some(Ok(Message::Ping(payload))) => {
+                    stream
+                        .send(Message::Pong(payload))
+                        .await
+                        .change_context(Error::WebSocketError("Failed to send pong".to_string()))?;
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    return Err(report!(Error::WebSocketError(
+                        "Connection closed before registration".to_string()
+                    )));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => {
+                    return Err(report!(Error::WebSocketError(
+                        "Failed to read register response".to_string()
+                    ))
+                    .attach_printable(err.to_string()));
+                }
+            };
+            match handle_ws_auctioneer_request_msg(&bytes)?.inner() {
+                WsAuctioneerMessageInner::RegisterResponse(registration) => {
+                    return Ok(registration);
+                }
+                WsAuctioneerMessageInner::ErrorMessage(error) => {
+                    return Err(report!(Error::WebSocketError(
+                        "Registration rejected".to_string()
+                    ))
+                    .attach_printable(format!("Auctioneer error: {error:?}")));
+                }
+                message => self.senders.dispatch(message),
+            }
+        }
+    }
+
+    async fn serve(&mut self, session: Session) -> SessionEnd {
+        let Session { stream, version } = session;
+        let (mut sink, mut stream) = stream.split();
+        loop {
+            if let Some(message) = self.unsent.take() {
+                match encode_message(&message, version) {
+                    Ok(frame) => {
+                        if let Err(err) = sink.send(frame).await {
+                            tracing::warn!("Failed to send {} message: {err}", message.kind());
+                            self.unsent = Some(message);
+                            return SessionEnd::Disconnected;
+                        }
+                    }
+                    Err(err) => tracing::error!("Dropping solver message: {err:?}"),
+                }
+                continue;
+            }
+
+            tokio::select! {
+                message = self.messages.recv() => match message {
+                    Some(message) => self.unsent = Some(message),
+                    None => {
+                        let _ = sink.send(Message::Close(None)).await;
+                        return SessionEnd::Stopped;
+                    }
+                },
+                frame = stream.next() => {
+                    if !self.handle_frame(frame, &mut sink).await {
+                        return SessionEnd::Disconnected;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns false if connection is lost
+    async fn handle_frame(
+        &self,
+        frame: Option<Result<Message, tokio_tungstenite::tungstenite::Error>>,
+        sink: &mut SplitSink<WsStream, Message>,
+    ) -> bool {
+        let bytes = match frame {
+            Some(Ok(Message::Text(text))) => text.into_bytes(),
+            Some(Ok(Message::Binary(bytes))) => bytes,
+            Some(Ok(Message::Ping(payload))) => {
+                return sink.send(Message::Pong(payload)).await.is_ok();
+            }
+            Some(Ok(Message::Close(_))) | None => return false,
+            Some(Ok(_)) => return true,
+            Some(Err(err)) => {
+                tracing::warn!("Auctioneer connection error: {err}");
+                return false;
+            }
+        };
+        match handle_ws_auctioneer_request_msg(&bytes) {
+            Ok(message) => match message.inner() {
+                WsAuctioneerMessageInner::RegisterResponse(registration) => {
+                    self.senders.recover(&registration);
+                    self.registration.send_replace(Some(registration));
+                }
+                message => self.senders.dispatch(message),
+            },
+            Err(err) => tracing::error!("Failed to decode auctioneer message: {err:?}"),
+        }
+        true
+    }
+}
+
+fn encode_message(message: &WsSolverMessage, version: u32) -> ModelResult<Message> {
+    let bytes = encode_ws_solver_message(message, version)?;
+    let text = String::from_utf8(bytes)
+        .change_context(Error::SerdeSerialize("Message is not UTF-8".to_string()))?;
+    Ok(Message::Text(text))
+}
+
+async fn send_message(
+    stream: &mut WsStream,
+    message: &WsSolverMessage,
+    version: u32,
+) -> ModelResult<()> {
+    stream
+        .send(encode_message(message, version)?)
+        .await
+        .change_context(Error::WebSocketError(format!(
+            "Failed to send {} message",
+            message.kind()
+        )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::chains::ChainId;
+    use crate::models::ws_messages::auctioneer_message::WsAuctioneerMessage;
+    use crate::models::ws_messages::protocol::{LEGACY_PROTOCOL_VERSION, SolverCapability};
+    use crate::models::ws_messages::solver_message::SingleChainAuctionParticipate;
+    use crate::models::ws_messages::{encode_ws_auctioneer_message, handle_ws_solver_request_msg};
+    use crate::test_fixtures::{self as fixtures, USER, single_chain_auction_request};
+    use tokio::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    type ServerStream = WebSocketStream<TcpStream>;
+
+    async fn with_timeout<T>(future: impl Future<Output = T>) -> T {
+        tokio::time::timeout(TIMEOUT, future)
+            .await
+            .expect("Timed out")
+    }
+
+    async fn accept(listener: &TcpListener) -> ServerStream {
+        let (tcp, _) = with_timeout(listener.accept()).await.expect("Must accept");
+        tokio_tungstenite::accept_async(tcp)
+            .await
+            .expect("Must handshake")
+    }
+
+    async fn recv(ws: &mut ServerStream) -> WsSolverMessage {
+        loop {
+            match with_timeout(ws.next()).await {
+                Some(Ok(Message::Text(text))) => {
+                    return handle_ws_solver_request_msg(text.as_bytes()).expect("Must decode");
+                }
+                Some(Ok(_)) => continue,
+                frame => panic!("Unexpected frame: {frame:?}"),
+            }
+        }
+    }
+
+    async fn send(ws: &mut ServerStream, message: WsAuctioneerMessage, version: u32) {
+        let bytes = encode_ws_auctioneer_message(&message, version).expect("Must encode");
+        ws.send(Message::Text(String::from_utf8(bytes).expect("UTF-8")))
+            .await
+            .expect("Must send");
+    }
+
+    fn features() -> ProtocolFeatures {
+        ProtocolFeatures::new(vec![OrderType::SingleChainLimitOrder], vec![ChainId::Base])
+            .with_capabilities(vec![SolverCapability::SessionResume])
+    }
+
+    fn config(listener: &TcpListener) -> SolverClientConfig {
+        let address = listener.local_addr().expect("Bound listener");
+        SolverClientConfig::new(format!("ws://{address}"), features())
+            .with_reconnect_delay(Duration::from_millis(10), Duration::from_millis(50))
+            .with_register_timeout(TIMEOUT)
+    }
+
+    fn auction_result(intent_id: &str) -> AuctionResult {
+        AuctionResult {
+            intent_id: intent_id.to_string(),
+            amount_out: 100,
+            solver_start_permission: None,
+        }
+    }
+
+    fn auction_request(intent_id: &str) -> AuctionRequest {
+        fixtures::auction_request(single_chain_auction_request(intent_id))
+    }
+
+    fn register_response(
+        pending_auction_results: Vec<AuctionResult>,
+        unfinished_orders: Vec<AuctionRequest>,
+        negotiated_features: Option<ProtocolFeatures>,
+    ) -> WsAuctioneerMessage {
+        WsAuctioneerMessage::register_response(RegisterResponseData {
+            solver_id: "solver".to_string(),
+            status: "registered".to_string(),
+            pending_auction_results,
+            unfinished_orders,
+            negotiated_features,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_register_and_recover_after_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Must bind");
+        let config = config(&listener);
+        let (queued_tx, queued_rx) = tokio::sync::oneshot::channel();
+
+        let server = tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            let WsSolverMessage::Register(solver_features) = recv(&mut ws).await else {
+                panic!("Expected Register");
+            };
+            assert_eq!(solver_features, features());
+            send(
+                &mut ws,
+                register_response(
+                    vec![auction_result("pending")],
+                    vec![auction_request("unfinished")],
+                    Some(solver_features),
+                ),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+            send(
+                &mut ws,
+                WsAuctioneerMessage::auction_result(auction_result("live")),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+            assert!(matches!(
+                recv(&mut ws).await,
+                WsSolverMessage::GetStartPermissions(intent_id, OrderType::SingleChainLimitOrder)
+                    if intent_id == "live"
+            ));
+            // Drop connection, accept a new one once client queued a message
+            drop(ws);
+            with_timeout(queued_rx)
+                .await
+                .expect("Message must be queued");
+
+            let mut ws = accept(&listener).await;
+            assert!(matches!(recv(&mut ws).await, WsSolverMessage::Register(_)));
+            send(
+                &mut ws,
+                register_response(
+                    vec![auction_result("pending")],
+                    vec![auction_request("unfinished")],
+                    Some(features()),
+                ),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+            // Message queued while disconnected
+            assert!(matches!(
+                recv(&mut ws).await,
+                WsSolverMessage::Participate(ParticipateAuction::Single(participate))
+                    if participate.intent_id == "unfinished"
+            ));
+            ws
+        });
+
+        let (client, mut streams) = SolverClient::connect(config).await.expect("Must connect");
+        assert_eq!(client.negotiated_features(), Some(features()));
+
+        let recovered = with_timeout(streams.auction_results.recv())
+            .await
+            .expect("Result");
+        assert_eq!(recovered.intent_id, "pending");
+        let recovered = with_timeout(streams.auction_requests.recv())
+            .await
+            .expect("Request");
+        assert_eq!(recovered.intent_id, "unfinished");
+        let live = with_timeout(streams.auction_results.recv())
+            .await
+            .expect("Result");
+        assert_eq!(live.intent_id, "live");
+
+        client
+            .get_start_permissions("live".to_string(), OrderType::SingleChainLimitOrder)
+            .await
+            .expect("Must send");
+        with_timeout(async {
+            while client.is_registered() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        client
+            .participate(ParticipateAuction::Single(SingleChainAuctionParticipate {
+                intent_id: "unfinished".to_string(),
+                order_type: OrderType::SingleChainLimitOrder,
+                solver_address: USER.to_string(),
+                amount_out: 3_000_000_000,
+            }))
+            .await
+            .expect("Must send");
+        queued_tx.send(()).expect("Server is running");
+
+        // Pending data is recovered once again after re-registration
+        let recovered = with_timeout(streams.auction_results.recv())
+            .await
+            .expect("Result");
+        assert_eq!(recovered.intent_id, "pending");
+        let recovered = with_timeout(streams.auction_requests.recv())
+            .await
+            .expect("Request");
+        assert_eq!(recovered.intent_id, "unfinished");
+
+        let mut ws = with_timeout(server).await.expect("Server must succeed");
+        assert!(client.is_registered());
+
+        // Dropping the client closes the connection
+        drop(client);
+        assert!(matches!(
+            with_timeout(ws.next()).await,
+            Some(Ok(Message::Close(_))) | None
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_auctioneer() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Must bind");
+        let config = config(&listener).with_register_version(LEGACY_PROTOCOL_VERSION);
+
+        let server = tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            match with_timeout(ws.next()).await {
+                Some(Ok(Message::Text(text))) => assert_eq!(text, r#""Register""#),
+                frame => panic!("Unexpected frame: {frame:?}"),
+            }
+            send(
+                &mut ws,
+                register_response(vec![], vec![], None),
+                LEGACY_PROTOCOL_VERSION,
+            )
+            .await;
+            let participate = with_timeout(ws.next()).await;
+            let Some(Ok(Message::Text(text))) = participate else {
+                panic!("Unexpected frame: {participate:?}");
+            };
+            assert!(text.starts_with(r#"{"GetStartPermissions""#));
+        });
+
+        let (client, _streams) = SolverClient::connect(config).await.expect("Must connect");
+        assert_eq!(
+            client.negotiated_features(),
+            Some(ProtocolFeatures::legacy())
+        );
+        client
+            .get_start_permissions("intent".to_string(), OrderType::SingleChainLimitOrder)
+            .await
+            .expect("Must send");
+        with_timeout(server).await.expect("Server must succeed");
+    }
+
+    #[tokio::test]
+    async fn test_undrained_stream_does_not_stall_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Must bind");
+        let config = config(&listener).with_channel_capacity(1);
+
+        let server = tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            recv(&mut ws).await;
+            send(
+                &mut ws,
+                register_response(vec![], vec![], Some(features())),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+            // Solver never reads `errors`
+            for _ in 0..3 {
+                send(
+                    &mut ws,
+                    WsAuctioneerMessage::error(ApiResponse::unauthorized("Unknown intent")),
+                    WS_PROTOCOL_VERSION,
+                )
+                .await;
+            }
+            ws.send(Message::Ping(b"alive".to_vec()))
+                .await
+                .expect("Must send");
+            loop {
+                match with_timeout(ws.next()).await {
+                    Some(Ok(Message::Pong(payload))) => {
+                        assert_eq!(payload, b"alive".to_vec());
+                        break;
+                    }
+                    Some(Ok(_)) => continue,
+                    frame => panic!("Unexpected frame: {frame:?}"),
+                }
+            }
+            send(
+                &mut ws,
+                WsAuctioneerMessage::auction_result(auction_result("live")),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+            ws
+        });
+
+        let (client, mut streams) = SolverClient::connect(config).await.expect("Must connect");
+        let live = with_timeout(streams.auction_results.recv())
+            .await
+            .expect("Result");
+        assert_eq!(live.intent_id, "live");
+        let _ws = with_timeout(server).await.expect("Server must succeed");
+        assert!(client.is_registered());
+
+        // Only the first message fitted into the undrained stream
+        assert!(streams.errors.try_recv().is_ok());
+        assert!(streams.errors.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auction_results_are_never_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Must bind");
+        let config = config(&listener).with_channel_capacity(1);
+
+        let server = tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            recv(&mut ws).await;
+            send(
+                &mut ws,
+                register_response(vec![auction_result("pending")], vec![], Some(features())),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+            for intent_id in ["first", "second", "third"] {
+                send(
+                    &mut ws,
+                    WsAuctioneerMessage::auction_result(auction_result(intent_id)),
+                    WS_PROTOCOL_VERSION,
+                )
+                .await;
+            }
+            // Connection is still served while results are not drained
+            ws.send(Message::Ping(b"alive".to_vec()))
+                .await
+                .expect("Must send");
+            loop {
+                match with_timeout(ws.next()).await {
+                    Some(Ok(Message::Pong(_))) => break,
+                    Some(Ok(_)) => continue,
+                    frame => panic!("Unexpected frame: {frame:?}"),
+                }
+            }
+            ws
+        });
+
+        let (_client, mut streams) = SolverClient::connect(config).await.expect("Must connect");
+        let _ws = with_timeout(server).await.expect("Server must succeed");
+
+        // Every result is delivered although the stream was filled beyond `channel_capacity`
+        for expected in ["pending", "first", "second", "third"] {
+            let result = with_timeout(streams.auction_results.recv())
+                .await
+                .expect("Result");
+            assert_eq!(result.intent_id, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejected_registration() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Must bind");
+        let config = config(&listener);
+
+        tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            recv(&mut ws).await;
+            send(
+                &mut ws,
+                WsAuctioneerMessage::error(ApiResponse::unauthorized("Unknown solver")),
+                WS_PROTOCOL_VERSION,
+            )
+            .await;
+        });
+
+        assert!(SolverClient::connect(config).await.is_err());
+    }
+}
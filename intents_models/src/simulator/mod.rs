@@ -0,0 +1,523 @@
+//! Local auctioneer simulator for solver integration testing
+//!
+//! `AuctioneerSimulator` runs a WebSocket server speaking the auctioneer protocol. Once enough
+//! solvers are registered, it runs an auction for every intent of the `Scenario`, sends start
+//! permission to the bid with the highest `amount_out` and checks the fulfillment reported by
+//! the winner. Cross chain winners get success confirmation after destination chain fulfillment.
+//!
+//! Solvers are identified by `x-solver-id` handshake header, so a reconnected solver gets its
+//! start permissions back on registration. Solvers without the header get a new id on every
+//! connection.
+
+mod permissions;
+mod report;
+mod scenario;
+mod server;
+mod state;
+
+use crate::constants::chains::{ChainId, EVM_NULL_ADDRESS};
+use crate::error::{Error, ModelResult};
+use crate::models::types::order::OrderType;
+use crate::models::ws_messages::protocol::{ProtocolFeatures, SolverCapability};
+use crate::simulator::server::lock;
+use crate::simulator::state::SimulatorState;
+use error_stack::{ResultExt, report};
+pub use report::*;
+pub use scenario::*;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Handshake header with solver id
+pub const SOLVER_ID_HEADER: &str = "x-solver-id";
+
+const DEFAULT_AUCTION_DURATION: Duration = Duration::from_secs(2);
+const DEFAULT_SOLVER_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_FULFILLMENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    /// Features offered to solvers
+    pub features: ProtocolFeatures,
+    /// Duration of auctions without scripted duration
+    pub auction_duration: Duration,
+    /// Auctions start once this number of solvers is registered
+    pub min_solvers: usize,
+    pub solver_wait_timeout: Duration,
+    /// Time given to winners to report fulfillment after the last auction is closed
+    pub fulfillment_timeout: Duration,
+    /// Guard contract address put into start permissions and success confirmations
+    pub guard_contract: String,
+    /// Protocol fee receiver of cross chain start permissions
+    pub protocol_fee_receiver: String,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            features: ProtocolFeatures::new(OrderType::iter().collect(), ChainId::iter().collect())
+                .with_capabilities(vec![
+                    SolverCapability::ExtraTransfers,
+                    SolverCapability::StopLoss,
+                    SolverCapability::SessionResume,
+                ]),
+            auction_duration: DEFAULT_AUCTION_DURATION,
+            min_solvers: 1,
+            solver_wait_timeout: DEFAULT_SOLVER_WAIT_TIMEOUT,
+            fulfillment_timeout: DEFAULT_FULFILLMENT_TIMEOUT,
+            guard_contract: EVM_NULL_ADDRESS.to_string(),
+            protocol_fee_receiver: EVM_NULL_ADDRESS.to_string(),
+        }
+    }
+}
+
+impl SimulatorConfig {
+    pub fn with_features(mut self, features: ProtocolFeatures) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub fn with_auction_duration(mut self, auction_duration: Duration) -> Self {
+        self.auction_duration = auction_duration;
+        self
+    }
+
+    pub fn with_min_solvers(mut self, min_solvers: usize, wait_timeout: Duration) -> Self {
+        self.min_solvers = min_solvers;
+        self.solver_wait_timeout = wait_timeout;
+        self
+    }
+
+    pub fn with_fulfillment_timeout(mut self, fulfillment_timeout: Duration) -> Self {
+        self.fulfillment_timeout = fulfillment_timeout;
+        self
+    }
+
+    pub fn with_guard_contract(mut self, guard_contract: impl Into<String>) -> Self {
+        self.guard_contract = guard_contract.into();
+        self
+    }
+
+    pub fn with_protocol_fee_receiver(mut self, protocol_fee_receiver: impl Into<String>) -> Self {
+        self.protocol_fee_receiver = protocol_fee_receiver.into();
+        self
+    }
+}
+
+pub struct AuctioneerSimulator {
+    config: SimulatorConfig,
+    scenario: Scenario,
+    listener: TcpListener,
+}
+
+impl AuctioneerSimulator {
+    /// Binds the simulator to `address`. Solvers can connect once it is bound
+    pub async fn bind(
+        address: impl ToSocketAddrs,
+        config: SimulatorConfig,
+        scenario: Scenario,
+    ) -> ModelResult<Self> {
+        scenario.validate()?;
+        let listener = TcpListener::bind(address)
+            .await
+            .change_context(Error::WebSocketError(
+                "Failed to bind auctioneer simulator".to_string(),
+            ))?;
+        Ok(Self {
+            config,
+            scenario,
+            listener,
+        })
+    }
+
+    pub fn local_addr(&self) -> ModelResult<SocketAddr> {
+        self.listener
+            .local_addr()
+            .change_context(Error::WebSocketError(
+                "Simulator address is unknown".to_string(),
+            ))
+    }
+
+    /// WebSocket URL solvers connect to
+    pub fn url(&self) -> ModelResult<String> {
+        Ok(format!("ws://{}", self.local_addr()?))
+    }
+
+    /// Runs every scripted auction and waits for winners to report fulfillment.
+    ///
+    /// Fails if solvers didn't register in time. Missing or rejected fulfillments don't fail
+    /// the simulation, they are listed in the report
+    pub async fn run(self) -> ModelResult<SimulationReport> {
+        let changed = Arc::new(Notify::new());
+        let state = Arc::new(Mutex::new(SimulatorState::new(
+            self.config.clone(),
+            self.scenario.clone(),
+            changed.clone(),
+        )));
+        let accept = tokio::spawn(server::accept_solvers(self.listener, state.clone()));
+
+        let result = simulate(&self.config, &self.scenario, &state, &changed).await;
+        accept.abort();
+        let report = {
+            let mut state = lock(&state);
+            state.disconnect_all();
+            state.report()
+        };
+        result.map(|_| report)
+    }
+}
+
+async fn simulate(
+    config: &SimulatorConfig,
+    scenario: &Scenario,
+    state: &Arc<Mutex<SimulatorState>>,
+    changed: &Notify,
+) -> ModelResult<()> {
+    let min_solvers = config.min_solvers;
+    wait_until(changed, config.solver_wait_timeout, || {
+        lock(state).registered_solvers() >= min_solvers
+    })
+    .await
+    .map_err(|_| {
+        report!(Error::LogicError(format!(
+            "{min_solvers} solvers weren't registered in {:?}",
+            config.solver_wait_timeout
+        )))
+    })?;
+
+    let start = Instant::now();
+    let auctions = scenario.intents.iter().map(|scripted| {
+        let auction_duration = scripted
+            .auction_duration()
+            .unwrap_or(config.auction_duration);
+        async move {
+            tokio::time::sleep_until(start + scripted.start_delay()).await;
+            lock(state).open_auction(&scripted.intent_id);
+            tokio::time::sleep(auction_duration).await;
+            lock(state).close_auction(&scripted.intent_id);
+        }
+    });
+    futures::future::join_all(auctions).await;
+
+    // Not reported fulfillments are listed in the report
+    let _ = wait_until(changed, config.fulfillment_timeout, || {
+        lock(state).is_settled()
+    })
+    .await;
+    Ok(())
+}
+
+async fn wait_until(
+    changed: &Notify,
+    timeout: Duration,
+    condition: impl Fn() -> bool,
+) -> Result<(), tokio::time::error::Elapsed> {
+    tokio::time::timeout(timeout, async {
+        loop {
+            // Created before the check, so notification sent in between isn't lost
+            let notified = changed.notified();
+            if condition() {
+                return;
+            }
+            notified.await;
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::types::cross_chain::{
+        CrossChainSolverFulfillmentData, EvmCrossChainRequestedFulfillment,
+    };
+    use crate::models::types::order::OrderStatus;
+    use crate::models::types::order::OrderTypeFulfillmentData;
+    use crate::models::types::single_chain::{
+        SingleChainLimitOrderExecutionDetails, SingleChainOrderExecutionDetails,
+        SingleChainSolverExecutionDetailsEnum,
+    };
+    use crate::models::types::solver_types::SolverStartPermission;
+    use crate::models::ws_messages::solver_message::{
+        CrossChainAuctionParticipate, ParticipateAuction, SingleChainAuctionParticipate,
+        SolverDstChainData,
+    };
+    use crate::network::solver_client::{SolverClient, SolverClientConfig, SolverStreams};
+    use crate::test_fixtures::{USER, cross_chain_auction_request, single_chain_auction_request};
+    use serde_json::{Value, json};
+    use tokio::task::JoinHandle;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    const SOLVER_A: &str = "0x1111111111111111111111111111111111111111";
+    const SOLVER_B: &str = "0x2222222222222222222222222222222222222222";
+
+    async fn recv<T>(message: impl Future<Output = Option<T>>) -> T {
+        tokio::time::timeout(TIMEOUT, message)
+            .await
+            .expect("Timed out")
+            .expect("Stream closed")
+    }
+
+    fn single_chain_intent(intent_id: &str) -> Value {
+        let mut intent = single_chain_auction_request(intent_id);
+        intent["auction_duration_ms"] = json!(500);
+        intent
+    }
+
+    fn cross_chain_intent(intent_id: &str) -> Value {
+        let mut intent = cross_chain_auction_request(intent_id, true);
+        intent["auction_duration_ms"] = json!(500);
+        intent
+    }
+
+    fn scenario(intents: Vec<Value>) -> ModelResult<Scenario> {
+        Scenario::from_slice(&serde_json::to_vec(&json!({ "intents": intents })).expect("JSON"))
+    }
+
+    async fn start(
+        intents: Vec<Value>,
+        min_solvers: usize,
+    ) -> (String, JoinHandle<ModelResult<SimulationReport>>) {
+        let config = SimulatorConfig::default()
+            .with_min_solvers(min_solvers, TIMEOUT)
+            .with_fulfillment_timeout(TIMEOUT);
+        let scenario = scenario(intents).expect("Valid scenario");
+        let simulator = AuctioneerSimulator::bind("127.0.0.1:0", config, scenario)
+            .await
+            .expect("Must bind");
+        let url = simulator.url().expect("Bound simulator");
+        (url, tokio::spawn(simulator.run()))
+    }
+
+    async fn connect(
+        url: &str,
+        solver_id: &str,
+        features: ProtocolFeatures,
+    ) -> (SolverClient, SolverStreams) {
+        let config =
+            SolverClientConfig::new(url, features).with_header(SOLVER_ID_HEADER, solver_id);
+        SolverClient::connect(config).await.expect("Must register")
+    }
+
+    async fn finish(run: JoinHandle<ModelResult<SimulationReport>>) -> SimulationReport {
+        tokio::time::timeout(TIMEOUT, run)
+            .await
+            .expect("Timed out")
+            .expect("Simulator panicked")
+            .expect("Simulation failed")
+    }
+
+    fn single_chain_bid(
+        intent_id: &str,
+        solver_address: &str,
+        amount_out: u128,
+    ) -> ParticipateAuction {
+        ParticipateAuction::Single(SingleChainAuctionParticipate {
+            intent_id: intent_id.to_string(),
+            order_type: OrderType::SingleChainLimitOrder,
+            solver_address: solver_address.to_string(),
+            amount_out,
+        })
+    }
+
+    fn limit_order_fulfilled(
+        intent_id: &str,
+        chain_id: ChainId,
+    ) -> SingleChainSolverExecutionDetailsEnum {
+        SingleChainSolverExecutionDetailsEnum::Limit(SingleChainLimitOrderExecutionDetails {
+            common_data: SingleChainOrderExecutionDetails {
+                chain_id,
+                intent_id: intent_id.to_string(),
+                tx_hash: "0xfulfillment".to_string(),
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_single_chain_auction() {
+        let (url, run) = start(vec![single_chain_intent("intent")], 2).await;
+        let features = SimulatorConfig::default().features;
+        let (solver_a, mut streams_a) = connect(&url, "solver-a", features.clone()).await;
+        let (solver_b, mut streams_b) = connect(&url, "solver-b", features).await;
+
+        for (solver, streams, address, amount_out) in [
+            (&solver_a, &mut streams_a, SOLVER_A, 3_100_000_000),
+            (&solver_b, &mut streams_b, SOLVER_B, 3_200_000_000),
+        ] {
+            let request = recv(streams.auction_requests.recv()).await;
+            assert_eq!(request.intent_id, "intent");
+            solver
+                .participate(single_chain_bid("intent", address, amount_out))
+                .await
+                .expect("Must send");
+        }
+
+        // Every bidder learns the winning amount, only the winner gets permission
+        let result = recv(streams_a.auction_results.recv()).await;
+        assert_eq!(result.amount_out, 3_200_000_000);
+        assert!(result.solver_start_permission.is_none());
+        let result = recv(streams_b.auction_results.recv()).await;
+        let Some(SolverStartPermission::SingleChainLimit(permission)) =
+            result.solver_start_permission
+        else {
+            panic!(
+                "Unexpected permission: {:?}",
+                result.solver_start_permission
+            );
+        };
+        assert_eq!(permission.common_data.solver_address, SOLVER_B);
+        assert_eq!(permission.common_data.expected_amount_out, 3_200_000_000);
+        let evm_data = permission
+            .common_data
+            .chain_specific_data
+            .try_get_evm_data()
+            .expect("EVM permission");
+        assert_eq!(evm_data.order_type_data.get_intent_id(), "intent");
+
+        // Reports of solvers which didn't win are rejected
+        solver_a
+            .report_single_chain_fulfillment(limit_order_fulfilled("intent", ChainId::Base))
+            .await
+            .expect("Must send");
+        let error = recv(streams_a.errors.recv()).await;
+        assert_eq!(error.code, 400);
+
+        solver_b
+            .report_single_chain_fulfillment(limit_order_fulfilled("intent", ChainId::Base))
+            .await
+            .expect("Must send");
+
+        let report = finish(run).await;
+        let auction = report.get_auction("intent").expect("Scripted auction");
+        assert_eq!(auction.status, OrderStatus::Fulfilled);
+        assert_eq!(auction.bids, 2);
+        assert_eq!(
+            auction.winner,
+            Some(WinningBid {
+                solver_id: "solver-b".to_string(),
+                amount_out: 3_200_000_000,
+            })
+        );
+        assert_eq!(auction.tx_hash.as_deref(), Some("0xfulfillment"));
+        assert_eq!(auction.issues.len(), 1, "{:?}", auction.issues);
+        assert!(!report.is_success());
+    }
+
+    #[tokio::test]
+    async fn test_cross_chain_auction() {
+        let (url, run) = start(
+            vec![cross_chain_intent("cross"), single_chain_intent("single")],
+            1,
+        )
+        .await;
+        // Solver doesn't support single chain orders, so nobody bids on them
+        let features = ProtocolFeatures::new(
+            vec![OrderType::CrossChainLimitOrder],
+            vec![ChainId::Ethereum, ChainId::Base],
+        );
+        let (solver, mut streams) = connect(&url, "solver", features).await;
+
+        let request = recv(streams.auction_requests.recv()).await;
+        assert_eq!(request.intent_id, "cross");
+        solver
+            .participate(ParticipateAuction::Multi(CrossChainAuctionParticipate {
+                intent_id: "cross".to_string(),
+                order_type: OrderType::CrossChainLimitOrder,
+                amount_out: 99_500_000,
+                will_swap: false,
+                stablecoins_amount: 100_000_000,
+                src_chain_solver_address: SOLVER_A.to_string(),
+                dest_chain_solver_address: SOLVER_B.to_string(),
+            }))
+            .await
+            .expect("Must send");
+
+        let result = recv(streams.auction_results.recv()).await;
+        let Some(SolverStartPermission::CrossChainLimit(permission)) =
+            result.solver_start_permission
+        else {
+            panic!(
+                "Unexpected permission: {:?}",
+                result.solver_start_permission
+            );
+        };
+        assert_eq!(permission.common_data.collateral_amount, 1_000_000);
+        let CrossChainSolverFulfillmentData::EVM(fulfillment) =
+            &permission.common_data.dest_chain_fulfillment_details
+        else {
+            panic!("EVM destination chain");
+        };
+        let EvmCrossChainRequestedFulfillment::SimpleFulfillment(requested) =
+            &fulfillment.requested_fulfillment
+        else {
+            panic!("Simple fulfillment");
+        };
+        assert_eq!(requested.order_id, "cross");
+        assert_eq!(requested.requested_amount, 99_500_000);
+        assert_eq!(requested.receiver, USER);
+
+        // Permission is sent again on request
+        solver
+            .get_start_permissions("cross".to_string(), OrderType::CrossChainLimitOrder)
+            .await
+            .expect("Must send");
+        let result = recv(streams.auction_results.recv()).await;
+        assert!(result.solver_start_permission.is_some());
+
+        solver
+            .report_dst_chain_fulfillment(SolverDstChainData {
+                intent_id: "cross".to_string(),
+                order_type_specific_data: OrderTypeFulfillmentData::Limit,
+                tx_hash: "0xfulfillment".to_string(),
+                extra_transfers_tx_hashes: None,
+            })
+            .await
+            .expect("Must send");
+        let auction_end = recv(streams.auction_ends.recv()).await;
+        assert_eq!(auction_end.intent_id, "cross");
+        assert_eq!(
+            auction_end
+                .solver_success_confirmation
+                .src_chain_solver_address,
+            SOLVER_A
+        );
+
+        let report = finish(run).await;
+        let cross = report.get_auction("cross").expect("Scripted auction");
+        assert_eq!(cross.status, OrderStatus::Fulfilled);
+        assert!(cross.issues.is_empty(), "{:?}", cross.issues);
+        let single = report.get_auction("single").expect("Scripted auction");
+        assert_eq!(single.status, OrderStatus::NoBids);
+        assert_eq!(single.bids, 0);
+        assert!(single.winner.is_none());
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn test_scenario_validation() {
+        assert!(scenario(vec![single_chain_intent("a"), cross_chain_intent("b")]).is_ok());
+
+        // Duplicated intent
+        assert!(scenario(vec![single_chain_intent("a"), cross_chain_intent("a")]).is_err());
+
+        // Execution terms of another intent kind
+        let mut intent = single_chain_intent("a");
+        intent["execution_terms"] = cross_chain_intent("a")["execution_terms"].clone();
+        assert!(scenario(vec![intent]).is_err());
+
+        // DCA interval for limit order
+        let mut intent = single_chain_intent("a");
+        intent["execution_terms"]["order_type_specific_data"] =
+            json!({ "type": "Dca", "interval_number": 1 });
+        assert!(scenario(vec![intent]).is_err());
+
+        // Permissions are only issued on EVM chains
+        let mut intent = cross_chain_intent("a");
+        intent["intent"]["genericData"]["srcChainId"] = json!(7565164);
+        assert!(scenario(vec![intent]).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! Start permissions and success confirmations issued by the simulator.
+//!
+//! Order hash of EVM orders is the intent id, as in auctioneer. Signatures are zeroed
+//! placeholders, so simulated permissions can't be used on chain.
+
+use crate::constants::chains::{ChainType, EVM_NULL_ADDRESS};
+use crate::error::{Error, ModelResult};
+use crate::models::types::cross_chain::{
+    CrossChainDcaOrderSolverStartPermission, CrossChainExecutionTerms, CrossChainGenericData,
+    CrossChainGenericDataEnum, CrossChainLimitOrderSolverStartPermission,
+    CrossChainSolverFulfillmentData, CrossChainSolverStartOrderData,
+    CrossChainSolverStartPermission, CrossChainSolverSuccessConfirmation,
+    EvmCrossChainDcaOrderInfo, EvmCrossChainDcaSolverPermission, EvmCrossChainFulfillmentData,
+    EvmCrossChainLimitOrderInfo, EvmCrossChainLimitSolverPermission,
+    EvmCrossChainRequestedFulfillment, EvmSuccessConfirmationCrossChainDcaOrderData,
+    EvmSuccessConfirmationCrossChainLimitOrderData, EvmSuccessConfirmationOrderTypeData,
+    SimpleEvmRequestedFulfillment, SolverSuccessConfirmationData, StartEvmCrossChainDcaOrderData,
+    StartEvmCrossChainLimitOrderData, SuccessConfirmationEVMData,
+};
+use crate::models::types::order::OrderTypeFulfillmentData;
+use crate::models::types::single_chain::{
+    EvmSingleChainDcaOrderInfo, EvmSingleChainDcaSolverPermission, EvmSingleChainLimitOrderInfo,
+    EvmSingleChainLimitSolverPermission, SingleChainDcaOrderSolverStartPermission,
+    SingleChainExecutionTerms, SingleChainLimitOrderSolverStartPermission,
+    SingleChainSolverStartOrderData, SingleChainSolverStartPermission,
+    StartEvmSingleChainDcaOrderData, StartEvmSingleChainLimitOrderData,
+};
+use crate::models::types::solver_types::{
+    ExecutionTerms, SolverStartPermission, StartEvmOrderTypeData, StartOrderEVMData,
+};
+use crate::models::types::user_types::IntentRequest;
+use crate::models::ws_messages::solver_message::{
+    CrossChainAuctionParticipate, ParticipateAuction, SingleChainAuctionParticipate,
+};
+use crate::simulator::SimulatorConfig;
+use crate::simulator::scenario::ScriptedIntent;
+use error_stack::report;
+
+pub(crate) fn simulated_signature() -> String {
+    format!("0x{}", "00".repeat(65))
+}
+
+/// Permission for the winner `bid` to start execution of `scripted` intent
+pub(crate) fn start_permission(
+    scripted: &ScriptedIntent,
+    bid: &ParticipateAuction,
+    solver_deadline: u64,
+    config: &SimulatorConfig,
+) -> ModelResult<SolverStartPermission> {
+    let intent_id = &scripted.intent_id;
+    // EVM contracts store deadlines as uint32
+    let permission_deadline = u32::try_from(solver_deadline).map_err(|_| {
+        report!(Error::LogicError(format!(
+            "Solver deadline {solver_deadline} doesn't fit into u32"
+        )))
+    })?;
+    let permission = match (&scripted.intent, &scripted.execution_terms, bid) {
+        (
+            IntentRequest::SingleChainLimitOrder(intent),
+            ExecutionTerms::SingleChain(terms),
+            ParticipateAuction::Single(bid),
+        ) => {
+            let order_type_data =
+                StartEvmOrderTypeData::SingleChainLimit(StartEvmSingleChainLimitOrderData {
+                    order_info: EvmSingleChainLimitOrderInfo::try_from(intent)?,
+                    start_permission: EvmSingleChainLimitSolverPermission {
+                        solver: bid.solver_address.clone(),
+                        order_hash: intent_id.clone(),
+                        amount_out_min: bid.amount_out,
+                        protocol_fee_transfer: terms.protocol_fee_transfer.clone(),
+                        permission_deadline,
+                    },
+                });
+            let evm_data = evm_start_data(
+                &intent.chain_specific_data.try_get_evm()?.signature,
+                order_type_data,
+                config,
+            );
+            SolverStartPermission::SingleChainLimit(SingleChainLimitOrderSolverStartPermission {
+                common_data: single_chain_common_data(bid, terms, solver_deadline, evm_data),
+                generic_data: intent.generic_data.clone(),
+            })
+        }
+        (
+            IntentRequest::SingleChainDcaOrder(intent),
+            ExecutionTerms::SingleChain(terms),
+            ParticipateAuction::Single(bid),
+        ) => {
+            let interval_number = interval_number(&terms.order_type_specific_data)?;
+            let order_type_data =
+                StartEvmOrderTypeData::SingleChainDca(StartEvmSingleChainDcaOrderData {
+                    order_info: EvmSingleChainDcaOrderInfo::try_from(intent)?,
+                    start_permission: EvmSingleChainDcaSolverPermission {
+                        solver: bid.solver_address.clone(),
+                        order_hash: intent_id.clone(),
+                        interval_number_to_execute: interval_number,
+                        amount_out_min: bid.amount_out,
+                        protocol_fee_transfer: terms.protocol_fee_transfer.clone(),
+                        permission_deadline,
+                    },
+                });
+            let evm_data = evm_start_data(
+                &intent.chain_specific_data.try_get_evm()?.signature,
+                order_type_data,
+                config,
+            );
+            SolverStartPermission::SingleChainDca(SingleChainDcaOrderSolverStartPermission {
+                common_data: single_chain_common_data(bid, terms, solver_deadline, evm_data),
+                generic_data: intent.generic_data.clone(),
+                interval_number,
+            })
+        }
+        (
+            IntentRequest::CrossChainLimitOrder(intent),
+            ExecutionTerms::CrossChain(terms),
+            ParticipateAuction::Multi(bid),
+        ) => {
+            let order_type_data =
+                StartEvmOrderTypeData::CrossChainLimit(StartEvmCrossChainLimitOrderData {
+                    order_info: EvmCrossChainLimitOrderInfo::try_from(intent)?,
+                    start_permission: EvmCrossChainLimitSolverPermission {
+                        solver: bid.src_chain_solver_address.clone(),
+                        order_hash: intent_id.clone(),
+                        collateral_amount: terms.collateral_amount,
+                        protocol_fee: terms.protocol_fee,
+                        protocol_fee_receiver: config.protocol_fee_receiver.clone(),
+                        allow_swap: terms.allow_swap,
+                        min_stablecoins_amount: terms.min_stablecoins_amount,
+                        deadline: permission_deadline,
+                    },
+                });
+            let evm_data = evm_start_data(
+                &intent.chain_specific_data.try_get_evm()?.signature,
+                order_type_data,
+                config,
+            );
+            SolverStartPermission::CrossChainLimit(CrossChainLimitOrderSolverStartPermission {
+                common_data: cross_chain_common_data(
+                    bid,
+                    terms,
+                    solver_deadline,
+                    evm_data,
+                    dest_chain_fulfillment(
+                        intent_id,
+                        &intent.generic_data.common_data,
+                        bid.amount_out,
+                        solver_deadline,
+                        config,
+                    ),
+                ),
+                generic_data: intent.generic_data.clone(),
+            })
+        }
+        (
+            IntentRequest::CrossChainDcaOrder(intent),
+            ExecutionTerms::CrossChain(terms),
+            ParticipateAuction::Multi(bid),
+        ) => {
+            let interval_number = interval_number(&terms.order_type_specific_data)?;
+            let generic_data = &intent.generic_data;
+            let order_type_data =
+                StartEvmOrderTypeData::CrossChainDca(StartEvmCrossChainDcaOrderData {
+                    order_info: EvmCrossChainDcaOrderInfo::try_from(intent)?,
+                    start_permission: EvmCrossChainDcaSolverPermission {
+                        solver: bid.src_chain_solver_address.clone(),
+                        order_hash: intent_id.clone(),
+                        interval_number_to_execute: interval_number,
+                        collateral_amount: terms.collateral_amount,
+                        protocol_fee: terms.protocol_fee,
+                        protocol_fee_receiver: config.protocol_fee_receiver.clone(),
+                        allow_swap: terms.allow_swap,
+                        min_stablecoins_amount: terms.min_stablecoins_amount,
+                        previous_executed_interval_index: generic_data
+                            .common_dca_state
+                            .last_executed_interval_index,
+                        previous_executed_interval_solver: generic_data
+                            .last_executed_interval_solver
+                            .clone()
+                            .unwrap_or_else(|| EVM_NULL_ADDRESS.to_string()),
+                        deadline: permission_deadline,
+                    },
+                });
+            let evm_data = evm_start_data(
+                &intent.chain_specific_data.try_get_evm()?.signature,
+                order_type_data,
+                config,
+            );
+            SolverStartPermission::CrossChainDca(CrossChainDcaOrderSolverStartPermission {
+                common_data: cross_chain_common_data(
+                    bid,
+                    terms,
+                    solver_deadline,
+                    evm_data,
+                    dest_chain_fulfillment(
+                        intent_id,
+                        &generic_data.common_data,
+                        bid.amount_out,
+                        solver_deadline,
+                        config,
+                    ),
+                ),
+                generic_data: generic_data.clone(),
+                interval_number,
+                previous_executed_interval_index: generic_data
+                    .common_dca_state
+                    .last_executed_interval_index,
+                previous_executed_interval_solver: generic_data
+                    .last_executed_interval_solver
+                    .clone(),
+            })
+        }
+        _ => {
+            return Err(report!(Error::LogicError(format!(
+                "Bid doesn't match {} intent {intent_id}",
+                scripted.intent.get_order_type()
+            ))));
+        }
+    };
+    Ok(permission)
+}
+
+/// Confirmation allowing the solver to claim tokens of `scripted` cross chain intent
+pub(crate) fn success_confirmation(
+    scripted: &ScriptedIntent,
+    src_chain_solver_address: &str,
+    order_type_specific_data: OrderTypeFulfillmentData,
+    config: &SimulatorConfig,
+) -> ModelResult<CrossChainSolverSuccessConfirmation> {
+    // Contracts don't verify simulated confirmations, so there is no data to pass
+    let (generic_data, order_type_data) = match &scripted.intent {
+        IntentRequest::CrossChainLimitOrder(intent) => (
+            CrossChainGenericDataEnum::Limit(intent.generic_data.clone()),
+            EvmSuccessConfirmationOrderTypeData::CrossChainLimit(
+                EvmSuccessConfirmationCrossChainLimitOrderData {
+                    order_info: EvmCrossChainLimitOrderInfo::try_from(intent)?,
+                    success_confirmation_data: serde_json::Value::Null,
+                },
+            ),
+        ),
+        IntentRequest::CrossChainDcaOrder(intent) => (
+            CrossChainGenericDataEnum::DCA(intent.generic_data.clone()),
+            EvmSuccessConfirmationOrderTypeData::CrossChainDca(
+                EvmSuccessConfirmationCrossChainDcaOrderData {
+                    order_info: EvmCrossChainDcaOrderInfo::try_from(intent)?,
+                    success_confirmation_data: serde_json::Value::Null,
+                },
+            ),
+        ),
+        IntentRequest::SingleChainLimitOrder(_) | IntentRequest::SingleChainDcaOrder(_) => {
+            return Err(report!(Error::LogicError(format!(
+                "Intent {} is not a cross chain intent",
+                scripted.intent_id
+            ))));
+        }
+    };
+    Ok(CrossChainSolverSuccessConfirmation {
+        order_id: scripted.intent_id.clone(),
+        src_chain_solver_address: src_chain_solver_address.to_string(),
+        generic_data,
+        order_type_specific_data,
+        chain_specific_data: SolverSuccessConfirmationData::EVM(SuccessConfirmationEVMData {
+            guard_contract: config.guard_contract.clone(),
+            auctioneer_signature: simulated_signature(),
+            order_type_data,
+        }),
+    })
+}
+
+fn interval_number(order_type_data: &OrderTypeFulfillmentData) -> ModelResult<u32> {
+    match order_type_data {
+        OrderTypeFulfillmentData::Dca(data) => Ok(data.interval_number),
+        OrderTypeFulfillmentData::Limit => Err(report!(Error::LogicError(
+            "DCA interval is missing in execution terms".to_string()
+        ))),
+    }
+}
+
+fn evm_start_data(
+    user_signature: &str,
+    order_type_data: StartEvmOrderTypeData,
+    config: &SimulatorConfig,
+) -> StartOrderEVMData {
+    StartOrderEVMData {
+        guard_contract: config.guard_contract.clone(),
+        user_signature: user_signature.to_string(),
+        auctioneer_start_permission_signature: simulated_signature(),
+        order_type_data,
+    }
+}
+
+fn single_chain_common_data(
+    bid: &SingleChainAuctionParticipate,
+    terms: &SingleChainExecutionTerms,
+    solver_deadline: u64,
+    evm_data: StartOrderEVMData,
+) -> SingleChainSolverStartPermission {
+    SingleChainSolverStartPermission {
+        solver_address: bid.solver_address.clone(),
+        expected_amount_out: bid.amount_out,
+        solver_deadline,
+        protocol_fee_transfer: terms.protocol_fee_transfer.clone(),
+        chain_specific_data: SingleChainSolverStartOrderData::EVM(evm_data),
+    }
+}
+
+fn cross_chain_common_data(
+    bid: &CrossChainAuctionParticipate,
+    terms: &CrossChainExecutionTerms,
+    solver_deadline: u64,
+    evm_data: StartOrderEVMData,
+    dest_chain_fulfillment_details: CrossChainSolverFulfillmentData,
+) -> CrossChainSolverStartPermission {
+    CrossChainSolverStartPermission {
+        src_chain_solver_address: bid.src_chain_solver_address.clone(),
+        dest_chain_solver_address: bid.dest_chain_solver_address.clone(),
+        expected_amount_out: bid.amount_out,
+        allow_swap: terms.allow_swap,
+        min_stablecoins_amount: terms.min_stablecoins_amount,
+        stablecoins_address: terms.stablecoin_address.clone(),
+        collateral_amount: terms.collateral_amount,
+        protocol_fee: terms.protocol_fee,
+        collateral_token_address: terms.collateral_token_address.clone(),
+        solver_deadline,
+        src_chain_specific_data: CrossChainSolverStartOrderData::EVM(evm_data),
+        dest_chain_fulfillment_details,
+    }
+}
+
+/// External calls are not simulated, EVM destination chains get a simple fulfillment
+fn dest_chain_fulfillment(
+    intent_id: &str,
+    generic_data: &CrossChainGenericData,
+    amount_out: u128,
+    solver_deadline: u64,
+    config: &SimulatorConfig,
+) -> CrossChainSolverFulfillmentData {
+    match generic_data.dest_chain_id.to_chain_type() {
+        ChainType::EVM => CrossChainSolverFulfillmentData::EVM(EvmCrossChainFulfillmentData {
+            dest_chain_guard_address: config.guard_contract.clone(),
+            requested_fulfillment: EvmCrossChainRequestedFulfillment::SimpleFulfillment(
+                SimpleEvmRequestedFulfillment {
+                    order_id: intent_id.to_string(),
+                    deadline: solver_deadline,
+                    token: generic_data.token_out.clone(),
+                    receiver: generic_data.destination_address.clone(),
+                    requested_amount: amount_out,
+                    extra_transfers: generic_data.extra_transfers.clone().unwrap_or_default(),
+                },
+            ),
+            destination_chain_auctioneer_signature: simulated_signature(),
+        }),
+        ChainType::Solana => CrossChainSolverFulfillmentData::Solana,
+        ChainType::Sui => CrossChainSolverFulfillmentData::Sui,
+    }
+}
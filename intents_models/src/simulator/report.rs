@@ -0,0 +1,48 @@
+use crate::models::types::order::{OrderStatus, OrderType};
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Outcome of every scripted auction, in scenario order
+pub struct SimulationReport {
+    pub auctions: Vec<AuctionOutcome>,
+}
+
+impl SimulationReport {
+    /// Every auction got a winner, which reported a fulfillment accepted by the simulator,
+    /// and no solver violated the protocol
+    pub fn is_success(&self) -> bool {
+        self.auctions
+            .iter()
+            .all(|auction| auction.tx_hash.is_some() && auction.issues.is_empty())
+    }
+
+    pub fn get_auction(&self, intent_id: &str) -> Option<&AuctionOutcome> {
+        self.auctions
+            .iter()
+            .find(|auction| auction.intent_id == intent_id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuctionOutcome {
+    pub intent_id: String,
+    pub order_type: OrderType,
+    /// Order status after the last accepted event
+    pub status: OrderStatus,
+    /// Number of valid bids at auction close
+    pub bids: usize,
+    pub winner: Option<WinningBid>,
+    /// Fulfillment transaction hash, set once the winner report is accepted
+    pub tx_hash: Option<String>,
+    /// Rejected solver messages and other protocol violations
+    pub issues: Vec<String>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WinningBid {
+    pub solver_id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_out: u128,
+}
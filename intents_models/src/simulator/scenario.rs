@@ -0,0 +1,115 @@
+use crate::constants::chains::ChainType;
+use crate::error::{Error, ModelResult};
+use crate::models::types::order::OrderTypeFulfillmentData;
+use crate::models::types::solver_types::ExecutionTerms;
+use crate::models::types::user_types::IntentRequest;
+use error_stack::{ResultExt, report};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Intents auctioned by the simulator
+pub struct Scenario {
+    pub intents: Vec<ScriptedIntent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptedIntent {
+    pub intent_id: String,
+    pub intent: IntentRequest,
+    pub execution_terms: ExecutionTerms,
+    /// Delay between simulation start and auction start, in milliseconds
+    #[serde(default)]
+    pub start_delay_ms: u64,
+    /// Auction duration in milliseconds, simulator default if missing
+    #[serde(default)]
+    pub auction_duration_ms: Option<u64>,
+}
+
+impl ScriptedIntent {
+    pub fn start_delay(&self) -> Duration {
+        Duration::from_millis(self.start_delay_ms)
+    }
+
+    pub fn auction_duration(&self) -> Option<Duration> {
+        self.auction_duration_ms.map(Duration::from_millis)
+    }
+}
+
+impl Scenario {
+    /// Reads and validates scenario JSON file
+    pub fn load(path: impl AsRef<Path>) -> ModelResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|err| {
+            report!(Error::LogicError(format!(
+                "Failed to read scenario {}",
+                path.display()
+            )))
+            .attach_printable(err.to_string())
+        })?;
+        Self::from_slice(&bytes)
+    }
+
+    /// Parses and validates scenario JSON
+    pub fn from_slice(bytes: &[u8]) -> ModelResult<Self> {
+        let scenario: Scenario = serde_json::from_slice(bytes).change_context(
+            Error::SerdeDeserialize("Failed to deserialize scenario".to_string()),
+        )?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Checks execution terms of every intent match the intent.
+    ///
+    /// Simulator only issues EVM start permissions, so source chains must be EVM chains
+    pub fn validate(&self) -> ModelResult<()> {
+        let mut intent_ids = HashSet::new();
+        for scripted in &self.intents {
+            let intent_id = &scripted.intent_id;
+            if !intent_ids.insert(intent_id.as_str()) {
+                return Err(report!(Error::ValidationError))
+                    .attach_printable(format!("Duplicated intent {intent_id}"));
+            }
+
+            let intent = &scripted.intent;
+            let terms_match = matches!(
+                (&scripted.execution_terms, intent),
+                (
+                    ExecutionTerms::SingleChain(_),
+                    IntentRequest::SingleChainLimitOrder(_) | IntentRequest::SingleChainDcaOrder(_),
+                ) | (
+                    ExecutionTerms::CrossChain(_),
+                    IntentRequest::CrossChainLimitOrder(_) | IntentRequest::CrossChainDcaOrder(_),
+                )
+            );
+            if !terms_match {
+                return Err(report!(Error::ValidationError)).attach_printable(format!(
+                    "Execution terms of intent {intent_id} don't match {} intent",
+                    intent.get_order_type()
+                ));
+            }
+
+            let order_type_data = scripted.execution_terms.get_order_type_fulfillment_data();
+            let is_dca = matches!(
+                intent,
+                IntentRequest::SingleChainDcaOrder(_) | IntentRequest::CrossChainDcaOrder(_)
+            );
+            if is_dca != matches!(order_type_data, OrderTypeFulfillmentData::Dca(_)) {
+                return Err(report!(Error::ValidationError)).attach_printable(format!(
+                    "Order type data {order_type_data:?} of intent {intent_id} doesn't match {} intent",
+                    intent.get_order_type()
+                ));
+            }
+
+            let src_chain = intent.get_src_chain();
+            if src_chain.to_chain_type() != ChainType::EVM {
+                return Err(report!(Error::ValidationError)).attach_printable(format!(
+                    "Intent {intent_id} source chain {src_chain} is not supported by simulator"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use crate::error::{Error, ModelResult};
+use crate::models::ws_messages::api_response::ApiResponse;
+use crate::models::ws_messages::auctioneer_message::WsAuctioneerMessage;
+use crate::models::ws_messages::protocol::LEGACY_PROTOCOL_VERSION;
+use crate::models::ws_messages::solver_message::WsSolverMessage;
+use crate::models::ws_messages::{encode_ws_auctioneer_message, handle_ws_solver_request_msg};
+use crate::simulator::SOLVER_ID_HEADER;
+use crate::simulator::state::SimulatorState;
+use error_stack::{ResultExt, report};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+type SharedState = Arc<Mutex<SimulatorState>>;
+
+pub(crate) async fn accept_solvers(listener: TcpListener, state: SharedState) {
+    loop {
+        match listener.accept().await {
+            Ok((tcp, address)) => {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = serve_solver(tcp, state).await {
+                        tracing::warn!("Solver connection {address} failed: {err:?}");
+                    }
+                });
+            }
+            Err(err) => tracing::warn!("Failed to accept solver connection: {err}"),
+        }
+    }
+}
+
+async fn serve_solver(tcp: TcpStream, state: SharedState) -> ModelResult<()> {
+    let mut solver_id_header = None;
+    // Callback signature is defined by tungstenite
+    #[allow(clippy::result_large_err)]
+    let mut ws = tokio_tungstenite::accept_hdr_async(tcp, |request: &Request, response| {
+        solver_id_header = request
+            .headers()
+            .get(SOLVER_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        Ok(response)
+    })
+    .await
+    .change_context(Error::WebSocketError("Handshake failed".to_string()))?;
+
+    let (connection_id, solver_id) = {
+        let mut state = lock(&state);
+        let connection_id = state.next_connection_id();
+        let solver_id = solver_id_header.unwrap_or_else(|| format!("solver-{connection_id}"));
+        (connection_id, solver_id)
+    };
+
+    let requested = match next_message(&mut ws).await? {
+        Some(WsSolverMessage::Register(features)) => features,
+        Some(message) => {
+            let error = ApiResponse::bad_request(format!(
+                "Solver must register first, received {}",
+                message.kind()
+            ));
+            send_message(
+                &mut ws,
+                &WsAuctioneerMessage::error(error),
+                LEGACY_PROTOCOL_VERSION,
+            )
+            .await?;
+            let _ = ws.close(None).await;
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel();
+    let registration = lock(&state).register(&solver_id, connection_id, &requested, outgoing_tx);
+    let version = match registration {
+        Ok((response, version)) => {
+            send_message(
+                &mut ws,
+                &WsAuctioneerMessage::register_response(response),
+                version,
+            )
+            .await?;
+            version
+        }
+        Err(err) => {
+            let error = ApiResponse::bad_request(err.current_context().to_string());
+            send_message(
+                &mut ws,
+                &WsAuctioneerMessage::error(error),
+                requested.protocol_version,
+            )
+            .await?;
+            let _ = ws.close(None).await;
+            return Ok(());
+        }
+    };
+    tracing::info!("Solver {solver_id} registered with protocol version {version}");
+
+    let (mut sink, mut stream) = ws.split();
+    let result = loop {
+        tokio::select! {
+            message = outgoing_rx.recv() => match message {
+                Some(message) => {
+                    if let Err(err) = send_message(&mut sink, &message, version).await {
+                        break Err(err);
+                    }
+                }
+                // Session was replaced by a new connection or simulation is over
+                None => {
+                    let _ = sink.send(Message::Close(None)).await;
+                    break Ok(());
+                }
+            },
+            message = next_message(&mut stream) => match message {
+                Ok(Some(message)) => {
+                    lock(&state).handle_message(&solver_id, message);
+                }
+                Ok(None) => break Ok(()),
+                Err(err) => break Err(err),
+            },
+        }
+    };
+    lock(&state).disconnect(&solver_id, connection_id);
+    tracing::info!("Solver {solver_id} disconnected");
+    result
+}
+
+/// Next solver message, None if connection is closed. Undecodable messages are skipped
+async fn next_message(
+    stream: &mut (impl Stream<Item = Result<Message, WsError>> + Unpin),
+) -> ModelResult<Option<WsSolverMessage>> {
+    loop {
+        let bytes = match stream.next().await {
+            Some(Ok(Message::Text(text))) => text.into_bytes(),
+            Some(Ok(Message::Binary(bytes))) => bytes,
+            Some(Ok(Message::Close(_))) | None => return Ok(None),
+            // Pings are answered by tungstenite
+            Some(Ok(_)) => continue,
+            Some(Err(err)) => {
+                return Err(report!(Error::WebSocketError(
+                    "Failed to read solver message".to_string()
+                ))
+                .attach_printable(err.to_string()));
+            }
+        };
+        match handle_ws_solver_request_msg(&bytes) {
+            Ok(message) => return Ok(Some(message)),
+            Err(err) => tracing::warn!("Failed to decode solver message: {err:?}"),
+        }
+    }
+}
+
+async fn send_message(
+    sink: &mut (impl Sink<Message, Error = WsError> + Unpin),
+    message: &WsAuctioneerMessage,
+    version: u32,
+) -> ModelResult<()> {
+    let bytes = encode_ws_auctioneer_message(message, version)?;
+    let text = String::from_utf8(bytes)
+        .change_context(Error::SerdeSerialize("Message is not UTF-8".to_string()))?;
+    sink.send(Message::Text(text))
+        .await
+        .change_context(Error::WebSocketError(format!(
+            "Failed to send {} message",
+            message.kind()
+        )))
+}
+
+pub(crate) fn lock(state: &SharedState) -> std::sync::MutexGuard<'_, SimulatorState> {
+    // State is never left inconsistent by a panic, so poisoning is ignored
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,692 @@
+use crate::error::{ModelResult, ReportDisplayExt};
+use crate::models::types::auction::AuctionBook;
+use crate::models::types::common::CommonDcaOrderState;
+use crate::models::types::order::{
+    DcaOrderFulfillmentData, OrderEvent, OrderStatus, OrderType, OrderTypeFulfillmentData,
+};
+use crate::models::types::single_chain::SingleChainSolverExecutionDetailsEnum;
+use crate::models::types::solver_types::{ExecutionTerms, SolverStartPermission};
+use crate::models::types::user_types::IntentRequest;
+use crate::models::ws_messages::api_response::ApiResponse;
+use crate::models::ws_messages::auctioneer_message::{
+    AuctionEndData, AuctionRequest, AuctionResult, RegisterResponseData, WsAuctioneerMessage,
+};
+use crate::models::ws_messages::protocol::{LEGACY_PROTOCOL_VERSION, ProtocolFeatures};
+use crate::models::ws_messages::solver_message::{
+    ParticipateAuction, SolverDstChainData, WsSolverMessage,
+};
+use crate::simulator::SimulatorConfig;
+use crate::simulator::permissions;
+use crate::simulator::report::{AuctionOutcome, SimulationReport, WinningBid};
+use crate::simulator::scenario::{Scenario, ScriptedIntent};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Notify, mpsc};
+
+struct SolverSession {
+    connection_id: u64,
+    features: ProtocolFeatures,
+    outgoing: mpsc::UnboundedSender<WsAuctioneerMessage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuctionPhase {
+    Scheduled,
+    Open,
+    Closed,
+}
+
+struct Winner {
+    solver_id: String,
+    permission: SolverStartPermission,
+}
+
+struct Auction {
+    scripted: ScriptedIntent,
+    phase: AuctionPhase,
+    status: OrderStatus,
+    /// Number of bids, known once the auction is closed
+    bids: usize,
+    winner: Option<Winner>,
+    tx_hash: Option<String>,
+    issues: Vec<String>,
+}
+
+/// Simulated auctioneer state shared by the auction driver and solver connections
+pub(crate) struct SimulatorState {
+    config: SimulatorConfig,
+    solvers: HashMap<String, SolverSession>,
+    auctions: HashMap<String, Auction>,
+    /// Bids of open auctions
+    book: AuctionBook,
+    /// Intent ids in scenario order
+    intent_ids: Vec<String>,
+    next_connection_id: u64,
+    /// Notified on solver registration and order status changes
+    changed: Arc<Notify>,
+}
+
+impl SimulatorState {
+    pub(crate) fn new(config: SimulatorConfig, scenario: Scenario, changed: Arc<Notify>) -> Self {
+        let intent_ids = scenario
+            .intents
+            .iter()
+            .map(|scripted| scripted.intent_id.clone())
+            .collect();
+        let auctions = scenario
+            .intents
+            .into_iter()
+            .map(|scripted| {
+                let auction = Auction {
+                    scripted,
+                    phase: AuctionPhase::Scheduled,
+                    status: OrderStatus::Auction,
+                    bids: 0,
+                    winner: None,
+                    tx_hash: None,
+                    issues: vec![],
+                };
+                (auction.scripted.intent_id.clone(), auction)
+            })
+            .collect();
+        Self {
+            config,
+            solvers: HashMap::new(),
+            auctions,
+            book: AuctionBook::default(),
+            intent_ids,
+            next_connection_id: 0,
+            changed,
+        }
+    }
+
+    pub(crate) fn next_connection_id(&mut self) -> u64 {
+        self.next_connection_id += 1;
+        self.next_connection_id
+    }
+
+    pub(crate) fn registered_solvers(&self) -> usize {
+        self.solvers.len()
+    }
+
+    /// Registers solver connection and returns register response with protocol version
+    /// of the connection. Replaces previous connection of the same solver
+    pub(crate) fn register(
+        &mut self,
+        solver_id: &str,
+        connection_id: u64,
+        requested: &ProtocolFeatures,
+        outgoing: mpsc::UnboundedSender<WsAuctioneerMessage>,
+    ) -> ModelResult<(RegisterResponseData, u32)> {
+        let features = self.config.features.negotiate(requested)?;
+        let version = features.protocol_version;
+
+        let pending_auction_results = self
+            .auctions
+            .values()
+            .filter_map(|auction| auction.pending_result(solver_id))
+            .collect();
+        let unfinished_orders = self
+            .auctions
+            .values()
+            .filter(|auction| {
+                auction.phase == AuctionPhase::Open && supports(&features, &auction.scripted.intent)
+            })
+            .map(|auction| auction.request())
+            .collect();
+        let response = RegisterResponseData {
+            solver_id: solver_id.to_string(),
+            status: "registered".to_string(),
+            pending_auction_results,
+            unfinished_orders,
+            negotiated_features: (requested.protocol_version != LEGACY_PROTOCOL_VERSION)
+                .then(|| features.clone()),
+        };
+
+        self.solvers.insert(
+            solver_id.to_string(),
+            SolverSession {
+                connection_id,
+                features,
+                outgoing,
+            },
+        );
+        self.changed.notify_one();
+        Ok((response, version))
+    }
+
+    /// Removes solver session, unless the solver has already reconnected
+    pub(crate) fn disconnect(&mut self, solver_id: &str, connection_id: u64) {
+        if self
+            .solvers
+            .get(solver_id)
+            .is_some_and(|session| session.connection_id == connection_id)
+        {
+            self.solvers.remove(solver_id);
+        }
+    }
+
+    /// Drops all solver sessions, closing their connections
+    pub(crate) fn disconnect_all(&mut self) {
+        self.solvers.clear();
+    }
+
+    /// Starts accepting bids and broadcasts auction request to solvers supporting the intent
+    pub(crate) fn open_auction(&mut self, intent_id: &str) {
+        let Some(auction) = self.auctions.get_mut(intent_id) else {
+            return;
+        };
+        let request = auction.request();
+        if let Err(err) = self.book.open(request.clone()) {
+            auction
+                .issues
+                .push(format!("Failed to open auction: {err:?}"));
+            return;
+        }
+        auction.phase = AuctionPhase::Open;
+        for session in self.solvers.values() {
+            if supports(&session.features, &request.intent) {
+                let _ = session
+                    .outgoing
+                    .send(WsAuctioneerMessage::auction_request(request.clone()));
+            }
+        }
+    }
+
+    /// Stops accepting bids and picks the winner by `AuctionBook` rules. Every bidder gets
+    /// auction result, only the winner gets start permission
+    pub(crate) fn close_auction(&mut self, intent_id: &str) {
+        let Some(auction) = self.auctions.get_mut(intent_id) else {
+            return;
+        };
+        let was_open = auction.phase == AuctionPhase::Open;
+        auction.phase = AuctionPhase::Closed;
+        // Auction failed to open, the issue is already recorded
+        if !was_open {
+            self.changed.notify_one();
+            return;
+        }
+        let closed = match self.book.close(intent_id) {
+            Ok(closed) => closed,
+            Err(err) => {
+                auction
+                    .issues
+                    .push(format!("Failed to close auction: {err:?}"));
+                self.changed.notify_one();
+                return;
+            }
+        };
+        auction.bids = closed.bids.len();
+        let Some(best_bid) = closed.winner() else {
+            auction.apply(OrderEvent::AuctionClosedWithoutBids);
+            self.changed.notify_one();
+            return;
+        };
+
+        let solver_deadline =
+            now_in_seconds() + solver_execution_duration(&auction.scripted.execution_terms);
+        let permission = match permissions::start_permission(
+            &auction.scripted,
+            &best_bid.participate,
+            solver_deadline,
+            &self.config,
+        ) {
+            Ok(permission) => permission,
+            Err(err) => {
+                auction
+                    .issues
+                    .push(format!("Failed to issue start permission: {err:?}"));
+                self.changed.notify_one();
+                return;
+            }
+        };
+
+        auction.apply(OrderEvent::BidReceived);
+        for (solver_id, result) in closed.results(&permission) {
+            send(
+                &self.solvers,
+                &solver_id,
+                WsAuctioneerMessage::auction_result(result),
+            );
+        }
+        auction.winner = Some(Winner {
+            solver_id: best_bid.solver_id.clone(),
+            permission,
+        });
+        self.changed.notify_one();
+    }
+
+    pub(crate) fn handle_message(&mut self, solver_id: &str, message: WsSolverMessage) {
+        match message {
+            WsSolverMessage::Register(_) => {
+                self.reject(solver_id, None, "Solver is already registered".to_string());
+            }
+            WsSolverMessage::Participate(participate) => {
+                self.handle_participate(solver_id, participate);
+            }
+            WsSolverMessage::GetStartPermissions(intent_id, order_type) => {
+                self.handle_get_start_permissions(solver_id, &intent_id, order_type);
+            }
+            WsSolverMessage::SingleChainOrderFulfilled(details) => {
+                self.handle_single_chain_fulfilled(solver_id, details);
+            }
+            WsSolverMessage::SolverDstChain(data) => {
+                self.handle_dst_chain_fulfilled(solver_id, data);
+            }
+        }
+    }
+
+    /// Every auction is closed, and no winner is executing an intent
+    pub(crate) fn is_settled(&self) -> bool {
+        self.auctions.values().all(|auction| {
+            auction.phase == AuctionPhase::Closed && auction.status != OrderStatus::Executing
+        })
+    }
+
+    pub(crate) fn report(&self) -> SimulationReport {
+        let auctions = self
+            .intent_ids
+            .iter()
+            .filter_map(|intent_id| self.auctions.get(intent_id))
+            .map(|auction| AuctionOutcome {
+                intent_id: auction.scripted.intent_id.clone(),
+                order_type: auction.scripted.intent.get_order_type(),
+                status: auction.status,
+                bids: auction.bids,
+                winner: auction.winner.as_ref().map(|winner| WinningBid {
+                    solver_id: winner.solver_id.clone(),
+                    amount_out: winner.permission.get_solver_amount_out(),
+                }),
+                tx_hash: auction.tx_hash.clone(),
+                issues: auction.issues_at_end(),
+            })
+            .collect();
+        SimulationReport { auctions }
+    }
+
+    fn handle_participate(&mut self, solver_id: &str, participate: ParticipateAuction) {
+        let intent_id = match &participate {
+            ParticipateAuction::Single(bid) => bid.intent_id.clone(),
+            ParticipateAuction::Multi(bid) => bid.intent_id.clone(),
+        };
+        let result = self
+            .check_solver_support(solver_id, &intent_id)
+            .and_then(|_| {
+                // New bid of the solver replaces the previous one
+                self.book
+                    .submit(solver_id, participate, now_in_millis())
+                    .map_err(|err| err.format())
+            });
+        if let Err(reason) = result {
+            self.reject(solver_id, Some(&intent_id), reason);
+        }
+    }
+
+    fn check_solver_support(&self, solver_id: &str, intent_id: &str) -> Result<(), String> {
+        let intent = &self
+            .auctions
+            .get(intent_id)
+            .ok_or_else(|| format!("Unknown intent {intent_id}"))?
+            .scripted
+            .intent;
+        if !self
+            .solvers
+            .get(solver_id)
+            .is_some_and(|session| supports(&session.features, intent))
+        {
+            return Err(format!(
+                "Solver {solver_id} didn't negotiate support of intent {intent_id}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sends start permission again, e.g. after it was lost by the solver
+    fn handle_get_start_permissions(
+        &mut self,
+        solver_id: &str,
+        intent_id: &str,
+        order_type: OrderType,
+    ) {
+        let result = self.auctions.get(intent_id).and_then(|auction| {
+            if auction.scripted.intent.get_order_type() != order_type {
+                return None;
+            }
+            auction.pending_result(solver_id)
+        });
+        match result {
+            Some(result) => send(
+                &self.solvers,
+                solver_id,
+                WsAuctioneerMessage::auction_result(result),
+            ),
+            None => self.reject(
+                solver_id,
+                Some(intent_id),
+                format!(
+                    "Solver {solver_id} has no start permission for {order_type} intent {intent_id}"
+                ),
+            ),
+        }
+    }
+
+    fn handle_single_chain_fulfilled(
+        &mut self,
+        solver_id: &str,
+        details: SingleChainSolverExecutionDetailsEnum,
+    ) {
+        let order_type_data = match &details {
+            SingleChainSolverExecutionDetailsEnum::Limit(_) => OrderTypeFulfillmentData::Limit,
+            SingleChainSolverExecutionDetailsEnum::Dca(details) => {
+                OrderTypeFulfillmentData::Dca(DcaOrderFulfillmentData {
+                    interval_number: details.interval_number,
+                })
+            }
+        };
+        let common_data = details.get_common_data();
+        let intent_id = &common_data.intent_id;
+        let event = self
+            .check_fulfillment(solver_id, intent_id, &common_data.tx_hash, &order_type_data)
+            .and_then(|(event, scripted)| {
+                if is_cross_chain(&scripted.intent) {
+                    return Err(format!(
+                        "Cross chain intent {intent_id} is reported as single chain"
+                    ));
+                }
+                let chain_id = scripted.intent.get_src_chain();
+                if common_data.chain_id != chain_id {
+                    return Err(format!(
+                        "Intent {intent_id} is reported on {}, but must be executed on {chain_id}",
+                        common_data.chain_id
+                    ));
+                }
+                Ok(event)
+            });
+        self.confirm_fulfillment(solver_id, intent_id, &common_data.tx_hash, event);
+    }
+
+    /// Checks destination chain fulfillment and sends success confirmation to the winner
+    fn handle_dst_chain_fulfilled(&mut self, solver_id: &str, data: SolverDstChainData) {
+        let intent_id = &data.intent_id;
+        let event = self
+            .check_fulfillment(
+                solver_id,
+                intent_id,
+                &data.tx_hash,
+                &data.order_type_specific_data,
+            )
+            .and_then(|(event, scripted)| {
+                let common_data =
+                    scripted
+                        .intent
+                        .try_get_cross_chain_common_data()
+                        .map_err(|_| {
+                            format!("Single chain intent {intent_id} is reported as cross chain")
+                        })?;
+                // Main transfer is a part of the fulfillment transaction
+                let has_extra_transfers = common_data.get_number_of_unique_receivers() > 1;
+                let has_extra_transfer_hashes = data
+                    .extra_transfers_tx_hashes
+                    .as_ref()
+                    .is_some_and(|hashes| !hashes.is_empty());
+                if has_extra_transfers && !has_extra_transfer_hashes {
+                    return Err(format!(
+                        "Extra transfers transaction hashes of intent {intent_id} are missing"
+                    ));
+                }
+                Ok(event)
+            });
+        if !self.confirm_fulfillment(solver_id, intent_id, &data.tx_hash, event) {
+            return;
+        }
+
+        let Some(auction) = self.auctions.get_mut(intent_id) else {
+            return;
+        };
+        let confirmation = auction
+            .winner
+            .as_ref()
+            .and_then(|winner| src_chain_solver_address(&winner.permission))
+            .map(|src_chain_solver_address| {
+                permissions::success_confirmation(
+                    &auction.scripted,
+                    src_chain_solver_address,
+                    data.order_type_specific_data,
+                    &self.config,
+                )
+            });
+        match confirmation {
+            Some(Ok(solver_success_confirmation)) => send(
+                &self.solvers,
+                solver_id,
+                WsAuctioneerMessage::auction_end(AuctionEndData {
+                    intent_id: intent_id.clone(),
+                    solver_success_confirmation,
+                }),
+            ),
+            Some(Err(err)) => auction
+                .issues
+                .push(format!("Failed to issue success confirmation: {err:?}")),
+            None => auction.issues.push(format!(
+                "Winner of intent {intent_id} has no cross chain start permission"
+            )),
+        }
+    }
+
+    /// Checks generic fulfillment data and returns the event confirming it
+    fn check_fulfillment(
+        &self,
+        solver_id: &str,
+        intent_id: &str,
+        tx_hash: &str,
+        order_type_data: &OrderTypeFulfillmentData,
+    ) -> Result<(OrderEvent, &ScriptedIntent), String> {
+        let auction = self
+            .auctions
+            .get(intent_id)
+            .ok_or_else(|| format!("Unknown intent {intent_id}"))?;
+        let winner = auction
+            .winner
+            .as_ref()
+            .filter(|winner| winner.solver_id == solver_id)
+            .ok_or_else(|| {
+                format!("Solver {solver_id} didn't win auction of intent {intent_id}")
+            })?;
+        if auction.status != OrderStatus::Executing {
+            return Err(format!(
+                "Intent {intent_id} is not executed, current status: {}",
+                auction.status
+            ));
+        }
+        if tx_hash.is_empty() {
+            return Err(format!(
+                "Fulfillment transaction hash of intent {intent_id} is empty"
+            ));
+        }
+        let permitted = winner.permission.get_order_type_fulfillment_data();
+        if *order_type_data != permitted {
+            return Err(format!(
+                "Intent {intent_id} is reported with {order_type_data:?}, but {permitted:?} was permitted"
+            ));
+        }
+        let solver_deadline = winner.permission.get_solver_deadline();
+        if now_in_seconds() > solver_deadline {
+            return Err(format!(
+                "Intent {intent_id} is reported after solver deadline {solver_deadline}"
+            ));
+        }
+
+        let event = match order_type_data {
+            OrderTypeFulfillmentData::Limit => OrderEvent::ExecutionConfirmed,
+            OrderTypeFulfillmentData::Dca(data) => {
+                confirmed_interval(&auction.scripted.intent, data.interval_number)?
+            }
+        };
+        Ok((event, &auction.scripted))
+    }
+
+    /// Applies confirmed `event` or rejects the report. Returns true if fulfillment is accepted
+    fn confirm_fulfillment(
+        &mut self,
+        solver_id: &str,
+        intent_id: &str,
+        tx_hash: &str,
+        event: Result<OrderEvent, String>,
+    ) -> bool {
+        let event = match event {
+            Ok(event) => event,
+            Err(reason) => {
+                self.reject(solver_id, Some(intent_id), reason);
+                return false;
+            }
+        };
+        let Some(auction) = self.auctions.get_mut(intent_id) else {
+            return false;
+        };
+        auction.apply(event);
+        auction.tx_hash = Some(tx_hash.to_string());
+        self.changed.notify_one();
+        true
+    }
+
+    /// Records the rejection on the intent auction and sends error to the solver
+    fn reject(&mut self, solver_id: &str, intent_id: Option<&str>, reason: String) {
+        tracing::warn!("Rejected message of solver {solver_id}: {reason}");
+        if let Some(auction) = intent_id.and_then(|intent_id| self.auctions.get_mut(intent_id)) {
+            auction.issues.push(reason.clone());
+        }
+        send(
+            &self.solvers,
+            solver_id,
+            WsAuctioneerMessage::error(ApiResponse::bad_request(reason)),
+        );
+    }
+}
+
+impl Auction {
+    fn apply(&mut self, event: OrderEvent) {
+        match self
+            .status
+            .transition(self.scripted.intent.get_order_type(), event)
+        {
+            Ok(status) => self.status = status,
+            Err(err) => self.issues.push(err.current_context().to_string()),
+        }
+    }
+
+    /// Issues including the fulfillment the winner didn't report
+    fn issues_at_end(&self) -> Vec<String> {
+        let mut issues = self.issues.clone();
+        if self.status == OrderStatus::Executing {
+            issues.push(format!(
+                "Fulfillment of intent {} was not reported",
+                self.scripted.intent_id
+            ));
+        }
+        issues
+    }
+
+    fn request(&self) -> AuctionRequest {
+        AuctionRequest {
+            intent_id: self.scripted.intent_id.clone(),
+            intent: self.scripted.intent.clone(),
+            execution_terms: self.scripted.execution_terms.clone(),
+        }
+    }
+
+    /// Start permission of `solver_id`, if it won the auction and is still executing the intent
+    fn pending_result(&self, solver_id: &str) -> Option<AuctionResult> {
+        let winner = self.winner.as_ref()?;
+        if winner.solver_id != solver_id || self.status != OrderStatus::Executing {
+            return None;
+        }
+        Some(AuctionResult {
+            intent_id: self.scripted.intent_id.clone(),
+            amount_out: winner.permission.get_solver_amount_out(),
+            solver_start_permission: Some(winner.permission.clone()),
+        })
+    }
+}
+
+// Solver may disconnect at any time, messages to it are dropped then
+fn send(solvers: &HashMap<String, SolverSession>, solver_id: &str, message: WsAuctioneerMessage) {
+    if let Some(session) = solvers.get(solver_id) {
+        let _ = session.outgoing.send(message);
+    }
+}
+
+fn supports(features: &ProtocolFeatures, intent: &IntentRequest) -> bool {
+    features.supports_order_type(intent.get_order_type())
+        && features.supports_chain(intent.get_src_chain())
+        && features.supports_chain(intent.get_dest_chain())
+}
+
+fn is_cross_chain(intent: &IntentRequest) -> bool {
+    matches!(
+        intent,
+        IntentRequest::CrossChainLimitOrder(_) | IntentRequest::CrossChainDcaOrder(_)
+    )
+}
+
+fn solver_execution_duration(terms: &ExecutionTerms) -> u64 {
+    match terms {
+        ExecutionTerms::SingleChain(terms) => terms.solver_execution_duration,
+        ExecutionTerms::CrossChain(terms) => terms.solver_execution_duration,
+    }
+}
+
+fn src_chain_solver_address(permission: &SolverStartPermission) -> Option<&str> {
+    match permission {
+        SolverStartPermission::CrossChainLimit(permission) => {
+            Some(&permission.common_data.src_chain_solver_address)
+        }
+        SolverStartPermission::CrossChainDca(permission) => {
+            Some(&permission.common_data.src_chain_solver_address)
+        }
+        SolverStartPermission::SingleChainLimit(_) | SolverStartPermission::SingleChainDca(_) => {
+            None
+        }
+    }
+}
+
+/// Event confirming execution of `interval_number` interval of DCA `intent`
+fn confirmed_interval(intent: &IntentRequest, interval_number: u32) -> Result<OrderEvent, String> {
+    let (dca_order_data, dca_state) = match intent {
+        IntentRequest::SingleChainDcaOrder(intent) => (
+            &intent.generic_data.common_dca_order_data,
+            &intent.generic_data.common_dca_state,
+        ),
+        IntentRequest::CrossChainDcaOrder(intent) => (
+            &intent.generic_data.common_dca_order_data,
+            &intent.generic_data.common_dca_state,
+        ),
+        IntentRequest::SingleChainLimitOrder(_) | IntentRequest::CrossChainLimitOrder(_) => {
+            return Err(format!(
+                "DCA interval is reported for {} intent",
+                intent.get_order_type()
+            ));
+        }
+    };
+    Ok(OrderEvent::IntervalConfirmed {
+        total_intervals: dca_order_data.total_intervals,
+        dca_state: CommonDcaOrderState {
+            total_executed_intervals: dca_state.total_executed_intervals + 1,
+            last_executed_interval_index: interval_number,
+        },
+    })
+}
+
+fn now_in_seconds() -> u64 {
+    now().as_secs()
+}
+
+fn now_in_millis() -> u64 {
+    now().as_millis() as u64
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("We don't live in the past")
+}
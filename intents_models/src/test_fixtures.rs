@@ -0,0 +1,141 @@
+//! Intents and auction requests shared by tests.
+//!
+//! Fixtures are JSON values in wire format, so tests can tweak single fields before
+//! deserializing them.
+
+use crate::models::types::user_types::IntentRequest;
+use crate::models::ws_messages::auctioneer_message::AuctionRequest;
+use serde_json::{Value, json};
+
+pub(crate) const USER: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+pub(crate) const RECEIVER: &str = "0x4444444444444444444444444444444444444444";
+pub(crate) const WETH_BASE: &str = "0x4200000000000000000000000000000000000006";
+pub(crate) const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+pub(crate) const USDC_ETHEREUM: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+/// Swap of 1 WETH to at least 3000 USDC on Base
+pub(crate) fn single_chain_limit_order() -> Value {
+    json!({
+        "type": "SingleChainLimitOrder",
+        "genericData": {
+            "user": USER,
+            "chainId": 8453,
+            "tokenIn": WETH_BASE,
+            "tokenOut": USDC_BASE,
+            "amountIn": "1000000000000000000",
+            "amountOutMin": "3000000000",
+            "destinationAddress": USER,
+            "deadline": 1700003600,
+            "stopLossTriggered": false,
+        },
+        "chainSpecificData": { "EVM": { "nonce": "123456789", "signature": "0x00" } },
+    })
+}
+
+/// `single_chain_limit_order` which also pays 1 USDC to `RECEIVER`
+pub(crate) fn single_chain_limit_order_with_extra_transfer() -> Value {
+    let mut intent = single_chain_limit_order();
+    intent["genericData"]["extraTransfers"] = json!([{
+        "token": USDC_BASE,
+        "receiver": RECEIVER,
+        "amount": "1000000",
+    }]);
+    intent
+}
+
+/// Transfer of 100 USDC from Ethereum to Base
+pub(crate) fn cross_chain_limit_order() -> Value {
+    json!({
+        "type": "CrossChainLimitOrder",
+        "genericData": {
+            "user": USER,
+            "srcChainId": 1,
+            "tokenIn": USDC_ETHEREUM,
+            "minStablecoinsAmount": "99000000",
+            "destChainId": 8453,
+            "tokenOut": USDC_BASE,
+            "amountOutMin": "98000000",
+            "destinationAddress": USER,
+            "deadline": 1700040000,
+            // SHA-256 of "{}"
+            "executionDetailsHash": "0x44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+            "amountIn": "100000000",
+            "stopLossTriggered": false,
+        },
+        "chainSpecificData": { "EVM": { "nonce": "0x2a", "signature": "0x00" } },
+    })
+}
+
+/// 10 hourly transfers of 100 USDC from Ethereum to Solana
+pub(crate) fn cross_chain_dca_order() -> Value {
+    json!({
+        "type": "CrossChainDcaOrder",
+        "genericData": {
+            "user": USER,
+            "srcChainId": 1,
+            "tokenIn": USDC_ETHEREUM,
+            "minStablecoinsAmount": "99000000",
+            "destChainId": 7565164,
+            "tokenOut": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            "amountOutMin": "98000000",
+            "destinationAddress": "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            "deadline": 1700040000,
+            // SHA-256 of "{}"
+            "executionDetailsHash": "0x44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+            "startTime": 1700000000,
+            "amountInPerInterval": "100000000",
+            "totalIntervals": 10,
+            "intervalDuration": 3600,
+            "totalExecutedIntervals": 0,
+            "lastExecutedIntervalIndex": 0,
+        },
+        "chainSpecificData": { "EVM": { "nonce": "0x2a", "signature": "0x00" } },
+    })
+}
+
+pub(crate) fn intent_request(intent: Value) -> IntentRequest {
+    serde_json::from_value(intent).expect("Valid intent")
+}
+
+/// Auction request for `single_chain_limit_order`
+pub(crate) fn single_chain_auction_request(intent_id: &str) -> Value {
+    json!({
+        "intent_id": intent_id,
+        "intent": single_chain_limit_order(),
+        "execution_terms": {
+            "type": "SingleChain",
+            "protocol_fee_transfer": {
+                "token": USDC_BASE,
+                "receiver": RECEIVER,
+                "amount": "1000",
+            },
+            "solver_execution_duration": 60,
+            "order_type_specific_data": { "type": "Limit" },
+        },
+    })
+}
+
+/// Auction request for `cross_chain_limit_order`
+pub(crate) fn cross_chain_auction_request(intent_id: &str, allow_swap: bool) -> Value {
+    json!({
+        "intent_id": intent_id,
+        "intent": cross_chain_limit_order(),
+        "execution_terms": {
+            "type": "CrossChain",
+            "collateral_amount": "1000000",
+            "protocol_fee": "50000",
+            "collateral_token_address": USDC_ETHEREUM,
+            "allow_swap": allow_swap,
+            "min_stablecoins_amount": "99000000",
+            "stablecoin_address": USDC_ETHEREUM,
+            "solver_execution_duration": 60,
+            "tokens_in_were_swapped_to_stablecoins": false,
+            "stablecoins_locked": "0",
+            "order_type_specific_data": { "type": "Limit" },
+        },
+    })
+}
+
+pub(crate) fn auction_request(request: Value) -> AuctionRequest {
+    serde_json::from_value(request).expect("Valid auction request")
+}
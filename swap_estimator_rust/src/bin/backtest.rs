@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+use std::process;
+
+use intents_models::log::init_tracing;
+use swap_estimator_rust::error::ReportDisplayExt;
+use swap_estimator_rust::monitoring::backtest::{
+    BacktestOrder, BacktestReport, backtest_tokens, fetch_price_series, load_price_series,
+    run_backtest,
+};
+use swap_estimator_rust::prices::codex::pricing::CodexProvider;
+
+const USAGE: &str =
+    "Usage: backtest <orders.json> [--prices <prices.csv|prices.json>] [--step <seconds>] [--json]";
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run().await {
+        eprintln!("backtest error: {err}");
+        process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), String> {
+    dotenv::dotenv().ok();
+    init_tracing(false);
+
+    let mut orders_path: Option<PathBuf> = None;
+    let mut prices_path: Option<PathBuf> = None;
+    let mut step: u64 = 60;
+    let mut json_output = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--prices" => prices_path = Some(args.next().ok_or(USAGE)?.into()),
+            "--step" => {
+                step = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .ok_or("Invalid or missing --step <seconds>")?;
+            }
+            "--json" => json_output = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if orders_path.is_none() => orders_path = Some(arg.into()),
+            _ => return Err(format!("Unexpected argument {arg}\n{USAGE}")),
+        }
+    }
+    let orders_path = orders_path.ok_or(USAGE)?;
+
+    let orders: Vec<BacktestOrder> = serde_json::from_str(
+        &std::fs::read_to_string(&orders_path)
+            .map_err(|e| format!("Failed to read {}: {e}", orders_path.display()))?,
+    )
+    .map_err(|e| format!("Failed to parse {}: {e}", orders_path.display()))?;
+
+    let series = match prices_path {
+        Some(path) => load_price_series(&path).map_err(|e| e.format())?,
+        None => {
+            // No recorded prices, replay Codex history over the lifetime of the orders
+            let codex_api_key = std::env::var("CODEX_API_KEY").map_err(|_| {
+                "CODEX_API_KEY environment variable is not set and no --prices file was given"
+                    .to_string()
+            })?;
+            let from = orders.iter().map(|order| order.submitted_at).min();
+            let to = orders
+                .iter()
+                .map(|order| order.pending_trade.deadline)
+                .max();
+            let (Some(from), Some(to)) = (from, to) else {
+                return Err("No orders to backtest".to_string());
+            };
+            let provider = CodexProvider::new(codex_api_key);
+            fetch_price_series(&provider, &backtest_tokens(&orders), from, to, step)
+                .await
+                .map_err(|e| format!("Failed to fetch historical prices: {}", e.format()))?
+        }
+    };
+
+    let report = run_backtest(series, orders)
+        .await
+        .map_err(|e| format!("Backtest failed: {}", e.format()))?;
+
+    if json_output {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize report: {e}"))?;
+        println!("{json}");
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+fn print_report(report: &BacktestReport) {
+    let time = |timestamp: Option<u64>| timestamp.map_or("-".to_string(), |t| t.to_string());
+    println!(
+        "{:<24} {:>12} {:>12} {:<18} {:>12} {:>12} {:>8}",
+        "order_id", "submitted", "flagged", "alert", "filled", "expired", "lead(s)"
+    );
+    for outcome in report.outcomes.iter() {
+        println!(
+            "{:<24} {:>12} {:>12} {:<18} {:>12} {:>12} {:>8}{}",
+            outcome.order_id,
+            outcome.submitted_at,
+            time(outcome.flagged_at),
+            outcome.alert.as_deref().unwrap_or("-"),
+            time(outcome.filled_at),
+            time(outcome.expired_at),
+            outcome
+                .lead_time()
+                .map_or("-".to_string(), |lead| lead.to_string()),
+            if outcome.price_data_missing {
+                " (missing prices)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    let summary = &report.summary;
+    println!();
+    println!(
+        "orders: {}, flagged: {}, filled: {}",
+        summary.orders, summary.flagged, summary.filled
+    );
+    println!(
+        "flagged and filled: {}, flagged but not filled: {}, filled but never flagged: {}",
+        summary.flagged_and_filled, summary.flagged_not_filled, summary.missed_fills
+    );
+    match summary.mean_lead_time {
+        Some(lead) => println!("mean lead time before fill: {lead:.1}s"),
+        None => println!("mean lead time before fill: -"),
+    }
+}
@@ -1,12 +1,26 @@
 use std::collections::{HashMap, HashSet};
 use std::process;
+use std::sync::Arc;
+use std::time::Duration;
 
 use intents_models::constants::chains::ChainId;
 use intents_models::log::init_tracing;
 use intents_models::models::types::order::OrderTypeFulfillmentData;
-use swap_estimator_rust::monitoring::manager::{MonitorManager, PendingTrade};
+use intents_models::network::nats::NatsManager;
+use swap_estimator_rust::monitoring::client::MonitorClient;
+use swap_estimator_rust::monitoring::manager::{MonitorConfig, MonitorManager, PendingTrade};
 use swap_estimator_rust::monitoring::messages::{MonitorAlert, MonitorRequest};
+use swap_estimator_rust::monitoring::nats::{
+    MONITOR_ALERTS_SUBJECT, MONITOR_REQUESTS_SUBJECT, publish_monitor_alerts,
+    serve_monitor_requests,
+};
+use swap_estimator_rust::monitoring::snapshot::JsonFileMonitorSnapshotStore;
 use swap_estimator_rust::prices::TokenId;
+use swap_estimator_rust::prices::codex::pricing::CodexProvider;
+use swap_estimator_rust::prices::metadata_store::{
+    DEFAULT_METADATA_REFRESH_SECS, JsonFileTokenMetadataStore, TokenMetadataCache,
+    TokenMetadataStore,
+};
 use swap_estimator_rust::utils::get_timestamp;
 use tokio::io::{self, AsyncBufReadExt, BufReader};
 use tokio::sync::{broadcast, mpsc, oneshot};
@@ -31,8 +45,80 @@ async fn run() -> Result<(), String> {
     // Requests channel (this binary -> manager)
     let (monitor_tx, monitor_rx) = mpsc::channel::<MonitorRequest>(100);
 
+    // Optionally share this monitor with other solver instances through NATS
+    if let Ok(nats_url) = std::env::var("NATS_URL") {
+        let nats = NatsManager::new(
+            nats_url,
+            std::env::var("NATS_USER").unwrap_or_default(),
+            std::env::var("NATS_PASSWORD").unwrap_or_default(),
+            std::env::var("NATS_TLS_CERT_PATH").ok(),
+            1024 * 1024,
+            32,
+            1024,
+            16,
+        )
+        .await
+        .map_err(|e| format!("Failed to connect to NATS: {e:?}"))?;
+
+        tokio::spawn(publish_monitor_alerts(
+            nats.retyped(),
+            MONITOR_ALERTS_SUBJECT,
+            alert_tx.subscribe(),
+        ));
+        let monitor = MonitorClient::new(monitor_tx.clone());
+        tokio::spawn(async move {
+            if let Err(e) = serve_monitor_requests(nats, MONITOR_REQUESTS_SUBJECT, monitor).await {
+                eprintln!("Monitor NATS service stopped with error: {e:?}");
+            }
+        });
+        println!(
+            "Monitor exposed over NATS on {MONITOR_REQUESTS_SUBJECT} / {MONITOR_ALERTS_SUBJECT}"
+        );
+    }
+
+    // Optionally serve the HTTP control API, e.g. MONITOR_HTTP_ADDR=127.0.0.1:8080
+    let http_api = match std::env::var("MONITOR_HTTP_ADDR") {
+        Ok(addr) => Some(spawn_http_api(
+            &addr,
+            MonitorClient::new(monitor_tx.clone()),
+            alert_tx.clone(),
+        )?),
+        Err(_) => None,
+    };
+
     // Spawn manager
-    let manager = MonitorManager::new(monitor_rx, alert_tx, codex_api_key, (true, 5000));
+    let mut manager = MonitorManager::new(
+        monitor_rx,
+        alert_tx,
+        CodexProvider::new(codex_api_key),
+        MonitorConfig::polling(Duration::from_millis(5000)),
+    );
+    // Keep token metadata across restarts, e.g. TOKEN_METADATA_JSON_PATH=./token_metadata.json
+    if let Some(store) = open_token_metadata_store().await? {
+        manager = manager.with_metadata_cache(TokenMetadataCache::new(
+            store,
+            Duration::from_secs(DEFAULT_METADATA_REFRESH_SECS),
+        ));
+    }
+    // Resume the orders monitored before a restart, e.g. MONITOR_SNAPSHOT_PATH=./monitor_snapshot.json
+    if let Ok(path) = std::env::var("MONITOR_SNAPSHOT_PATH") {
+        let interval = match std::env::var("MONITOR_SNAPSHOT_INTERVAL_SECS") {
+            Ok(secs) => Duration::from_secs(
+                secs.parse()
+                    .map_err(|e| format!("Invalid MONITOR_SNAPSHOT_INTERVAL_SECS {secs}: {e}"))?,
+            ),
+            Err(_) => Duration::from_secs(30),
+        };
+        manager = manager.with_snapshot_store(
+            Arc::new(JsonFileMonitorSnapshotStore::new(path.clone())),
+            interval,
+        );
+        // A broken snapshot must not keep the monitor down, solvers resend their orders anyway
+        match manager.restore().await {
+            Ok(restored) => println!("Restored {restored} orders from {path}"),
+            Err(e) => eprintln!("Failed to restore monitor snapshot from {path}: {e:?}"),
+        }
+    }
     tokio::spawn(async move {
         if let Err(e) = manager.run().await {
             eprintln!("MonitorManager stopped with error: {e:?}");
@@ -51,6 +137,37 @@ async fn run() -> Result<(), String> {
                         "[ALERT] Swap is feasible for order_id={order_id}, order_type_fulfillment_data={order_type_fulfillment_data:?}"
                     );
                 }
+                MonitorAlert::StopLossTriggered {
+                    order_id,
+                    peak_ratio,
+                    trigger_ratio,
+                    current_ratio,
+                    ..
+                } => {
+                    println!(
+                        "[ALERT] Stop loss triggered for order_id={order_id}, peak={peak_ratio}, trigger={trigger_ratio}, current={current_ratio}"
+                    );
+                }
+                MonitorAlert::TakeProfitReached {
+                    order_id,
+                    take_profit_min_out,
+                    estimated_amount_out,
+                    ..
+                } => {
+                    println!(
+                        "[ALERT] Take profit reached for order_id={order_id}, take_profit_min_out={take_profit_min_out}, estimated_amount_out={estimated_amount_out}"
+                    );
+                }
+                MonitorAlert::OrderExpired {
+                    order_id, deadline, ..
+                } => {
+                    println!("[ALERT] Order expired: order_id={order_id}, deadline={deadline}");
+                }
+                MonitorAlert::PriceDataMissing {
+                    order_id, missing, ..
+                } => {
+                    println!("[ALERT] Missing prices for order_id={order_id}: {missing:?}");
+                }
             }
         }
     });
@@ -62,11 +179,14 @@ async fn run() -> Result<(), String> {
     );
     println!("  remove <order_id>");
     println!("  prices <chain:address> [chain:address...]");
+    println!("  explain <order_id>");
+    println!("  metrics");
     println!("  quit");
 
     let stdin = BufReader::new(io::stdin());
     let mut lines = stdin.lines();
 
+    let mut quit = false;
     while let Ok(Some(line)) = lines.next_line().await {
         let line = line.trim();
         if line.is_empty() {
@@ -78,6 +198,7 @@ async fn run() -> Result<(), String> {
         match cmd {
             "quit" | "exit" => {
                 println!("Exiting…");
+                quit = true;
                 break;
             }
 
@@ -169,6 +290,8 @@ async fn run() -> Result<(), String> {
                             order_type_fulfillment_data: OrderTypeFulfillmentData::Limit,
                             extra_expenses: HashMap::new(),
                             stablecoin_swap_info: None,
+                            limit_order_data: None,
+                            stop_loss_initial_ratio: None,
                         },
                         solver_last_bid,
                     })
@@ -226,15 +349,125 @@ async fn run() -> Result<(), String> {
                 }
             }
 
+            "explain" => {
+                let order_id = match parts.next() {
+                    Some(v) => v.to_string(),
+                    None => {
+                        eprintln!("Usage: explain <order_id>");
+                        continue;
+                    }
+                };
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = monitor_tx
+                    .send(MonitorRequest::ExplainOrder { order_id, resp: tx })
+                    .await
+                {
+                    eprintln!("Failed to send ExplainOrder: {e}");
+                    continue;
+                }
+                match rx.await {
+                    Ok(Ok(explanation)) => match serde_json::to_string_pretty(&explanation) {
+                        Ok(json) => println!("{json}"),
+                        Err(e) => eprintln!("Failed to serialize explanation: {e}"),
+                    },
+                    Ok(Err(e)) => eprintln!("ExplainOrder error: {e:?}"),
+                    Err(e) => eprintln!("ExplainOrder oneshot recv error: {e}"),
+                }
+            }
+
+            "metrics" => {
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = monitor_tx
+                    .send(MonitorRequest::GetMetrics { resp: tx })
+                    .await
+                {
+                    eprintln!("Failed to send GetMetrics: {e}");
+                    continue;
+                }
+                match rx.await {
+                    Ok(Ok(metrics)) => print!("{}", metrics.to_prometheus()),
+                    Ok(Err(e)) => eprintln!("GetMetrics error: {e:?}"),
+                    Err(e) => eprintln!("GetMetrics oneshot recv error: {e}"),
+                }
+            }
+
             other => {
                 eprintln!("Unknown command '{other}'");
             }
         }
     }
 
+    // Without a terminal attached stdin is closed right away, keep serving the HTTP API
+    if !quit && let Some(http_api) = http_api {
+        println!("stdin closed, serving the HTTP API only");
+        http_api
+            .await
+            .map_err(|e| format!("HTTP API task failed: {e}"))?;
+    }
+
     Ok(())
 }
 
+#[cfg(feature = "http-api")]
+fn spawn_http_api(
+    addr: &str,
+    monitor: MonitorClient,
+    alerts: broadcast::Sender<MonitorAlert>,
+) -> Result<tokio::task::JoinHandle<()>, String> {
+    let addr: std::net::SocketAddr = addr
+        .parse()
+        .map_err(|e| format!("Invalid MONITOR_HTTP_ADDR {addr}: {e}"))?;
+    println!("Monitor HTTP API listening on http://{addr}");
+    Ok(tokio::spawn(async move {
+        if let Err(e) =
+            swap_estimator_rust::monitoring::http::serve_http_api(addr, monitor, alerts).await
+        {
+            eprintln!("Monitor HTTP API stopped with error: {e:?}");
+        }
+    }))
+}
+
+#[cfg(not(feature = "http-api"))]
+fn spawn_http_api(
+    _addr: &str,
+    _monitor: MonitorClient,
+    _alerts: broadcast::Sender<MonitorAlert>,
+) -> Result<tokio::task::JoinHandle<()>, String> {
+    Err("MONITOR_HTTP_ADDR is set but the monitor was built without the http-api feature".into())
+}
+
+async fn open_token_metadata_store() -> Result<Option<Arc<dyn TokenMetadataStore>>, String> {
+    if let Ok(path) = std::env::var("TOKEN_METADATA_SQLITE_PATH") {
+        return open_sqlite_token_metadata_store(&path).map(Some);
+    }
+    match std::env::var("TOKEN_METADATA_JSON_PATH") {
+        Ok(path) => {
+            let store = JsonFileTokenMetadataStore::open(&path)
+                .await
+                .map_err(|e| format!("Failed to open token metadata file {path}: {e:?}"))?;
+            println!("Token metadata stored in {path}");
+            Ok(Some(Arc::new(store)))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite_token_metadata_store(path: &str) -> Result<Arc<dyn TokenMetadataStore>, String> {
+    let store = swap_estimator_rust::prices::metadata_store::SqliteTokenMetadataStore::open(path)
+        .map_err(|e| format!("Failed to open token metadata database {path}: {e:?}"))?;
+    println!("Token metadata stored in SQLite database {path}");
+    Ok(Arc::new(store))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite_token_metadata_store(_path: &str) -> Result<Arc<dyn TokenMetadataStore>, String> {
+    Err(
+        "TOKEN_METADATA_SQLITE_PATH is set but the monitor was built without the sqlite feature"
+            .into(),
+    )
+}
+
 fn parse_chain_id(s: &str) -> Option<ChainId> {
     // Parse s to u32
     if let Ok(id_num) = s.parse::<u32>() {
@@ -24,6 +24,9 @@ pub enum Error {
     #[error("Token not found: {0}")]
     TokenNotFound(String),
 
+    #[error("Order not found: {0}")]
+    OrderNotFound(String),
+
     #[error("Aggregator error: {0}")]
     AggregatorError(String),
 
@@ -42,6 +45,9 @@ pub enum Error {
     #[error("Logic Error: {0}")]
     LogicError(String),
 
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
     #[error("Unknown error")]
     Unknown,
 }
@@ -0,0 +1,536 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use error_stack::{ResultExt as _, report};
+use intents_models::constants::chains::ChainId;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::{
+    error::{Error, EstimatorResult},
+    monitoring::{
+        manager::{MonitorConfig, MonitorManager, PendingTrade, required_tokens},
+        messages::MonitorAlert,
+    },
+    prices::{
+        PriceEvent, PriceProvider, TokenId, TokenMetadata, TokenPrice,
+        codex::pricing::CodexProvider, valuation::PriceSource,
+    },
+    utils::{Clock, ManualClock},
+};
+
+/// Historical prices per token, keyed by unix timestamp (seconds). Same shape as
+/// `CodexProvider::fetch_historical_prices` returns.
+pub type PriceSeries = HashMap<TokenId, BTreeMap<u64, TokenPrice>>;
+
+/// Row of a recorded price file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricePoint {
+    pub timestamp: u64,
+    pub token: TokenId,
+    pub price: f64,
+    pub decimals: u8,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestOrder {
+    pub pending_trade: PendingTrade,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub solver_last_bid: Option<u128>,
+    /// Timestamp (in seconds) when the order started being monitored
+    pub submitted_at: u64,
+    /// Timestamp (in seconds) when the order was actually filled, if it was
+    #[serde(default)]
+    pub filled_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestOutcome {
+    pub order_id: String,
+    pub submitted_at: u64,
+    /// First time the monitor flagged the order, and the alert it sent
+    pub flagged_at: Option<u64>,
+    pub alert: Option<String>,
+    pub expired_at: Option<u64>,
+    pub price_data_missing: bool,
+    pub filled_at: Option<u64>,
+}
+
+impl BacktestOutcome {
+    /// Seconds between the monitor flagging the order and the real fill. Negative when the
+    /// order was filled before the monitor noticed.
+    pub fn lead_time(&self) -> Option<i64> {
+        Some(self.filled_at? as i64 - self.flagged_at? as i64)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestSummary {
+    pub orders: usize,
+    pub flagged: usize,
+    pub filled: usize,
+    pub flagged_and_filled: usize,
+    /// Flagged by the monitor, never filled
+    pub flagged_not_filled: usize,
+    /// Filled, never flagged by the monitor
+    pub missed_fills: usize,
+    pub mean_lead_time: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub outcomes: Vec<BacktestOutcome>,
+    pub summary: BacktestSummary,
+}
+
+impl BacktestReport {
+    fn new(outcomes: Vec<BacktestOutcome>) -> Self {
+        let mut summary = BacktestSummary {
+            orders: outcomes.len(),
+            ..Default::default()
+        };
+        let mut lead_times = Vec::new();
+        for outcome in outcomes.iter() {
+            let flagged = outcome.flagged_at.is_some();
+            let filled = outcome.filled_at.is_some();
+            summary.flagged += flagged as usize;
+            summary.filled += filled as usize;
+            match (flagged, filled) {
+                (true, true) => summary.flagged_and_filled += 1,
+                (true, false) => summary.flagged_not_filled += 1,
+                (false, true) => summary.missed_fills += 1,
+                (false, false) => {}
+            }
+            lead_times.extend(outcome.lead_time());
+        }
+        if !lead_times.is_empty() {
+            summary.mean_lead_time =
+                Some(lead_times.iter().sum::<i64>() as f64 / lead_times.len() as f64);
+        }
+        Self { outcomes, summary }
+    }
+}
+
+/// Price provider serving the last price of `series` at or before the replay clock
+#[derive(Debug, Clone)]
+pub struct ReplayPriceProvider {
+    series: Arc<PriceSeries>,
+    clock: ManualClock,
+}
+
+impl ReplayPriceProvider {
+    pub fn new(series: PriceSeries, clock: ManualClock) -> Self {
+        Self {
+            series: Arc::new(series),
+            clock,
+        }
+    }
+
+    fn price_at(&self, token: &TokenId) -> Option<TokenPrice> {
+        let points = self.series.get(token)?;
+        points
+            .range(..=self.clock.now())
+            .next_back()
+            .map(|(_, price)| *price)
+    }
+}
+
+#[async_trait::async_trait]
+impl PriceProvider for ReplayPriceProvider {
+    fn source(&self) -> PriceSource {
+        PriceSource::Replay
+    }
+
+    async fn get_tokens_price(
+        &self,
+        tokens: &[TokenId],
+        _with_subscriptions: bool,
+    ) -> EstimatorResult<HashMap<TokenId, TokenPrice>> {
+        Ok(tokens
+            .iter()
+            .filter_map(|token| Some((token.clone(), self.price_at(token)?)))
+            .collect())
+    }
+
+    async fn get_tokens_prices_events(&self) -> EstimatorResult<broadcast::Receiver<PriceEvent>> {
+        // Prices only move when the replay polls them
+        let (_tx, rx) = broadcast::channel(1);
+        Ok(rx)
+    }
+
+    async fn subscribe_to_token(&self, _token: TokenId) -> EstimatorResult<()> {
+        Ok(())
+    }
+
+    async fn unsubscribe_from_token(&self, _token: TokenId) -> EstimatorResult<bool> {
+        Ok(false)
+    }
+
+    async fn get_tokens_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+        Ok(tokens
+            .iter()
+            .filter_map(|token| {
+                let (_, price) = self.series.get(token)?.first_key_value()?;
+                Some((
+                    token.clone(),
+                    TokenMetadata {
+                        name: token.address.clone(),
+                        symbol: token.address.clone(),
+                        decimals: price.decimals,
+                    },
+                ))
+            })
+            .collect())
+    }
+}
+
+/// Replays `series` through the `MonitorManager` evaluation path and reports when each order
+/// would have been flagged. Prices are re-polled at every timestamp of the series, orders are
+/// submitted at their `submitted_at` and expire after their deadline.
+pub async fn run_backtest(
+    series: PriceSeries,
+    mut orders: Vec<BacktestOrder>,
+) -> EstimatorResult<BacktestReport> {
+    orders.sort_by_key(|order| order.submitted_at);
+    let (Some(start), Some(end)) = (
+        orders.first().map(|order| order.submitted_at),
+        orders
+            .iter()
+            .map(|order| order.pending_trade.deadline)
+            .max(),
+    ) else {
+        return Ok(BacktestReport::default());
+    };
+
+    let mut timeline: BTreeSet<u64> = series
+        .values()
+        .flat_map(|points| points.keys().copied())
+        .filter(|timestamp| (start..=end).contains(timestamp))
+        .collect();
+    timeline.extend(orders.iter().map(|order| order.submitted_at));
+    // Lets the last orders expire
+    timeline.insert(end.saturating_add(1));
+
+    let clock = ManualClock::new(start);
+    let (alert_sender, mut alerts) = broadcast::channel(orders.len().max(16) * 4);
+    let (_request_sender, request_receiver) = mpsc::channel(1);
+    // Intervals are unused, the replay drives the manager step by step
+    let mut manager = MonitorManager::new(
+        request_receiver,
+        alert_sender,
+        ReplayPriceProvider::new(series, clock.clone()),
+        MonitorConfig::polling(Duration::from_secs(1)),
+    )
+    .with_clock(Arc::new(clock.clone()));
+
+    let mut outcomes: Vec<BacktestOutcome> = orders
+        .iter()
+        .map(|order| BacktestOutcome {
+            order_id: order.pending_trade.order_id.clone(),
+            submitted_at: order.submitted_at,
+            flagged_at: None,
+            alert: None,
+            expired_at: None,
+            price_data_missing: false,
+            filled_at: order.filled_at,
+        })
+        .collect();
+    let indexes: HashMap<String, usize> = outcomes
+        .iter()
+        .enumerate()
+        .map(|(index, outcome)| (outcome.order_id.clone(), index))
+        .collect();
+
+    let mut pending_orders = orders.into_iter().peekable();
+    for timestamp in timeline {
+        clock.set(timestamp);
+        manager.clean_expired_orders().await;
+        while let Some(order) = pending_orders.next_if(|order| order.submitted_at <= timestamp) {
+            let order_id = order.pending_trade.order_id.clone();
+            if let Err(error) = manager
+                .check_swap_feasibility(order.pending_trade, order.solver_last_bid)
+                .await
+            {
+                tracing::warn!("Backtest order {} was not monitored: {:?}", order_id, error);
+            }
+            record_alerts(&mut alerts, timestamp, &indexes, &mut outcomes);
+        }
+        manager.poll_prices(&HashSet::new()).await?;
+        record_alerts(&mut alerts, timestamp, &indexes, &mut outcomes);
+    }
+
+    Ok(BacktestReport::new(outcomes))
+}
+
+fn record_alerts(
+    alerts: &mut broadcast::Receiver<MonitorAlert>,
+    timestamp: u64,
+    indexes: &HashMap<String, usize>,
+    outcomes: &mut [BacktestOutcome],
+) {
+    loop {
+        let alert = match alerts.try_recv() {
+            Ok(alert) => alert,
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                tracing::warn!("Backtest lagged, {} alerts skipped", skipped);
+                continue;
+            }
+            Err(_) => return,
+        };
+        let Some(outcome) = indexes
+            .get(alert.order_id())
+            .map(|index| &mut outcomes[*index])
+        else {
+            continue;
+        };
+        let kind = match alert {
+            MonitorAlert::SwapIsFeasible { .. }
+            | MonitorAlert::TakeProfitReached { .. }
+            | MonitorAlert::StopLossTriggered { .. } => alert.kind(),
+            MonitorAlert::OrderExpired { .. } => {
+                outcome.expired_at.get_or_insert(timestamp);
+                continue;
+            }
+            MonitorAlert::PriceDataMissing { .. } => {
+                outcome.price_data_missing = true;
+                continue;
+            }
+        };
+        if outcome.flagged_at.is_none() {
+            outcome.flagged_at = Some(timestamp);
+            outcome.alert = Some(kind.to_string());
+        }
+    }
+}
+
+/// Every token whose price history is needed to replay `orders`
+pub fn backtest_tokens(orders: &[BacktestOrder]) -> HashSet<TokenId> {
+    orders
+        .iter()
+        .flat_map(|order| required_tokens(&order.pending_trade))
+        .collect()
+}
+
+/// Reads a recorded price series, either a JSON array of `PricePoint`s or a CSV file with
+/// `timestamp,chain,address,price,decimals` rows
+pub fn load_price_series(path: &Path) -> EstimatorResult<PriceSeries> {
+    let content = std::fs::read_to_string(path).map_err(|error| {
+        report!(Error::StorageError(format!(
+            "Failed to read price series {}: {error}",
+            path.display()
+        )))
+    })?;
+    let points = if path.extension().is_some_and(|extension| extension == "csv") {
+        parse_price_points_csv(&content)?
+    } else {
+        serde_json::from_str(&content).change_context(Error::SerdeDeserialize(format!(
+            "Failed to parse price series {}",
+            path.display()
+        )))?
+    };
+    Ok(price_series_from_points(points))
+}
+
+pub fn parse_price_points_csv(content: &str) -> EstimatorResult<Vec<PricePoint>> {
+    let mut points = Vec::new();
+    for (line_number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("timestamp") {
+            continue;
+        }
+        let invalid_row = || {
+            report!(Error::ParseError)
+                .attach_printable(format!("Invalid price row {}: {line}", line_number + 1))
+        };
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [timestamp, chain, address, price, decimals] = fields[..] else {
+            return Err(invalid_row());
+        };
+        let chain = ChainId::try_from(chain).map_err(|_| invalid_row())?;
+        points.push(PricePoint {
+            timestamp: timestamp.parse().map_err(|_| invalid_row())?,
+            token: TokenId::new(chain, address.to_string()),
+            price: price.parse().map_err(|_| invalid_row())?,
+            decimals: decimals.parse().map_err(|_| invalid_row())?,
+        });
+    }
+    Ok(points)
+}
+
+/// Groups points per token, with token ids normalized the way the monitor looks them up
+pub fn price_series_from_points(points: Vec<PricePoint>) -> PriceSeries {
+    let mut series = PriceSeries::new();
+    for point in points {
+        let token = TokenId::new_for_codex(point.token.chain, &point.token.address);
+        series.entry(token).or_default().insert(
+            point.timestamp,
+            TokenPrice {
+                price: point.price,
+                decimals: point.decimals,
+            },
+        );
+    }
+    series
+}
+
+/// Fetches a price every `step` seconds between `from` and `to` for every token from Codex.
+/// Codex historical prices come with default decimals, so metadata decimals are applied on top.
+pub async fn fetch_price_series(
+    provider: &CodexProvider,
+    tokens: &HashSet<TokenId>,
+    from: u64,
+    to: u64,
+    step: u64,
+) -> EstimatorResult<PriceSeries> {
+    // Same limit as the Codex price-only batches
+    const BATCH_SIZE: usize = 200;
+
+    let tokens: Vec<TokenId> = tokens
+        .iter()
+        .map(|token| TokenId::new_for_codex(token.chain, &token.address))
+        .collect();
+    let tokens_and_dates: Vec<(TokenId, u64)> = tokens
+        .iter()
+        .flat_map(|token| {
+            (from..=to)
+                .step_by(step.max(1) as usize)
+                .map(|timestamp| (token.clone(), timestamp))
+        })
+        .collect();
+
+    let mut series = PriceSeries::new();
+    for batch in tokens_and_dates.chunks(BATCH_SIZE) {
+        for (token, points) in provider.fetch_historical_prices(batch).await? {
+            series.entry(token).or_default().extend(points);
+        }
+    }
+
+    let metadata = provider.fetch_token_metadata(&tokens).await?;
+    for (token, points) in series.iter_mut() {
+        if let Some(metadata) = metadata.get(token) {
+            for price in points.values_mut() {
+                price.decimals = metadata.decimals;
+            }
+        }
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intents_models::models::types::order::OrderTypeFulfillmentData;
+
+    const TOKEN_A: &str = "0x1111111111111111111111111111111111111111";
+    const TOKEN_B: &str = "0x2222222222222222222222222222222222222222";
+
+    fn order(
+        order_id: &str,
+        amount_out: u128,
+        deadline: u64,
+        filled_at: Option<u64>,
+    ) -> BacktestOrder {
+        BacktestOrder {
+            pending_trade: PendingTrade {
+                order_id: order_id.to_string(),
+                src_chain: ChainId::Ethereum,
+                dst_chain: ChainId::Base,
+                token_in: TOKEN_A.to_string(),
+                token_out: TOKEN_B.to_string(),
+                amount_in: 1_000_000_000_000_000_000,
+                amount_out,
+                deadline,
+                order_type_fulfillment_data: OrderTypeFulfillmentData::Limit,
+                extra_expenses: HashMap::new(),
+                stablecoin_swap_info: None,
+                limit_order_data: None,
+                stop_loss_initial_ratio: None,
+            },
+            solver_last_bid: None,
+            submitted_at: 100,
+            filled_at,
+        }
+    }
+
+    #[test]
+    fn test_parse_price_points_csv() {
+        let csv = "timestamp,chain,address,price,decimals\n\
+                   100,Ethereum,0xAbC,1.5,18\n\
+                   \n\
+                   160,8453,0xdef,2,6\n";
+        let points = parse_price_points_csv(csv).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(
+            points[0].token,
+            TokenId::new(ChainId::Ethereum, "0xabc".to_string())
+        );
+        assert_eq!(points[1].token.chain, ChainId::Base);
+        assert_eq!(points[1].price, 2.0);
+
+        assert!(parse_price_points_csv("100,Ethereum,0xabc,1.5").is_err());
+        assert!(parse_price_points_csv("100,Unknown,0xabc,1.5,18").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backtest_reports_flag_times_against_fills() {
+        let mut points = Vec::new();
+        for (timestamp, price_a) in [(100, 1.0), (160, 1.5), (220, 2.5), (280, 1.0)] {
+            points.push(PricePoint {
+                timestamp,
+                token: TokenId::new(ChainId::Ethereum, TOKEN_A.to_string()),
+                price: price_a,
+                decimals: 18,
+            });
+        }
+        points.push(PricePoint {
+            timestamp: 100,
+            token: TokenId::new(ChainId::Base, TOKEN_B.to_string()),
+            price: 1.0,
+            decimals: 6,
+        });
+
+        let report = run_backtest(
+            price_series_from_points(points),
+            vec![
+                // Feasible once token A reaches $2.5, filled 30s later
+                order("filled", 2_000_000, 1_000, Some(250)),
+                // Never reaches $5, but was filled anyway
+                order("missed", 5_000_000, 1_000, Some(300)),
+                // Feasible right away, never filled
+                order("unfilled", 500_000, 1_000, None),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let outcome = |order_id: &str| {
+            report
+                .outcomes
+                .iter()
+                .find(|outcome| outcome.order_id == order_id)
+                .unwrap()
+        };
+        assert_eq!(outcome("filled").flagged_at, Some(220));
+        assert_eq!(outcome("filled").alert.as_deref(), Some("SwapIsFeasible"));
+        assert_eq!(outcome("filled").lead_time(), Some(30));
+        assert_eq!(outcome("missed").flagged_at, None);
+        assert_eq!(outcome("missed").expired_at, Some(1_001));
+        assert_eq!(outcome("unfilled").flagged_at, Some(100));
+
+        assert_eq!(report.summary.orders, 3);
+        assert_eq!(report.summary.flagged_and_filled, 1);
+        assert_eq!(report.summary.missed_fills, 1);
+        assert_eq!(report.summary.flagged_not_filled, 1);
+        assert_eq!(report.summary.mean_lead_time, Some(30.0));
+    }
+}
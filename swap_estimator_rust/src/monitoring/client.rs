@@ -1,30 +1,96 @@
 use std::collections::{HashMap, HashSet};
 
 use error_stack::{ResultExt, report};
+use intents_models::network::nats::NatsManager;
 use tokio::sync::{mpsc::Sender, oneshot};
 
 use crate::{
     error::{Error, EstimatorResult},
-    monitoring::{manager::PendingTrade, messages::MonitorRequest},
-    prices::{TokenId, TokenPrice, estimating::OrderEstimationData},
+    monitoring::{
+        manager::PendingTrade,
+        messages::{MonitorRequest, OrderExplanation},
+        metrics::MonitorMetricsSnapshot,
+        nats::{MonitorNatsRequest, MonitorNatsResponse},
+    },
+    prices::{TokenId, TokenPrice, estimating::OrderEstimationData, valuation::BasketValuation},
 };
 
 #[derive(Debug, Clone)]
 pub struct MonitorClient {
-    client: Sender<MonitorRequest>,
+    transport: MonitorTransport,
+}
+
+#[derive(Debug, Clone)]
+enum MonitorTransport {
+    /// Monitor running in this process
+    Local(Sender<MonitorRequest>),
+    Remote(RemoteMonitor),
+}
+
+/// Monitor served by another process through `serve_monitor_requests`
+#[derive(Debug, Clone)]
+struct RemoteMonitor {
+    nats: NatsManager<MonitorNatsRequest, MonitorNatsResponse>,
+    subject: &'static str,
+}
+
+impl RemoteMonitor {
+    async fn request(&self, request: MonitorNatsRequest) -> EstimatorResult<MonitorNatsResponse> {
+        match self.nats.request(self.subject, request).await {
+            Ok(MonitorNatsResponse::Error(e)) => {
+                tracing::error!("Error in remote monitoring service response: {e}");
+                Err(report!(Error::ResponseError)
+                    .attach_printable(format!("Remote monitoring service error: {e}")))
+            }
+            Ok(response) => Ok(response),
+            Err(e) => Err(e)
+                .change_context(Error::ResponseError)
+                .attach_printable("Failed to request remote monitoring service"),
+        }
+    }
+}
+
+fn unexpected_response(response: MonitorNatsResponse) -> error_stack::Report<Error> {
+    report!(Error::ResponseError).attach_printable(format!(
+        "Unexpected response from remote monitoring service: {response:?}"
+    ))
 }
 
 impl MonitorClient {
     pub fn new(client: Sender<MonitorRequest>) -> Self {
-        Self { client }
+        Self {
+            transport: MonitorTransport::Local(client),
+        }
+    }
+
+    /// Client for a monitor shared through NATS, so several solver instances can use one monitor
+    pub fn new_remote(
+        nats: NatsManager<MonitorNatsRequest, MonitorNatsResponse>,
+        subject: &'static str,
+    ) -> Self {
+        Self {
+            transport: MonitorTransport::Remote(RemoteMonitor { nats, subject }),
+        }
     }
 
     pub async fn get_coins_data(
         &self,
         token_ids: HashSet<TokenId>,
     ) -> EstimatorResult<HashMap<TokenId, TokenPrice>> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::GetCoinsData { token_ids })
+                    .await?
+                {
+                    MonitorNatsResponse::CoinsData(data) => Ok(data),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
         let (resp_sender, resp_receiver) = oneshot::channel();
-        self.client
+        client
             .send(MonitorRequest::GetCoinsData {
                 token_ids,
                 resp: resp_sender,
@@ -52,8 +118,20 @@ impl MonitorClient {
         &self,
         tokens: Vec<(TokenId, u128)>,
     ) -> EstimatorResult<(Vec<f64>, f64)> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::EvaluateCoins { tokens })
+                    .await?
+                {
+                    MonitorNatsResponse::CoinsEvaluation { values, total } => Ok((values, total)),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
         let (resp_sender, resp_receiver) = oneshot::channel();
-        self.client
+        client
             .send(MonitorRequest::EvaluateCoins {
                 tokens,
                 resp: resp_sender,
@@ -77,12 +155,69 @@ impl MonitorClient {
         }
     }
 
+    /// Values `tokens` in USD with exact decimal math, reporting tokens without price as missing
+    pub async fn value_basket(
+        &self,
+        tokens: Vec<(TokenId, u128)>,
+    ) -> EstimatorResult<BasketValuation> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::ValueBasket { tokens })
+                    .await?
+                {
+                    MonitorNatsResponse::BasketValuation(data) => Ok(data),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        client
+            .send(MonitorRequest::ValueBasket {
+                tokens,
+                resp: resp_sender,
+            })
+            .await
+            .change_context(Error::ResponseError)
+            .attach_printable("Failed to send result of value basket")?;
+        match resp_receiver.await {
+            Ok(Ok(data)) => Ok(data),
+            Ok(Err(e)) => {
+                tracing::error!("Error in monitoring service response: {e}");
+                Err(e.clone())
+                    .change_context(Error::ResponseError)
+                    .attach_printable_lazy(|| format!("Failed to value basket: {e}"))
+            }
+            Err(_) => {
+                tracing::error!("Failed to receive response from monitoring service");
+                Err(report!(Error::ResponseError)
+                    .attach_printable("Failed to receive response from monitoring service"))
+            }
+        }
+    }
+
     pub async fn check_swap_feasibility(
         &self,
         pending_trade: PendingTrade,
         solver_last_bid: Option<u128>,
     ) -> EstimatorResult<()> {
-        self.client
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::CheckSwapFeasibility {
+                        pending_trade,
+                        solver_last_bid,
+                    })
+                    .await?
+                {
+                    MonitorNatsResponse::Accepted => Ok(()),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
+        client
             .send(MonitorRequest::CheckSwapFeasibility {
                 pending_trade,
                 solver_last_bid,
@@ -93,7 +228,19 @@ impl MonitorClient {
     }
 
     pub async fn remove_check_swap_feasibility(&self, order_id: String) -> EstimatorResult<()> {
-        self.client
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::RemoveCheckSwapFeasibility { order_id })
+                    .await?
+                {
+                    MonitorNatsResponse::Accepted => Ok(()),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
+        client
             .send(MonitorRequest::RemoveCheckSwapFeasibility { order_id })
             .await
             .change_context(Error::ResponseError)
@@ -104,8 +251,20 @@ impl MonitorClient {
         &self,
         orders: Vec<OrderEstimationData>,
     ) -> EstimatorResult<HashMap<String, u128>> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::EstimateOrdersAmountOut { orders })
+                    .await?
+                {
+                    MonitorNatsResponse::OrdersAmountOut(data) => Ok(data),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
         let (resp_sender, resp_receiver) = oneshot::channel();
-        self.client
+        client
             .send(MonitorRequest::EstimateOrdersAmountOut {
                 orders,
                 resp: resp_sender,
@@ -128,4 +287,107 @@ impl MonitorClient {
             }
         }
     }
+
+    /// Latest estimation breakdown of a monitored order, to tell why it did not fire yet
+    pub async fn explain_order(&self, order_id: String) -> EstimatorResult<OrderExplanation> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote
+                    .request(MonitorNatsRequest::ExplainOrder { order_id })
+                    .await?
+                {
+                    MonitorNatsResponse::OrderExplanation(data) => Ok(*data),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        client
+            .send(MonitorRequest::ExplainOrder {
+                order_id,
+                resp: resp_sender,
+            })
+            .await
+            .change_context(Error::ResponseError)
+            .attach_printable("Failed to send result of explain order")?;
+        match resp_receiver.await {
+            Ok(Ok(data)) => Ok(data),
+            Ok(Err(e)) => {
+                tracing::error!("Error in monitoring service response: {e}");
+                Err(e.clone())
+                    .change_context(Error::ResponseError)
+                    .attach_printable_lazy(|| format!("Failed to explain order: {e}"))
+            }
+            Err(_) => {
+                tracing::error!("Failed to receive response from monitoring service");
+                Err(report!(Error::ResponseError)
+                    .attach_printable("Failed to receive response from monitoring service"))
+            }
+        }
+    }
+
+    pub async fn get_metrics(&self) -> EstimatorResult<MonitorMetricsSnapshot> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote.request(MonitorNatsRequest::GetMetrics).await? {
+                    MonitorNatsResponse::Metrics(data) => Ok(data),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        client
+            .send(MonitorRequest::GetMetrics { resp: resp_sender })
+            .await
+            .change_context(Error::ResponseError)
+            .attach_printable("Failed to send result of get metrics")?;
+        match resp_receiver.await {
+            Ok(Ok(data)) => Ok(data),
+            Ok(Err(e)) => {
+                tracing::error!("Error in monitoring service response: {e}");
+                Err(e.clone())
+                    .change_context(Error::ResponseError)
+                    .attach_printable_lazy(|| format!("Failed to get metrics: {e}"))
+            }
+            Err(_) => {
+                tracing::error!("Failed to receive response from monitoring service");
+                Err(report!(Error::ResponseError)
+                    .attach_printable("Failed to receive response from monitoring service"))
+            }
+        }
+    }
+
+    pub async fn get_pending_orders(&self) -> EstimatorResult<Vec<PendingTrade>> {
+        let client = match &self.transport {
+            MonitorTransport::Local(client) => client,
+            MonitorTransport::Remote(remote) => {
+                return match remote.request(MonitorNatsRequest::GetPendingOrders).await? {
+                    MonitorNatsResponse::PendingOrders(data) => Ok(data),
+                    response => Err(unexpected_response(response)),
+                };
+            }
+        };
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        client
+            .send(MonitorRequest::GetPendingOrders { resp: resp_sender })
+            .await
+            .change_context(Error::ResponseError)
+            .attach_printable("Failed to send result of get pending orders")?;
+        match resp_receiver.await {
+            Ok(Ok(data)) => Ok(data),
+            Ok(Err(e)) => {
+                tracing::error!("Error in monitoring service response: {e}");
+                Err(e.clone())
+                    .change_context(Error::ResponseError)
+                    .attach_printable_lazy(|| format!("Failed to get pending orders: {e}"))
+            }
+            Err(_) => {
+                tracing::error!("Failed to receive response from monitoring service");
+                Err(report!(Error::ResponseError)
+                    .attach_printable("Failed to receive response from monitoring service"))
+            }
+        }
+    }
 }
@@ -0,0 +1,606 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use error_stack::{ResultExt as _, report};
+use intents_models::models::types::{
+    common::{CommonDcaOrderData, CommonDcaOrderState, DcaSchedule},
+    order::{DcaOrderFulfillmentData, OrderTypeFulfillmentData},
+};
+use tokio::sync::{
+    broadcast,
+    mpsc::{self, Receiver, Sender},
+};
+
+use crate::{
+    error::{Error, EstimatorResult},
+    utils::get_timestamp,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcaIntervalEvent {
+    pub order_id: String,
+    pub fulfillment_data: DcaOrderFulfillmentData,
+    /// Timestamp (in seconds) when the interval started
+    pub interval_start: u64,
+    /// Intervals that started and ended without being emitted, e.g. while the scheduler was down.
+    /// They are not lost: skipped intervals roll over to the following ones
+    pub skipped_intervals: u32,
+}
+
+impl DcaIntervalEvent {
+    /// Fulfillment data to attach to the `PendingTrade` checked for this interval
+    pub fn order_type_fulfillment_data(&self) -> OrderTypeFulfillmentData {
+        OrderTypeFulfillmentData::Dca(self.fulfillment_data)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DcaSchedulerEvent {
+    /// A new interval started and the order can be fulfilled for it
+    IntervalStarted(DcaIntervalEvent),
+    /// Every interval of the order was executed; it is no longer scheduled
+    Completed { order_id: String },
+    /// Order deadline passed before every interval was executed; it is no longer scheduled
+    Expired { order_id: String },
+}
+
+#[derive(Debug)]
+pub enum DcaSchedulerRequest {
+    Schedule {
+        order_id: String,
+        data: CommonDcaOrderData,
+        state: CommonDcaOrderState,
+        /// Order deadline (in seconds)
+        deadline: u64,
+    },
+    IntervalExecuted {
+        order_id: String,
+        interval_number: u32,
+    },
+    Remove {
+        order_id: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct ScheduledDca {
+    schedule: DcaSchedule,
+    state: CommonDcaOrderState,
+    /// Last interval index an event was emitted for
+    last_emitted_interval: u32,
+    next_wakeup: u64,
+}
+
+impl ScheduledDca {
+    fn is_completed(&self) -> bool {
+        self.state.total_executed_intervals >= self.schedule.total_intervals
+    }
+
+    fn deadline(&self) -> u64 {
+        self.schedule.deadline.unwrap_or(u64::MAX)
+    }
+
+    /// Start of the next interval to emit, or the deadline if no interval starts before it
+    fn next_wakeup(&self) -> u64 {
+        self.schedule
+            .get_permission_window(self.next_interval())
+            .map(|window| window.start)
+            .unwrap_or(self.deadline())
+    }
+
+    /// First interval index not executed nor emitted yet
+    fn next_interval(&self) -> u32 {
+        self.state
+            .last_executed_interval_index
+            .max(self.last_emitted_interval)
+            + 1
+    }
+}
+
+/// Drives DCA orders over time: wakes up at every interval start and emits
+/// `DcaSchedulerEvent::IntervalStarted` with the interval number to fulfill.
+///
+/// Intervals that are not executed roll over, as `DcaSchedule` allows, so an order is only
+/// dropped once `IntervalExecuted` has been reported `total_intervals` times, its deadline
+/// passed, or it is removed explicitly.
+#[derive(Debug)]
+pub struct DcaScheduler {
+    receiver: Receiver<DcaSchedulerRequest>,
+    event_tx: broadcast::Sender<DcaSchedulerEvent>,
+    orders: HashMap<String, ScheduledDca>,
+    wakeups: BTreeMap<u64, HashSet<String>>, // wake up timestamp to OrderIds
+}
+
+impl DcaScheduler {
+    pub fn new(
+        receiver: Receiver<DcaSchedulerRequest>,
+        event_tx: broadcast::Sender<DcaSchedulerEvent>,
+    ) -> Self {
+        Self {
+            receiver,
+            event_tx,
+            orders: HashMap::new(),
+            wakeups: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a scheduler together with a handle to feed it
+    pub fn channel(buffer: usize) -> (Self, DcaSchedulerClient) {
+        let (request_tx, request_rx) = mpsc::channel(buffer);
+        let (event_tx, _event_rx) = broadcast::channel(buffer);
+        (
+            Self::new(request_rx, event_tx.clone()),
+            DcaSchedulerClient {
+                client: request_tx,
+                event_tx,
+            },
+        )
+    }
+
+    pub async fn run(mut self) -> EstimatorResult<()> {
+        loop {
+            let next_wakeup = self.wakeups.first_key_value().map(|(at, _)| *at);
+            let sleep = tokio::time::sleep(next_wakeup.map(until).unwrap_or(Duration::MAX));
+            tokio::select! {
+                _ = sleep, if next_wakeup.is_some() => {
+                    let events = self.on_tick(get_timestamp());
+                    self.send_events(events);
+                }
+                request = self.receiver.recv() => {
+                    let Some(request) = request else {
+                        tracing::warn!("DCA scheduler request channel closed, exiting...");
+                        return Err(report!(Error::Unknown)
+                            .attach_printable("DCA scheduler request channel closed"));
+                    };
+                    let events = self.handle_request(request, get_timestamp());
+                    self.send_events(events);
+                }
+            }
+        }
+    }
+
+    fn send_events(&self, events: Vec<DcaSchedulerEvent>) {
+        for event in events {
+            tracing::debug!("DCA scheduler event: {:?}", event);
+            if let Err(e) = self.event_tx.send(event) {
+                tracing::error!("Failed to send DCA scheduler event: {:?}", e);
+            }
+        }
+    }
+
+    fn handle_request(&mut self, request: DcaSchedulerRequest, now: u64) -> Vec<DcaSchedulerEvent> {
+        match request {
+            DcaSchedulerRequest::Schedule {
+                order_id,
+                data,
+                state,
+                deadline,
+            } => self.schedule(order_id, data, state, deadline, now),
+            DcaSchedulerRequest::IntervalExecuted {
+                order_id,
+                interval_number,
+            } => self.interval_executed(&order_id, interval_number),
+            DcaSchedulerRequest::Remove { order_id } => {
+                self.remove(&order_id);
+                vec![]
+            }
+        }
+    }
+
+    /// Adds (or replaces) an order. If its current interval was not executed yet, the event for
+    /// it is emitted right away.
+    fn schedule(
+        &mut self,
+        order_id: String,
+        data: CommonDcaOrderData,
+        state: CommonDcaOrderState,
+        deadline: u64,
+        now: u64,
+    ) -> Vec<DcaSchedulerEvent> {
+        self.remove(&order_id);
+        if data.interval_duration == 0 {
+            tracing::warn!(
+                "Ignoring DCA order {} with zero interval duration",
+                order_id
+            );
+            return vec![];
+        }
+        let mut order = ScheduledDca {
+            schedule: DcaSchedule::new(&data).with_deadline(deadline),
+            state,
+            last_emitted_interval: 0,
+            next_wakeup: 0,
+        };
+        if order.is_completed() {
+            return vec![DcaSchedulerEvent::Completed { order_id }];
+        }
+        order.next_wakeup = order.next_wakeup();
+        self.insert(order_id, order);
+        self.on_tick(now)
+    }
+
+    fn interval_executed(
+        &mut self,
+        order_id: &str,
+        interval_number: u32,
+    ) -> Vec<DcaSchedulerEvent> {
+        let Some(order) = self.orders.get_mut(order_id) else {
+            return vec![];
+        };
+        if interval_number <= order.state.last_executed_interval_index {
+            // Already known
+            return vec![];
+        }
+        order.state.total_executed_intervals += 1;
+        order.state.last_executed_interval_index = interval_number;
+        if order.is_completed() {
+            self.remove(order_id);
+            return vec![DcaSchedulerEvent::Completed {
+                order_id: order_id.to_string(),
+            }];
+        }
+        vec![]
+    }
+
+    /// Emits events for every order whose next interval started at or before `now`,
+    /// and drops orders whose deadline passed
+    fn on_tick(&mut self, now: u64) -> Vec<DcaSchedulerEvent> {
+        let mut events = Vec::new();
+        while let Some((&at, _)) = self.wakeups.first_key_value() {
+            if at > now {
+                break;
+            }
+            let Some((_, order_ids)) = self.wakeups.pop_first() else {
+                break;
+            };
+            for order_id in order_ids {
+                let Some(mut order) = self.orders.remove(&order_id) else {
+                    continue;
+                };
+                if now >= order.deadline() {
+                    events.push(DcaSchedulerEvent::Expired { order_id });
+                    continue;
+                }
+                // After a downtime several intervals may have started; only the current one can
+                // still be fulfilled
+                let current_interval = order.schedule.get_interval_index(now);
+                let next_interval = order.next_interval();
+                if current_interval >= next_interval
+                    && let Some(window) = order.schedule.get_permission_window(current_interval)
+                {
+                    events.push(DcaSchedulerEvent::IntervalStarted(DcaIntervalEvent {
+                        order_id: order_id.clone(),
+                        fulfillment_data: DcaOrderFulfillmentData {
+                            interval_number: current_interval,
+                        },
+                        interval_start: window.start,
+                        skipped_intervals: current_interval - next_interval,
+                    }));
+                    order.last_emitted_interval = current_interval;
+                }
+                order.next_wakeup = order.next_wakeup();
+                self.insert(order_id, order);
+            }
+        }
+        events
+    }
+
+    fn insert(&mut self, order_id: String, order: ScheduledDca) {
+        self.wakeups
+            .entry(order.next_wakeup)
+            .or_default()
+            .insert(order_id.clone());
+        self.orders.insert(order_id, order);
+    }
+
+    fn remove(&mut self, order_id: &str) {
+        if let Some(order) = self.orders.remove(order_id)
+            && let Some(set) = self.wakeups.get_mut(&order.next_wakeup)
+        {
+            set.remove(order_id);
+            if set.is_empty() {
+                self.wakeups.remove(&order.next_wakeup);
+            }
+        }
+    }
+}
+
+/// Time left until the unix timestamp `at` (in seconds), with millisecond precision
+fn until(at: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Duration::from_secs(at).saturating_sub(now)
+}
+
+#[derive(Debug, Clone)]
+pub struct DcaSchedulerClient {
+    client: Sender<DcaSchedulerRequest>,
+    event_tx: broadcast::Sender<DcaSchedulerEvent>,
+}
+
+impl DcaSchedulerClient {
+    pub fn subscribe(&self) -> broadcast::Receiver<DcaSchedulerEvent> {
+        self.event_tx.subscribe()
+    }
+
+    pub async fn schedule(
+        &self,
+        order_id: String,
+        data: CommonDcaOrderData,
+        state: CommonDcaOrderState,
+        deadline: u64,
+    ) -> EstimatorResult<()> {
+        self.send(DcaSchedulerRequest::Schedule {
+            order_id,
+            data,
+            state,
+            deadline,
+        })
+        .await
+    }
+
+    /// Reports that `interval_number` of the order was fulfilled
+    pub async fn interval_executed(
+        &self,
+        order_id: String,
+        interval_number: u32,
+    ) -> EstimatorResult<()> {
+        self.send(DcaSchedulerRequest::IntervalExecuted {
+            order_id,
+            interval_number,
+        })
+        .await
+    }
+
+    pub async fn remove(&self, order_id: String) -> EstimatorResult<()> {
+        self.send(DcaSchedulerRequest::Remove { order_id }).await
+    }
+
+    async fn send(&self, request: DcaSchedulerRequest) -> EstimatorResult<()> {
+        self.client
+            .send(request)
+            .await
+            .change_context(Error::ResponseError)
+            .attach_printable("Failed to send request to DCA scheduler")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dca_data(start_time: u32, total_intervals: u32) -> CommonDcaOrderData {
+        CommonDcaOrderData {
+            start_time,
+            amount_in_per_interval: 100,
+            total_intervals,
+            interval_duration: 60,
+        }
+    }
+
+    fn state(
+        total_executed_intervals: u32,
+        last_executed_interval_index: u32,
+    ) -> CommonDcaOrderState {
+        CommonDcaOrderState {
+            total_executed_intervals,
+            last_executed_interval_index,
+        }
+    }
+
+    const DEADLINE: u64 = 10_000;
+
+    fn scheduler() -> DcaScheduler {
+        DcaScheduler::channel(10).0
+    }
+
+    fn started(events: &[DcaSchedulerEvent]) -> Vec<(u32, u64, u32)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                DcaSchedulerEvent::IntervalStarted(event) => Some((
+                    event.fulfillment_data.interval_number,
+                    event.interval_start,
+                    event.skipped_intervals,
+                )),
+                DcaSchedulerEvent::Completed { .. } | DcaSchedulerEvent::Expired { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_emits_at_each_interval_start() {
+        let mut scheduler = scheduler();
+
+        // Scheduled before the order starts: nothing to emit, wake up at start_time
+        let events = scheduler.schedule(
+            "order".to_string(),
+            dca_data(1_000, 3),
+            state(0, 0),
+            DEADLINE,
+            900,
+        );
+        assert!(events.is_empty());
+        assert_eq!(scheduler.wakeups.keys().collect::<Vec<_>>(), vec![&1_000]);
+
+        assert!(scheduler.on_tick(999).is_empty());
+        assert_eq!(started(&scheduler.on_tick(1_000)), vec![(1, 1_000, 0)]);
+        assert_eq!(scheduler.wakeups.keys().collect::<Vec<_>>(), vec![&1_060]);
+
+        // Same interval is not emitted twice
+        assert!(scheduler.on_tick(1_030).is_empty());
+        assert_eq!(started(&scheduler.on_tick(1_060)), vec![(2, 1_060, 0)]);
+    }
+
+    #[test]
+    fn test_skipped_intervals_after_downtime() {
+        let mut scheduler = scheduler();
+
+        // Interval 2 was the last executed one, the scheduler comes back during interval 6
+        let events = scheduler.schedule(
+            "order".to_string(),
+            dca_data(1_000, 10),
+            state(2, 2),
+            DEADLINE,
+            1_310,
+        );
+
+        assert_eq!(started(&events), vec![(6, 1_300, 3)]);
+        assert_eq!(scheduler.orders["order"].next_wakeup, 1_360);
+    }
+
+    #[test]
+    fn test_stops_after_total_intervals() {
+        let mut scheduler = scheduler();
+        let events = scheduler.schedule(
+            "order".to_string(),
+            dca_data(1_000, 2),
+            state(1, 1),
+            DEADLINE,
+            1_060,
+        );
+        assert_eq!(started(&events), vec![(2, 1_060, 0)]);
+
+        // Stale report is ignored
+        assert!(scheduler.interval_executed("order", 1).is_empty());
+
+        let events = scheduler.interval_executed("order", 2);
+        assert_eq!(
+            events,
+            vec![DcaSchedulerEvent::Completed {
+                order_id: "order".to_string()
+            }]
+        );
+        assert!(scheduler.orders.is_empty());
+        assert!(scheduler.wakeups.is_empty());
+
+        // Already fulfilled orders are never scheduled
+        let events = scheduler.schedule(
+            "done".to_string(),
+            dca_data(1_000, 2),
+            state(2, 5),
+            DEADLINE,
+            1_400,
+        );
+        assert!(started(&events).is_empty());
+        assert!(scheduler.orders.is_empty());
+    }
+
+    #[test]
+    fn test_expires_at_deadline() {
+        let mut scheduler = scheduler();
+        // Deadline in the middle of rolled over interval 4
+        let events = scheduler.schedule(
+            "order".to_string(),
+            dca_data(1_000, 2),
+            state(0, 0),
+            1_200,
+            1_000,
+        );
+        assert_eq!(started(&events), vec![(1, 1_000, 0)]);
+
+        // Skipped intervals roll over past the nominal last one
+        assert_eq!(started(&scheduler.on_tick(1_060)), vec![(2, 1_060, 0)]);
+        assert_eq!(started(&scheduler.on_tick(1_120)), vec![(3, 1_120, 0)]);
+        assert_eq!(started(&scheduler.on_tick(1_180)), vec![(4, 1_180, 0)]);
+        assert_eq!(scheduler.orders["order"].next_wakeup, 1_200);
+
+        assert_eq!(
+            scheduler.on_tick(1_200),
+            vec![DcaSchedulerEvent::Expired {
+                order_id: "order".to_string()
+            }]
+        );
+        assert!(scheduler.orders.is_empty());
+        assert!(scheduler.wakeups.is_empty());
+
+        // Orders scheduled after their deadline expire right away
+        let events = scheduler.schedule(
+            "late".to_string(),
+            dca_data(1_000, 2),
+            state(0, 0),
+            1_200,
+            1_300,
+        );
+        assert_eq!(
+            events,
+            vec![DcaSchedulerEvent::Expired {
+                order_id: "late".to_string()
+            }]
+        );
+        assert!(scheduler.orders.is_empty());
+    }
+
+    #[test]
+    fn test_remove_and_reschedule() {
+        let mut scheduler = scheduler();
+        scheduler.schedule(
+            "a".to_string(),
+            dca_data(1_000, 5),
+            state(0, 0),
+            DEADLINE,
+            900,
+        );
+        scheduler.schedule(
+            "b".to_string(),
+            dca_data(1_000, 5),
+            state(0, 0),
+            DEADLINE,
+            900,
+        );
+        scheduler.remove("a");
+        assert_eq!(scheduler.wakeups[&1_000].len(), 1);
+
+        // Rescheduling replaces the previous entry instead of duplicating it
+        scheduler.schedule(
+            "b".to_string(),
+            dca_data(2_000, 5),
+            state(0, 0),
+            DEADLINE,
+            900,
+        );
+        assert_eq!(scheduler.wakeups.keys().collect::<Vec<_>>(), vec![&2_000]);
+
+        let events = scheduler.on_tick(2_000);
+        assert_eq!(events.len(), 1);
+        let DcaSchedulerEvent::IntervalStarted(event) = &events[0] else {
+            panic!("Unexpected event: {:?}", events[0]);
+        };
+        assert_eq!(
+            event.order_type_fulfillment_data(),
+            OrderTypeFulfillmentData::Dca(DcaOrderFulfillmentData { interval_number: 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_wakes_up_at_interval_start() {
+        let (scheduler, client) = DcaScheduler::channel(10);
+        let mut events = client.subscribe();
+        tokio::spawn(scheduler.run());
+
+        let now = get_timestamp();
+        let data = CommonDcaOrderData {
+            start_time: now as u32 + 1,
+            amount_in_per_interval: 100,
+            total_intervals: 2,
+            interval_duration: 1,
+        };
+        client
+            .schedule("order".to_string(), data, state(0, 0), now + 60)
+            .await
+            .unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(3), events.recv())
+            .await
+            .expect("Timed out waiting for DCA interval")
+            .unwrap();
+        let DcaSchedulerEvent::IntervalStarted(event) = event else {
+            panic!("Unexpected event: {event:?}");
+        };
+        assert_eq!(event.fulfillment_data.interval_number, 1);
+        assert!(get_timestamp() > now);
+    }
+}
@@ -0,0 +1,431 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{
+        IntoResponse, Response, Sse,
+        sse::{Event, KeepAlive},
+    },
+    routing::{delete, get},
+};
+use error_stack::{Report, ResultExt as _};
+use futures_util::{Stream, stream};
+use intents_models::constants::chains::ChainId;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use tokio::{net::TcpListener, sync::broadcast};
+
+use crate::{
+    error::{Error, EstimatorResult},
+    monitoring::{
+        client::MonitorClient,
+        manager::PendingTrade,
+        messages::{MonitorAlert, OrderExplanation},
+        metrics::MonitorMetricsSnapshot,
+    },
+    prices::{TokenId, TokenPrice},
+};
+
+#[derive(Debug, Clone)]
+struct HttpApiState {
+    monitor: MonitorClient,
+    alerts: broadcast::Sender<MonitorAlert>,
+}
+
+/// JSON control API of a monitor:
+/// - `GET /orders`: pending orders
+/// - `POST /orders`: start monitoring `{"pending_trade": .., "solver_last_bid": ..}`
+/// - `DELETE /orders/{order_id}`: stop monitoring an order
+/// - `GET /orders/{order_id}/explanation`: why the order did or didn't fire
+/// - `GET /prices?tokens=<chain:address>,..`: token prices
+/// - `GET /metrics[?format=json]`: metrics, in Prometheus text format by default
+/// - `GET /alerts`: alerts as server-sent events named after the alert type
+pub fn http_api_router(monitor: MonitorClient, alerts: broadcast::Sender<MonitorAlert>) -> Router {
+    Router::new()
+        .route("/orders", get(list_orders).post(check_order))
+        .route("/orders/{order_id}", delete(remove_order))
+        .route("/orders/{order_id}/explanation", get(explain_order))
+        .route("/prices", get(get_prices))
+        .route("/metrics", get(get_metrics))
+        .route("/alerts", get(stream_alerts))
+        .with_state(HttpApiState { monitor, alerts })
+}
+
+/// Serves `http_api_router` on `addr` until the server fails
+pub async fn serve_http_api(
+    addr: SocketAddr,
+    monitor: MonitorClient,
+    alerts: broadcast::Sender<MonitorAlert>,
+) -> EstimatorResult<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .change_context(Error::ResponseError)
+        .attach_printable_lazy(|| format!("Failed to bind monitor HTTP API on {addr}"))?;
+    axum::serve(listener, http_api_router(monitor, alerts))
+        .await
+        .change_context(Error::ResponseError)
+        .attach_printable("Monitor HTTP API stopped")
+}
+
+/// Answered as `{"error": "..."}`
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Report<Error>> for ApiError {
+    fn from(report: Report<Error>) -> Self {
+        // The client wraps every failure in `ResponseError`, the root cause tells what happened
+        let root = report
+            .frames()
+            .filter_map(|frame| frame.downcast_ref::<Error>())
+            .last()
+            .unwrap_or(report.current_context());
+        let status = match root {
+            Error::ParseError | Error::ChainError(_) | Error::SerdeDeserialize(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Error::OrderNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            message: root.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct ErrorBody {
+            error: String,
+        }
+        (
+            self.status,
+            Json(ErrorBody {
+                error: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+async fn list_orders(
+    State(state): State<HttpApiState>,
+) -> Result<Json<Vec<PendingTrade>>, ApiError> {
+    Ok(Json(state.monitor.get_pending_orders().await?))
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+struct CheckOrderBody {
+    pending_trade: PendingTrade,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    solver_last_bid: Option<u128>,
+}
+
+#[derive(Debug, Serialize)]
+struct AcceptedBody {
+    order_id: String,
+}
+
+async fn check_order(
+    State(state): State<HttpApiState>,
+    Json(body): Json<CheckOrderBody>,
+) -> Result<(StatusCode, Json<AcceptedBody>), ApiError> {
+    let order_id = body.pending_trade.order_id.clone();
+    state
+        .monitor
+        .check_swap_feasibility(body.pending_trade, body.solver_last_bid)
+        .await?;
+    Ok((StatusCode::ACCEPTED, Json(AcceptedBody { order_id })))
+}
+
+async fn remove_order(
+    State(state): State<HttpApiState>,
+    Path(order_id): Path<String>,
+) -> Result<(StatusCode, Json<AcceptedBody>), ApiError> {
+    state
+        .monitor
+        .remove_check_swap_feasibility(order_id.clone())
+        .await?;
+    Ok((StatusCode::ACCEPTED, Json(AcceptedBody { order_id })))
+}
+
+async fn explain_order(
+    State(state): State<HttpApiState>,
+    Path(order_id): Path<String>,
+) -> Result<Json<OrderExplanation>, ApiError> {
+    Ok(Json(state.monitor.explain_order(order_id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct PricesQuery {
+    /// Comma separated `<chain>:<address>`, chain being a name or an id
+    tokens: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TokenPriceEntry {
+    #[serde(flatten)]
+    token: TokenId,
+    #[serde(flatten)]
+    price: TokenPrice,
+}
+
+async fn get_prices(
+    State(state): State<HttpApiState>,
+    Query(query): Query<PricesQuery>,
+) -> Result<Json<Vec<TokenPriceEntry>>, ApiError> {
+    let mut tokens = Vec::new();
+    for token in query.tokens.split(',').filter(|token| !token.is_empty()) {
+        let (chain, address) = token.split_once(':').ok_or_else(|| {
+            ApiError::bad_request(format!("Invalid token {token}, expected chain:address"))
+        })?;
+        let chain = match chain.parse::<u32>() {
+            Ok(chain_id) => ChainId::try_from(chain_id).ok(),
+            Err(_) => ChainId::try_from(chain).ok(),
+        }
+        .ok_or_else(|| ApiError::bad_request(format!("Unknown chain {chain}")))?;
+        tokens.push(TokenId::new(chain, address.to_string()));
+    }
+    if tokens.is_empty() {
+        return Err(ApiError::bad_request("No tokens requested"));
+    }
+
+    let prices = state
+        .monitor
+        .get_coins_data(tokens.iter().cloned().collect())
+        .await?;
+    // Prices are keyed by both the requested and the Codex id, only answer what was asked
+    Ok(Json(
+        tokens
+            .into_iter()
+            .filter_map(|token| {
+                let price = *prices.get(&token)?;
+                Some(TokenPriceEntry { token, price })
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct MetricsQuery {
+    format: Option<String>,
+}
+
+async fn get_metrics(
+    State(state): State<HttpApiState>,
+    Query(query): Query<MetricsQuery>,
+) -> Result<Response, ApiError> {
+    let metrics: MonitorMetricsSnapshot = state.monitor.get_metrics().await?;
+    Ok(match query.format.as_deref() {
+        Some("json") => Json(metrics).into_response(),
+        _ => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            metrics.to_prometheus(),
+        )
+            .into_response(),
+    })
+}
+
+async fn stream_alerts(
+    State(state): State<HttpApiState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let alerts = stream::unfold(state.alerts.subscribe(), |mut alerts| async move {
+        let event = match alerts.recv().await {
+            Ok(alert) => Event::default()
+                .event(alert.kind())
+                .json_data(&alert)
+                .unwrap_or_else(|e| Event::default().event("error").data(e.to_string())),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                Event::default().event("lagged").data(skipped.to_string())
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        };
+        Some((Ok(event), alerts))
+    });
+    Sse::new(alerts).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        monitoring::{
+            manager::{MonitorConfig, MonitorManager},
+            messages::PriceSnapshot,
+        },
+        prices::codex::pricing::CodexProvider,
+        utils::get_timestamp,
+    };
+    use intents_models::models::types::order::OrderTypeFulfillmentData;
+    use std::{collections::HashMap, time::Duration};
+    use tokio::sync::mpsc;
+
+    fn token(chain: ChainId, address: &str) -> TokenId {
+        TokenId {
+            chain,
+            address: address.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_api_drives_local_monitor() {
+        let (alert_tx, _alert_rx) = broadcast::channel(10);
+        let (request_tx, request_rx) = mpsc::channel(10);
+        let mut manager = MonitorManager::new(
+            request_rx,
+            alert_tx.clone(),
+            CodexProvider::new("test".to_string()),
+            MonitorConfig::polling(Duration::from_secs(60)),
+        );
+        for (token_id, price, decimals) in [
+            (token(ChainId::Ethereum, "token_a"), 100.0, 18),
+            (token(ChainId::Base, "token_b"), 50.0, 6),
+        ] {
+            manager
+                .coin_cache
+                .insert(token_id, TokenPrice { price, decimals });
+        }
+        tokio::spawn(manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let router = http_api_router(MonitorClient::new(request_tx), alert_tx.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let http = reqwest::Client::new();
+
+        let pending_trade = PendingTrade {
+            order_id: "order_1".to_string(),
+            src_chain: ChainId::Ethereum,
+            dst_chain: ChainId::Base,
+            token_in: "token_a".to_string(),
+            token_out: "token_b".to_string(),
+            amount_in: 1_000_000_000_000_000_000,
+            amount_out: 3_000_000, // Not reachable
+            deadline: get_timestamp() + 300,
+            order_type_fulfillment_data: OrderTypeFulfillmentData::Limit,
+            extra_expenses: HashMap::new(),
+            stablecoin_swap_info: None,
+            limit_order_data: None,
+            stop_loss_initial_ratio: None,
+        };
+        let response = http
+            .post(format!("{base_url}/orders"))
+            .json(&serde_json::json!({ "pending_trade": pending_trade }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let orders: serde_json::Value = http
+            .get(format!("{base_url}/orders"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(orders[0]["order_id"], "order_1");
+
+        let explanation: serde_json::Value = http
+            .get(format!("{base_url}/orders/order_1/explanation"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(explanation["estimated_amount_out"], "2000000");
+        assert_eq!(explanation["target_amount_out"], "3000000");
+        assert_eq!(explanation["feasible"], false);
+
+        let response = http
+            .get(format!("{base_url}/orders/unknown/explanation"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let prices: serde_json::Value = http
+            .get(format!("{base_url}/prices?tokens=Base:token_b"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(prices[0]["address"], "token_b");
+        assert_eq!(prices[0]["price"], 50.0);
+        let response = http
+            .get(format!("{base_url}/prices?tokens=nowhere:token_b"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = http
+            .delete(format!("{base_url}/orders/order_1"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let metrics: MonitorMetricsSnapshot = http
+            .get(format!("{base_url}/metrics?format=json"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(metrics.pending_orders, 0);
+        assert_eq!(metrics.evaluations, 1);
+    }
+
+    #[tokio::test]
+    async fn test_alerts_are_streamed_as_server_sent_events() {
+        let (alert_tx, _alert_rx) = broadcast::channel(10);
+        let (request_tx, _request_rx) = mpsc::channel(10);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let router = http_api_router(MonitorClient::new(request_tx), alert_tx.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let mut response = reqwest::get(format!("{base_url}/alerts")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        alert_tx
+            .send(MonitorAlert::OrderExpired {
+                order_id: "order_1".to_string(),
+                deadline: 42,
+                snapshot: PriceSnapshot {
+                    prices: HashMap::new(),
+                    taken_at: 43,
+                },
+            })
+            .unwrap();
+
+        let chunk = tokio::time::timeout(Duration::from_secs(2), response.chunk())
+            .await
+            .expect("Expected an alert event")
+            .unwrap()
+            .unwrap();
+        let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+        assert!(chunk.contains("event: OrderExpired"));
+        assert!(chunk.contains("\"order_id\":\"order_1\""));
+    }
+}
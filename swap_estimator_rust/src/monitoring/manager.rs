@@ -1,47 +1,79 @@
 use error_stack::report;
 use futures_util::future;
-use intents_models::{constants::chains::ChainId, models::types::order::OrderTypeFulfillmentData};
+use intents_models::{
+    constants::chains::ChainId,
+    models::types::{common::CommonLimitOrderData, order::OrderTypeFulfillmentData},
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     sync::Arc,
     time::Duration,
     u64,
 };
 use strum::IntoEnumIterator;
-use tokio::sync::{RwLock, mpsc::Receiver};
+use tokio::{sync::mpsc::Receiver, time::Instant};
 
 use crate::{
     error::{Error, EstimatorResult},
-    monitoring::messages::{MonitorAlert, MonitorRequest},
+    monitoring::{
+        messages::{
+            ExplainedPrice, MonitorAlert, MonitorRequest, OrderExplanation, PriceSnapshot,
+            StablecoinCheck,
+        },
+        metrics::{MonitorMetrics, MonitorMetricsSnapshot},
+        snapshot::{
+            MONITOR_SNAPSHOT_VERSION, MonitorSnapshot, MonitorSnapshotStore, SnapshotPrice,
+            SnapshotTrade,
+        },
+        trailing_stop::{InMemoryStopLossStore, StopLossStore, StopLossTracker},
+        watchlist::{TokenDiscovery, TokenWatchlist, WatchlistConfig},
+    },
     prices::{
         PriceEvent, PriceProvider, TokenId, TokenMetadata, TokenPrice,
-        codex::pricing::CodexProvider, estimating::OrderEstimationData,
+        codex::pricing::CodexProvider,
+        estimating::OrderEstimationData,
+        metadata_store::TokenMetadataCache,
+        valuation::{
+            BasketValuation, PriceQuote, checked_add, checked_div, checked_mul, checked_sub,
+            price_to_decimal, usd_value, validate_decimals, value_basket,
+        },
     },
-    utils::{get_timestamp, number_conversion::u128_to_f64, uint::mul_div},
+    utils::{Clock, SystemClock, uint::mul_div},
 };
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 
+pub use crate::prices::valuation::decimal_to_raw;
+
 const STABLECOIN_SAFETY_MARGIN: i64 = 99; // 0.99
 
 // For limit order on solver src_token and dst_tokens are same as order,
 // and for stop loss on auctioneer, src_token and dst_token are switched to check when the
 // stop_loss_max_out of dst_token can buy amount_in of src_token
-#[derive(Debug, Clone)]
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingTrade {
     pub order_id: String,
     pub src_chain: ChainId,
     pub dst_chain: ChainId,
     pub token_in: String,
     pub token_out: String,
+    #[serde_as(as = "DisplayFromStr")]
     pub amount_in: u128,
+    #[serde_as(as = "DisplayFromStr")]
     pub amount_out: u128,
     pub deadline: u64,
     pub order_type_fulfillment_data: OrderTypeFulfillmentData,
+    #[serde_as(as = "Vec<(_, DisplayFromStr)>")]
     pub extra_expenses: HashMap<TokenId, u128>, // TokenId to amount
     pub stablecoin_swap_info: Option<StablecoinsSwapInfo>,
+    pub limit_order_data: Option<CommonLimitOrderData>, // Take profit / stop loss settings of limit orders
+    /// `token_in / token_out` price ratio at order creation, the reference of trailing stop losses.
+    /// If missing, the first ratio observed by the monitor is used instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_loss_initial_ratio: Option<f64>,
 }
 
 #[serde_as]
@@ -52,246 +84,734 @@ pub struct StablecoinsSwapInfo {
     pub stablecoin_address: String,
 }
 
+/// Timing and price sourcing settings of `MonitorManager`
+#[derive(Debug, Clone)]
+pub struct MonitorConfig {
+    /// Poll prices on every `polling_interval` instead of relying on provider subscriptions
+    pub polling_mode: bool,
+    pub polling_interval: Duration,
+    /// How often tokens without pending orders are looked up
+    pub unsubscribe_interval: Duration,
+    /// How long a token must stay without pending orders before it is unsubscribed and its
+    /// price evicted from the cache
+    pub unsubscribe_grace: Duration,
+    /// How often expired orders and stale stop loss trackers are cleaned
+    pub cleanup_interval: Duration,
+    /// Most requests drained from the channel, and most order registrations checked together
+    pub request_batch_size: usize,
+    /// Price events received within this window re-evaluate each impacted order once
+    pub price_event_coalesce_window: Duration,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            polling_mode: true,
+            polling_interval: Duration::from_secs(5),
+            unsubscribe_interval: Duration::from_secs(60),
+            unsubscribe_grace: Duration::ZERO,
+            cleanup_interval: Duration::from_secs(30),
+            request_batch_size: 64,
+            price_event_coalesce_window: Duration::from_millis(250),
+        }
+    }
+}
+
+impl MonitorConfig {
+    pub fn polling(polling_interval: Duration) -> Self {
+        Self {
+            polling_mode: true,
+            polling_interval,
+            ..Self::default()
+        }
+    }
+
+    pub fn subscriptions() -> Self {
+        Self {
+            polling_mode: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// Change to the set of monitored orders, queued behind queries
+// Same as `MonitorRequest`, updates are moved once from the request into the queue
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
-pub struct MonitorManager {
+enum OrderUpdate {
+    Register {
+        pending_trade: PendingTrade,
+        solver_last_bid: Option<u128>,
+    },
+    Remove {
+        order_id: String,
+    },
+}
+
+#[derive(Debug)]
+pub struct MonitorManager<P = CodexProvider> {
     pub receiver: Receiver<MonitorRequest>,
     pub alert_sender: tokio::sync::broadcast::Sender<MonitorAlert>,
     pub coin_cache: HashMap<TokenId, TokenPrice>,
+    pub coin_cache_updated_at: HashMap<TokenId, u64>, // TokenId to timestamp of its last price observation
     pub pending_trades: HashMap<String, (PendingTrade, Option<u128>)>, // OrderId to pending swap and optionally, estimated amount out calculated
     pub trades_by_token: HashMap<TokenId, Vec<String>>,                // TokenId to OrderIds
-    pub token_metadata: HashMap<TokenId, TokenMetadata>,
-    pub codex_provider: CodexProvider,
-    pub polling_mode: (bool, u64),
+    pub tokens_unused_since: HashMap<TokenId, Instant>, // TokenId to when it was first seen without orders
+    pub token_metadata: TokenMetadataCache,
+    pub price_provider: P,
+    pub config: MonitorConfig,
     pub orders_by_deadline: BTreeMap<u64, HashSet<String>>, // deadline timestamp to OrderIds
-    pub codex_http_requests: Arc<RwLock<u64>>,
+    pub metrics: Arc<MonitorMetrics>,
+    pub stop_loss_trackers: HashMap<String, StopLossTracker>, // OrderId to stop loss peak tracking
+    pub stop_loss_store: Arc<dyn StopLossStore>,
+    pub price_data_missing: HashSet<String>, // OrderIds already alerted about missing prices
+    pub snapshot_store: Option<Arc<dyn MonitorSnapshotStore>>,
+    pub snapshot_interval: Duration,
+    pub clock: Arc<dyn Clock>,
+    order_updates: VecDeque<OrderUpdate>,
+    stale_tokens: HashSet<TokenId>, // Tokens with price events not re-evaluated yet
 }
 
-impl MonitorManager {
+impl<P> MonitorManager<P>
+where
+    P: PriceProvider + Send + Sync + 'static,
+{
     pub fn new(
         receiver: Receiver<MonitorRequest>,
         sender: tokio::sync::broadcast::Sender<MonitorAlert>,
-        codex_api_key: String,
-        polling_mode: (bool, u64),
+        price_provider: P,
+        config: MonitorConfig,
     ) -> Self {
-        let codex_provider = CodexProvider::new(codex_api_key);
-
         Self {
             receiver,
             alert_sender: sender,
             coin_cache: HashMap::new(),
+            coin_cache_updated_at: HashMap::new(),
             pending_trades: HashMap::new(),
             trades_by_token: HashMap::new(),
-            token_metadata: HashMap::new(),
-            codex_provider,
-            polling_mode,
+            tokens_unused_since: HashMap::new(),
+            token_metadata: TokenMetadataCache::in_memory(),
+            price_provider,
+            config,
             orders_by_deadline: BTreeMap::new(),
-            codex_http_requests: Arc::new(RwLock::new(0)),
+            metrics: Arc::new(MonitorMetrics::new()),
+            stop_loss_trackers: HashMap::new(),
+            stop_loss_store: Arc::new(InMemoryStopLossStore::new()),
+            price_data_missing: HashSet::new(),
+            snapshot_store: None,
+            snapshot_interval: Duration::from_secs(30),
+            clock: Arc::new(SystemClock),
+            order_updates: VecDeque::new(),
+            stale_tokens: HashSet::new(),
         }
     }
 
+    /// Reads token metadata through `token_metadata`, so it can outlive the process
+    pub fn with_metadata_cache(mut self, token_metadata: TokenMetadataCache) -> Self {
+        self.token_metadata = token_metadata;
+        self
+    }
+
+    /// Reads the current time from `clock` instead of the system clock, e.g. to replay history
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Handle on the monitor metrics, readable while the monitor runs
+    pub fn metrics(&self) -> Arc<MonitorMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Persists stop loss peaks in `store`, so trailing stops resume from the same peak after a restart
+    pub fn with_stop_loss_store(mut self, store: Arc<dyn StopLossStore>) -> Self {
+        self.stop_loss_store = store;
+        self
+    }
+
+    /// Saves a snapshot of the monitored orders and prices to `store` every `interval`,
+    /// so `restore` can resume monitoring after a restart
+    pub fn with_snapshot_store(
+        mut self,
+        store: Arc<dyn MonitorSnapshotStore>,
+        interval: Duration,
+    ) -> Self {
+        self.snapshot_store = Some(store);
+        self.snapshot_interval = interval;
+        self
+    }
+
+    pub fn snapshot(&self) -> MonitorSnapshot {
+        let mut tokens = HashSet::new();
+        let pending_trades = self
+            .pending_trades
+            .values()
+            .map(|(pending_trade, estimated_amount_out)| {
+                tokens.extend(required_tokens(pending_trade));
+                SnapshotTrade {
+                    pending_trade: pending_trade.clone(),
+                    estimated_amount_out: *estimated_amount_out,
+                }
+            })
+            .collect();
+        // Prices of tokens without orders are dropped by the unsubscription cleanup anyway
+        let coin_cache = tokens
+            .into_iter()
+            .filter_map(|token| {
+                let price = *self.coin_cache.get(&token)?;
+                Some(SnapshotPrice {
+                    updated_at: self.coin_cache_updated_at.get(&token).copied(),
+                    token,
+                    price,
+                })
+            })
+            .collect();
+
+        MonitorSnapshot {
+            version: MONITOR_SNAPSHOT_VERSION,
+            taken_at: self.clock.now(),
+            pending_trades,
+            coin_cache,
+        }
+    }
+
+    async fn save_snapshot(&self) {
+        let Some(store) = &self.snapshot_store else {
+            return;
+        };
+        let snapshot = self.snapshot();
+        match store.save(&snapshot).await {
+            Ok(()) => tracing::debug!(
+                "Saved monitor snapshot with {} orders",
+                snapshot.pending_trades.len()
+            ),
+            Err(error) => tracing::error!("Failed to save monitor snapshot: {:?}", error),
+        }
+    }
+
+    /// Loads the latest snapshot from the snapshot store and resumes monitoring its orders.
+    /// Must be called before `run`. Returns the number of restored orders.
+    pub async fn restore(&mut self) -> EstimatorResult<usize> {
+        let Some(store) = self.snapshot_store.clone() else {
+            return Ok(0);
+        };
+        let Some(snapshot) = store.load().await? else {
+            tracing::info!("No monitor snapshot to restore");
+            return Ok(0);
+        };
+        self.apply_snapshot(snapshot, self.clock.now()).await
+    }
+
+    /// Re-inserts the orders of `snapshot` that are still valid at `now`, with their cached prices,
+    /// and re-subscribes to their tokens
+    async fn apply_snapshot(
+        &mut self,
+        snapshot: MonitorSnapshot,
+        now: u64,
+    ) -> EstimatorResult<usize> {
+        if snapshot.version != MONITOR_SNAPSHOT_VERSION {
+            tracing::warn!(
+                "Ignoring monitor snapshot with version {}, expected {}",
+                snapshot.version,
+                MONITOR_SNAPSHOT_VERSION
+            );
+            return Ok(0);
+        }
+
+        let mut tokens = HashSet::new();
+        let mut restored = 0;
+        for SnapshotTrade {
+            pending_trade,
+            estimated_amount_out,
+        } in snapshot.pending_trades
+        {
+            if pending_trade.deadline < now {
+                tracing::debug!(
+                    "Dropping expired order {} from snapshot, deadline: {}",
+                    pending_trade.order_id,
+                    pending_trade.deadline
+                );
+                continue;
+            }
+            tokens.extend(required_tokens(&pending_trade));
+            self.insert_pending_trade(pending_trade, estimated_amount_out);
+            restored += 1;
+        }
+
+        for SnapshotPrice {
+            token,
+            price,
+            updated_at,
+        } in snapshot.coin_cache
+        {
+            if !tokens.contains(&token) {
+                continue;
+            }
+            self.coin_cache.insert(token.clone(), price);
+            self.coin_cache_updated_at
+                .insert(token, updated_at.unwrap_or(snapshot.taken_at));
+        }
+
+        if !self.config.polling_mode {
+            for token in tokens {
+                self.price_provider.subscribe_to_token(token).await?;
+            }
+        }
+
+        tracing::info!(
+            "Restored {} orders from monitor snapshot taken at {}",
+            restored,
+            snapshot.taken_at
+        );
+        Ok(restored)
+    }
+
+    /// Watchlist sharing this manager's price provider and metadata cache, so tokens it
+    /// discovers are already subscribed when an order for them arrives. Must be spawned
+    /// separately with `TokenWatchlist::run`.
+    pub fn token_watchlist(&self, config: WatchlistConfig) -> TokenWatchlist<P>
+    where
+        P: TokenDiscovery + Clone,
+    {
+        TokenWatchlist::new(self.price_provider.clone(), config)
+            .with_metadata_cache(self.token_metadata.clone())
+    }
+
     pub async fn run(mut self) -> EstimatorResult<()> {
+        // Restore stop loss peaks of orders monitored before a restart. They are picked up again
+        // when the order is re-submitted and dropped once its deadline passes.
+        self.stop_loss_trackers = self.stop_loss_store.load_all().await?;
+        tracing::debug!(
+            "Restored {} stop loss trackers",
+            self.stop_loss_trackers.len()
+        );
+
         // Subscribe to native token price updates, as they are used in fee calculations
         let mut native_tokens = HashSet::new();
         for chain in ChainId::iter() {
             let native_token = chain.wrapped_native_token_address();
             let token_id = TokenId::new_for_codex(chain, &native_token);
             native_tokens.insert(token_id.clone());
-            if !self.polling_mode.0 {
-                self.codex_provider.subscribe_to_token(token_id).await?;
+            if !self.config.polling_mode {
+                self.price_provider.subscribe_to_token(token_id).await?;
             }
         }
 
-        // let mut codex_rx_opt = match self.codex_provider.subscribe_events().await {
-        //     Ok(rx) => rx,
-        //     Err(err) => {
-        //         tracing::error!("Failed to subscribe Codex price events: {:?}", err);
-        //         return Err(err);
-        //     }
-        // };
+        // Prices only move through events when subscriptions are used, polling updates the cache itself
+        let mut price_events = if self.config.polling_mode {
+            None
+        } else {
+            match self.price_provider.get_tokens_prices_events().await {
+                Ok(rx) => Some(rx),
+                Err(err) => {
+                    tracing::error!("Failed to subscribe to price events: {:?}", err);
+                    return Err(err);
+                }
+            }
+        };
 
-        let mut unsubscriptions_interval = tokio::time::interval(Duration::from_secs(60));
-        let mut polling_interval =
-            tokio::time::interval(Duration::from_millis(self.polling_mode.1));
-        let mut clean_expired_orders_interval = tokio::time::interval(Duration::from_secs(30));
+        let mut unsubscriptions_interval = tokio::time::interval(self.config.unsubscribe_interval);
+        let mut polling_interval = tokio::time::interval(self.config.polling_interval);
+        let mut clean_expired_orders_interval = tokio::time::interval(self.config.cleanup_interval);
+        let mut snapshot_interval = tokio::time::interval(self.snapshot_interval);
+        // Armed by the first price event of a burst, so the whole window is waited for
+        let coalesce_deadline = tokio::time::sleep(self.config.price_event_coalesce_window);
+        tokio::pin!(coalesce_deadline);
 
         loop {
             tokio::select! {
                 // Clean expired orders interval
                 _ = clean_expired_orders_interval.tick() => {
-                    let current_timestamp = get_timestamp();
-
-                    while let Some((&deadline, _order_ids)) = self.orders_by_deadline.first_key_value() {
-                        if deadline >= current_timestamp {
-                            break;
-                        }
-                        // Remove the entry
-                        if let Some(order_ids) = self.orders_by_deadline.pop_first() {
-                            let (_removed_deadline, order_ids) = order_ids;
-                            for order_id in order_ids {
-                                tracing::debug!(
-                                    "Removing expired pending swap for order_id: {}, deadline: {}",
-                                    order_id,
-                                    deadline
-                                );
-                                self.remove_order(&order_id).await;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
+                    self.clean_expired_orders().await;
+                }
+                _ = snapshot_interval.tick(), if self.snapshot_store.is_some() => {
+                    self.save_snapshot().await;
                 }
                 _ = unsubscriptions_interval.tick() => {
-                    tracing::debug!("Checking for tokens to unsubscribe due to no pending orders");
-                    // Collect tokens that no longer have pending orders
-                    let tokens_to_unsubscribe: Vec<TokenId> = self
-                        .trades_by_token
-                        .iter()
-                        .filter_map(|(token, order_ids)| {
-                            if order_ids.is_empty() {
-                                Some(token.clone())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    for token in tokens_to_unsubscribe.into_iter() {
-                        if !self.polling_mode.0 {
-                            match self.codex_provider.unsubscribe_from_token(token.clone()).await {
-                                Ok(_) => {
-                                    tracing::debug!(
-                                        "Unsubscribed from token {:?} due to no pending orders",
-                                        token
-                                    );
-                                }
-                                Err(e) => {
-                                    tracing::warn!("Codex unsubscribe_from_token failed: {:?}", e);
-                                }
+                    self.unsubscribe_unused_tokens().await;
+                }
+                // Polling interval
+                _ = polling_interval.tick(), if self.config.polling_mode => {
+                    self.poll_prices(&native_tokens).await?;
+                }
+                // Price provider update price event
+                evt = next_price_event(&mut price_events) => {
+                    tracing::trace!("Received price event: {:?}", evt);
+                    match evt {
+                        Ok(event) => {
+                            let burst_started = self.stale_tokens.is_empty();
+                            self.on_price_event(event).await;
+                            if burst_started {
+                                coalesce_deadline
+                                    .as_mut()
+                                    .reset(Instant::now() + self.config.price_event_coalesce_window);
                             }
                         }
-                        // Remove from coin cache and map
-                        self.coin_cache.remove(&token);
-                        self.trades_by_token.remove(&token);
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("Lagged on price events, skipped {}", skipped);
+                            continue;
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                            tracing::error!("Price events channel closed");
+                            return Err(report!(Error::Unknown)
+                                .attach_printable("Price events receiver closed"));
+                        }
                     }
                 }
-                // Polling interval
-                _ = polling_interval.tick(), if self.polling_mode.0 => {
-                    tracing::info!("Current Codex HTTP requests in total: {}", *self.codex_http_requests.read().await);
-                    tracing::debug!("Polling price updates for pending orders");
-                    // Get all tokens needed to estimate pending trades
-                    let mut tokens_to_fetch: HashSet<TokenId> = self
-                        .trades_by_token
-                        .iter()
-                        .filter_map(|(token, order_ids)| {
-                            if !order_ids.is_empty() {
-                                Some(token.clone())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    if tokens_to_fetch.is_empty() {
-                        tracing::debug!("No tokens to fetch prices for; skipping polling cycle");
-                        continue;
+                request = self.receiver.recv() => {
+                    let Some(request) = request else {
+                        tracing::warn!("Monitor request channel closed, exiting...");
+                        return Err(report!(Error::Unknown).attach_printable("Monitor request channel closed"));
+                    };
+                    self.accept_request(request).await;
+                    // Drain what else is queued, so queries are answered before queued registrations
+                    for _ in 1..self.config.request_batch_size {
+                        let Ok(request) = self.receiver.try_recv() else {
+                            break;
+                        };
+                        self.accept_request(request).await;
                     }
+                }
+                _ = future::ready(()), if !self.order_updates.is_empty() => {
+                    self.process_order_updates().await;
+                }
+                _ = &mut coalesce_deadline, if !self.stale_tokens.is_empty() => {
+                    let stale_tokens = std::mem::take(&mut self.stale_tokens);
+                    self.check_impacted_orders(stale_tokens).await;
+                }
+            }
+            self.refresh_metrics_gauges();
+        }
+    }
 
-                    tracing::debug!("Polling update for tokens: {:?}", tokens_to_fetch);
+    /// Answers queries right away and queues order registrations and removals, so a burst of
+    /// new orders never delays queries by more than one batch
+    async fn accept_request(&mut self, request: MonitorRequest) {
+        tracing::debug!("Received monitor request: {:?}", request);
+        match request {
+            MonitorRequest::RemoveCheckSwapFeasibility { order_id } => {
+                self.order_updates
+                    .push_back(OrderUpdate::Remove { order_id });
+            }
+            MonitorRequest::CheckSwapFeasibility {
+                pending_trade,
+                solver_last_bid,
+            } => {
+                self.order_updates.push_back(OrderUpdate::Register {
+                    pending_trade,
+                    solver_last_bid,
+                });
+            }
+            MonitorRequest::GetCoinsData { token_ids, resp } => {
+                let response = self.get_coins_data(token_ids).await;
+                let to_send = match response {
+                    Ok(result) => Ok(result),
+                    Err(e) => Err(e.current_context().clone()),
+                };
+                match resp.send(to_send) {
+                    Ok(_) => tracing::debug!("Response sent successfully"),
+                    Err(_) => tracing::error!("Failed to send error response"),
+                }
+            }
+            MonitorRequest::EstimateOrdersAmountOut { orders, resp } => {
+                let response = self.estimate_orders_amount_out(orders).await;
+                let to_send = match response {
+                    Ok(result) => Ok(result),
+                    Err(e) => Err(e.current_context().clone()),
+                };
+                match resp.send(to_send) {
+                    Ok(_) => tracing::debug!("Error response sent successfully"),
+                    Err(_) => tracing::error!("Failed to send error response"),
+                }
+            }
+            MonitorRequest::EvaluateCoins { tokens, resp } => {
+                let response = self.evaluate_coins(tokens).await;
+                let to_send = match response {
+                    Ok(result) => Ok(result),
+                    Err(e) => Err(e.current_context().clone()),
+                };
+                match resp.send(to_send) {
+                    Ok(_) => tracing::debug!("EvaluateCoins response sent successfully"),
+                    Err(_) => tracing::error!("Failed to send EvaluateCoins response"),
+                }
+            }
+            MonitorRequest::ValueBasket { tokens, resp } => {
+                let response = self.value_basket(tokens).await;
+                let to_send = match response {
+                    Ok(result) => Ok(result),
+                    Err(e) => Err(e.current_context().clone()),
+                };
+                match resp.send(to_send) {
+                    Ok(_) => tracing::debug!("ValueBasket response sent successfully"),
+                    Err(_) => tracing::error!("Failed to send ValueBasket response"),
+                }
+            }
+            MonitorRequest::ExplainOrder { order_id, resp } => {
+                let to_send = match self.explain_order(&order_id) {
+                    Ok(result) => Ok(result),
+                    Err(e) => Err(e.current_context().clone()),
+                };
+                match resp.send(to_send) {
+                    Ok(_) => tracing::debug!("ExplainOrder response sent successfully"),
+                    Err(_) => tracing::error!("Failed to send ExplainOrder response"),
+                }
+            }
+            MonitorRequest::GetMetrics { resp } => {
+                if resp.send(Ok(self.metrics_snapshot())).is_err() {
+                    tracing::error!("Failed to send GetMetrics response");
+                }
+            }
+            MonitorRequest::GetPendingOrders { resp } => {
+                let mut pending_orders: Vec<PendingTrade> = self
+                    .pending_trades
+                    .values()
+                    .map(|(pending_trade, _)| pending_trade.clone())
+                    .collect();
+                pending_orders.sort_by(|a, b| a.order_id.cmp(&b.order_id));
+                if resp.send(Ok(pending_orders)).is_err() {
+                    tracing::error!("Failed to send GetPendingOrders response");
+                }
+            }
+        }
+    }
 
-                    // Always check for native tokens too.
-                    tokens_to_fetch.extend(native_tokens.clone());
+    /// Applies up to `request_batch_size` queued order updates in arrival order. Consecutive
+    /// registrations are checked together with a single price fetch.
+    async fn process_order_updates(&mut self) {
+        let mut registrations = Vec::new();
+        for _ in 0..self.config.request_batch_size {
+            let Some(update) = self.order_updates.pop_front() else {
+                break;
+            };
+            match update {
+                OrderUpdate::Register {
+                    pending_trade,
+                    solver_last_bid,
+                } => registrations.push((pending_trade, solver_last_bid)),
+                OrderUpdate::Remove { order_id } => {
+                    // Registrations queued before the removal must be applied first
+                    self.check_swaps_feasibility(std::mem::take(&mut registrations))
+                        .await;
+                    tracing::debug!("Removing check swap feasibility for order_id: {}", order_id);
+                    self.remove_order(&order_id).await;
+                }
+            }
+        }
+        self.check_swaps_feasibility(registrations).await;
+    }
 
-                    let mut tokens_data = self.get_tokens_data(tokens_to_fetch).await?;
+    fn refresh_metrics_gauges(&self) {
+        self.metrics.set_pending_orders(self.pending_trades.len());
+        self.metrics.set_subscribed_tokens(
+            self.trades_by_token
+                .values()
+                .filter(|order_ids| !order_ids.is_empty())
+                .count(),
+        );
+    }
 
-                    self.update_tokens_metadata(&mut tokens_data).await?;
+    pub fn metrics_snapshot(&self) -> MonitorMetricsSnapshot {
+        self.refresh_metrics_gauges();
+        self.metrics.snapshot(self.clock.now())
+    }
 
-                    // Update cache and get updated tokens
-                    let updated_tokens = self.update_cache(tokens_data);
+    /// Breakdown of the estimation of a monitored order against the cached prices, so it can be
+    /// told why the order did not fire yet
+    pub fn explain_order(&self, order_id: &str) -> EstimatorResult<OrderExplanation> {
+        let Some((pending_trade, required_monitor_estimation)) = self.pending_trades.get(order_id)
+        else {
+            return Err(report!(Error::OrderNotFound(order_id.to_string())));
+        };
 
-                    for updated_token in updated_tokens.into_iter() {
-                        self.check_impacted_orders(updated_token).await;
-                    }
+        let mut prices: Vec<ExplainedPrice> = required_tokens(pending_trade)
+            .into_iter()
+            .map(|token| ExplainedPrice {
+                price: self.coin_cache.get(&token).cloned(),
+                updated_at: self.coin_cache_updated_at.get(&token).copied(),
+                token,
+            })
+            .collect();
+        prices.sort_by(|a, b| {
+            (a.token.chain as u32, &a.token.address).cmp(&(b.token.chain as u32, &b.token.address))
+        });
+
+        let target_amount_out = required_monitor_estimation.unwrap_or(pending_trade.amount_out);
+        let (estimation, estimation_error) =
+            match estimate_amount_out(pending_trade, &self.coin_cache) {
+                Ok(estimation) => (Some(estimation), None),
+                Err(error) => (None, Some(format!("{error:?}"))),
+            };
+        let feasible = estimation.is_some_and(|(estimated_amount_out, _, stablecoin_ok)| {
+            stablecoin_ok && estimated_amount_out >= target_amount_out
+        });
+
+        Ok(OrderExplanation {
+            order_id: order_id.to_string(),
+            deadline: pending_trade.deadline,
+            prices,
+            extra_expenses: pending_trade.extra_expenses.clone(),
+            expenses_in_token_out: estimation.map(|(_, expenses, _)| expenses),
+            estimated_amount_out: estimation
+                .map(|(estimated_amount_out, _, _)| estimated_amount_out),
+            target_amount_out,
+            take_profit_min_out: pending_trade
+                .limit_order_data
+                .as_ref()
+                .and_then(|data| data.take_profit_min_out),
+            stablecoin_check: pending_trade.stablecoin_swap_info.as_ref().map(|info| {
+                StablecoinCheck {
+                    stablecoin_address: info.stablecoin_address.clone(),
+                    min_stablecoins_amount: info.min_stablecoins_amount,
+                    passed: estimation.map(|(_, _, stablecoin_ok)| stablecoin_ok),
                 }
-                // Codex update price event
-                // evt = codex_rx_opt.recv() => {
-                //     tracing::trace!("Received Codex price event: {:?}", evt);
-                //     match evt {
-                //         Ok(event) => {
-                //             self.on_price_event(event).await;
-                //         }
-                //         Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
-                //             tracing::warn!("Lagged on Codex price events; skipping to latest");
-                //             continue;
-                //         }
-                //         Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                //             tracing::error!("Codex price events channel closed");
-                //             return Err(report!(Error::Unknown)
-                //                 .attach_printable("Codex price events receiver closed"));
-                //         }
-                //     }
-                // }
-                request = self.receiver.recv() => {
-                    match request {
-                        Some(request) => {
-                            tracing::debug!("Received monitor request: {:?}", request);
-                            match request {
-                                MonitorRequest::RemoveCheckSwapFeasibility { order_id } => {
-                                    tracing::debug!("Removing check swap feasibility for order_id: {}", order_id);
-                                    // Remove the swap from pending trades
-                                    self.remove_order(&order_id).await;
-                                }
-                                MonitorRequest::CheckSwapFeasibility {
-                                    pending_trade,
-                                    solver_last_bid,
-                                } => {
-                                    if let Err(error) = self.check_swap_feasibility(pending_trade, solver_last_bid).await {
-                                        tracing::error!("Error processing CheckSwapFeasibility request: {:?}", error);
-                                    }
-                                }
-                                MonitorRequest::GetCoinsData { token_ids, resp } => {
-                                    let response = self.get_coins_data(token_ids).await;
-                                    let to_send = match response {
-                                        Ok(result) => Ok(result),
-                                        Err(e) => Err(e.current_context().clone()),
-                                    };
-                                    match resp.send(to_send) {
-                                        Ok(_) => tracing::debug!("Response sent successfully"),
-                                        Err(_) => tracing::error!("Failed to send error response"),
-                                    }
-                                }
-                                MonitorRequest::EstimateOrdersAmountOut {
-                                    orders, resp,
-                                } => {
-                                    let response = self.estimate_orders_amount_out(orders).await;
-                                    let to_send = match response {
-                                        Ok(result) => Ok(result),
-                                        Err(e) => Err(e.current_context().clone()),
-                                    };
-                                    match resp.send(to_send) {
-                                        Ok(_) => tracing::debug!("Error response sent successfully"),
-                                        Err(_) => tracing::error!("Failed to send error response"),
-                                    }
-                                }
-                                MonitorRequest::EvaluateCoins { tokens, resp } => {
-                                    let response = self.evaluate_coins(tokens).await;
-                                    let to_send = match response {
-                                        Ok(result) => Ok(result),
-                                        Err(e) => Err(e.current_context().clone()),
-                                    };
-                                    match resp.send(to_send) {
-                                        Ok(_) => tracing::debug!("EvaluateCoins response sent successfully"),
-                                        Err(_) => tracing::error!("Failed to send EvaluateCoins response"),
-                                    }
-                                }
-                            }
-                        }
-                        None => {
-                            tracing::warn!("Monitor request channel closed, exiting...");
-                            return Err(report!(Error::Unknown).attach_printable("Monitor request channel closed"));
-                        }
+            }),
+            stop_loss: self.stop_loss_trackers.get(order_id).cloned(),
+            estimation_error,
+            feasible,
+        })
+    }
+
+    /// Broadcasts `alert`, counting it by type. Fails when nobody listens to alerts.
+    fn send_alert(
+        &self,
+        alert: MonitorAlert,
+    ) -> Result<(), tokio::sync::broadcast::error::SendError<()>> {
+        self.metrics.record_alert(alert.kind());
+        self.alert_sender
+            .send(alert)
+            .map(|_| ())
+            .map_err(|_| tokio::sync::broadcast::error::SendError(()))
+    }
+
+    /// Expires orders whose deadline passed and drops stop loss trackers of orders that never
+    /// came back after a restart
+    pub(crate) async fn clean_expired_orders(&mut self) {
+        let current_timestamp = self.clock.now();
+
+        while let Some((&deadline, _order_ids)) = self.orders_by_deadline.first_key_value() {
+            if deadline >= current_timestamp {
+                break;
+            }
+            // Remove the entry
+            if let Some(order_ids) = self.orders_by_deadline.pop_first() {
+                let (_removed_deadline, order_ids) = order_ids;
+                for order_id in order_ids {
+                    tracing::debug!(
+                        "Removing expired pending swap for order_id: {}, deadline: {}",
+                        order_id,
+                        deadline
+                    );
+                    self.expire_order(&order_id).await;
+                }
+            } else {
+                break;
+            }
+        }
+
+        // Trackers restored for orders that never came back
+        let stale_trackers: Vec<String> = self
+            .stop_loss_trackers
+            .iter()
+            .filter(|(order_id, tracker)| {
+                tracker.deadline < current_timestamp && !self.pending_trades.contains_key(*order_id)
+            })
+            .map(|(order_id, _)| order_id.clone())
+            .collect();
+        for order_id in stale_trackers {
+            self.remove_stop_loss_tracker(&order_id).await;
+        }
+    }
+
+    /// Fetches prices of every token with pending orders (plus `native_tokens`) and re-evaluates
+    /// the orders of tokens whose price changed
+    pub(crate) async fn poll_prices(
+        &mut self,
+        native_tokens: &HashSet<TokenId>,
+    ) -> EstimatorResult<()> {
+        tracing::info!(
+            "Current price HTTP requests in total: {}",
+            self.metrics.snapshot(self.clock.now()).provider_requests
+        );
+        tracing::debug!("Polling price updates for pending orders");
+        // Get all tokens needed to estimate pending trades
+        let mut tokens_to_fetch: HashSet<TokenId> = self
+            .trades_by_token
+            .iter()
+            .filter_map(|(token, order_ids)| {
+                if !order_ids.is_empty() {
+                    Some(token.clone())
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if tokens_to_fetch.is_empty() {
+            tracing::debug!("No tokens to fetch prices for; skipping polling cycle");
+            return Ok(());
+        }
+
+        tracing::debug!("Polling update for tokens: {:?}", tokens_to_fetch);
+
+        // Always check for native tokens too.
+        tokens_to_fetch.extend(native_tokens.iter().cloned());
+
+        let mut tokens_data = self.get_tokens_data(tokens_to_fetch).await?;
+
+        self.update_tokens_metadata(&mut tokens_data).await?;
+
+        // Update cache and re-evaluate the orders of updated tokens, once per order
+        let updated_tokens = self.update_cache(tokens_data);
+        self.check_impacted_orders(updated_tokens).await;
+        Ok(())
+    }
+
+    /// Unsubscribes from tokens that stayed without pending orders for longer than
+    /// `unsubscribe_grace`, and evicts their prices from the cache
+    async fn unsubscribe_unused_tokens(&mut self) {
+        tracing::debug!("Checking for tokens to unsubscribe due to no pending orders");
+        let now = Instant::now();
+        // Tokens that got orders again restart their grace period next time they are unused
+        self.tokens_unused_since.retain(|token, _| {
+            self.trades_by_token
+                .get(token)
+                .is_some_and(|order_ids| order_ids.is_empty())
+        });
+
+        let mut tokens_to_unsubscribe = Vec::new();
+        for (token, order_ids) in self.trades_by_token.iter() {
+            if !order_ids.is_empty() {
+                continue;
+            }
+            let unused_since = *self.tokens_unused_since.entry(token.clone()).or_insert(now);
+            if now.duration_since(unused_since) >= self.config.unsubscribe_grace {
+                tokens_to_unsubscribe.push(token.clone());
+            }
+        }
+
+        for token in tokens_to_unsubscribe.into_iter() {
+            if !self.config.polling_mode {
+                match self
+                    .price_provider
+                    .unsubscribe_from_token(token.clone())
+                    .await
+                {
+                    Ok(_) => {
+                        tracing::debug!(
+                            "Unsubscribed from token {:?} due to no pending orders",
+                            token
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!("unsubscribe_from_token failed: {:?}", e);
                     }
                 }
             }
+            // Remove from coin cache and map
+            self.coin_cache.remove(&token);
+            self.coin_cache_updated_at.remove(&token);
+            self.trades_by_token.remove(&token);
+            self.tokens_unused_since.remove(&token);
         }
     }
 
@@ -323,7 +843,7 @@ impl MonitorManager {
         }
     }
 
-    async fn check_swap_feasibility(
+    pub(crate) async fn check_swap_feasibility(
         &mut self,
         pending_trade: PendingTrade,
         solver_last_bid: Option<u128>,
@@ -337,19 +857,97 @@ impl MonitorManager {
             pending_trade.amount_out
         );
 
-        let order_already_present = self.pending_trades.contains_key(&pending_trade.order_id);
+        // Subscribe to price updates for both tokens
+        let tokens_data = self.get_all_coins_data_from_swap(&pending_trade).await;
+        self.register_order(pending_trade, solver_last_bid, tokens_data)
+            .await
+    }
 
-        let token_in_id = TokenId::new_for_codex(pending_trade.src_chain, &pending_trade.token_in);
+    /// Checks a batch of new orders, fetching prices and metadata of every token they need that
+    /// isn't cached in one provider call instead of one per order
+    pub(crate) async fn check_swaps_feasibility(
+        &mut self,
+        registrations: Vec<(PendingTrade, Option<u128>)>,
+    ) {
+        if registrations.is_empty() {
+            return;
+        }
+        let tokens: HashSet<TokenId> = registrations
+            .iter()
+            .flat_map(|(pending_trade, _)| required_tokens(pending_trade))
+            .collect();
+        let prefetch_failed = match self.prefetch_coins_data(tokens).await {
+            Ok(()) => false,
+            Err(error) => {
+                tracing::error!(
+                    "Failed to fetch prices for {} new orders: {:?}",
+                    registrations.len(),
+                    error
+                );
+                true
+            }
+        };
 
-        let token_out_id =
-            TokenId::new_for_codex(pending_trade.dst_chain, &pending_trade.token_out);
+        for (pending_trade, solver_last_bid) in registrations {
+            tracing::debug!(
+                "Checking swap feasibility for order_id: {}, token_in: {}, token_out: {}, amount_in: {}, amount_out: {}",
+                pending_trade.order_id,
+                pending_trade.token_in,
+                pending_trade.token_out,
+                pending_trade.amount_in,
+                pending_trade.amount_out
+            );
+            let tokens_data = self.cached_coins_data(&pending_trade);
+            // Same as a failed fetch for a single order: not monitored without its prices
+            let tokens_data =
+                if prefetch_failed && tokens_data.len() < required_tokens(&pending_trade).len() {
+                    Err(report!(Error::TokenNotFound(format!(
+                        "Failed to fetch prices for order_id {}",
+                        pending_trade.order_id
+                    ))))
+                } else {
+                    Ok(tokens_data)
+                };
+            if let Err(error) = self
+                .register_order(pending_trade, solver_last_bid, tokens_data)
+                .await
+            {
+                tracing::error!("Error processing CheckSwapFeasibility request: {:?}", error);
+            }
+        }
+    }
 
-        // Subscribe to price updates for both tokens
-        let tokens_data = self.get_all_coins_data_from_swap(&pending_trade).await?;
+    /// Evaluates a new order against `tokens_data` and monitors it unless an alert already fired
+    async fn register_order(
+        &mut self,
+        pending_trade: PendingTrade,
+        solver_last_bid: Option<u128>,
+        tokens_data: EstimatorResult<HashMap<TokenId, TokenPrice>>,
+    ) -> EstimatorResult<()> {
+        let tokens_data = match tokens_data {
+            Ok(tokens_data) => tokens_data,
+            Err(error) => {
+                let snapshot = price_snapshot(&pending_trade, &self.coin_cache, self.clock.now());
+                self.notify_price_data_missing(&pending_trade, snapshot);
+                return Err(error);
+            }
+        };
+        self.notify_price_data_missing(
+            &pending_trade,
+            price_snapshot(&pending_trade, &tokens_data, self.clock.now()),
+        );
+
+        if self.check_stop_loss(&pending_trade, &tokens_data).await {
+            // No need to monitor further
+            self.remove_order(&pending_trade.order_id).await;
+            return Ok(());
+        }
 
         // Check immediate feasibility
-        let estimate_amount_out_calculated = match estimate_amount_out(&pending_trade, &tokens_data)
-        {
+        let evaluation_started = Instant::now();
+        let estimation = estimate_amount_out(&pending_trade, &tokens_data);
+        self.metrics.record_evaluation(evaluation_started.elapsed());
+        let estimate_amount_out_calculated = match estimation {
             Ok((
                 estimated_amount_out,
                 fulfillment_expenses_in_tokens_out,
@@ -376,10 +974,12 @@ impl MonitorManager {
                             "Swap is immediately feasible for order_id: {}, sending alert",
                             pending_trade.order_id
                         );
-                        if let Err(e) = self.alert_sender.send(MonitorAlert::SwapIsFeasible {
-                            order_id: pending_trade.order_id.clone(),
-                            order_type_fulfillment_data: pending_trade.order_type_fulfillment_data,
-                        }) {
+                        if let Err(e) = self.send_alert(feasibility_alert(
+                            &pending_trade,
+                            estimated_amount_out,
+                            &tokens_data,
+                            self.clock.now(),
+                        )) {
                             tracing::error!(
                                 "Failed to send alert for order_id {}: {:?}",
                                 pending_trade.order_id,
@@ -401,10 +1001,12 @@ impl MonitorManager {
                             "Swap is immediately feasible for order_id: {}, sending alert",
                             pending_trade.order_id
                         );
-                        if let Err(e) = self.alert_sender.send(MonitorAlert::SwapIsFeasible {
-                            order_id: pending_trade.order_id.clone(),
-                            order_type_fulfillment_data: pending_trade.order_type_fulfillment_data,
-                        }) {
+                        if let Err(e) = self.send_alert(feasibility_alert(
+                            &pending_trade,
+                            estimated_amount_out,
+                            &tokens_data,
+                            self.clock.now(),
+                        )) {
                             tracing::error!(
                                 "Failed to send alert for order_id {}: {:?}",
                                 pending_trade.order_id,
@@ -430,13 +1032,9 @@ impl MonitorManager {
             }
         };
 
-        // Update cache and get updated tokens
+        // Update cache and re-evaluate impacted orders for updated tokens
         let updated_tokens = self.update_cache(tokens_data);
-
-        // Re-evaluate impacted orders for updated tokens
-        for updated_token in updated_tokens.into_iter() {
-            self.check_impacted_orders(updated_token).await;
-        }
+        self.check_impacted_orders(updated_tokens).await;
 
         // Add the swap to pending trades
         tracing::debug!(
@@ -450,8 +1048,28 @@ impl MonitorManager {
             pending_trade.amount_out
         );
 
+        self.insert_pending_trade(pending_trade, estimate_amount_out_calculated);
+        Ok(())
+    }
+
+    /// Adds `pending_trade` to every index used to monitor it, replacing a previous version of the order
+    fn insert_pending_trade(
+        &mut self,
+        pending_trade: PendingTrade,
+        estimate_amount_out_calculated: Option<u128>,
+    ) {
+        let previous_deadline = self
+            .pending_trades
+            .get(&pending_trade.order_id)
+            .map(|(previous, _)| previous.deadline);
+
         // Only add to trades_by_token if not already present
-        if !order_already_present {
+        if previous_deadline.is_none() {
+            let token_in_id =
+                TokenId::new_for_codex(pending_trade.src_chain, &pending_trade.token_in);
+            let token_out_id =
+                TokenId::new_for_codex(pending_trade.dst_chain, &pending_trade.token_out);
+
             self.trades_by_token
                 .entry(token_in_id)
                 .or_insert_with(Vec::new)
@@ -463,6 +1081,15 @@ impl MonitorManager {
                 .push(pending_trade.order_id.clone());
         }
 
+        if let Some(previous_deadline) = previous_deadline
+            && previous_deadline != pending_trade.deadline
+            && let Some(set) = self.orders_by_deadline.get_mut(&previous_deadline)
+        {
+            set.remove(&pending_trade.order_id);
+            if set.is_empty() {
+                self.orders_by_deadline.remove(&previous_deadline);
+            }
+        }
         self.orders_by_deadline
             .entry(pending_trade.deadline)
             .or_insert_with(HashSet::new)
@@ -472,7 +1099,6 @@ impl MonitorManager {
             pending_trade.order_id.clone(),
             (pending_trade, estimate_amount_out_calculated),
         );
-        Ok(())
     }
 
     /// Fetch price data for a set of tokens using cache-first strategy.
@@ -481,7 +1107,7 @@ impl MonitorManager {
     /// - Normalizes all incoming token ids to Codex format.
     /// - Returns cached entries whose price != 0.0 (a zero price is treated as “no data”).
     /// - For cache misses, batches and fetches fresh prices via Codex.
-    /// - If subscriptions mode is enabled (`!self.polling_mode.0`), subscribes to live updates
+    /// - If subscriptions mode is enabled (`!self.config.polling_mode`), subscribes to live updates
     ///   for the newly-fetched tokens as a side effect.
     /// - Updates the internal `coin_cache` with any newly-fetched prices and returns the merged map.
     ///
@@ -545,16 +1171,18 @@ impl MonitorManager {
         tracing::debug!("Fetched tokens data from Codex: {:?}", fetched_by_codex);
 
         // Subscribe to live updates (by CODEX id)
-        // if !self.polling_mode.0 {
+        // if !self.config.polling_mode {
         //     for token in tokens_not_in_cache {
-        //         self.codex_provider.subscribe_to_token(token).await?;
+        //         self.price_provider.subscribe_to_token(token).await?;
         //     }
         // }
 
         // Update coin cache (by CODEX id)
+        let now = self.clock.now();
         for (codex_id, token_price) in fetched_by_codex.iter() {
             self.coin_cache
                 .insert(codex_id.clone(), token_price.clone());
+            self.coin_cache_updated_at.insert(codex_id.clone(), now);
             // This will trigger cache removal for tokens without orders
             self.trades_by_token
                 .entry(codex_id.clone())
@@ -577,77 +1205,135 @@ impl MonitorManager {
         Ok(result)
     }
 
+    /// Caches prices of the `token_ids` that aren't cached yet. In polling mode prices and
+    /// decimals come from one provider query per batch of tokens, with subscriptions it goes
+    /// through `get_coins_data` so the tokens are followed.
+    async fn prefetch_coins_data(&mut self, token_ids: HashSet<TokenId>) -> EstimatorResult<()> {
+        let missing: Vec<TokenId> = token_ids
+            .into_iter()
+            .map(|token_id| TokenId::new_for_codex(token_id.chain, &token_id.address))
+            .filter(|codex_id| {
+                self.coin_cache
+                    .get(codex_id)
+                    .is_none_or(|data| data.price == 0.0)
+            })
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        if !self.config.polling_mode {
+            return self
+                .get_coins_data(missing.into_iter().collect())
+                .await
+                .map(|_| ());
+        }
+
+        // Split into batches of up to 200 tokens, fetched in parallel
+        const BATCH_SIZE: usize = 200;
+        let provider = &self.price_provider;
+        let batches: Vec<&[TokenId]> = missing.chunks(BATCH_SIZE).collect();
+        self.metrics.record_provider_requests(batches.len() as u64);
+        let fetches = batches
+            .into_iter()
+            .map(|batch| async move { provider.get_tokens_price_and_metadata(batch).await });
+        let results = future::join_all(fetches).await;
+
+        // Keep the batches that succeeded even if another one failed
+        let now = self.clock.now();
+        let mut first_error = None;
+        for result in results.into_iter() {
+            match result {
+                Ok(prices) => {
+                    for (token_id, token_price) in prices.into_iter() {
+                        let codex_id = TokenId::new_for_codex(token_id.chain, &token_id.address);
+                        self.coin_cache.insert(codex_id.clone(), token_price);
+                        self.coin_cache_updated_at.insert(codex_id.clone(), now);
+                        // This will trigger cache removal for tokens without orders
+                        self.trades_by_token.entry(codex_id).or_default();
+                    }
+                }
+                Err(error) => {
+                    tracing::error!("Batch get_tokens_price_and_metadata failed: {:?}", error);
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Cached usable prices of the tokens `swap` needs, by Codex id
+    fn cached_coins_data(&self, swap: &PendingTrade) -> HashMap<TokenId, TokenPrice> {
+        required_tokens(swap)
+            .into_iter()
+            .filter_map(|token| {
+                let data = self
+                    .coin_cache
+                    .get(&token)
+                    .filter(|data| data.price != 0.0)?;
+                Some((token, *data))
+            })
+            .collect()
+    }
+
     async fn update_tokens_metadata(
         &mut self,
         tokens_prices: &mut HashMap<TokenId, TokenPrice>,
     ) -> EstimatorResult<()> {
-        // Update data fetched with token metadata
-        let mut missing_tokens_metadata: HashSet<TokenId> = HashSet::new();
+        // Update data fetched with token metadata, only hitting Codex for unknown or stale tokens
+        let codex_ids: HashSet<TokenId> = tokens_prices
+            .keys()
+            .map(|token_id| TokenId::new_for_codex(token_id.chain, &token_id.address))
+            .collect();
+        let metadata = self
+            .token_metadata
+            .get_or_fetch(codex_ids, |missing| self.get_tokens_metadata(missing))
+            .await?;
+        // update decimals for all entries (original + codex aliases)
         for (token_id, token_price) in tokens_prices.iter_mut() {
             let codex_id = TokenId::new_for_codex(token_id.chain, &token_id.address);
-            if let Some(metadata) = self.token_metadata.get(&codex_id) {
-                token_price.decimals = metadata.decimals;
-            } else {
-                missing_tokens_metadata.insert(codex_id);
-            }
-        }
-        // Fetch and apply metadata for missing tokens, then cache it
-        if !missing_tokens_metadata.is_empty() {
-            let fetched = self.get_tokens_metadata(missing_tokens_metadata).await?;
-            for (token_id, meta) in fetched.iter() {
-                // cache metadata for future requests
-                self.token_metadata.insert(token_id.clone(), meta.clone());
-            }
-            // update decimals for all entries (original + codex aliases)
-            for (token_id, token_price) in tokens_prices.iter_mut() {
-                let codex_id = TokenId::new_for_codex(token_id.chain, &token_id.address);
-                if let Some(meta) = self.token_metadata.get(&codex_id) {
-                    token_price.decimals = meta.decimals;
-                }
+            if let Some(meta) = metadata.get(&codex_id) {
+                token_price.decimals = meta.decimals;
             }
         }
         Ok(())
     }
 
-    #[allow(dead_code)]
     // Handle a PriceEvent: update cache, re-evaluate affected orders, clean up and unsubscribe tokens if there are no trades depending on them anymore.
     async fn on_price_event(&mut self, mut event: PriceEvent) {
         // Sanitizing token id:
         event.token = TokenId::new_for_codex(event.token.chain.clone(), &event.token.address);
-        // Get metadata for the token if not present
-        let token_decimals = if let Some(token_data) = self.token_metadata.get(&event.token) {
-            token_data.decimals
-        } else {
-            tracing::warn!(
-                "Token data not found in cache for {:?}, fetching metadata. Unable to process price event.",
-                event.token
-            );
-            let mut one = HashSet::new();
-            one.insert(event.token.clone());
-            match self.get_tokens_metadata(one).await {
-                Ok(fetched) => {
-                    if let Some(meta) = fetched.get(&event.token) {
-                        self.token_metadata
-                            .insert(event.token.clone(), meta.clone());
-                        meta.decimals
-                    } else {
-                        // Conservative fallback: ignore this event
-                        tracing::warn!(
-                            "Missing metadata for {:?}; skipping price event",
-                            event.token
-                        );
-                        return;
-                    }
-                }
-                Err(err) => {
+        // Get metadata for the token, fetching it if not present
+        let one = HashSet::from([event.token.clone()]);
+        let token_decimals = match self
+            .token_metadata
+            .get_or_fetch(one, |missing| self.get_tokens_metadata(missing))
+            .await
+        {
+            Ok(fetched) => {
+                if let Some(meta) = fetched.get(&event.token) {
+                    meta.decimals
+                } else {
+                    // Conservative fallback: ignore this event
                     tracing::warn!(
-                        "Failed to fetch metadata for {:?}: {:?}; skipping event",
-                        event.token,
-                        err
+                        "Missing metadata for {:?}; skipping price event",
+                        event.token
                     );
                     return;
                 }
             }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to fetch metadata for {:?}: {:?}; skipping event",
+                    event.token,
+                    err
+                );
+                return;
+            }
         };
         // Update in-memory cache
         self.coin_cache.insert(
@@ -657,22 +1343,40 @@ impl MonitorManager {
                 decimals: token_decimals,
             },
         );
+        self.coin_cache_updated_at
+            .insert(event.token.clone(), self.clock.now());
+        self.metrics.record_price_updates(1, self.clock.now());
 
-        self.check_impacted_orders(event.token).await;
+        // Re-evaluated when the coalesce window of the burst ends, with the other updated tokens
+        self.stale_tokens.insert(event.token);
     }
 
-    async fn check_impacted_orders(&mut self, token: TokenId) {
-        tracing::debug!("Checking impacted orders for token: {:?}", token);
-        // Orders which have this token
-        let Some(impacted_orders) = self.trades_by_token.remove(&token) else {
-            tracing::debug!("No impacted orders for token: {:?}", token);
+    /// Re-evaluates every order depending on `tokens`, once per order even when several of its
+    /// tokens were updated
+    async fn check_impacted_orders(&mut self, tokens: HashSet<TokenId>) {
+        tracing::debug!("Checking impacted orders for tokens: {:?}", tokens);
+        // Orders which have these tokens, dropping the ones no longer monitored
+        let mut impacted_orders: Vec<String> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for token in tokens.iter() {
+            let Some(order_ids) = self.trades_by_token.get_mut(token) else {
+                continue;
+            };
+            order_ids.retain(|order_id| self.pending_trades.contains_key(order_id));
+            for order_id in order_ids.iter() {
+                if seen.insert(order_id.clone()) {
+                    impacted_orders.push(order_id.clone());
+                }
+            }
+        }
+        if impacted_orders.is_empty() {
+            tracing::debug!("No impacted orders for tokens: {:?}", tokens);
             return;
-        };
+        }
 
-        let current_timestamp = get_timestamp();
+        let current_timestamp = self.clock.now();
         // Get the swap data of these orders
         let mut subset: Vec<(PendingTrade, Option<u128>)> = Vec::new();
-        let mut remaining_orders: Vec<String> = Vec::new();
         for order_id in impacted_orders.iter() {
             if let Some(pending_trade) = self.pending_trades.get(order_id).cloned() {
                 // Skip expired orders
@@ -682,110 +1386,126 @@ impl MonitorManager {
                         order_id,
                         pending_trade.0.deadline
                     );
-                    // Remove from pending trades
-                    self.remove_order(&pending_trade.0.order_id).await;
+                    self.expire_order(&pending_trade.0.order_id).await;
                 } else {
                     subset.push(pending_trade);
                 }
             }
         }
-        if subset.is_empty() {
-            return;
-        }
 
         // Re-evaluate these trades
         for (pending_trade, estimated_minimum_monitor_amount) in subset.into_iter() {
-            tracing::debug!(
-                "Re-evaluating swap feasibility for order_id: {}, token_in: {}, token_out: {}",
-                pending_trade.order_id,
-                pending_trade.token_in,
-                pending_trade.token_out
-            );
-            let tokens_data = match self.get_all_coins_data_from_swap(&pending_trade).await {
-                Ok(data) => data,
-                Err(error) => {
-                    tracing::error!(
-                        "Error fetching tokens data for order_id {}: {:?}",
-                        pending_trade.order_id,
-                        error
-                    );
-                    remaining_orders.push(pending_trade.order_id.clone());
-                    continue;
-                }
-            };
-            match estimate_amount_out(&pending_trade, &tokens_data) {
-                Ok((estimated_amount_out, _, stablecoin_swap_is_feasible)) => {
+            let evaluation_started = Instant::now();
+            self.re_evaluate_order(pending_trade, estimated_minimum_monitor_amount)
+                .await;
+            self.metrics.record_evaluation(evaluation_started.elapsed());
+        }
+
+        // Drop fired and expired orders from the tokens they were indexed under
+        for token in tokens.iter() {
+            if let Some(order_ids) = self.trades_by_token.get_mut(token) {
+                order_ids.retain(|order_id| self.pending_trades.contains_key(order_id));
+            }
+        }
+    }
+
+    /// Estimates a monitored order with fresh prices, sending its alert and removing it once it
+    /// became feasible
+    async fn re_evaluate_order(
+        &mut self,
+        pending_trade: PendingTrade,
+        estimated_minimum_monitor_amount: Option<u128>,
+    ) {
+        tracing::debug!(
+            "Re-evaluating swap feasibility for order_id: {}, token_in: {}, token_out: {}",
+            pending_trade.order_id,
+            pending_trade.token_in,
+            pending_trade.token_out
+        );
+        let tokens_data = match self.get_all_coins_data_from_swap(&pending_trade).await {
+            Ok(data) => data,
+            Err(error) => {
+                tracing::error!(
+                    "Error fetching tokens data for order_id {}: {:?}",
+                    pending_trade.order_id,
+                    error
+                );
+                let snapshot = price_snapshot(&pending_trade, &self.coin_cache, self.clock.now());
+                self.notify_price_data_missing(&pending_trade, snapshot);
+                return;
+            }
+        };
+        self.notify_price_data_missing(
+            &pending_trade,
+            price_snapshot(&pending_trade, &tokens_data, self.clock.now()),
+        );
+        if self.check_stop_loss(&pending_trade, &tokens_data).await {
+            self.remove_order(&pending_trade.order_id).await;
+            return;
+        }
+        match estimate_amount_out(&pending_trade, &tokens_data) {
+            Ok((estimated_amount_out, _, stablecoin_swap_is_feasible)) => {
+                tracing::debug!(
+                    "Estimated amount out for order_id {}: {}",
+                    pending_trade.order_id,
+                    estimated_amount_out
+                );
+                let needed_amount_out =
+                    estimated_minimum_monitor_amount.unwrap_or(pending_trade.amount_out);
+                tracing::debug!(
+                    "Needed amount out for order_id {}: {}",
+                    pending_trade.order_id,
+                    needed_amount_out
+                );
+                if !stablecoin_swap_is_feasible {
                     tracing::debug!(
-                        "Estimated amount out for order_id {}: {}",
-                        pending_trade.order_id,
-                        estimated_amount_out
+                        "Stablecoin swap requirement not met for order_id: {}, keeping monitoring",
+                        pending_trade.order_id
                     );
-                    let needed_amount_out = if let Some(estimated_minimum_monitor_amount) =
-                        estimated_minimum_monitor_amount
-                    {
-                        estimated_minimum_monitor_amount
-                    } else {
-                        pending_trade.amount_out
-                    };
+                    // Still not feasible, keep monitoring
+                    return;
+                }
+                if estimated_amount_out >= needed_amount_out {
                     tracing::debug!(
-                        "Needed amount out for order_id {}: {}",
-                        pending_trade.order_id,
-                        needed_amount_out
+                        "Swap is feasible for order_id: {}, sending alert",
+                        pending_trade.order_id
                     );
-                    if !stablecoin_swap_is_feasible {
-                        tracing::debug!(
-                            "Stablecoin swap requirement not met for order_id: {}, keeping monitoring",
-                            pending_trade.order_id
+                    if let Err(e) = self.send_alert(feasibility_alert(
+                        &pending_trade,
+                        estimated_amount_out,
+                        &tokens_data,
+                        self.clock.now(),
+                    )) {
+                        tracing::error!(
+                            "Failed to send alert for order_id {}: {:?}",
+                            pending_trade.order_id,
+                            e
                         );
-                        // Still not feasible, keep monitoring
-                        remaining_orders.push(pending_trade.order_id.clone());
-                        continue;
-                    }
-                    if estimated_amount_out >= needed_amount_out {
-                        tracing::debug!(
-                            "Swap is feasible for order_id: {}, sending alert",
-                            pending_trade.order_id
-                        );
-                        if let Err(e) = self.alert_sender.send(MonitorAlert::SwapIsFeasible {
-                            order_id: pending_trade.order_id.clone(),
-                            order_type_fulfillment_data: pending_trade.order_type_fulfillment_data,
-                        }) {
-                            tracing::error!(
-                                "Failed to send alert for order_id {}: {:?}",
-                                pending_trade.order_id,
-                                e
-                            );
-                            // Do not remove the swap if we failed to send alert
-                            remaining_orders.push(pending_trade.order_id.clone());
-                            continue;
-                        }
-                        // Remove from pending trades and every other data structure
-                        self.remove_order(&pending_trade.order_id).await;
-                    } else {
-                        // Still not feasible, keep monitoring
-                        remaining_orders.push(pending_trade.order_id.clone());
+                        // Do not remove the swap if we failed to send alert
+                        return;
                     }
+                    // Remove from pending trades and every other data structure
+                    self.remove_order(&pending_trade.order_id).await;
                 }
-                Err(error) => {
-                    tracing::error!(
-                        "Error checking swap feasibility for order_id {}: {:?}",
-                        pending_trade.order_id,
-                        error
-                    );
-                    remaining_orders.push(pending_trade.order_id.clone());
-                }
+                // Otherwise still not feasible, keep monitoring
+            }
+            Err(error) => {
+                tracing::error!(
+                    "Error checking swap feasibility for order_id {}: {:?}",
+                    pending_trade.order_id,
+                    error
+                );
             }
         }
-
-        // Re-insert remaining orders back into the map
-        self.trades_by_token.insert(token, remaining_orders);
     }
 
     fn update_cache(&mut self, tokens_data: HashMap<TokenId, TokenPrice>) -> HashSet<TokenId> {
         tracing::debug!("Updating coin cache with tokens data: {:?}", tokens_data);
         let mut updated_tokens = HashSet::new();
+        let now = self.clock.now();
         for (token_id, token_price) in tokens_data.into_iter() {
             let mut modified = false;
+            self.coin_cache_updated_at.insert(token_id.clone(), now);
             self.coin_cache
                 .entry(token_id.clone())
                 .and_modify(|existing_price| {
@@ -802,6 +1522,8 @@ impl MonitorManager {
                 updated_tokens.insert(token_id);
             }
         }
+        self.metrics
+            .record_price_updates(updated_tokens.len() as u64, now);
         updated_tokens
     }
 
@@ -817,6 +1539,178 @@ impl MonitorManager {
                 }
             }
         }
+        self.remove_stop_loss_tracker(order_id).await;
+        self.price_data_missing.remove(order_id);
+    }
+
+    /// Sends `MonitorAlert::OrderExpired` with the last known prices and stops monitoring the order
+    async fn expire_order(&mut self, order_id: &str) {
+        if let Some((pending_trade, _)) = self.pending_trades.get(order_id) {
+            let alert = MonitorAlert::OrderExpired {
+                order_id: order_id.to_string(),
+                deadline: pending_trade.deadline,
+                snapshot: price_snapshot(pending_trade, &self.coin_cache, self.clock.now()),
+            };
+            if let Err(e) = self.send_alert(alert) {
+                tracing::error!(
+                    "Failed to send expiration alert for order_id {}: {:?}",
+                    order_id,
+                    e
+                );
+            }
+        }
+        self.remove_order(order_id).await;
+    }
+
+    /// Sends `MonitorAlert::PriceDataMissing` the first time `snapshot` lacks a usable price for
+    /// one of the order tokens, and re-arms it once every price is available again
+    fn notify_price_data_missing(&mut self, pending_trade: &PendingTrade, snapshot: PriceSnapshot) {
+        let mut missing: Vec<TokenId> = required_tokens(pending_trade)
+            .into_iter()
+            .filter(|token| {
+                snapshot
+                    .prices
+                    .get(token)
+                    .is_none_or(|data| !data.price.is_finite() || data.price <= 0.0)
+            })
+            .collect();
+        if missing.is_empty() {
+            self.price_data_missing.remove(&pending_trade.order_id);
+            return;
+        }
+        if !self
+            .price_data_missing
+            .insert(pending_trade.order_id.clone())
+        {
+            return;
+        }
+        missing.sort_by(|a, b| (a.chain as u32, &a.address).cmp(&(b.chain as u32, &b.address)));
+        tracing::debug!(
+            "Missing prices for order_id {}: {:?}",
+            pending_trade.order_id,
+            missing
+        );
+        if let Err(e) = self.send_alert(MonitorAlert::PriceDataMissing {
+            order_id: pending_trade.order_id.clone(),
+            missing,
+            snapshot,
+        }) {
+            tracing::error!(
+                "Failed to send missing price alert for order_id {}: {:?}",
+                pending_trade.order_id,
+                e
+            );
+        }
+    }
+
+    /// Updates the stop loss peak of `pending_trade` with the current `token_in / token_out`
+    /// ratio and sends `MonitorAlert::StopLossTriggered` once the ratio falls below the trigger.
+    /// Returns `true` when the alert was sent and the order doesn't need monitoring anymore.
+    async fn check_stop_loss(
+        &mut self,
+        pending_trade: &PendingTrade,
+        tokens_data: &HashMap<TokenId, TokenPrice>,
+    ) -> bool {
+        let Some(limit_order_data) = &pending_trade.limit_order_data else {
+            return false;
+        };
+        let Some(current_ratio) = stop_loss_ratio(pending_trade, tokens_data) else {
+            tracing::debug!(
+                "Missing prices to evaluate stop loss for order_id: {}",
+                pending_trade.order_id
+            );
+            return false;
+        };
+        let now = self.clock.now();
+
+        let tracker = match self.stop_loss_trackers.get_mut(&pending_trade.order_id) {
+            Some(tracker) if tracker.matches(limit_order_data) => {
+                if !tracker.observe(current_ratio, now) {
+                    return self
+                        .send_stop_loss_alert(pending_trade, current_ratio, tokens_data)
+                        .await;
+                }
+                tracker.clone()
+            }
+            _ => {
+                let Some(tracker) = StopLossTracker::new(
+                    limit_order_data,
+                    pending_trade.stop_loss_initial_ratio,
+                    current_ratio,
+                    now,
+                    pending_trade.deadline,
+                ) else {
+                    return false;
+                };
+                self.stop_loss_trackers
+                    .insert(pending_trade.order_id.clone(), tracker.clone());
+                tracker
+            }
+        };
+
+        // Peak moved (or tracking just started): persist it before evaluating
+        if let Err(error) = self
+            .stop_loss_store
+            .save(&pending_trade.order_id, &tracker)
+            .await
+        {
+            tracing::warn!(
+                "Failed to persist stop loss peak for order_id {}: {:?}",
+                pending_trade.order_id,
+                error
+            );
+        }
+        self.send_stop_loss_alert(pending_trade, current_ratio, tokens_data)
+            .await
+    }
+
+    async fn send_stop_loss_alert(
+        &self,
+        pending_trade: &PendingTrade,
+        current_ratio: f64,
+        tokens_data: &HashMap<TokenId, TokenPrice>,
+    ) -> bool {
+        let Some(tracker) = self.stop_loss_trackers.get(&pending_trade.order_id) else {
+            return false;
+        };
+        if !tracker.is_triggered(current_ratio) {
+            return false;
+        }
+        tracing::debug!(
+            "Stop loss triggered for order_id: {}, peak: {}, trigger: {}, current: {}",
+            pending_trade.order_id,
+            tracker.peak_ratio,
+            tracker.trigger_ratio(),
+            current_ratio
+        );
+        if let Err(e) = self.send_alert(MonitorAlert::StopLossTriggered {
+            order_id: pending_trade.order_id.clone(),
+            order_type_fulfillment_data: pending_trade.order_type_fulfillment_data,
+            peak_ratio: tracker.peak_ratio,
+            trigger_ratio: tracker.trigger_ratio(),
+            current_ratio,
+            snapshot: price_snapshot(pending_trade, tokens_data, self.clock.now()),
+        }) {
+            tracing::error!(
+                "Failed to send stop loss alert for order_id {}: {:?}",
+                pending_trade.order_id,
+                e
+            );
+            return false;
+        }
+        true
+    }
+
+    async fn remove_stop_loss_tracker(&mut self, order_id: &str) {
+        if self.stop_loss_trackers.remove(order_id).is_some()
+            && let Err(error) = self.stop_loss_store.remove(order_id).await
+        {
+            tracing::warn!(
+                "Failed to remove stop loss tracker for order_id {}: {:?}",
+                order_id,
+                error
+            );
+        }
     }
 
     async fn get_tokens_data(
@@ -845,15 +1739,14 @@ impl MonitorManager {
         }
 
         // Fire all batch requests in parallel
-        let provider = &self.codex_provider;
-        {
-            *self.codex_http_requests.write().await += estimated_codex_api_requests;
-        }
+        let provider = &self.price_provider;
+        self.metrics
+            .record_provider_requests(estimated_codex_api_requests);
         let fetches = batches.into_iter().map(|batch| {
             // each future captures provider by shared reference
             async move {
                 provider
-                    .get_tokens_price(&batch, !self.polling_mode.0)
+                    .get_tokens_price(&batch, !self.config.polling_mode)
                     .await
             }
         });
@@ -868,7 +1761,7 @@ impl MonitorManager {
                     combined_by_codex.extend(map.drain());
                 }
                 Err(e) => {
-                    tracing::error!("Codex batch get_tokens_price failed: {:?}", e);
+                    tracing::error!("Batch get_tokens_price failed: {:?}", e);
                     return Err(e);
                 }
             }
@@ -888,7 +1781,7 @@ impl MonitorManager {
     }
 
     async fn get_tokens_metadata(
-        &mut self,
+        &self,
         token_ids: HashSet<TokenId>,
     ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
         // Refine token ids to codex format
@@ -906,14 +1799,13 @@ impl MonitorManager {
         }
 
         // Fire all batch requests in parallel
-        let provider = &self.codex_provider;
+        let provider = &self.price_provider;
         let estimated_codex_api_requests = (token_ids.len() as f64 / 25.0_f64).ceil() as u64;
-        {
-            *self.codex_http_requests.write().await += estimated_codex_api_requests;
-        }
+        self.metrics
+            .record_provider_requests(estimated_codex_api_requests);
         let fetches = batches.into_iter().map(|batch| {
             // each future captures provider by shared reference
-            async move { provider.fetch_token_metadata(&batch).await }
+            async move { provider.get_tokens_metadata(&batch).await }
         });
 
         let results = future::join_all(fetches).await;
@@ -926,7 +1818,7 @@ impl MonitorManager {
                     combined.extend(map.drain());
                 }
                 Err(e) => {
-                    tracing::error!("Codex batch fetch_token_metadata failed: {:?}", e);
+                    tracing::error!("Batch get_tokens_metadata failed: {:?}", e);
                     return Err(e);
                 }
             }
@@ -939,48 +1831,58 @@ impl MonitorManager {
         &mut self,
         tokens: Vec<(TokenId, u128)>,
     ) -> EstimatorResult<(Vec<f64>, f64)> {
+        let valuation = self.value_basket(tokens).await?;
+        if let Some(token) = valuation.missing.first() {
+            return Err(report!(Error::TokenNotFound(format!(
+                "Token {token:?} not found in Codex response"
+            ))));
+        }
+
+        let values = valuation
+            .items
+            .iter()
+            .map(|item| {
+                item.value
+                    .as_ref()
+                    .and_then(|value| value.value_usd.to_f64())
+                    .ok_or(report!(Error::ParseError))
+            })
+            .collect::<EstimatorResult<Vec<f64>>>()?;
+        let total_value = valuation.total_usd.to_f64().ok_or(Error::ParseError)?;
+        Ok((values, total_value))
+    }
+
+    async fn value_basket(
+        &mut self,
+        tokens: Vec<(TokenId, u128)>,
+    ) -> EstimatorResult<BasketValuation> {
         let tokens_to_search = tokens
             .iter()
             .map(|(token_id, _)| token_id.clone())
             .collect::<HashSet<_>>();
         let tokens_data = self.get_coins_data(tokens_to_search).await?;
 
-        let mut total_value = 0.0;
-        let mut values = vec![];
-        for (token, amount) in tokens.into_iter() {
-            let Some(token_data) = tokens_data.get(&token) else {
-                return Err(report!(Error::TokenNotFound(format!(
-                    "Token {token:?} not found in Codex response"
-                ))));
-            };
-            let token_dec_amount = u128_to_f64(amount, token_data.decimals);
-            let token_usd_value = token_dec_amount * token_data.price;
-            total_value += token_usd_value;
-            values.push(token_usd_value);
-        }
-        Ok((values, total_value))
+        let quotes = tokens_data
+            .into_iter()
+            .map(|(token, price)| {
+                let codex_id = TokenId::new_for_codex(token.chain, &token.address);
+                let quote = PriceQuote {
+                    price,
+                    source: self.price_provider.source(),
+                    observed_at: self.coin_cache_updated_at.get(&codex_id).copied(),
+                };
+                (token, quote)
+            })
+            .collect::<HashMap<_, _>>();
+
+        value_basket(&tokens, &quotes, self.clock.now())
     }
 
     async fn get_all_coins_data_from_swap(
         &mut self,
         swap: &PendingTrade,
     ) -> EstimatorResult<HashMap<TokenId, TokenPrice>> {
-        let mut token_ids = HashSet::new();
-        token_ids.insert(TokenId::new_for_codex(swap.src_chain, &swap.token_in));
-        token_ids.insert(TokenId::new_for_codex(swap.dst_chain, &swap.token_out));
-        // Get stablecoin data if needed
-        if let Some(stablecoin_swap_info) = &swap.stablecoin_swap_info {
-            token_ids.insert(TokenId::new_for_codex(
-                swap.src_chain,
-                &stablecoin_swap_info.stablecoin_address,
-            ));
-        }
-        for expense in swap.extra_expenses.iter() {
-            token_ids.insert(TokenId::new_for_codex(
-                expense.0.chain.clone(),
-                &expense.0.address,
-            ));
-        }
+        let token_ids = required_tokens(swap);
         let tokens_data = self.get_coins_data(token_ids).await?;
         Ok(tokens_data)
     }
@@ -1000,43 +1902,14 @@ fn estimate_amount_out(
     ));
 
     if let (Some(src_data), Some(dst_data)) = (src_chain_data, dst_chain_data) {
-        // Fail-fast validation for decimals scale supported by rust_decimal (max 28)
-        let validate_decimals = |d: u8| -> Result<(), Error> {
-            if d > 28 {
-                return Err(Error::ParseError);
-            }
-            Ok(())
-        };
         validate_decimals(src_data.decimals)?;
         validate_decimals(dst_data.decimals)?;
 
-        // Helper to convert amount (u128) with decimals -> Decimal safely
-        let amount_to_decimal = |amount: u128, decimals: u8| -> EstimatorResult<Decimal> {
-            let Some(amount_dec) = Decimal::from_u128(amount) else {
-                return Err(report!(Error::ParseError)
-                    .attach_printable("Failed to convert u128 amount to Decimal"));
-            };
-            let factor = Decimal::from(10u128).powi(-(decimals as i64));
-            Ok(amount_dec * factor)
-        };
-
-        // Validate prices are finite and strictly positive
-        if !src_data.price.is_finite() || !dst_data.price.is_finite() {
-            return Err(report!(Error::ParseError));
-        }
-        let src_price = Decimal::from_f64(src_data.price).ok_or(Error::ParseError)?;
-        let dst_price = Decimal::from_f64(dst_data.price).ok_or(Error::ParseError)?;
-        if src_price.is_sign_negative()
-            || src_price.is_zero()
-            || dst_price.is_sign_negative()
-            || dst_price.is_zero()
-        {
-            return Err(report!(Error::ZeroPriceError));
-        }
+        // Validate price is finite and strictly positive
+        let dst_price = price_to_decimal(dst_data.price)?;
 
         // Value of input in dollars
-        let src_amount_dec = amount_to_decimal(pending_trade.amount_in, src_data.decimals)?;
-        let in_usd_value = src_amount_dec * src_price;
+        let in_usd_value = usd_value(pending_trade.amount_in, src_data)?;
 
         // Check if we can reach min stablecoins that user wants in exchange for token_in
         let stablecoin_swap_is_feasible = if let Some(stablecoin_swap_info) =
@@ -1052,23 +1925,17 @@ fn estimate_amount_out(
                 ))));
             };
 
-            validate_decimals(stablecoin_data.decimals)?;
-
             // Could just ignore this as we are going to always use USD stablecoins, but just in case
-            let stablecoin_price =
-                Decimal::from_f64(stablecoin_data.price).ok_or(Error::ParseError)?;
-            let stablecoin_amount_dec = amount_to_decimal(
-                stablecoin_swap_info.min_stablecoins_amount,
-                stablecoin_data.decimals,
-            )?;
-            let stablecoin_usd_value = stablecoin_amount_dec * stablecoin_price;
+            let stablecoin_usd_value =
+                usd_value(stablecoin_swap_info.min_stablecoins_amount, stablecoin_data)?;
             let stablecoin_safety_margin = Decimal::new(STABLECOIN_SAFETY_MARGIN, 2);
+            let in_usd_value_with_margin = checked_mul(in_usd_value, stablecoin_safety_margin)?;
 
-            if in_usd_value * stablecoin_safety_margin < stablecoin_usd_value {
+            if in_usd_value_with_margin < stablecoin_usd_value {
                 tracing::debug!(
                     "Stablecoin requirement not met for pending swap {:?}: in_usd_value * 0.99 = {}, stablecoin_usd_value = {}",
                     pending_trade,
-                    in_usd_value * stablecoin_safety_margin,
+                    in_usd_value_with_margin,
                     stablecoin_usd_value
                 );
                 false
@@ -1090,22 +1957,14 @@ fn estimate_amount_out(
                     token_id
                 ))));
             };
-            validate_decimals(expense_data.decimals)?;
-            if !expense_data.price.is_finite() {
-                return Err(report!(Error::ParseError));
-            }
-            let expense_price = Decimal::from_f64(expense_data.price).ok_or(Error::ParseError)?;
-            if expense_price.is_sign_negative() || expense_price.is_zero() {
-                return Err(report!(Error::ZeroPriceError));
-            }
-            let expense_amount_dec = amount_to_decimal(*expense.1, expense_data.decimals)?;
-            expenses_usd_value += expense_amount_dec * expense_price;
+            expenses_usd_value =
+                checked_add(expenses_usd_value, usd_value(*expense.1, expense_data)?)?;
         }
 
         // Calculate how many dst tokens can be bought with remaining value
-        let total_value = in_usd_value - expenses_usd_value;
-        let dst_token_amount_dec = total_value / dst_price;
-        let expenses_in_dest_tokens = expenses_usd_value / dst_price;
+        let total_value = checked_sub(in_usd_value, expenses_usd_value)?;
+        let dst_token_amount_dec = checked_div(total_value, dst_price)?;
+        let expenses_in_dest_tokens = checked_div(expenses_usd_value, dst_price)?;
 
         // Convert it back to u128 with proper decimals
         let estimated_amount_out = decimal_to_raw(dst_token_amount_dec, dst_data.decimals as i64)?;
@@ -1131,20 +1990,96 @@ fn estimate_amount_out(
     }
 }
 
-pub fn decimal_to_raw(amount: Decimal, decimals: i64) -> EstimatorResult<u128> {
-    if amount < Decimal::ZERO {
-        return Err(report!(Error::ParseError)
-            .attach_printable("Cannot convert negative decimal amount to raw u128"));
+/// Every token (by Codex id) whose price is needed to evaluate `swap`
+pub(crate) fn required_tokens(swap: &PendingTrade) -> HashSet<TokenId> {
+    let mut token_ids = HashSet::new();
+    token_ids.insert(TokenId::new_for_codex(swap.src_chain, &swap.token_in));
+    token_ids.insert(TokenId::new_for_codex(swap.dst_chain, &swap.token_out));
+    // Get stablecoin data if needed
+    if let Some(stablecoin_swap_info) = &swap.stablecoin_swap_info {
+        token_ids.insert(TokenId::new_for_codex(
+            swap.src_chain,
+            &stablecoin_swap_info.stablecoin_address,
+        ));
+    }
+    for expense in swap.extra_expenses.iter() {
+        token_ids.insert(TokenId::new_for_codex(
+            expense.0.chain.clone(),
+            &expense.0.address,
+        ));
+    }
+    token_ids
+}
+
+/// Prices of the tokens required by `swap` that are present in `prices`
+fn price_snapshot(
+    swap: &PendingTrade,
+    prices: &HashMap<TokenId, TokenPrice>,
+    taken_at: u64,
+) -> PriceSnapshot {
+    PriceSnapshot {
+        prices: required_tokens(swap)
+            .into_iter()
+            .filter_map(|token| prices.get(&token).map(|price| (token, *price)))
+            .collect(),
+        taken_at,
+    }
+}
+
+/// Alert for an order whose estimated amount out reached the required amount.
+/// Orders with take profit settings get `TakeProfitReached`, the rest `SwapIsFeasible`.
+fn feasibility_alert(
+    pending_trade: &PendingTrade,
+    estimated_amount_out: u128,
+    tokens_data: &HashMap<TokenId, TokenPrice>,
+    now: u64,
+) -> MonitorAlert {
+    // Reaching `amount_out` alone only makes the swap feasible, take profit needs its own threshold
+    match pending_trade
+        .limit_order_data
+        .as_ref()
+        .and_then(|data| data.take_profit_min_out)
+        .filter(|take_profit_min_out| estimated_amount_out >= *take_profit_min_out)
+    {
+        Some(take_profit_min_out) => MonitorAlert::TakeProfitReached {
+            order_id: pending_trade.order_id.clone(),
+            order_type_fulfillment_data: pending_trade.order_type_fulfillment_data,
+            take_profit_min_out,
+            estimated_amount_out,
+            snapshot: price_snapshot(pending_trade, tokens_data, now),
+        },
+        None => MonitorAlert::SwapIsFeasible {
+            order_id: pending_trade.order_id.clone(),
+            order_type_fulfillment_data: pending_trade.order_type_fulfillment_data,
+        },
     }
-    // 10^decimals
-    let factor = Decimal::from(10u128).powi(decimals);
-    // amount * 10^decimals
-    let scaled = amount * factor;
+}
 
-    let scaled_int = scaled.trunc();
+/// Next event of `price_events`, never resolving when the monitor doesn't listen to price events
+async fn next_price_event(
+    price_events: &mut Option<tokio::sync::broadcast::Receiver<PriceEvent>>,
+) -> Result<PriceEvent, tokio::sync::broadcast::error::RecvError> {
+    match price_events {
+        Some(price_events) => price_events.recv().await,
+        None => future::pending().await,
+    }
+}
 
-    let raw = scaled_int.to_u128().ok_or(Error::ParseError)?;
-    Ok(raw)
+/// `token_in / token_out` price ratio of the trade, the unit stop loss triggers are expressed in
+fn stop_loss_ratio(
+    pending_trade: &PendingTrade,
+    coin_cache: &HashMap<TokenId, TokenPrice>,
+) -> Option<f64> {
+    let src_data = coin_cache.get(&TokenId::new_for_codex(
+        pending_trade.src_chain,
+        &pending_trade.token_in,
+    ))?;
+    let dst_data = coin_cache.get(&TokenId::new_for_codex(
+        pending_trade.dst_chain,
+        &pending_trade.token_out,
+    ))?;
+    let ratio = src_data.price / dst_data.price;
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
 }
 
 /// Computes how much the monitor should estimate so the solver reaches `min_user`,
@@ -1195,9 +2130,10 @@ mod tests {
     use crate::utils::get_timestamp;
 
     use super::*;
+    use crate::prices::{gecko_terminal::pricing::GeckoTerminalProvider, valuation::PriceSource};
     use crate::tests::init_tracing_in_tests;
-    use intents_models::constants::chains::ChainId;
-    use tokio::sync::{broadcast, mpsc};
+    use intents_models::{constants::chains::ChainId, models::types::common::StopLossType};
+    use tokio::sync::{broadcast, mpsc, oneshot};
 
     fn create_coin_data(price: f64, decimals: u8) -> TokenPrice {
         TokenPrice { price, decimals }
@@ -1246,6 +2182,8 @@ mod tests {
             order_type_fulfillment_data,
             extra_expenses,
             stablecoin_swap_info: min_stablecoins_amount,
+            limit_order_data: None,
+            stop_loss_initial_ratio: None,
         }
     }
 
@@ -1318,55 +2256,303 @@ mod tests {
             "token_a".to_string(),
             "token_b".to_string(),
             1_000_000_000_000_000_000,
-            2_000_000_000_000_000_000,
-            get_timestamp() + 300,
+            2_000_000_000_000_000_000,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+
+        let result = estimate_amount_out(&pending_trade, &coin_cache);
+
+        // real_price_limit = 50/100 = 0.5
+        assert!(result.unwrap().0 > 2_000_000_000_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_check_swaps_feasibility_unsuccessful_swap_extra_expenses() {
+        dotenv::dotenv().ok();
+        init_tracing_in_tests();
+
+        let mut coin_cache = HashMap::new();
+        coin_cache.insert(
+            TokenId {
+                chain: ChainId::Ethereum,
+                address: "token_a".to_string(),
+            },
+            create_coin_data(100.0, 18),
+        );
+        coin_cache.insert(
+            TokenId {
+                chain: ChainId::Base,
+                address: "token_b".to_string(),
+            },
+            create_coin_data(50.0, 18),
+        );
+        coin_cache.insert(
+            TokenId {
+                chain: ChainId::Base,
+                address: "token_c".to_string(),
+            },
+            create_coin_data(10.0, 18),
+        );
+
+        let extra_expenses = HashMap::from([(
+            TokenId {
+                chain: ChainId::Base,
+                address: "token_c".to_string(),
+            },
+            1_000_000_000_000_000_000u128, // 1 token
+        )]);
+
+        let pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(),
+            1_000_000_000_000_000_000,
+            2_000_000_000_000_000_000,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            extra_expenses,
+            None,
+        );
+
+        let result = estimate_amount_out(&pending_trade, &coin_cache);
+
+        // real_price_limit = 50/100 = 0.5
+        assert!(result.unwrap().0 < 2_000_000_000_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_check_swaps_feasibility_missing_coin_data() {
+        let mut coin_cache = HashMap::new();
+        // Only add one token, missing the other
+        coin_cache.insert(
+            TokenId {
+                chain: ChainId::Ethereum,
+                address: "token_a".to_string(),
+            },
+            create_coin_data(100.0, 18),
+        );
+
+        let pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(), // This token is missing from cache
+            1_000_000_000_000_000_000,
+            2_000_000_000_000_000_000,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+
+        let result = estimate_amount_out(&pending_trade, &coin_cache);
+
+        // Should not process the swap due to missing data
+        assert!(result.is_err());
+    }
+
+    fn create_cached_manager(sender: broadcast::Sender<MonitorAlert>) -> MonitorManager {
+        let (_, monitor_receiver) = mpsc::channel(10);
+        let mut manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            CodexProvider::new("test".to_string()),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        );
+        manager.coin_cache.insert(
+            TokenId {
+                chain: ChainId::Ethereum,
+                address: "token_a".to_string(),
+            },
+            create_coin_data(100.0, 18),
+        );
+        manager.coin_cache.insert(
+            TokenId {
+                chain: ChainId::Base,
+                address: "token_b".to_string(),
+            },
+            create_coin_data(50.0, 6),
+        );
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_estimate_amount_out_overflow_is_an_error() {
+        let mut coin_cache = HashMap::new();
+        coin_cache.insert(
+            TokenId {
+                chain: ChainId::Ethereum,
+                address: "token_a".to_string(),
+            },
+            create_coin_data(1e18, 0),
+        );
+        coin_cache.insert(
+            TokenId {
+                chain: ChainId::Base,
+                address: "token_b".to_string(),
+            },
+            create_coin_data(1.0, 0),
+        );
+
+        // 10^28 tokens worth $10^18 each: USD value far beyond what `Decimal` can hold
+        let pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(),
+            10u128.pow(28),
+            1,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+
+        assert!(estimate_amount_out(&pending_trade, &coin_cache).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_reached_alert() {
+        let (sender, mut alerts) = broadcast::channel(10);
+        let mut manager = create_cached_manager(sender);
+        let mut pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(),
+            1_000_000_000_000_000_000,
+            1_900_000,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+        pending_trade.limit_order_data = Some(CommonLimitOrderData {
+            take_profit_min_out: Some(1_900_000),
+            stop_loss_type: Some(StopLossType::Fixed),
+            stop_loss_trigger_price: Some(1.0),
+            stop_loss_triggered: false,
+        });
+
+        manager
+            .check_swap_feasibility(pending_trade, None)
+            .await
+            .unwrap();
+
+        match alerts.try_recv().expect("Expected take profit alert") {
+            MonitorAlert::TakeProfitReached {
+                order_id,
+                take_profit_min_out,
+                estimated_amount_out,
+                snapshot,
+                ..
+            } => {
+                assert_eq!(order_id, "order_1");
+                assert_eq!(take_profit_min_out, 1_900_000);
+                assert_eq!(estimated_amount_out, 2_000_000);
+                assert_eq!(snapshot.prices.len(), 2);
+            }
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+        assert!(manager.pending_trades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_not_reached_reports_feasible_swap() {
+        let (sender, mut alerts) = broadcast::channel(10);
+        let mut manager = create_cached_manager(sender);
+        // amount_out < estimated amount out (2_000_000) < take_profit_min_out
+        let mut pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(),
+            1_000_000_000_000_000_000,
+            1_900_000,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+        pending_trade.limit_order_data = Some(CommonLimitOrderData {
+            take_profit_min_out: Some(2_100_000),
+            stop_loss_type: Some(StopLossType::Fixed),
+            stop_loss_trigger_price: Some(1.0),
+            stop_loss_triggered: false,
+        });
+
+        manager
+            .check_swap_feasibility(pending_trade, None)
+            .await
+            .unwrap();
+
+        match alerts.try_recv().expect("Expected feasibility alert") {
+            MonitorAlert::SwapIsFeasible { order_id, .. } => assert_eq!(order_id, "order_1"),
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+        assert!(manager.pending_trades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_order_expired_alert() {
+        let (sender, mut alerts) = broadcast::channel(10);
+        let mut manager = create_cached_manager(sender);
+        let deadline = get_timestamp() - 1;
+        let pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(),
+            1_000_000_000_000_000_000,
+            3_000_000, // Not reachable
+            deadline,
             OrderTypeFulfillmentData::Limit,
             HashMap::new(),
             None,
         );
-
-        let result = estimate_amount_out(&pending_trade, &coin_cache);
-
-        // real_price_limit = 50/100 = 0.5
-        assert!(result.unwrap().0 > 2_000_000_000_000_000_000);
-    }
-
-    #[tokio::test]
-    async fn test_check_swaps_feasibility_unsuccessful_swap_extra_expenses() {
-        dotenv::dotenv().ok();
-        init_tracing_in_tests();
-
-        let mut coin_cache = HashMap::new();
-        coin_cache.insert(
-            TokenId {
+        manager
+            .check_swap_feasibility(pending_trade, None)
+            .await
+            .unwrap();
+        assert!(alerts.try_recv().is_err());
+
+        manager
+            .check_impacted_orders(HashSet::from([TokenId {
                 chain: ChainId::Ethereum,
                 address: "token_a".to_string(),
-            },
-            create_coin_data(100.0, 18),
-        );
-        coin_cache.insert(
-            TokenId {
-                chain: ChainId::Base,
-                address: "token_b".to_string(),
-            },
-            create_coin_data(50.0, 18),
-        );
-        coin_cache.insert(
-            TokenId {
-                chain: ChainId::Base,
-                address: "token_c".to_string(),
-            },
-            create_coin_data(10.0, 18),
-        );
-
-        let extra_expenses = HashMap::from([(
-            TokenId {
-                chain: ChainId::Base,
-                address: "token_c".to_string(),
-            },
-            1_000_000_000_000_000_000u128, // 1 token
-        )]);
+            }]))
+            .await;
+
+        match alerts.try_recv().expect("Expected expiration alert") {
+            MonitorAlert::OrderExpired {
+                order_id,
+                deadline: expired_deadline,
+                snapshot,
+            } => {
+                assert_eq!(order_id, "order_1");
+                assert_eq!(expired_deadline, deadline);
+                assert_eq!(snapshot.prices.len(), 2);
+            }
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+        assert!(manager.pending_trades.is_empty());
+    }
 
+    #[tokio::test]
+    async fn test_explain_order_and_metrics() {
+        let (sender, _alerts) = broadcast::channel(10);
+        let mut manager = create_cached_manager(sender);
+        let deadline = get_timestamp() + 300;
         let pending_trade = create_pending_trade(
             "order_1".to_string(),
             ChainId::Ethereum,
@@ -1374,49 +2560,200 @@ mod tests {
             "token_a".to_string(),
             "token_b".to_string(),
             1_000_000_000_000_000_000,
-            2_000_000_000_000_000_000,
-            get_timestamp() + 300,
+            3_000_000, // Not reachable
+            deadline,
             OrderTypeFulfillmentData::Limit,
-            extra_expenses,
+            HashMap::new(),
             None,
         );
+        manager
+            .check_swap_feasibility(pending_trade, None)
+            .await
+            .unwrap();
+
+        let explanation = manager.explain_order("order_1").unwrap();
+        assert_eq!(explanation.deadline, deadline);
+        assert_eq!(explanation.prices.len(), 2);
+        assert!(explanation.prices.iter().all(|price| price.price.is_some()));
+        assert_eq!(explanation.estimated_amount_out, Some(2_000_000));
+        assert_eq!(explanation.expenses_in_token_out, Some(0));
+        assert_eq!(explanation.target_amount_out, 3_000_000);
+        assert!(explanation.stablecoin_check.is_none());
+        assert!(explanation.estimation_error.is_none());
+        assert!(!explanation.feasible);
+        assert_eq!(
+            manager
+                .explain_order("unknown")
+                .unwrap_err()
+                .current_context(),
+            &Error::OrderNotFound("unknown".to_string())
+        );
 
-        let result = estimate_amount_out(&pending_trade, &coin_cache);
-
-        // real_price_limit = 50/100 = 0.5
-        assert!(result.unwrap().0 < 2_000_000_000_000_000_000);
-    }
-
-    #[tokio::test]
-    async fn test_check_swaps_feasibility_missing_coin_data() {
-        let mut coin_cache = HashMap::new();
-        // Only add one token, missing the other
-        coin_cache.insert(
-            TokenId {
+        // Expire it to fire an alert
+        manager
+            .pending_trades
+            .get_mut("order_1")
+            .unwrap()
+            .0
+            .deadline = get_timestamp() - 1;
+        manager
+            .check_impacted_orders(HashSet::from([TokenId {
                 chain: ChainId::Ethereum,
                 address: "token_a".to_string(),
-            },
-            create_coin_data(100.0, 18),
-        );
+            }]))
+            .await;
+
+        let metrics = manager.metrics_snapshot();
+        assert_eq!(metrics.pending_orders, 0);
+        assert_eq!(metrics.evaluations, 1);
+        assert_eq!(metrics.price_updates, 0);
+        assert_eq!(metrics.alerts.get("OrderExpired"), Some(&1));
+        assert_eq!(metrics.alerts.len(), 1);
+    }
 
+    #[tokio::test]
+    async fn test_price_data_missing_alert_is_sent_once() {
+        let (sender, mut alerts) = broadcast::channel(10);
+        let mut manager = create_cached_manager(sender);
+        let token_a = TokenId {
+            chain: ChainId::Ethereum,
+            address: "token_a".to_string(),
+        };
+        let token_b = TokenId {
+            chain: ChainId::Base,
+            address: "token_b".to_string(),
+        };
         let pending_trade = create_pending_trade(
             "order_1".to_string(),
             ChainId::Ethereum,
             ChainId::Base,
             "token_a".to_string(),
-            "token_b".to_string(), // This token is missing from cache
-            1_000_000_000_000_000_000,
-            2_000_000_000_000_000_000,
+            "token_b".to_string(),
+            1,
+            1,
             get_timestamp() + 300,
             OrderTypeFulfillmentData::Limit,
             HashMap::new(),
             None,
         );
+        let partial = HashMap::from([(token_a.clone(), create_coin_data(100.0, 18))]);
+        let full = HashMap::from([
+            (token_a.clone(), create_coin_data(100.0, 18)),
+            (token_b.clone(), create_coin_data(50.0, 6)),
+        ]);
+
+        manager.notify_price_data_missing(
+            &pending_trade,
+            price_snapshot(&pending_trade, &partial, get_timestamp()),
+        );
+        manager.notify_price_data_missing(
+            &pending_trade,
+            price_snapshot(&pending_trade, &partial, get_timestamp()),
+        );
+        match alerts.try_recv().expect("Expected missing price alert") {
+            MonitorAlert::PriceDataMissing {
+                order_id,
+                missing,
+                snapshot,
+            } => {
+                assert_eq!(order_id, "order_1");
+                assert_eq!(missing, vec![token_b.clone()]);
+                assert_eq!(snapshot.prices.keys().collect::<Vec<_>>(), vec![&token_a]);
+            }
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+        assert!(alerts.try_recv().is_err());
 
-        let result = estimate_amount_out(&pending_trade, &coin_cache);
+        // Prices came back, so the next gap is reported again
+        manager.notify_price_data_missing(
+            &pending_trade,
+            price_snapshot(&pending_trade, &full, get_timestamp()),
+        );
+        manager.notify_price_data_missing(
+            &pending_trade,
+            price_snapshot(&pending_trade, &partial, get_timestamp()),
+        );
+        assert!(matches!(
+            alerts.try_recv(),
+            Ok(MonitorAlert::PriceDataMissing { .. })
+        ));
+    }
 
-        // Should not process the swap due to missing data
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_snapshot_restore_drops_expired_orders() {
+        let path = std::env::temp_dir().join(format!(
+            "monitor_snapshot_{}_{}.json",
+            std::process::id(),
+            get_timestamp()
+        ));
+        let store: Arc<dyn MonitorSnapshotStore> =
+            Arc::new(crate::monitoring::snapshot::JsonFileMonitorSnapshotStore::new(&path));
+        let now = get_timestamp();
+        let expense_token = TokenId {
+            chain: ChainId::Base,
+            address: "token_c".to_string(),
+        };
+
+        let (sender, _alerts) = broadcast::channel(10);
+        let mut manager = create_cached_manager(sender)
+            .with_snapshot_store(store.clone(), Duration::from_secs(30));
+        manager
+            .coin_cache
+            .insert(expense_token.clone(), create_coin_data(2.0, 6));
+        for (order_id, deadline) in [("live", now + 300), ("expired", now + 5)] {
+            let pending_trade = create_pending_trade(
+                order_id.to_string(),
+                ChainId::Ethereum,
+                ChainId::Base,
+                "token_a".to_string(),
+                "token_b".to_string(),
+                1_000_000_000_000_000_000,
+                3_000_000, // Not reachable
+                deadline,
+                OrderTypeFulfillmentData::Limit,
+                HashMap::from([(expense_token.clone(), 10u128)]),
+                None,
+            );
+            manager
+                .check_swap_feasibility(pending_trade, None)
+                .await
+                .unwrap();
+        }
+        manager.save_snapshot().await;
+        drop(manager);
+
+        let (sender, _alerts) = broadcast::channel(10);
+        let (_, monitor_receiver) = mpsc::channel(10);
+        let mut restored_manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            CodexProvider::new("test".to_string()),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        )
+        .with_snapshot_store(store.clone(), Duration::from_secs(30));
+        let snapshot = store.load().await.unwrap().unwrap();
+        // Restart happening after the second order deadline
+        let restored = restored_manager
+            .apply_snapshot(snapshot, now + 10)
+            .await
+            .unwrap();
+
+        assert_eq!(restored, 1);
+        let (pending_trade, _) = &restored_manager.pending_trades["live"];
+        assert_eq!(pending_trade.extra_expenses[&expense_token], 10);
+        assert_eq!(
+            restored_manager.orders_by_deadline,
+            BTreeMap::from([(now + 300, HashSet::from(["live".to_string()]))])
+        );
+        let token_a = TokenId {
+            chain: ChainId::Ethereum,
+            address: "token_a".to_string(),
+        };
+        assert_eq!(restored_manager.trades_by_token[&token_a], vec!["live"]);
+        assert_eq!(restored_manager.coin_cache.len(), 3);
+        assert_eq!(restored_manager.coin_cache[&token_a].price, 100.0);
+
+        tokio::fs::remove_file(&path).await.ok();
     }
 
     #[tokio::test]
@@ -1435,8 +2772,12 @@ mod tests {
         let (sender, _receiver) = broadcast::channel(10);
         let (_, monitor_receiver) = mpsc::channel(10);
 
-        let mut monitor_manager =
-            MonitorManager::new(monitor_receiver, sender, codex_api_key, (true, 5));
+        let mut monitor_manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            CodexProvider::new(codex_api_key),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        );
 
         // Prepare cache with a token that has zero price (considered as not in cache)
         let eth_token = TokenId {
@@ -1492,8 +2833,12 @@ mod tests {
         let (sender, _receiver) = broadcast::channel(10);
         let (_, monitor_receiver) = mpsc::channel(10);
 
-        let mut monitor_manager =
-            MonitorManager::new(monitor_receiver, sender, codex_api_key, (true, 5));
+        let mut monitor_manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            CodexProvider::new(codex_api_key),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        );
 
         // Prepare cache with a token
         let eth_token = TokenId {
@@ -1519,6 +2864,211 @@ mod tests {
         assert_eq!(token_prices.len(), 0, "Result should be empty");
     }
 
+    #[tokio::test]
+    async fn test_value_basket_from_cache() {
+        init_tracing_in_tests();
+
+        let (sender, _receiver) = broadcast::channel(10);
+        let (_, monitor_receiver) = mpsc::channel(10);
+        // Every token is served from the cache, so no Codex request is made
+        let mut monitor_manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            CodexProvider::new("test".to_string()),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        );
+
+        let usdc = TokenId::new(
+            ChainId::Base,
+            "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string(),
+        );
+        let weth = TokenId::new(
+            ChainId::Base,
+            "0x4200000000000000000000000000000000000006".to_string(),
+        );
+        monitor_manager.coin_cache.insert(
+            usdc.clone(),
+            TokenPrice {
+                price: 1.0,
+                decimals: 6,
+            },
+        );
+        monitor_manager.coin_cache.insert(
+            weth.clone(),
+            TokenPrice {
+                price: 2500.5,
+                decimals: 18,
+            },
+        );
+        let observed_at = get_timestamp() - 10;
+        monitor_manager
+            .coin_cache_updated_at
+            .insert(usdc.clone(), observed_at);
+
+        let valuation = monitor_manager
+            .value_basket(vec![
+                (usdc.clone(), 1_500_000),
+                (weth.clone(), 2_000_000_000_000_000_000),
+            ])
+            .await
+            .expect("value_basket failed");
+
+        assert!(valuation.is_complete());
+        assert_eq!(valuation.total_usd, Decimal::new(50025, 1));
+        let usdc_value = valuation.items[0].value.as_ref().unwrap();
+        assert_eq!(usdc_value.source, PriceSource::Codex);
+        assert_eq!(usdc_value.observed_at, Some(observed_at));
+        assert!(usdc_value.age_secs.unwrap() >= 10);
+        assert_eq!(valuation.items[1].value.as_ref().unwrap().age_secs, None);
+
+        let (values, total) = monitor_manager
+            .evaluate_coins(vec![(usdc, 1_500_000), (weth, 2_000_000_000_000_000_000)])
+            .await
+            .expect("evaluate_coins failed");
+        assert_eq!(values, vec![1.5, 5001.0]);
+        assert_eq!(total, 5002.5);
+    }
+
+    #[tokio::test]
+    async fn test_value_basket_reports_provider_source() {
+        let (sender, _receiver) = broadcast::channel(10);
+        let (_, monitor_receiver) = mpsc::channel(10);
+        let mut monitor_manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            GeckoTerminalProvider::new(),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        );
+        let usdc = TokenId::new(
+            ChainId::Base,
+            "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string(),
+        );
+        monitor_manager.coin_cache.insert(
+            usdc.clone(),
+            TokenPrice {
+                price: 1.0,
+                decimals: 6,
+            },
+        );
+
+        let valuation = monitor_manager
+            .value_basket(vec![(usdc, 1_000_000)])
+            .await
+            .expect("value_basket failed");
+
+        assert_eq!(
+            valuation.items[0].value.as_ref().unwrap().source,
+            PriceSource::GeckoTerminal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trailing_stop_loss_peak_survives_restart() {
+        init_tracing_in_tests();
+
+        let token_a = TokenId {
+            chain: ChainId::Ethereum,
+            address: "token_a".to_string(),
+        };
+        let token_b = TokenId {
+            chain: ChainId::Base,
+            address: "token_b".to_string(),
+        };
+        let mut pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            "token_a".to_string(),
+            "token_b".to_string(),
+            1_000_000_000_000_000_000,
+            1_000_000_000, // Way above the estimation, so only the stop loss can fire
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+        pending_trade.limit_order_data = Some(CommonLimitOrderData {
+            take_profit_min_out: None,
+            stop_loss_type: Some(StopLossType::TrailingPercent),
+            stop_loss_trigger_price: Some(90.0),
+            stop_loss_triggered: false,
+        });
+        let store: Arc<dyn StopLossStore> = Arc::new(InMemoryStopLossStore::new());
+
+        let new_manager = |sender| {
+            let (_, monitor_receiver) = mpsc::channel(10);
+            let mut manager = MonitorManager::new(
+                monitor_receiver,
+                sender,
+                CodexProvider::new("test".to_string()),
+                MonitorConfig::polling(Duration::from_millis(5)),
+            )
+            .with_stop_loss_store(store.clone());
+            manager
+                .coin_cache
+                .insert(token_b.clone(), create_coin_data(1.0, 6));
+            manager
+        };
+
+        // First run: ratio starts at 100 and peaks at 120
+        let (sender, mut alerts) = broadcast::channel(10);
+        let mut manager = new_manager(sender);
+        manager
+            .coin_cache
+            .insert(token_a.clone(), create_coin_data(100.0, 18));
+        manager
+            .check_swap_feasibility(pending_trade.clone(), None)
+            .await
+            .unwrap();
+        manager
+            .coin_cache
+            .insert(token_a.clone(), create_coin_data(120.0, 18));
+        manager
+            .check_impacted_orders(HashSet::from([token_a.clone()]))
+            .await;
+        assert!(alerts.try_recv().is_err());
+        drop(manager);
+
+        // Restart: the peak is restored, so 110 is above the trailing trigger of 108
+        let (sender, mut alerts) = broadcast::channel(10);
+        let mut manager = new_manager(sender);
+        manager.stop_loss_trackers = store.load_all().await.unwrap();
+        manager
+            .coin_cache
+            .insert(token_a.clone(), create_coin_data(110.0, 18));
+        manager
+            .check_swap_feasibility(pending_trade.clone(), None)
+            .await
+            .unwrap();
+        assert!(alerts.try_recv().is_err());
+        assert_eq!(manager.stop_loss_trackers["order_1"].peak_ratio, 120.0);
+
+        manager
+            .coin_cache
+            .insert(token_a.clone(), create_coin_data(107.0, 18));
+        manager
+            .check_impacted_orders(HashSet::from([token_a.clone()]))
+            .await;
+
+        match alerts.try_recv().expect("Expected stop loss alert") {
+            MonitorAlert::StopLossTriggered {
+                order_id,
+                peak_ratio,
+                trigger_ratio,
+                current_ratio,
+                ..
+            } => {
+                assert_eq!(order_id, "order_1");
+                assert_eq!(peak_ratio, 120.0);
+                assert!((trigger_ratio - 108.0).abs() < 1e-9);
+                assert_eq!(current_ratio, 107.0);
+            }
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+        assert!(!manager.pending_trades.contains_key("order_1"));
+        assert!(store.load_all().await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_estimate_orders_amount_out_missing_token_data() {
         dotenv::dotenv().ok();
@@ -1535,8 +3085,12 @@ mod tests {
         let (sender, _receiver) = broadcast::channel(10);
         let (_, monitor_receiver) = mpsc::channel(10);
 
-        let mut monitor_manager =
-            MonitorManager::new(monitor_receiver, sender, codex_api_key, (true, 5));
+        let mut monitor_manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            CodexProvider::new(codex_api_key),
+            MonitorConfig::polling(Duration::from_millis(5)),
+        );
 
         // Only add source token, missing destination token
         monitor_manager.coin_cache.insert(
@@ -1728,4 +3282,340 @@ mod tests {
         let result = estimate_amount_out(&pending_trade, &coin_cache);
         assert!(result.is_err());
     }
+
+    /// Price provider answering `get_tokens_price` from scripted rounds, one round per call.
+    /// The last round keeps being served once the script runs out. Price events are pushed
+    /// through `events`.
+    #[derive(Debug, Clone)]
+    struct ScriptedPriceProvider {
+        rounds: Arc<std::sync::Mutex<std::collections::VecDeque<HashMap<TokenId, f64>>>>,
+        decimals: HashMap<TokenId, u8>,
+        unsubscribed: Arc<std::sync::Mutex<Vec<TokenId>>>,
+        price_requests: Arc<std::sync::atomic::AtomicUsize>,
+        events: broadcast::Sender<PriceEvent>,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceProvider for ScriptedPriceProvider {
+        fn source(&self) -> PriceSource {
+            PriceSource::Replay
+        }
+
+        async fn get_tokens_price(
+            &self,
+            tokens: &[TokenId],
+            _with_subscriptions: bool,
+        ) -> EstimatorResult<HashMap<TokenId, TokenPrice>> {
+            self.price_requests
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let mut rounds = self.rounds.lock().unwrap();
+            let round = if rounds.len() > 1 {
+                rounds.pop_front().unwrap()
+            } else {
+                rounds.front().cloned().unwrap_or_default()
+            };
+            Ok(tokens
+                .iter()
+                .filter_map(|token| {
+                    let price = *round.get(token)?;
+                    Some((token.clone(), create_coin_data(price, self.decimals[token])))
+                })
+                .collect())
+        }
+
+        async fn get_tokens_prices_events(
+            &self,
+        ) -> EstimatorResult<broadcast::Receiver<PriceEvent>> {
+            Ok(self.events.subscribe())
+        }
+
+        async fn subscribe_to_token(&self, _token: TokenId) -> EstimatorResult<()> {
+            Ok(())
+        }
+
+        async fn unsubscribe_from_token(&self, token: TokenId) -> EstimatorResult<bool> {
+            self.unsubscribed.lock().unwrap().push(token);
+            Ok(true)
+        }
+
+        async fn get_tokens_metadata(
+            &self,
+            tokens: &[TokenId],
+        ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+            Ok(tokens
+                .iter()
+                .filter_map(|token| {
+                    let decimals = *self.decimals.get(token)?;
+                    Some((
+                        token.clone(),
+                        TokenMetadata {
+                            name: token.address.clone(),
+                            symbol: token.address.clone(),
+                            decimals,
+                        },
+                    ))
+                })
+                .collect())
+        }
+    }
+
+    const SCRIPTED_TOKEN_A: &str = "0x1111111111111111111111111111111111111111";
+    const SCRIPTED_TOKEN_B: &str = "0x2222222222222222222222222222222222222222";
+
+    fn scripted_provider(rounds: Vec<(f64, f64)>) -> ScriptedPriceProvider {
+        let token_a = TokenId::new_for_codex(ChainId::Ethereum, SCRIPTED_TOKEN_A);
+        let token_b = TokenId::new_for_codex(ChainId::Base, SCRIPTED_TOKEN_B);
+        ScriptedPriceProvider {
+            rounds: Arc::new(std::sync::Mutex::new(
+                rounds
+                    .into_iter()
+                    .map(|(price_a, price_b)| {
+                        HashMap::from([(token_a.clone(), price_a), (token_b.clone(), price_b)])
+                    })
+                    .collect(),
+            )),
+            decimals: HashMap::from([(token_a, 18), (token_b, 6)]),
+            unsubscribed: Arc::default(),
+            price_requests: Arc::default(),
+            events: broadcast::channel(64).0,
+        }
+    }
+
+    fn scripted_pending_trade(order_id: &str, amount_out: u128) -> PendingTrade {
+        create_pending_trade(
+            order_id.to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            SCRIPTED_TOKEN_A.to_string(),
+            SCRIPTED_TOKEN_B.to_string(),
+            1_000_000_000_000_000_000,
+            amount_out,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_queries_are_answered_before_queued_registrations() {
+        let provider = scripted_provider(vec![(1.0, 1.0)]);
+        let (sender, _alerts) = broadcast::channel(10);
+        let (_, monitor_receiver) = mpsc::channel(10);
+        let mut manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            provider.clone(),
+            MonitorConfig::polling(Duration::from_secs(60)),
+        );
+
+        for order_id in ["order_0", "order_1"] {
+            manager
+                .accept_request(MonitorRequest::CheckSwapFeasibility {
+                    pending_trade: scripted_pending_trade(order_id, 2_000_000),
+                    solver_last_bid: None,
+                })
+                .await;
+        }
+        manager
+            .accept_request(MonitorRequest::RemoveCheckSwapFeasibility {
+                order_id: "order_1".to_string(),
+            })
+            .await;
+        manager
+            .accept_request(MonitorRequest::CheckSwapFeasibility {
+                pending_trade: scripted_pending_trade("order_2", 2_000_000),
+                solver_last_bid: None,
+            })
+            .await;
+        let (resp, mut response) = oneshot::channel();
+        manager
+            .accept_request(MonitorRequest::GetMetrics { resp })
+            .await;
+
+        // The query was answered while registrations are still queued
+        let metrics = response.try_recv().unwrap().unwrap();
+        assert_eq!(metrics.pending_orders, 0);
+        assert_eq!(manager.order_updates.len(), 4);
+        assert_eq!(
+            provider
+                .price_requests
+                .load(std::sync::atomic::Ordering::SeqCst),
+            0
+        );
+
+        // Registrations are applied in order, sharing one price fetch
+        manager.process_order_updates().await;
+        assert!(manager.order_updates.is_empty());
+        let mut pending: Vec<&String> = manager.pending_trades.keys().collect();
+        pending.sort();
+        assert_eq!(pending, vec!["order_0", "order_2"]);
+        assert_eq!(
+            provider
+                .price_requests
+                .load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+        assert_eq!(manager.metrics_snapshot().provider_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_impacted_orders_are_evaluated_once_per_burst() {
+        let provider = scripted_provider(vec![(1.0, 1.0)]);
+        let token_a = TokenId::new_for_codex(ChainId::Ethereum, SCRIPTED_TOKEN_A);
+        let token_b = TokenId::new_for_codex(ChainId::Base, SCRIPTED_TOKEN_B);
+        let (sender, mut alerts) = broadcast::channel(10);
+        let (monitor_sender, monitor_receiver) = mpsc::channel(10);
+        let manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            provider.clone(),
+            MonitorConfig {
+                price_event_coalesce_window: Duration::from_millis(50),
+                ..MonitorConfig::subscriptions()
+            },
+        );
+        let metrics = manager.metrics();
+        tokio::spawn(manager.run());
+
+        monitor_sender
+            .send(MonitorRequest::CheckSwapFeasibility {
+                pending_trade: scripted_pending_trade("order_1", 2_000_000),
+                solver_last_bid: None,
+            })
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while metrics.snapshot(0).evaluations < 1 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("Order was never evaluated");
+        let price_updates_before = metrics.snapshot(0).price_updates;
+
+        // Both tokens of the order moved within one window: token A doubled, token B stayed almost flat
+        for (token, price) in [(&token_a, 1.5), (&token_b, 0.99), (&token_a, 2.0)] {
+            provider
+                .events
+                .send(PriceEvent {
+                    token: token.clone(),
+                    price: create_coin_data(price, 0),
+                })
+                .unwrap();
+        }
+
+        let alert = tokio::time::timeout(Duration::from_secs(2), alerts.recv())
+            .await
+            .expect("Expected a feasibility alert after the price events")
+            .unwrap();
+        match alert {
+            MonitorAlert::SwapIsFeasible { order_id, .. } => assert_eq!(order_id, "order_1"),
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(alerts.try_recv().is_err());
+        let snapshot = metrics.snapshot(0);
+        assert_eq!(snapshot.evaluations, 2);
+        assert_eq!(snapshot.price_updates - price_updates_before, 3);
+    }
+
+    #[tokio::test]
+    async fn test_polling_scripted_provider_sends_alert_once_feasible() {
+        // Token A goes from $1 to $3 between the first fetch and the first polling round
+        let provider = scripted_provider(vec![(1.0, 1.0), (3.0, 1.0)]);
+        let (sender, mut alerts) = broadcast::channel(10);
+        let (monitor_sender, monitor_receiver) = mpsc::channel(10);
+        let manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            provider,
+            MonitorConfig::polling(Duration::from_millis(20)),
+        );
+        tokio::spawn(manager.run());
+
+        let pending_trade = create_pending_trade(
+            "order_1".to_string(),
+            ChainId::Ethereum,
+            ChainId::Base,
+            SCRIPTED_TOKEN_A.to_string(),
+            SCRIPTED_TOKEN_B.to_string(),
+            1_000_000_000_000_000_000,
+            2_000_000,
+            get_timestamp() + 300,
+            OrderTypeFulfillmentData::Limit,
+            HashMap::new(),
+            None,
+        );
+        monitor_sender
+            .send(MonitorRequest::CheckSwapFeasibility {
+                pending_trade,
+                solver_last_bid: None,
+            })
+            .await
+            .unwrap();
+
+        let alert = tokio::time::timeout(Duration::from_secs(2), alerts.recv())
+            .await
+            .expect("Expected an alert after the price moved")
+            .unwrap();
+        match alert {
+            MonitorAlert::SwapIsFeasible { order_id, .. } => assert_eq!(order_id, "order_1"),
+            other => panic!("Unexpected alert: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unused_tokens_are_unsubscribed_after_grace() {
+        let provider = scripted_provider(vec![(1.0, 1.0)]);
+        let token_a = TokenId::new_for_codex(ChainId::Ethereum, SCRIPTED_TOKEN_A);
+        let token_b = TokenId::new_for_codex(ChainId::Base, SCRIPTED_TOKEN_B);
+        let (sender, _alerts) = broadcast::channel(10);
+        let (_, monitor_receiver) = mpsc::channel(10);
+        let mut manager = MonitorManager::new(
+            monitor_receiver,
+            sender,
+            provider.clone(),
+            MonitorConfig {
+                unsubscribe_grace: Duration::from_millis(100),
+                ..MonitorConfig::subscriptions()
+            },
+        );
+
+        // Fetched tokens are tracked without orders
+        manager
+            .get_coins_data(HashSet::from([token_a.clone(), token_b.clone()]))
+            .await
+            .unwrap();
+        manager.unsubscribe_unused_tokens().await;
+        assert!(provider.unsubscribed.lock().unwrap().is_empty());
+        assert_eq!(manager.coin_cache.len(), 2);
+
+        // Token A gets an order during the grace period
+        manager
+            .trades_by_token
+            .get_mut(&token_a)
+            .unwrap()
+            .push("order_1".to_string());
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        manager.unsubscribe_unused_tokens().await;
+        assert_eq!(
+            *provider.unsubscribed.lock().unwrap(),
+            vec![token_b.clone()]
+        );
+        assert!(!manager.coin_cache.contains_key(&token_b));
+        assert!(manager.coin_cache.contains_key(&token_a));
+
+        // Its grace period restarts once the order is gone
+        manager.trades_by_token.get_mut(&token_a).unwrap().clear();
+        manager.unsubscribe_unused_tokens().await;
+        assert_eq!(provider.unsubscribed.lock().unwrap().len(), 1);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        manager.unsubscribe_unused_tokens().await;
+        assert_eq!(
+            *provider.unsubscribed.lock().unwrap(),
+            vec![token_b, token_a]
+        );
+        assert!(manager.trades_by_token.is_empty());
+    }
 }
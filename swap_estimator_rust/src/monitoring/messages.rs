@@ -1,16 +1,22 @@
 use std::collections::{HashMap, HashSet};
 
 use intents_models::models::types::order::OrderTypeFulfillmentData;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
 use tokio::sync::oneshot;
 
 use crate::{
     error::Error,
-    monitoring::manager::PendingTrade,
-    prices::{TokenId, TokenPrice, estimating::OrderEstimationData},
+    monitoring::{
+        manager::PendingTrade, metrics::MonitorMetricsSnapshot, trailing_stop::StopLossTracker,
+    },
+    prices::{TokenId, TokenPrice, estimating::OrderEstimationData, valuation::BasketValuation},
 };
 
 type Responder<T> = oneshot::Sender<Result<T, Error>>;
 
+// Requests are moved through the channel once, boxing `PendingTrade` would buy nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum MonitorRequest {
     GetCoinsData {
@@ -32,12 +38,149 @@ pub enum MonitorRequest {
         tokens: Vec<(TokenId, u128)>,
         resp: Responder<(Vec<f64>, f64)>,
     },
+    ValueBasket {
+        tokens: Vec<(TokenId, u128)>,
+        resp: Responder<BasketValuation>,
+    },
+    /// Latest estimation breakdown of a monitored order
+    ExplainOrder {
+        order_id: String,
+        resp: Responder<OrderExplanation>,
+    },
+    GetMetrics {
+        resp: Responder<MonitorMetricsSnapshot>,
+    },
+    /// Orders being monitored, sorted by order id
+    GetPendingOrders {
+        resp: Responder<Vec<PendingTrade>>,
+    },
 }
 
-#[derive(Debug, Clone)]
+/// Prices the monitor evaluated an order with, keyed by Codex token id
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceSnapshot {
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub prices: HashMap<TokenId, TokenPrice>,
+    /// Unix timestamp (seconds) of the evaluation
+    pub taken_at: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
 pub enum MonitorAlert {
+    /// Estimated amount out reached the amount the order asks for. Sent for orders without
+    /// take profit settings.
     SwapIsFeasible {
         order_id: String,
         order_type_fulfillment_data: OrderTypeFulfillmentData,
     },
+    /// Estimated amount out reached the amount required by a limit order with `take_profit_min_out`
+    TakeProfitReached {
+        order_id: String,
+        order_type_fulfillment_data: OrderTypeFulfillmentData,
+        #[serde_as(as = "DisplayFromStr")]
+        take_profit_min_out: u128,
+        #[serde_as(as = "DisplayFromStr")]
+        estimated_amount_out: u128,
+        snapshot: PriceSnapshot,
+    },
+    /// `token_in / token_out` ratio fell below the (trailing) stop loss trigger
+    StopLossTriggered {
+        order_id: String,
+        order_type_fulfillment_data: OrderTypeFulfillmentData,
+        /// Highest ratio observed while monitoring the order
+        peak_ratio: f64,
+        /// Trigger level derived from the peak and the stop loss type
+        trigger_ratio: f64,
+        current_ratio: f64,
+        snapshot: PriceSnapshot,
+    },
+    /// Order deadline passed before any other alert fired; it is no longer monitored
+    OrderExpired {
+        order_id: String,
+        deadline: u64,
+        /// Last known prices of the order tokens
+        snapshot: PriceSnapshot,
+    },
+    /// Order can't be evaluated because some of its tokens have no usable price.
+    /// Sent once until prices become available again.
+    PriceDataMissing {
+        order_id: String,
+        missing: Vec<TokenId>,
+        /// Prices that were available
+        snapshot: PriceSnapshot,
+    },
+}
+
+impl MonitorAlert {
+    pub fn order_id(&self) -> &str {
+        match self {
+            MonitorAlert::SwapIsFeasible { order_id, .. }
+            | MonitorAlert::TakeProfitReached { order_id, .. }
+            | MonitorAlert::StopLossTriggered { order_id, .. }
+            | MonitorAlert::OrderExpired { order_id, .. }
+            | MonitorAlert::PriceDataMissing { order_id, .. } => order_id,
+        }
+    }
+
+    /// Name of the alert variant, as used in metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MonitorAlert::SwapIsFeasible { .. } => "SwapIsFeasible",
+            MonitorAlert::TakeProfitReached { .. } => "TakeProfitReached",
+            MonitorAlert::StopLossTriggered { .. } => "StopLossTriggered",
+            MonitorAlert::OrderExpired { .. } => "OrderExpired",
+            MonitorAlert::PriceDataMissing { .. } => "PriceDataMissing",
+        }
+    }
+}
+
+/// Why a monitored order has (or hasn't) fired, computed from the prices currently cached
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderExplanation {
+    pub order_id: String,
+    pub deadline: u64,
+    /// Every token the estimation needs, by Codex id
+    pub prices: Vec<ExplainedPrice>,
+    #[serde_as(as = "Vec<(_, DisplayFromStr)>")]
+    pub extra_expenses: HashMap<TokenId, u128>,
+    /// `extra_expenses` valued in `token_out`
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub expenses_in_token_out: Option<u128>,
+    /// Amount out after expenses, None when it couldn't be estimated
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub estimated_amount_out: Option<u128>,
+    /// Amount the estimation must reach: the monitor estimation required by the solver last bid,
+    /// or the order `amount_out`
+    #[serde_as(as = "DisplayFromStr")]
+    pub target_amount_out: u128,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub take_profit_min_out: Option<u128>,
+    pub stablecoin_check: Option<StablecoinCheck>,
+    pub stop_loss: Option<StopLossTracker>,
+    /// Why the estimation failed, e.g. a missing price
+    pub estimation_error: Option<String>,
+    /// Whether the order would fire with the cached prices
+    pub feasible: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainedPrice {
+    pub token: TokenId,
+    pub price: Option<TokenPrice>,
+    /// Unix timestamp (seconds) of the last price observation
+    pub updated_at: Option<u64>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StablecoinCheck {
+    pub stablecoin_address: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_stablecoins_amount: u128,
+    /// None when the estimation failed before the check
+    pub passed: Option<bool>,
 }
@@ -0,0 +1,250 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write as _,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds of price updates `price_updates_per_second` is averaged over
+pub const PRICE_RATE_WINDOW_SECS: u64 = 60;
+
+/// Counters and gauges of a `MonitorManager`. Shared through an `Arc`, so they can be read
+/// without going through the monitor request loop.
+#[derive(Debug, Default)]
+pub struct MonitorMetrics {
+    pending_orders: AtomicU64,
+    subscribed_tokens: AtomicU64,
+    price_updates: AtomicU64,
+    /// (unix second, updates observed in it), oldest first
+    price_update_window: Mutex<VecDeque<(u64, u64)>>,
+    evaluations: AtomicU64,
+    evaluation_micros_total: AtomicU64,
+    evaluation_micros_max: AtomicU64,
+    alerts: Mutex<BTreeMap<&'static str, u64>>,
+    provider_requests: AtomicU64,
+}
+
+/// Point in time copy of `MonitorMetrics`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonitorMetricsSnapshot {
+    pub pending_orders: u64,
+    /// Tokens with at least one pending order
+    pub subscribed_tokens: u64,
+    pub price_updates: u64,
+    /// Average over the last `PRICE_RATE_WINDOW_SECS` seconds
+    pub price_updates_per_second: f64,
+    /// Order evaluations, i.e. estimations of an order against fresh prices
+    pub evaluations: u64,
+    pub evaluation_latency_avg_micros: u64,
+    /// Slowest single evaluation
+    pub evaluation_latency_max_micros: u64,
+    /// Alert type to alerts fired
+    pub alerts: BTreeMap<String, u64>,
+    /// Estimated HTTP requests made to the price provider
+    pub provider_requests: u64,
+}
+
+impl MonitorMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_pending_orders(&self, pending_orders: usize) {
+        self.pending_orders
+            .store(pending_orders as u64, Ordering::Relaxed);
+    }
+
+    pub fn set_subscribed_tokens(&self, subscribed_tokens: usize) {
+        self.subscribed_tokens
+            .store(subscribed_tokens as u64, Ordering::Relaxed);
+    }
+
+    /// Records `count` price updates observed at unix second `now`
+    pub fn record_price_updates(&self, count: u64, now: u64) {
+        if count == 0 {
+            return;
+        }
+        self.price_updates.fetch_add(count, Ordering::Relaxed);
+        let mut window = self
+            .price_update_window
+            .lock()
+            .expect("price update window lock poisoned");
+        match window.back_mut() {
+            Some((second, updates)) if *second == now => *updates += count,
+            _ => window.push_back((now, count)),
+        }
+        while window
+            .front()
+            .is_some_and(|(second, _)| second + PRICE_RATE_WINDOW_SECS <= now)
+        {
+            window.pop_front();
+        }
+    }
+
+    /// Records an order evaluation that took `elapsed`
+    pub fn record_evaluation(&self, elapsed: Duration) {
+        let micros = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        self.evaluation_micros_total
+            .fetch_add(micros, Ordering::Relaxed);
+        self.evaluation_micros_max
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub fn record_alert(&self, kind: &'static str) {
+        *self
+            .alerts
+            .lock()
+            .expect("alerts lock poisoned")
+            .entry(kind)
+            .or_default() += 1;
+    }
+
+    pub fn record_provider_requests(&self, count: u64) {
+        self.provider_requests.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Current values, with the price update rate computed at unix second `now`
+    pub fn snapshot(&self, now: u64) -> MonitorMetricsSnapshot {
+        let recent_price_updates: u64 = self
+            .price_update_window
+            .lock()
+            .expect("price update window lock poisoned")
+            .iter()
+            .filter(|(second, _)| second + PRICE_RATE_WINDOW_SECS > now)
+            .map(|(_, updates)| updates)
+            .sum();
+        let evaluations = self.evaluations.load(Ordering::Relaxed);
+        let evaluation_micros_total = self.evaluation_micros_total.load(Ordering::Relaxed);
+        MonitorMetricsSnapshot {
+            pending_orders: self.pending_orders.load(Ordering::Relaxed),
+            subscribed_tokens: self.subscribed_tokens.load(Ordering::Relaxed),
+            price_updates: self.price_updates.load(Ordering::Relaxed),
+            price_updates_per_second: recent_price_updates as f64 / PRICE_RATE_WINDOW_SECS as f64,
+            evaluations,
+            evaluation_latency_avg_micros: evaluation_micros_total
+                .checked_div(evaluations)
+                .unwrap_or_default(),
+            evaluation_latency_max_micros: self.evaluation_micros_max.load(Ordering::Relaxed),
+            alerts: self
+                .alerts
+                .lock()
+                .expect("alerts lock poisoned")
+                .iter()
+                .map(|(kind, count)| (kind.to_string(), *count))
+                .collect(),
+            provider_requests: self.provider_requests.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl MonitorMetricsSnapshot {
+    /// Renders the metrics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {value}");
+        };
+        metric(
+            "monitor_pending_orders",
+            "gauge",
+            "Orders being monitored",
+            self.pending_orders.to_string(),
+        );
+        metric(
+            "monitor_subscribed_tokens",
+            "gauge",
+            "Tokens with at least one pending order",
+            self.subscribed_tokens.to_string(),
+        );
+        metric(
+            "monitor_price_updates_total",
+            "counter",
+            "Price updates received",
+            self.price_updates.to_string(),
+        );
+        metric(
+            "monitor_price_updates_per_second",
+            "gauge",
+            "Price updates per second over the last minute",
+            self.price_updates_per_second.to_string(),
+        );
+        metric(
+            "monitor_evaluations_total",
+            "counter",
+            "Order evaluations",
+            self.evaluations.to_string(),
+        );
+        metric(
+            "monitor_evaluation_latency_avg_micros",
+            "gauge",
+            "Average order evaluation latency",
+            self.evaluation_latency_avg_micros.to_string(),
+        );
+        metric(
+            "monitor_evaluation_latency_max_micros",
+            "gauge",
+            "Highest order evaluation latency",
+            self.evaluation_latency_max_micros.to_string(),
+        );
+        metric(
+            "monitor_provider_requests_total",
+            "counter",
+            "Estimated price provider HTTP requests",
+            self.provider_requests.to_string(),
+        );
+        let _ = writeln!(out, "# HELP monitor_alerts_total Alerts fired by type");
+        let _ = writeln!(out, "# TYPE monitor_alerts_total counter");
+        for (kind, count) in self.alerts.iter() {
+            let _ = writeln!(out, "monitor_alerts_total{{type=\"{kind}\"}} {count}");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_aggregates_counters() {
+        let metrics = MonitorMetrics::new();
+        metrics.set_pending_orders(3);
+        metrics.set_subscribed_tokens(2);
+        metrics.record_price_updates(30, 1_000);
+        metrics.record_price_updates(30, 1_030);
+        metrics.record_evaluation(Duration::from_micros(100));
+        metrics.record_evaluation(Duration::from_micros(200));
+        metrics.record_evaluation(Duration::from_micros(600));
+        metrics.record_alert("SwapIsFeasible");
+        metrics.record_alert("SwapIsFeasible");
+        metrics.record_alert("OrderExpired");
+        metrics.record_provider_requests(4);
+
+        let snapshot = metrics.snapshot(1_030);
+        assert_eq!(snapshot.pending_orders, 3);
+        assert_eq!(snapshot.subscribed_tokens, 2);
+        assert_eq!(snapshot.price_updates, 60);
+        assert_eq!(snapshot.price_updates_per_second, 1.0);
+        assert_eq!(snapshot.evaluations, 3);
+        assert_eq!(snapshot.evaluation_latency_avg_micros, 300);
+        assert_eq!(snapshot.evaluation_latency_max_micros, 600);
+        assert_eq!(snapshot.alerts["SwapIsFeasible"], 2);
+        assert_eq!(snapshot.alerts["OrderExpired"], 1);
+        assert_eq!(snapshot.provider_requests, 4);
+
+        // First second fell out of the rate window
+        assert_eq!(metrics.snapshot(1_060).price_updates_per_second, 0.5);
+
+        let text = snapshot.to_prometheus();
+        assert!(text.contains("monitor_pending_orders 3\n"));
+        assert!(text.contains("monitor_alerts_total{type=\"SwapIsFeasible\"} 2\n"));
+    }
+}
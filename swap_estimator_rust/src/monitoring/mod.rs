@@ -1,3 +1,13 @@
+pub mod backtest;
 pub mod client;
+pub mod dca_scheduler;
+#[cfg(feature = "http-api")]
+pub mod http;
 pub mod manager;
 pub mod messages;
+pub mod metrics;
+pub mod nats;
+pub mod price_watcher;
+pub mod snapshot;
+pub mod trailing_stop;
+pub mod watchlist;
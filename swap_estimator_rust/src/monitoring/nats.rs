@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+
+use error_stack::ResultExt as _;
+use futures_util::Stream;
+use intents_models::network::nats::NatsManager;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use tokio::sync::broadcast;
+
+use crate::{
+    error::{Error, EstimatorResult},
+    monitoring::{
+        client::MonitorClient,
+        manager::PendingTrade,
+        messages::{MonitorAlert, OrderExplanation},
+        metrics::MonitorMetricsSnapshot,
+    },
+    prices::{TokenId, TokenPrice, estimating::OrderEstimationData, valuation::BasketValuation},
+};
+
+pub const MONITOR_REQUESTS_SUBJECT: &str = "monitor.requests";
+pub const MONITOR_ALERTS_SUBJECT: &str = "monitor.alerts";
+
+/// Serializable counterpart of `MonitorRequest`, answered with a `MonitorNatsResponse`
+// Same as `MonitorRequest`, requests are only built to be serialized once
+#[allow(clippy::large_enum_variant)]
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum MonitorNatsRequest {
+    GetCoinsData {
+        token_ids: HashSet<TokenId>,
+    },
+    CheckSwapFeasibility {
+        pending_trade: PendingTrade,
+        #[serde_as(as = "Option<DisplayFromStr>")]
+        solver_last_bid: Option<u128>,
+    },
+    RemoveCheckSwapFeasibility {
+        order_id: String,
+    },
+    EstimateOrdersAmountOut {
+        orders: Vec<OrderEstimationData>,
+    },
+    EvaluateCoins {
+        #[serde_as(as = "Vec<(_, DisplayFromStr)>")]
+        tokens: Vec<(TokenId, u128)>,
+    },
+    ValueBasket {
+        #[serde_as(as = "Vec<(_, DisplayFromStr)>")]
+        tokens: Vec<(TokenId, u128)>,
+    },
+    ExplainOrder {
+        order_id: String,
+    },
+    GetMetrics,
+    GetPendingOrders,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum MonitorNatsResponse {
+    CoinsData(#[serde_as(as = "Vec<(_, _)>")] HashMap<TokenId, TokenPrice>),
+    /// Fire-and-forget requests were queued on the monitor
+    Accepted,
+    OrdersAmountOut(#[serde_as(as = "HashMap<_, DisplayFromStr>")] HashMap<String, u128>),
+    CoinsEvaluation {
+        values: Vec<f64>,
+        total: f64,
+    },
+    BasketValuation(BasketValuation),
+    OrderExplanation(Box<OrderExplanation>),
+    Metrics(MonitorMetricsSnapshot),
+    PendingOrders(Vec<PendingTrade>),
+    Error(String),
+}
+
+/// Answers `MonitorNatsRequest`s published on `subject` using the in-process `monitor`.
+/// Runs until the NATS subscription ends.
+pub async fn serve_monitor_requests(
+    nats: NatsManager<MonitorNatsResponse, MonitorNatsRequest>,
+    subject: &'static str,
+    monitor: MonitorClient,
+) -> EstimatorResult<()> {
+    nats.subscribe_and_process(subject, move |request| {
+        let monitor = monitor.clone();
+        async move { process_monitor_request(&monitor, request).await }
+    })
+    .await
+    .change_context(Error::ResponseError)
+    .attach_printable("Monitor NATS request subscription failed")
+}
+
+pub async fn process_monitor_request(
+    monitor: &MonitorClient,
+    request: MonitorNatsRequest,
+) -> MonitorNatsResponse {
+    tracing::debug!("Received monitor NATS request: {:?}", request);
+    let response = match request {
+        MonitorNatsRequest::GetCoinsData { token_ids } => monitor
+            .get_coins_data(token_ids)
+            .await
+            .map(MonitorNatsResponse::CoinsData),
+        MonitorNatsRequest::CheckSwapFeasibility {
+            pending_trade,
+            solver_last_bid,
+        } => monitor
+            .check_swap_feasibility(pending_trade, solver_last_bid)
+            .await
+            .map(|_| MonitorNatsResponse::Accepted),
+        MonitorNatsRequest::RemoveCheckSwapFeasibility { order_id } => monitor
+            .remove_check_swap_feasibility(order_id)
+            .await
+            .map(|_| MonitorNatsResponse::Accepted),
+        MonitorNatsRequest::EstimateOrdersAmountOut { orders } => monitor
+            .estimate_orders_amount_out(orders)
+            .await
+            .map(MonitorNatsResponse::OrdersAmountOut),
+        MonitorNatsRequest::EvaluateCoins { tokens } => monitor
+            .evaluate_coins(tokens)
+            .await
+            .map(|(values, total)| MonitorNatsResponse::CoinsEvaluation { values, total }),
+        MonitorNatsRequest::ValueBasket { tokens } => monitor
+            .value_basket(tokens)
+            .await
+            .map(MonitorNatsResponse::BasketValuation),
+        MonitorNatsRequest::ExplainOrder { order_id } => monitor
+            .explain_order(order_id)
+            .await
+            .map(|explanation| MonitorNatsResponse::OrderExplanation(Box::new(explanation))),
+        MonitorNatsRequest::GetMetrics => monitor
+            .get_metrics()
+            .await
+            .map(MonitorNatsResponse::Metrics),
+        MonitorNatsRequest::GetPendingOrders => monitor
+            .get_pending_orders()
+            .await
+            .map(MonitorNatsResponse::PendingOrders),
+    };
+    response.unwrap_or_else(|error| {
+        tracing::error!("Monitor NATS request failed: {:?}", error);
+        MonitorNatsResponse::Error(error.current_context().to_string())
+    })
+}
+
+/// Publishes every alert of the monitor on `subject`, so remote clients can follow them
+pub async fn publish_monitor_alerts(
+    nats: NatsManager<MonitorAlert, MonitorAlert>,
+    subject: &'static str,
+    mut alerts: broadcast::Receiver<MonitorAlert>,
+) {
+    loop {
+        match alerts.recv().await {
+            Ok(alert) => {
+                if let Err(error) = nats.publish(subject, alert).await {
+                    tracing::error!("Failed to publish monitor alert: {:?}", error);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!(
+                    "Monitor alerts publisher lagged, {} alerts skipped",
+                    skipped
+                );
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                tracing::warn!("Monitor alerts channel closed, stopping NATS publisher");
+                return;
+            }
+        }
+    }
+}
+
+/// Alerts published by `publish_monitor_alerts` on `subject`
+pub async fn subscribe_monitor_alerts(
+    nats: &NatsManager<MonitorAlert, MonitorAlert>,
+    subject: &'static str,
+) -> EstimatorResult<impl Stream<Item = MonitorAlert> + Send + 'static> {
+    nats.subscribe(subject)
+        .await
+        .change_context(Error::ResponseError)
+        .attach_printable("Failed to subscribe to monitor alerts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        monitoring::{
+            manager::{MonitorConfig, MonitorManager},
+            messages::PriceSnapshot,
+        },
+        prices::codex::pricing::CodexProvider,
+    };
+    use intents_models::{
+        constants::chains::ChainId, models::types::order::OrderTypeFulfillmentData,
+    };
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn token(address: &str) -> TokenId {
+        TokenId {
+            chain: ChainId::Base,
+            address: address.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = MonitorNatsRequest::CheckSwapFeasibility {
+            pending_trade: PendingTrade {
+                order_id: "order_1".to_string(),
+                src_chain: ChainId::Ethereum,
+                dst_chain: ChainId::Base,
+                token_in: "token_a".to_string(),
+                token_out: "token_b".to_string(),
+                amount_in: u128::MAX,
+                amount_out: 1,
+                deadline: 100,
+                order_type_fulfillment_data: OrderTypeFulfillmentData::Limit,
+                extra_expenses: HashMap::from([(token("token_c"), 5)]),
+                stablecoin_swap_info: None,
+                limit_order_data: None,
+                stop_loss_initial_ratio: None,
+            },
+            solver_last_bid: Some(u128::MAX),
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["type"], "CheckSwapFeasibility");
+        assert_eq!(
+            json["payload"]["pending_trade"]["amount_in"],
+            u128::MAX.to_string()
+        );
+
+        let MonitorNatsRequest::CheckSwapFeasibility {
+            pending_trade,
+            solver_last_bid,
+        } = serde_json::from_value(json).unwrap()
+        else {
+            panic!("Unexpected request variant");
+        };
+        assert_eq!(pending_trade.amount_in, u128::MAX);
+        assert_eq!(pending_trade.extra_expenses[&token("token_c")], 5);
+        assert_eq!(solver_last_bid, Some(u128::MAX));
+    }
+
+    #[test]
+    fn test_alert_round_trip() {
+        let alert = MonitorAlert::TakeProfitReached {
+            order_id: "order_1".to_string(),
+            order_type_fulfillment_data: OrderTypeFulfillmentData::Limit,
+            take_profit_min_out: 10,
+            estimated_amount_out: 12,
+            snapshot: PriceSnapshot {
+                prices: HashMap::from([(
+                    token("token_b"),
+                    TokenPrice {
+                        price: 1.5,
+                        decimals: 6,
+                    },
+                )]),
+                taken_at: 42,
+            },
+        };
+
+        let bytes = serde_json::to_vec(&alert).unwrap();
+        let MonitorAlert::TakeProfitReached {
+            order_id,
+            estimated_amount_out,
+            snapshot,
+            ..
+        } = serde_json::from_slice(&bytes).unwrap()
+        else {
+            panic!("Unexpected alert variant");
+        };
+        assert_eq!(order_id, "order_1");
+        assert_eq!(estimated_amount_out, 12);
+        assert_eq!(snapshot.prices[&token("token_b")].price, 1.5);
+    }
+
+    #[tokio::test]
+    async fn test_process_request_against_local_monitor() {
+        let (alert_tx, _alert_rx) = broadcast::channel(10);
+        let (request_tx, request_rx) = mpsc::channel(10);
+        let mut manager = MonitorManager::new(
+            request_rx,
+            alert_tx,
+            CodexProvider::new("test".to_string()),
+            MonitorConfig::polling(Duration::from_secs(60)),
+        );
+        manager.coin_cache.insert(
+            token("token_b"),
+            TokenPrice {
+                price: 2.0,
+                decimals: 6,
+            },
+        );
+        tokio::spawn(manager.run());
+        let monitor = MonitorClient::new(request_tx);
+
+        let response = process_monitor_request(
+            &monitor,
+            MonitorNatsRequest::EvaluateCoins {
+                tokens: vec![(token("token_b"), 3_000_000)],
+            },
+        )
+        .await;
+        let MonitorNatsResponse::CoinsEvaluation { values, total } = response else {
+            panic!("Unexpected response: {response:?}");
+        };
+        assert_eq!(values, vec![6.0]);
+        assert_eq!(total, 6.0);
+
+        let response = process_monitor_request(
+            &monitor,
+            MonitorNatsRequest::RemoveCheckSwapFeasibility {
+                order_id: "unknown".to_string(),
+            },
+        )
+        .await;
+        assert!(matches!(response, MonitorNatsResponse::Accepted));
+    }
+}
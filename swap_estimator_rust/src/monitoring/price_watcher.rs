@@ -0,0 +1,597 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    time::Duration,
+};
+
+use intents_models::slack::SlackClients;
+use tokio::{
+    sync::{broadcast, mpsc},
+    time::{self, Instant},
+};
+
+use crate::{
+    error::EstimatorResult,
+    prices::{PriceEvent, PriceProvider, TokenId},
+};
+
+const ALERTS_BUFFER: usize = 1024;
+const SOURCE_EVENTS_BUFFER: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct PriceWatcherConfig {
+    /// Alert when a source moves more than this percentage...
+    pub sudden_move_percent: f64,
+    /// ...within this window
+    pub sudden_move_window: Duration,
+    /// Alert when the fresh prices of different sources are further apart than this percentage
+    pub divergence_percent: f64,
+    /// Prices older than this are not compared against other sources
+    pub divergence_max_age: Duration,
+    /// Alert when a source stops sending updates for a token it used to price
+    pub silence_timeout: Duration,
+    /// How often feed silence is checked
+    pub silence_check_interval: Duration,
+    /// Minimum time between two alerts of the same kind for the same token
+    pub alert_cooldown: Duration,
+}
+
+impl Default for PriceWatcherConfig {
+    fn default() -> Self {
+        Self {
+            sudden_move_percent: 10.0,
+            sudden_move_window: Duration::from_secs(60),
+            divergence_percent: 5.0,
+            divergence_max_age: Duration::from_secs(60),
+            silence_timeout: Duration::from_secs(300),
+            silence_check_interval: Duration::from_secs(10),
+            alert_cooldown: Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceAlert {
+    SuddenMove {
+        token: TokenId,
+        source: String,
+        from_price: f64,
+        to_price: f64,
+        change_percent: f64,
+        window: Duration,
+    },
+    SourceDivergence {
+        token: TokenId,
+        /// Latest fresh price of every source, sorted by source name
+        prices: Vec<(String, f64)>,
+        spread_percent: f64,
+    },
+    FeedSilence {
+        token: TokenId,
+        source: String,
+        last_price: f64,
+        silent_for: Duration,
+    },
+}
+
+impl PriceAlert {
+    pub fn token(&self) -> &TokenId {
+        match self {
+            PriceAlert::SuddenMove { token, .. }
+            | PriceAlert::SourceDivergence { token, .. }
+            | PriceAlert::FeedSilence { token, .. } => token,
+        }
+    }
+
+    fn kind(&self) -> AlertKind {
+        match self {
+            PriceAlert::SuddenMove { .. } => AlertKind::SuddenMove,
+            PriceAlert::SourceDivergence { .. } => AlertKind::SourceDivergence,
+            PriceAlert::FeedSilence { .. } => AlertKind::FeedSilence,
+        }
+    }
+}
+
+impl fmt::Display for PriceAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceAlert::SuddenMove {
+                token,
+                source,
+                from_price,
+                to_price,
+                change_percent,
+                window,
+            } => write!(
+                f,
+                "Sudden price move for {} on {:?} ({source}): {from_price} -> {to_price} ({change_percent:+.2}%) within {}s",
+                token.address,
+                token.chain,
+                window.as_secs()
+            ),
+            PriceAlert::SourceDivergence {
+                token,
+                prices,
+                spread_percent,
+            } => {
+                let prices = prices
+                    .iter()
+                    .map(|(source, price)| format!("{source}={price}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "Price sources disagree for {} on {:?} by {spread_percent:.2}%: {prices}",
+                    token.address, token.chain
+                )
+            }
+            PriceAlert::FeedSilence {
+                token,
+                source,
+                last_price,
+                silent_for,
+            } => write!(
+                f,
+                "No price updates from {source} for {} on {:?} in {}s (last price {last_price})",
+                token.address,
+                token.chain,
+                silent_for.as_secs()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AlertKind {
+    SuddenMove,
+    SourceDivergence,
+    FeedSilence,
+}
+
+#[derive(Debug, Default)]
+struct SourceWindow {
+    // (received_at, price), oldest first
+    prices: VecDeque<(Instant, f64)>,
+    silence_reported: bool,
+}
+
+impl SourceWindow {
+    fn latest(&self) -> Option<(Instant, f64)> {
+        self.prices.back().copied()
+    }
+}
+
+/// Rolling per-token price windows and the alerting rules evaluated over them
+#[derive(Debug)]
+struct PriceWindows {
+    config: PriceWatcherConfig,
+    windows: HashMap<TokenId, HashMap<String, SourceWindow>>,
+    last_alerts: HashMap<(TokenId, AlertKind), Instant>,
+}
+
+impl PriceWindows {
+    fn new(config: PriceWatcherConfig) -> Self {
+        Self {
+            config,
+            windows: HashMap::new(),
+            last_alerts: HashMap::new(),
+        }
+    }
+
+    fn on_price(&mut self, source: &str, event: &PriceEvent, now: Instant) -> Vec<PriceAlert> {
+        let price = event.price.price;
+        if !price.is_finite() || price <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut alerts = Vec::new();
+        let sources = self.windows.entry(event.token.clone()).or_default();
+        let window = sources.entry(source.to_string()).or_default();
+        window.silence_reported = false;
+        while let Some((received_at, _)) = window.prices.front() {
+            if now.duration_since(*received_at) > self.config.sudden_move_window {
+                window.prices.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        // Compare against the window extreme furthest away from the new price
+        let reference = window
+            .prices
+            .iter()
+            .map(|(_, previous)| *previous)
+            .max_by(|a, b| {
+                let a = (price - a).abs() / a;
+                let b = (price - b).abs() / b;
+                a.total_cmp(&b)
+            });
+        window.prices.push_back((now, price));
+
+        if let Some(reference) = reference {
+            let change_percent = (price - reference) / reference * 100.0;
+            if change_percent.abs() >= self.config.sudden_move_percent {
+                alerts.push(PriceAlert::SuddenMove {
+                    token: event.token.clone(),
+                    source: source.to_string(),
+                    from_price: reference,
+                    to_price: price,
+                    change_percent,
+                    window: self.config.sudden_move_window,
+                });
+            }
+        }
+
+        let mut fresh_prices = sources
+            .iter()
+            .filter_map(|(source, window)| {
+                let (received_at, price) = window.latest()?;
+                (now.duration_since(received_at) <= self.config.divergence_max_age)
+                    .then(|| (source.clone(), price))
+            })
+            .collect::<Vec<_>>();
+        if fresh_prices.len() > 1 {
+            fresh_prices.sort_by(|a, b| a.0.cmp(&b.0));
+            let min = fresh_prices
+                .iter()
+                .map(|(_, p)| *p)
+                .fold(f64::MAX, f64::min);
+            let max = fresh_prices
+                .iter()
+                .map(|(_, p)| *p)
+                .fold(f64::MIN, f64::max);
+            let spread_percent = (max - min) / min * 100.0;
+            if spread_percent >= self.config.divergence_percent {
+                alerts.push(PriceAlert::SourceDivergence {
+                    token: event.token.clone(),
+                    prices: fresh_prices,
+                    spread_percent,
+                });
+            }
+        }
+
+        self.throttle(alerts, now)
+    }
+
+    fn check_silence(&mut self, now: Instant) -> Vec<PriceAlert> {
+        let mut alerts = Vec::new();
+        for (token, sources) in self.windows.iter_mut() {
+            for (source, window) in sources.iter_mut() {
+                let Some((received_at, last_price)) = window.latest() else {
+                    continue;
+                };
+                let silent_for = now.duration_since(received_at);
+                if !window.silence_reported && silent_for >= self.config.silence_timeout {
+                    window.silence_reported = true;
+                    alerts.push(PriceAlert::FeedSilence {
+                        token: token.clone(),
+                        source: source.clone(),
+                        last_price,
+                        silent_for,
+                    });
+                }
+            }
+        }
+        self.throttle(alerts, now)
+    }
+
+    fn throttle(&mut self, alerts: Vec<PriceAlert>, now: Instant) -> Vec<PriceAlert> {
+        alerts
+            .into_iter()
+            .filter(|alert| {
+                let key = (alert.token().clone(), alert.kind());
+                match self.last_alerts.get(&key) {
+                    Some(last) if now.duration_since(*last) < self.config.alert_cooldown => false,
+                    _ => {
+                        self.last_alerts.insert(key, now);
+                        true
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Watches the price event streams of one or more named sources and broadcasts `PriceAlert`s
+pub struct PriceWatcher {
+    windows: PriceWindows,
+    events_tx: mpsc::Sender<(String, PriceEvent)>,
+    events_rx: mpsc::Receiver<(String, PriceEvent)>,
+    alert_sender: broadcast::Sender<PriceAlert>,
+}
+
+impl PriceWatcher {
+    pub fn new(config: PriceWatcherConfig) -> Self {
+        let (events_tx, events_rx) = mpsc::channel(SOURCE_EVENTS_BUFFER);
+        let (alert_sender, _alert_receiver) = broadcast::channel(ALERTS_BUFFER);
+        Self {
+            windows: PriceWindows::new(config),
+            events_tx,
+            events_rx,
+            alert_sender,
+        }
+    }
+
+    pub fn subscribe_alerts(&self) -> broadcast::Receiver<PriceAlert> {
+        self.alert_sender.subscribe()
+    }
+
+    /// Watches the events of `provider` under the source name `name`
+    pub async fn add_provider<P>(
+        &self,
+        name: impl Into<String>,
+        provider: &P,
+    ) -> EstimatorResult<()>
+    where
+        P: PriceProvider + ?Sized,
+    {
+        let receiver = provider.get_tokens_prices_events().await?;
+        self.add_source(name, receiver);
+        Ok(())
+    }
+
+    pub fn add_source(
+        &self,
+        name: impl Into<String>,
+        mut receiver: broadcast::Receiver<PriceEvent>,
+    ) {
+        let name = name.into();
+        let events_tx = self.events_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if events_tx.send((name.clone(), event)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Price watcher source {} lagged by {} events",
+                            name,
+                            skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        tracing::warn!("Price watcher source {} closed", name);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    pub async fn run(mut self) {
+        let mut silence_interval = time::interval(self.windows.config.silence_check_interval);
+        loop {
+            tokio::select! {
+                Some((source, event)) = self.events_rx.recv() => {
+                    let alerts = self.windows.on_price(&source, &event, Instant::now());
+                    self.publish(alerts);
+                }
+                _ = silence_interval.tick() => {
+                    let alerts = self.windows.check_silence(Instant::now());
+                    self.publish(alerts);
+                }
+            }
+        }
+    }
+
+    fn publish(&self, alerts: Vec<PriceAlert>) {
+        for alert in alerts {
+            tracing::warn!("Price alert: {}", alert);
+            if let Err(err) = self.alert_sender.send(alert) {
+                tracing::trace!("No listeners for price alert: {:?}", err);
+            }
+        }
+    }
+}
+
+/// Forwards every alert to the Slack errors channel until the alert stream closes
+pub async fn forward_price_alerts_to_slack(
+    mut alerts: broadcast::Receiver<PriceAlert>,
+    slack_clients: SlackClients,
+) {
+    loop {
+        match alerts.recv().await {
+            Ok(alert) => {
+                if let Err(error) = slack_clients.send_error(alert.to_string()).await {
+                    tracing::error!("Failed to send price alert to Slack: {:?}", error);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!("Slack price alert forwarder lagged by {} alerts", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prices::TokenPrice;
+    use intents_models::constants::chains::ChainId;
+
+    fn token() -> TokenId {
+        TokenId::new(
+            ChainId::Base,
+            "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string(),
+        )
+    }
+
+    fn event(price: f64) -> PriceEvent {
+        PriceEvent {
+            token: token(),
+            price: TokenPrice { price, decimals: 6 },
+        }
+    }
+
+    fn config() -> PriceWatcherConfig {
+        PriceWatcherConfig {
+            sudden_move_percent: 10.0,
+            sudden_move_window: Duration::from_secs(60),
+            divergence_percent: 5.0,
+            divergence_max_age: Duration::from_secs(30),
+            silence_timeout: Duration::from_secs(120),
+            silence_check_interval: Duration::from_secs(10),
+            alert_cooldown: Duration::from_secs(0),
+        }
+    }
+
+    #[test]
+    fn test_sudden_move_within_window() {
+        let mut windows = PriceWindows::new(config());
+        let start = Instant::now();
+
+        assert!(windows.on_price("codex", &event(100.0), start).is_empty());
+        assert!(
+            windows
+                .on_price("codex", &event(105.0), start + Duration::from_secs(10))
+                .is_empty()
+        );
+        let alerts = windows.on_price("codex", &event(88.0), start + Duration::from_secs(20));
+        assert_eq!(alerts.len(), 1);
+        let PriceAlert::SuddenMove {
+            from_price,
+            to_price,
+            change_percent,
+            ..
+        } = &alerts[0]
+        else {
+            panic!("Expected SuddenMove, got {:?}", alerts[0]);
+        };
+        assert_eq!(*from_price, 105.0);
+        assert_eq!(*to_price, 88.0);
+        assert!(*change_percent < -10.0);
+    }
+
+    #[test]
+    fn test_sudden_move_outside_window_is_ignored() {
+        let mut windows = PriceWindows::new(config());
+        let start = Instant::now();
+
+        windows.on_price("codex", &event(100.0), start);
+        let alerts = windows.on_price("codex", &event(120.0), start + Duration::from_secs(61));
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_source_divergence() {
+        let mut windows = PriceWindows::new(config());
+        let start = Instant::now();
+
+        windows.on_price("codex", &event(100.0), start);
+        assert!(
+            windows
+                .on_price(
+                    "gecko_terminal",
+                    &event(103.0),
+                    start + Duration::from_secs(1)
+                )
+                .is_empty()
+        );
+        let alerts = windows.on_price(
+            "gecko_terminal",
+            &event(107.0),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(
+            alerts,
+            vec![PriceAlert::SourceDivergence {
+                token: token(),
+                prices: vec![
+                    ("codex".to_string(), 100.0),
+                    ("gecko_terminal".to_string(), 107.0)
+                ],
+                spread_percent: 7.000000000000001,
+            }]
+        );
+
+        // Stale prices are not compared
+        let alerts = windows.on_price(
+            "gecko_terminal",
+            &event(107.0),
+            start + Duration::from_secs(40),
+        );
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_feed_silence_reported_once_per_gap() {
+        let mut windows = PriceWindows::new(config());
+        let start = Instant::now();
+
+        windows.on_price("codex", &event(100.0), start);
+        assert!(
+            windows
+                .check_silence(start + Duration::from_secs(60))
+                .is_empty()
+        );
+        let alerts = windows.check_silence(start + Duration::from_secs(120));
+        assert_eq!(
+            alerts,
+            vec![PriceAlert::FeedSilence {
+                token: token(),
+                source: "codex".to_string(),
+                last_price: 100.0,
+                silent_for: Duration::from_secs(120),
+            }]
+        );
+        assert!(
+            windows
+                .check_silence(start + Duration::from_secs(200))
+                .is_empty()
+        );
+
+        // A new update re-arms the silence check
+        windows.on_price("codex", &event(100.0), start + Duration::from_secs(210));
+        assert_eq!(
+            windows
+                .check_silence(start + Duration::from_secs(330))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_alert_cooldown() {
+        let mut windows = PriceWindows::new(PriceWatcherConfig {
+            alert_cooldown: Duration::from_secs(60),
+            ..config()
+        });
+        let start = Instant::now();
+
+        windows.on_price("codex", &event(100.0), start);
+        assert_eq!(
+            windows
+                .on_price("codex", &event(120.0), start + Duration::from_secs(1))
+                .len(),
+            1
+        );
+        assert!(
+            windows
+                .on_price("codex", &event(80.0), start + Duration::from_secs(2))
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watcher_broadcasts_alerts_from_sources() {
+        let watcher = PriceWatcher::new(config());
+        let mut alerts = watcher.subscribe_alerts();
+        let (source_tx, source_rx) = broadcast::channel(16);
+        watcher.add_source("codex", source_rx);
+        tokio::spawn(watcher.run());
+
+        source_tx.send(event(100.0)).unwrap();
+        source_tx.send(event(150.0)).unwrap();
+
+        let alert = time::timeout(Duration::from_secs(5), alerts.recv())
+            .await
+            .expect("Timed out waiting for price alert")
+            .unwrap();
+        assert!(matches!(alert, PriceAlert::SuddenMove { .. }));
+        assert!(alert.to_string().contains("+50.00%"));
+    }
+}
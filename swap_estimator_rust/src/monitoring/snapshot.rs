@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use error_stack::{ResultExt as _, report};
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use tokio::sync::RwLock;
+
+use crate::{
+    error::{Error, EstimatorResult},
+    monitoring::manager::PendingTrade,
+    prices::{TokenId, TokenPrice},
+    utils::fs::write_atomically,
+};
+
+/// Bumped whenever the snapshot layout changes incompatibly
+pub const MONITOR_SNAPSHOT_VERSION: u32 = 1;
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotTrade {
+    pub pending_trade: PendingTrade,
+    /// Required monitor estimation calculated from the solver last bid, if any
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub estimated_amount_out: Option<u128>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotPrice {
+    pub token: TokenId,
+    pub price: TokenPrice,
+    /// Timestamp (in seconds) of the price observation
+    pub updated_at: Option<u64>,
+}
+
+/// Serializable copy of the `MonitorManager` state needed to resume monitoring after a restart.
+/// Indexes (`trades_by_token`, `orders_by_deadline`) are rebuilt from `pending_trades`, and token
+/// metadata is persisted on its own by `TokenMetadataCache`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSnapshot {
+    pub version: u32,
+    /// Timestamp (in seconds) when the snapshot was taken
+    pub taken_at: u64,
+    pub pending_trades: Vec<SnapshotTrade>,
+    pub coin_cache: Vec<SnapshotPrice>,
+}
+
+/// Persistence backend for monitor snapshots. Only the latest snapshot is kept.
+#[async_trait::async_trait]
+pub trait MonitorSnapshotStore: std::fmt::Debug + Send + Sync {
+    async fn save(&self, snapshot: &MonitorSnapshot) -> EstimatorResult<()>;
+
+    async fn load(&self) -> EstimatorResult<Option<MonitorSnapshot>>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryMonitorSnapshotStore {
+    snapshot: RwLock<Option<MonitorSnapshot>>,
+}
+
+impl InMemoryMonitorSnapshotStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl MonitorSnapshotStore for InMemoryMonitorSnapshotStore {
+    async fn save(&self, snapshot: &MonitorSnapshot) -> EstimatorResult<()> {
+        *self.snapshot.write().await = Some(snapshot.clone());
+        Ok(())
+    }
+
+    async fn load(&self) -> EstimatorResult<Option<MonitorSnapshot>> {
+        Ok(self.snapshot.read().await.clone())
+    }
+}
+
+/// Writes the snapshot with `write_atomically`, so a crash mid-write keeps the previous
+/// snapshot intact
+#[derive(Debug)]
+pub struct JsonFileMonitorSnapshotStore {
+    path: PathBuf,
+}
+
+impl JsonFileMonitorSnapshotStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl MonitorSnapshotStore for JsonFileMonitorSnapshotStore {
+    async fn save(&self, snapshot: &MonitorSnapshot) -> EstimatorResult<()> {
+        let bytes = serde_json::to_vec(snapshot).change_context(Error::SerdeSerialize(
+            "Failed to serialize monitor snapshot".to_string(),
+        ))?;
+
+        write_atomically(&self.path, bytes).await
+    }
+
+    async fn load(&self) -> EstimatorResult<Option<MonitorSnapshot>> {
+        let bytes = match tokio::fs::read(&self.path).await {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(report!(Error::StorageError(format!(
+                    "Failed to read monitor snapshot {}: {error}",
+                    self.path.display()
+                ))));
+            }
+        };
+        let snapshot: MonitorSnapshot =
+            serde_json::from_slice(&bytes).change_context(Error::SerdeDeserialize(format!(
+                "Failed to parse monitor snapshot {}",
+                self.path.display()
+            )))?;
+        Ok(Some(snapshot))
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use error_stack::{ResultExt as _, report};
+use intents_models::models::types::common::{CommonLimitOrderData, StopLossType};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    error::{Error, EstimatorResult},
+    utils::fs::write_atomically,
+};
+
+/// Stop loss state of a single order, expressed on the `token_in / token_out` price ratio.
+///
+/// Trailing rules follow `StopLossType`:
+/// - `Fixed`: the trigger stays at `trigger_price`
+/// - `TrailingAbsolute`: the trigger keeps the `initial_ratio - trigger_price` distance below the
+///   peak, where `initial_ratio` is the ratio at order creation
+/// - `TrailingPercent`: `trigger_price` is a percentage of the peak (e.g. `90.0` means 90%)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StopLossTracker {
+    pub stop_loss_type: StopLossType,
+    pub trigger_price: f64,
+    /// Ratio at order creation, or when the order started being monitored if it is unknown
+    pub initial_ratio: f64,
+    /// Highest ratio observed since then
+    pub peak_ratio: f64,
+    /// Timestamp (in seconds) when `peak_ratio` was observed
+    pub peak_at: u64,
+    /// Order deadline, so trackers of orders that never come back can be dropped
+    pub deadline: u64,
+}
+
+impl StopLossTracker {
+    /// Starts tracking from `creation_ratio`, the ratio at order creation, falling back to the
+    /// current `ratio` when it is unknown.
+    /// Returns `None` when the order has no stop loss, or it already fired
+    pub fn new(
+        limit_order_data: &CommonLimitOrderData,
+        creation_ratio: Option<f64>,
+        ratio: f64,
+        now: u64,
+        deadline: u64,
+    ) -> Option<Self> {
+        if limit_order_data.stop_loss_triggered {
+            return None;
+        }
+        let trigger_price = limit_order_data.stop_loss_trigger_price?;
+        let initial_ratio = creation_ratio
+            .filter(|ratio| ratio.is_finite() && *ratio > 0.0)
+            .unwrap_or(ratio);
+        Some(Self {
+            stop_loss_type: limit_order_data.get_stop_loss_type(),
+            trigger_price,
+            initial_ratio,
+            peak_ratio: initial_ratio.max(ratio),
+            peak_at: now,
+            deadline,
+        })
+    }
+
+    /// Whether this tracker was created for the same stop loss settings as `limit_order_data`
+    pub fn matches(&self, limit_order_data: &CommonLimitOrderData) -> bool {
+        limit_order_data.get_stop_loss_type() == self.stop_loss_type
+            && limit_order_data.stop_loss_trigger_price == Some(self.trigger_price)
+    }
+
+    /// Current trigger level, moved according to the trailing rule
+    pub fn trigger_ratio(&self) -> f64 {
+        match self.stop_loss_type {
+            StopLossType::Fixed => self.trigger_price,
+            StopLossType::TrailingAbsolute => {
+                self.peak_ratio - (self.initial_ratio - self.trigger_price)
+            }
+            StopLossType::TrailingPercent => self.peak_ratio * self.trigger_price / 100.0,
+        }
+    }
+
+    /// Records a new ratio observation. Returns `true` when the peak moved.
+    pub fn observe(&mut self, ratio: f64, now: u64) -> bool {
+        if !ratio.is_finite() || ratio <= self.peak_ratio {
+            return false;
+        }
+        self.peak_ratio = ratio;
+        self.peak_at = now;
+        true
+    }
+
+    pub fn is_triggered(&self, ratio: f64) -> bool {
+        ratio < self.trigger_ratio()
+    }
+}
+
+/// Persistence backend for stop loss trackers, keyed by order id
+#[async_trait::async_trait]
+pub trait StopLossStore: std::fmt::Debug + Send + Sync {
+    async fn load_all(&self) -> EstimatorResult<HashMap<String, StopLossTracker>>;
+
+    async fn save(&self, order_id: &str, tracker: &StopLossTracker) -> EstimatorResult<()>;
+
+    async fn remove(&self, order_id: &str) -> EstimatorResult<()>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryStopLossStore {
+    trackers: RwLock<HashMap<String, StopLossTracker>>,
+}
+
+impl InMemoryStopLossStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl StopLossStore for InMemoryStopLossStore {
+    async fn load_all(&self) -> EstimatorResult<HashMap<String, StopLossTracker>> {
+        Ok(self.trackers.read().await.clone())
+    }
+
+    async fn save(&self, order_id: &str, tracker: &StopLossTracker) -> EstimatorResult<()> {
+        self.trackers
+            .write()
+            .await
+            .insert(order_id.to_string(), tracker.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, order_id: &str) -> EstimatorResult<()> {
+        self.trackers.write().await.remove(order_id);
+        Ok(())
+    }
+}
+
+/// Same layout as `JsonFileTokenMetadataStore`: everything is kept in memory and the whole
+/// file is rewritten with `write_atomically` on every change
+#[derive(Debug)]
+pub struct JsonFileStopLossStore {
+    path: PathBuf,
+    trackers: RwLock<BTreeMap<String, StopLossTracker>>,
+}
+
+impl JsonFileStopLossStore {
+    pub async fn open(path: impl Into<PathBuf>) -> EstimatorResult<Self> {
+        let path = path.into();
+        let trackers = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).change_context(Error::SerdeDeserialize(
+                format!("Failed to parse stop loss file {}", path.display()),
+            ))?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => {
+                return Err(report!(Error::StorageError(format!(
+                    "Failed to read stop loss file {}: {error}",
+                    path.display()
+                ))));
+            }
+        };
+
+        Ok(Self {
+            path,
+            trackers: RwLock::new(trackers),
+        })
+    }
+
+    async fn persist(&self, trackers: &BTreeMap<String, StopLossTracker>) -> EstimatorResult<()> {
+        let bytes = serde_json::to_vec_pretty(trackers).change_context(Error::SerdeSerialize(
+            "Failed to serialize stop loss trackers".to_string(),
+        ))?;
+
+        write_atomically(&self.path, bytes).await
+    }
+}
+
+#[async_trait::async_trait]
+impl StopLossStore for JsonFileStopLossStore {
+    async fn load_all(&self) -> EstimatorResult<HashMap<String, StopLossTracker>> {
+        Ok(self
+            .trackers
+            .read()
+            .await
+            .iter()
+            .map(|(order_id, tracker)| (order_id.clone(), tracker.clone()))
+            .collect())
+    }
+
+    async fn save(&self, order_id: &str, tracker: &StopLossTracker) -> EstimatorResult<()> {
+        let mut trackers = self.trackers.write().await;
+        trackers.insert(order_id.to_string(), tracker.clone());
+        self.persist(&trackers).await
+    }
+
+    async fn remove(&self, order_id: &str) -> EstimatorResult<()> {
+        let mut trackers = self.trackers.write().await;
+        if trackers.remove(order_id).is_some() {
+            self.persist(&trackers).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit_order_data(stop_loss_type: StopLossType, trigger_price: f64) -> CommonLimitOrderData {
+        CommonLimitOrderData {
+            take_profit_min_out: None,
+            stop_loss_type: Some(stop_loss_type),
+            stop_loss_trigger_price: Some(trigger_price),
+            stop_loss_triggered: false,
+        }
+    }
+
+    #[test]
+    fn test_trailing_absolute_moves_with_peak() {
+        let data = limit_order_data(StopLossType::TrailingAbsolute, 90.0);
+        let mut tracker = StopLossTracker::new(&data, None, 100.0, 1, u64::MAX).unwrap();
+        assert_eq!(tracker.trigger_ratio(), 90.0);
+
+        assert!(tracker.observe(120.0, 2));
+        assert!(!tracker.observe(115.0, 3));
+        assert_eq!(tracker.peak_ratio, 120.0);
+        assert_eq!(tracker.peak_at, 2);
+        assert_eq!(tracker.trigger_ratio(), 110.0);
+        assert!(!tracker.is_triggered(110.0));
+        assert!(tracker.is_triggered(109.9));
+    }
+
+    #[test]
+    fn test_trailing_absolute_keeps_distance_from_creation_ratio() {
+        // Created at 100 with a trigger at 90, first seen by the monitor at 105
+        let data = limit_order_data(StopLossType::TrailingAbsolute, 90.0);
+        let mut tracker = StopLossTracker::new(&data, Some(100.0), 105.0, 1, u64::MAX).unwrap();
+        assert_eq!(tracker.initial_ratio, 100.0);
+        assert_eq!(tracker.peak_ratio, 105.0);
+        assert_eq!(tracker.trigger_ratio(), 95.0);
+
+        tracker.observe(120.0, 2);
+        assert_eq!(tracker.trigger_ratio(), 110.0);
+
+        // Price fell since creation: the peak stays at the creation ratio
+        let tracker = StopLossTracker::new(&data, Some(100.0), 95.0, 1, u64::MAX).unwrap();
+        assert_eq!(tracker.peak_ratio, 100.0);
+        assert_eq!(tracker.trigger_ratio(), 90.0);
+    }
+
+    #[test]
+    fn test_trailing_percent_moves_with_peak() {
+        let data = limit_order_data(StopLossType::TrailingPercent, 90.0);
+        let mut tracker = StopLossTracker::new(&data, None, 100.0, 1, u64::MAX).unwrap();
+        assert_eq!(tracker.trigger_ratio(), 90.0);
+
+        tracker.observe(120.0, 2);
+        assert!((tracker.trigger_ratio() - 108.0).abs() < 1e-9);
+        assert!(!tracker.is_triggered(108.1));
+        assert!(tracker.is_triggered(107.9));
+    }
+
+    #[test]
+    fn test_fixed_stop_loss_does_not_trail() {
+        let data = limit_order_data(StopLossType::Fixed, 90.0);
+        let mut tracker = StopLossTracker::new(&data, None, 100.0, 1, u64::MAX).unwrap();
+        tracker.observe(150.0, 2);
+        assert_eq!(tracker.trigger_ratio(), 90.0);
+        assert!(!tracker.is_triggered(95.0));
+        assert!(tracker.is_triggered(89.0));
+    }
+
+    #[test]
+    fn test_no_tracker_without_active_stop_loss() {
+        let mut data = limit_order_data(StopLossType::Fixed, 90.0);
+        data.stop_loss_triggered = true;
+        assert!(StopLossTracker::new(&data, None, 100.0, 1, u64::MAX).is_none());
+
+        data.stop_loss_triggered = false;
+        data.stop_loss_trigger_price = None;
+        assert!(StopLossTracker::new(&data, None, 100.0, 1, u64::MAX).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_json_file_store_survives_reopen() {
+        let path = std::env::temp_dir().join(format!(
+            "stop_loss_store_{}_{}.json",
+            std::process::id(),
+            crate::utils::get_timestamp()
+        ));
+        let data = limit_order_data(StopLossType::TrailingPercent, 95.0);
+        let mut tracker = StopLossTracker::new(&data, None, 2.0, 1, 1_000).unwrap();
+        tracker.observe(2.5, 5);
+
+        let store = JsonFileStopLossStore::open(&path).await.unwrap();
+        store.save("order_1", &tracker).await.unwrap();
+        store.save("order_2", &tracker).await.unwrap();
+        store.remove("order_2").await.unwrap();
+        drop(store);
+
+        let reopened = JsonFileStopLossStore::open(&path).await.unwrap();
+        let trackers = reopened.load_all().await.unwrap();
+        assert_eq!(trackers.len(), 1);
+        assert_eq!(trackers["order_1"], tracker);
+        assert!(trackers["order_1"].matches(&data));
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use tokio::{sync::watch, time};
+
+use crate::{
+    error::EstimatorResult,
+    prices::{
+        PriceProvider, TokenId, TokenMetadata,
+        codex::{
+            discovery::{TokenDiscoveryPage, TokenDiscoveryQuery},
+            pricing::CodexProvider,
+        },
+        metadata_store::TokenMetadataCache,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct WatchlistConfig {
+    /// Every query is paged through until `max_tokens` is reached or results run out
+    pub queries: Vec<TokenDiscoveryQuery>,
+    pub refresh_interval: Duration,
+    pub max_tokens: usize,
+}
+
+/// Source of discovery pages, implemented by `CodexProvider`
+#[async_trait::async_trait]
+pub trait TokenDiscovery {
+    async fn discover_tokens(
+        &self,
+        query: &TokenDiscoveryQuery,
+    ) -> EstimatorResult<TokenDiscoveryPage>;
+}
+
+#[async_trait::async_trait]
+impl TokenDiscovery for CodexProvider {
+    async fn discover_tokens(
+        &self,
+        query: &TokenDiscoveryQuery,
+    ) -> EstimatorResult<TokenDiscoveryPage> {
+        CodexProvider::discover_tokens(self, query).await
+    }
+}
+
+/// Background job keeping the provider subscribed to the tokens most likely to show up in
+/// orders, so their prices are already live when the order arrives
+pub struct TokenWatchlist<P> {
+    provider: P,
+    config: WatchlistConfig,
+    metadata: Option<TokenMetadataCache>,
+    subscribed: HashSet<TokenId>,
+    watchlist_tx: watch::Sender<HashSet<TokenId>>,
+}
+
+impl<P> TokenWatchlist<P>
+where
+    P: PriceProvider + TokenDiscovery + Send + Sync,
+{
+    pub fn new(provider: P, config: WatchlistConfig) -> Self {
+        let (watchlist_tx, _watchlist_rx) = watch::channel(HashSet::new());
+        Self {
+            provider,
+            config,
+            metadata: None,
+            subscribed: HashSet::new(),
+            watchlist_tx,
+        }
+    }
+
+    /// Records the metadata returned by discovery so later lookups don't hit the API
+    pub fn with_metadata_cache(mut self, metadata: TokenMetadataCache) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<HashSet<TokenId>> {
+        self.watchlist_tx.subscribe()
+    }
+
+    pub async fn run(mut self) {
+        let mut refresh_interval = time::interval(self.config.refresh_interval);
+        loop {
+            refresh_interval.tick().await;
+            if let Err(error) = self.refresh().await {
+                tracing::error!("Failed to refresh token watchlist: {:?}", error);
+            }
+        }
+    }
+
+    /// Runs every discovery query and moves subscriptions to the new watchlist.
+    /// On a discovery error the current subscriptions are kept untouched.
+    pub async fn refresh(&mut self) -> EstimatorResult<()> {
+        let (tokens, metadata) = self.discover().await?;
+
+        if let Some(cache) = &self.metadata
+            && let Err(error) = cache.record(metadata).await
+        {
+            tracing::warn!("Failed to record discovered token metadata: {:?}", error);
+        }
+
+        let added = tokens
+            .difference(&self.subscribed)
+            .cloned()
+            .collect::<Vec<_>>();
+        let removed = self
+            .subscribed
+            .difference(&tokens)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for token in added {
+            match self.provider.subscribe_to_token(token.clone()).await {
+                Ok(()) => {
+                    self.subscribed.insert(token);
+                }
+                Err(error) => {
+                    tracing::warn!("Failed to pre-subscribe to {:?}: {:?}", token, error);
+                }
+            }
+        }
+        for token in removed {
+            if let Err(error) = self.provider.unsubscribe_from_token(token.clone()).await {
+                tracing::warn!("Failed to unsubscribe from {:?}: {:?}", token, error);
+            }
+            self.subscribed.remove(&token);
+        }
+
+        tracing::debug!(
+            "Token watchlist refreshed: {} tokens",
+            self.subscribed.len()
+        );
+        self.watchlist_tx.send_replace(self.subscribed.clone());
+        Ok(())
+    }
+
+    async fn discover(
+        &self,
+    ) -> EstimatorResult<(HashSet<TokenId>, HashMap<TokenId, TokenMetadata>)> {
+        let mut tokens = HashSet::new();
+        let mut metadata = HashMap::new();
+
+        'queries: for query in self.config.queries.iter() {
+            let mut next_query = Some(query.clone());
+            while let Some(query) = next_query {
+                let page = self.provider.discover_tokens(&query).await?;
+                next_query = query.next_page(&page);
+                for discovered in page.results {
+                    if tokens.len() >= self.config.max_tokens {
+                        break 'queries;
+                    }
+                    tokens.insert(discovered.token.clone());
+                    metadata.insert(discovered.token, discovered.metadata);
+                }
+            }
+        }
+
+        Ok((tokens, metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prices::{
+        PriceEvent, TokenPrice, codex::discovery::DiscoveredToken, valuation::PriceSource,
+    };
+    use intents_models::constants::chains::ChainId;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::broadcast;
+
+    #[derive(Clone, Default)]
+    struct FakeProvider {
+        // Pages returned by successive discover calls, keyed by offset
+        pages: Arc<Mutex<HashMap<u32, Vec<u32>>>>,
+        subscribed: Arc<Mutex<HashSet<TokenId>>>,
+    }
+
+    fn token(index: u32) -> TokenId {
+        TokenId::new(ChainId::Base, format!("0x{index:040x}"))
+    }
+
+    #[async_trait::async_trait]
+    impl TokenDiscovery for FakeProvider {
+        async fn discover_tokens(
+            &self,
+            query: &TokenDiscoveryQuery,
+        ) -> EstimatorResult<TokenDiscoveryPage> {
+            let pages = self.pages.lock().unwrap();
+            let indexes = pages.get(&query.offset).cloned().unwrap_or_default();
+            let next_offset = pages
+                .contains_key(&(query.offset + indexes.len() as u32))
+                .then_some(query.offset + indexes.len() as u32);
+            Ok(TokenDiscoveryPage {
+                results: indexes
+                    .into_iter()
+                    .map(|index| DiscoveredToken {
+                        token: token(index),
+                        metadata: TokenMetadata {
+                            name: format!("Token {index}"),
+                            symbol: format!("T{index}"),
+                            decimals: 18,
+                        },
+                        market_cap: None,
+                        liquidity: None,
+                        volume_24: None,
+                        holders: 0,
+                        wallet_age_avg: None,
+                        buy_count_24: 0,
+                    })
+                    .collect(),
+                total: None,
+                offset: query.offset,
+                next_offset,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl PriceProvider for FakeProvider {
+        fn source(&self) -> PriceSource {
+            PriceSource::Replay
+        }
+
+        async fn get_tokens_price(
+            &self,
+            _tokens: &[TokenId],
+            _with_subscriptions: bool,
+        ) -> EstimatorResult<HashMap<TokenId, TokenPrice>> {
+            Ok(HashMap::new())
+        }
+
+        async fn get_tokens_prices_events(
+            &self,
+        ) -> EstimatorResult<broadcast::Receiver<PriceEvent>> {
+            let (_tx, rx) = broadcast::channel(1);
+            Ok(rx)
+        }
+
+        async fn subscribe_to_token(&self, token: TokenId) -> EstimatorResult<()> {
+            self.subscribed.lock().unwrap().insert(token);
+            Ok(())
+        }
+
+        async fn unsubscribe_from_token(&self, token: TokenId) -> EstimatorResult<bool> {
+            Ok(self.subscribed.lock().unwrap().remove(&token))
+        }
+
+        async fn get_tokens_metadata(
+            &self,
+            _tokens: &[TokenId],
+        ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watchlist_pages_and_resubscribes() {
+        let provider = FakeProvider::default();
+        *provider.pages.lock().unwrap() = HashMap::from([(0, vec![1, 2]), (2, vec![3, 4])]);
+        let metadata = TokenMetadataCache::in_memory();
+        let mut watchlist = TokenWatchlist::new(
+            provider.clone(),
+            WatchlistConfig {
+                queries: vec![TokenDiscoveryQuery::new(vec![ChainId::Base])],
+                refresh_interval: Duration::from_secs(60),
+                max_tokens: 3,
+            },
+        )
+        .with_metadata_cache(metadata.clone());
+        let watchlist_rx = watchlist.subscribe();
+
+        watchlist.refresh().await.unwrap();
+
+        let expected = HashSet::from([token(1), token(2), token(3)]);
+        assert_eq!(*provider.subscribed.lock().unwrap(), expected);
+        assert_eq!(*watchlist_rx.borrow(), expected);
+        let cached = metadata.get_cached(&[token(1)]).await.unwrap();
+        assert_eq!(cached[&token(1)].symbol, "T1");
+
+        // Token 1 drops out of the feed and token 5 enters it
+        *provider.pages.lock().unwrap() = HashMap::from([(0, vec![2, 3, 5])]);
+        watchlist.refresh().await.unwrap();
+
+        let expected = HashSet::from([token(2), token(3), token(5)]);
+        assert_eq!(*provider.subscribed.lock().unwrap(), expected);
+        assert_eq!(*watchlist_rx.borrow(), expected);
+    }
+}
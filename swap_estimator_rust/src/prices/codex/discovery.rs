@@ -0,0 +1,310 @@
+use intents_models::constants::chains::ChainId;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use strum_macros::{Display, EnumString};
+
+use crate::prices::{
+    TokenId, TokenMetadata,
+    codex::{CodexChain as _, models::TrendingTokenData},
+};
+
+pub const MAX_DISCOVERY_PAGE_SIZE: u32 = 200;
+
+pub(crate) const FILTER_TOKENS_QUERY: &str = r#"
+query FilterTokens(
+    $filters: TokenFilters,
+    $rankings: [TokenRanking],
+    $limit: Int,
+    $offset: Int
+) {
+    filterTokens(
+        filters: $filters
+        rankings: $rankings
+        statsType: FILTERED
+        limit: $limit
+        offset: $offset
+    ) {
+        count
+        results {
+            token {
+                name
+                symbol
+                decimals
+                address
+                networkId
+            }
+            marketCap
+            liquidity
+            holders
+            volume24
+            walletAgeAvg
+            buyCount24
+        }
+    }
+}
+"#;
+
+/// Codex `TokenRankingAttribute` values supported for discovery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub enum TokenRankingAttribute {
+    Change1,
+    Change4,
+    Change12,
+    Change24,
+    Volume24,
+    Liquidity,
+    MarketCap,
+    Holders,
+    WalletAgeAvg,
+    BuyCount24,
+    TrendingScore,
+    TrendingScore24,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "UPPERCASE")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RankingDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenRanking {
+    pub attribute: TokenRankingAttribute,
+    pub direction: RankingDirection,
+}
+
+impl Default for TokenRanking {
+    fn default() -> Self {
+        Self {
+            attribute: TokenRankingAttribute::Change1,
+            direction: RankingDirection::Desc,
+        }
+    }
+}
+
+/// Lower/upper bounds applied by Codex before ranking. `None` leaves the attribute unfiltered.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenDiscoveryFilters {
+    pub min_liquidity: Option<f64>,
+    pub min_market_cap: Option<f64>,
+    pub max_market_cap: Option<f64>,
+    pub min_volume_24: Option<f64>,
+    pub min_holders: Option<u64>,
+    /// Average age, in seconds, of the wallets trading the token
+    pub min_wallet_age_avg: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenDiscoveryQuery {
+    /// Networks searched at once; results are ranked across all of them
+    pub networks: Vec<ChainId>,
+    pub filters: TokenDiscoveryFilters,
+    pub ranking: TokenRanking,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl TokenDiscoveryQuery {
+    pub fn new(networks: Vec<ChainId>) -> Self {
+        Self {
+            networks,
+            filters: TokenDiscoveryFilters::default(),
+            ranking: TokenRanking::default(),
+            limit: 50,
+            offset: 0,
+        }
+    }
+
+    /// Same query for the page following `page`, if there is one
+    pub fn next_page(&self, page: &TokenDiscoveryPage) -> Option<Self> {
+        page.next_offset.map(|offset| Self {
+            offset,
+            ..self.clone()
+        })
+    }
+
+    pub(crate) fn to_graphql_body(&self) -> Value {
+        let mut filters = Map::new();
+        filters.insert(
+            "network".to_string(),
+            Value::Array(
+                self.networks
+                    .iter()
+                    .map(|network| network.to_codex_chain_number().into())
+                    .collect(),
+            ),
+        );
+        let mut range = |name: &str, gt: Option<Value>, lt: Option<Value>| {
+            let mut bounds = Map::new();
+            if let Some(gt) = gt {
+                bounds.insert("gt".to_string(), gt);
+            }
+            if let Some(lt) = lt {
+                bounds.insert("lt".to_string(), lt);
+            }
+            if !bounds.is_empty() {
+                filters.insert(name.to_string(), Value::Object(bounds));
+            }
+        };
+        let f = &self.filters;
+        range("liquidity", f.min_liquidity.map(Into::into), None);
+        range(
+            "marketCap",
+            f.min_market_cap.map(Into::into),
+            f.max_market_cap.map(Into::into),
+        );
+        range("volume24", f.min_volume_24.map(Into::into), None);
+        range("holders", f.min_holders.map(Into::into), None);
+        range("walletAgeAvg", f.min_wallet_age_avg.map(Into::into), None);
+
+        serde_json::json!({
+            "query": FILTER_TOKENS_QUERY,
+            "variables": {
+                "filters": filters,
+                "rankings": [{
+                    "attribute": self.ranking.attribute.to_string(),
+                    "direction": self.ranking.direction.to_string()
+                }],
+                "limit": self.limit.min(MAX_DISCOVERY_PAGE_SIZE),
+                "offset": self.offset
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveredToken {
+    pub token: TokenId,
+    pub metadata: TokenMetadata,
+    pub market_cap: Option<f64>,
+    pub liquidity: Option<f64>,
+    pub volume_24: Option<f64>,
+    pub holders: i64,
+    pub wallet_age_avg: Option<f64>,
+    pub buy_count_24: u64,
+}
+
+impl DiscoveredToken {
+    /// Returns `None` for tokens on networks we don't support
+    pub fn from_codex(data: TrendingTokenData) -> Option<Self> {
+        let chain = ChainId::from_codex_chain_number(data.token.network_id)?;
+        Some(Self {
+            token: TokenId::new(chain, data.token.address),
+            metadata: TokenMetadata {
+                name: data.token.name,
+                symbol: data.token.symbol,
+                decimals: data.token.decimals,
+            },
+            market_cap: data.market_cap.parse().ok(),
+            liquidity: data.liquidity.parse().ok(),
+            volume_24: data.volume_24.parse().ok(),
+            holders: data.holders,
+            wallet_age_avg: data.wallet_age_avg.parse().ok(),
+            buy_count_24: data.buy_count_24,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenDiscoveryPage {
+    pub results: Vec<DiscoveredToken>,
+    /// Total number of tokens matching the filters, when reported by Codex
+    pub total: Option<u64>,
+    pub offset: u32,
+    /// Offset of the next page, `None` when this was the last one
+    pub next_offset: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_query_body() {
+        let query = TokenDiscoveryQuery {
+            networks: vec![ChainId::Solana, ChainId::Base],
+            filters: TokenDiscoveryFilters {
+                min_liquidity: Some(10_000.0),
+                min_market_cap: Some(100_000.0),
+                max_market_cap: Some(5_000_000.0),
+                min_volume_24: None,
+                min_holders: Some(500),
+                min_wallet_age_avg: None,
+            },
+            ranking: TokenRanking {
+                attribute: TokenRankingAttribute::Volume24,
+                direction: RankingDirection::Desc,
+            },
+            limit: 1_000,
+            offset: 40,
+        };
+
+        let body = query.to_graphql_body();
+
+        assert_eq!(
+            body["variables"],
+            serde_json::json!({
+                "filters": {
+                    "network": [1399811149, 8453],
+                    "liquidity": { "gt": 10_000.0 },
+                    "marketCap": { "gt": 100_000.0, "lt": 5_000_000.0 },
+                    "holders": { "gt": 500 }
+                },
+                "rankings": [{ "attribute": "volume24", "direction": "DESC" }],
+                "limit": MAX_DISCOVERY_PAGE_SIZE,
+                "offset": 40
+            })
+        );
+    }
+
+    #[test]
+    fn test_discovered_token_from_codex() {
+        let data: TrendingTokenData = serde_json::from_value(serde_json::json!({
+            "token": {
+                "address": "0x833589FCD6EDB6E08F4C7C32D4F71B54BDA02913",
+                "networkId": 8453,
+                "name": "USD Coin",
+                "symbol": "USDC",
+                "decimals": 6
+            },
+            "marketCap": "4000000000",
+            "liquidity": "12345.5",
+            "holders": 1000,
+            "volume24": "not-a-number",
+            "walletAgeAvg": "86400",
+            "buyCount24": 12
+        }))
+        .unwrap();
+
+        let token = DiscoveredToken::from_codex(data).unwrap();
+
+        assert_eq!(
+            token.token,
+            TokenId::new(
+                ChainId::Base,
+                "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string()
+            )
+        );
+        assert_eq!(token.metadata.decimals, 6);
+        assert_eq!(token.liquidity, Some(12345.5));
+        assert_eq!(token.volume_24, None);
+    }
+
+    #[test]
+    fn test_ranking_attribute_names() {
+        assert_eq!(TokenRankingAttribute::Change1.to_string(), "change1");
+        assert_eq!(
+            TokenRankingAttribute::TrendingScore24.to_string(),
+            "trendingScore24"
+        );
+        assert_eq!(
+            "walletAgeAvg".parse::<TokenRankingAttribute>().unwrap(),
+            TokenRankingAttribute::WalletAgeAvg
+        );
+    }
+}
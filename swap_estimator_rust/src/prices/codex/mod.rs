@@ -1,5 +1,6 @@
 use intents_models::constants::chains::ChainId;
 
+pub mod discovery;
 pub mod models;
 pub mod pricing;
 pub mod utils;
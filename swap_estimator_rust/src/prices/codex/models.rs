@@ -24,6 +24,7 @@ pub struct CodexGetTrendingTokensData {
 
 #[derive(Debug, Deserialize)]
 pub struct CodexTrendingTokens {
+    pub count: Option<u64>,
     pub results: Vec<TrendingTokenData>,
 }
 
@@ -1,10 +1,10 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+        atomic::{AtomicU64, Ordering},
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use error_stack::{ResultExt as _, report};
@@ -15,11 +15,12 @@ use reqwest::{
     header::{AUTHORIZATION, HeaderMap, HeaderValue as ReqwestHeaderValue},
 };
 use tokio::{
-    sync::{Mutex, Notify, OnceCell, RwLock, broadcast, watch},
+    net::TcpStream,
+    sync::{OnceCell, RwLock, broadcast, mpsc, watch},
     time,
 };
 use tokio_tungstenite::{
-    connect_async,
+    MaybeTlsStream, WebSocketStream, connect_async,
     tungstenite::{client::IntoClientRequest, protocol::Message},
 };
 
@@ -29,6 +30,10 @@ use crate::{
         PriceEvent, PriceProvider, TokenId, TokenMetadata, TokenPrice,
         codex::{
             CODEX_HTTP_URL, CODEX_WS_URL, CodexChain,
+            discovery::{
+                DiscoveredToken, MAX_DISCOVERY_PAGE_SIZE, TokenDiscoveryFilters,
+                TokenDiscoveryPage, TokenDiscoveryQuery, TokenRanking,
+            },
             models::{
                 CodexGetTrendingTokensData, CodexGraphqlResponse, GraphqlWsMessage, NextPayload,
                 TokenSubscription, TrendingTokenData,
@@ -40,7 +45,9 @@ use crate::{
                 combine_price_and_metadata_query, default_decimals, subscription_id,
             },
         },
+        valuation::PriceSource,
     },
+    utils::get_timestamp,
 };
 
 const GRAPHQL_SUBSCRIPTION: &str = r#"
@@ -57,66 +64,85 @@ subscription OnPriceUpdated($address: String!, $networkId: Int!) {
 
 const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 20;
 const MAX_CONNECTIONS: usize = 300;
+const CONNECTION_ACK_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_INITIAL_RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+const HEALTH_EVENTS_BUFFER: usize = 1024;
 
-const TRENDING_TOKENS_QUERY: &str = r#"
-query FilterTokens(
-    $minLiquidity: Float!,
-    $minMarketCap: Float!,
-    $network: Int!,
-    $minVolume24: Float!
-    $limit: Int!
-    $offset: Int!
-) {
-    filterTokens(
-        rankings: {attribute: change1, direction: DESC}
-        filters: {
-            liquidity: { gt: $minLiquidity },
-            marketCap: { gt: $minMarketCap },
-            network: $network,
-            volume24: { gt: $minVolume24 }
-        }
-        statsType: FILTERED
-        limit: $limit
-        offset: $offset
-    ) {
-        results {
-            token {
-                name
-                symbol
-                decimals
-                address
-                networkId
-            }
-            marketCap
-            liquidity
-            holders
-            volume24
-            walletAgeAvg
-            buyCount24
+type CodexWsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Endpoints and reconnect behaviour of the Codex connections
+#[derive(Debug, Clone)]
+pub struct CodexConnectionConfig {
+    pub ws_url: String,
+    pub http_url: String,
+    /// Delay before the second reconnect attempt, doubled after every failure
+    pub initial_reconnect_backoff: Duration,
+    pub max_reconnect_backoff: Duration,
+}
+
+impl Default for CodexConnectionConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: CODEX_WS_URL.to_string(),
+            http_url: CODEX_HTTP_URL.to_string(),
+            initial_reconnect_backoff: DEFAULT_INITIAL_RECONNECT_BACKOFF,
+            max_reconnect_backoff: DEFAULT_MAX_RECONNECT_BACKOFF,
         }
     }
 }
-"#;
+
+/// Connection health notifications, one `Disconnected`/`Reconnected` pair per gap
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodexHealthEvent {
+    /// A websocket dropped while `tokens` were subscribed on it
+    Disconnected {
+        connection_id: u64,
+        disconnected_at: u64,
+        tokens: Vec<TokenId>,
+    },
+    /// The websocket was re-established and `tokens` were resubscribed on it.
+    /// Their prices are backfilled over HTTP right after this event.
+    Reconnected {
+        connection_id: u64,
+        disconnected_at: u64,
+        reconnected_at: u64,
+        downtime: Duration,
+        attempts: u32,
+        tokens: Vec<TokenId>,
+    },
+}
 
 #[derive(Debug, Clone)]
 pub struct CodexProvider {
     api_key: String,
+    config: CodexConnectionConfig,
     pool: Arc<OnceCell<Arc<CodexConnectionPool>>>,
 }
 
 impl CodexProvider {
     pub fn new(api_key: String) -> Self {
+        Self::new_with_config(api_key, CodexConnectionConfig::default())
+    }
+
+    pub fn new_with_config(api_key: String, config: CodexConnectionConfig) -> Self {
         Self {
             api_key,
+            config,
             pool: Arc::new(OnceCell::new()),
         }
     }
 
     async fn pool(&self) -> EstimatorResult<Arc<CodexConnectionPool>> {
         let api_key = self.api_key.clone();
+        let config = self.config.clone();
         let reference = self
             .pool
-            .get_or_try_init(|| async move { CodexConnectionPool::new(api_key).map(Arc::new) })
+            .get_or_try_init(|| async move {
+                let pool = Arc::new(CodexConnectionPool::new(api_key, config)?);
+                pool.spawn_backfill_task();
+                EstimatorResult::Ok(pool)
+            })
             .await?;
         Ok(reference.clone())
     }
@@ -192,15 +218,43 @@ impl CodexProvider {
         offset: u32,
     ) -> EstimatorResult<Vec<TrendingTokenData>> {
         let pool = self.pool().await?;
-        pool.fetch_trending_tokens(
-            min_liquidity,
-            min_market_cap,
-            network,
-            min_volume_24,
+        let query = TokenDiscoveryQuery {
+            networks: vec![network],
+            filters: TokenDiscoveryFilters {
+                min_liquidity: Some(min_liquidity),
+                min_market_cap: Some(min_market_cap),
+                min_volume_24: Some(min_volume_24),
+                ..Default::default()
+            },
+            ranking: TokenRanking::default(),
             limit,
             offset,
-        )
-        .await
+        };
+        let (results, _total) = pool.filter_tokens(&query).await?;
+        Ok(results)
+    }
+
+    /// Runs a token discovery query across all of its networks and returns one page of results
+    pub async fn discover_tokens(
+        &self,
+        query: &TokenDiscoveryQuery,
+    ) -> EstimatorResult<TokenDiscoveryPage> {
+        let pool = self.pool().await?;
+        let (results, total) = pool.filter_tokens(query).await?;
+        let page_len = results.len() as u32;
+        let results = results
+            .into_iter()
+            .filter_map(DiscoveredToken::from_codex)
+            .collect();
+        let next_offset = (page_len > 0 && page_len >= query.limit.min(MAX_DISCOVERY_PAGE_SIZE))
+            .then_some(query.offset + page_len)
+            .filter(|next| total.is_none_or(|total| u64::from(*next) < total));
+        Ok(TokenDiscoveryPage {
+            results,
+            total,
+            offset: query.offset,
+            next_offset,
+        })
     }
 
     // Public method to subscribe to the global price event stream
@@ -209,6 +263,14 @@ impl CodexProvider {
         Ok(pool.get_events_subscriber())
     }
 
+    // Public method to subscribe to websocket disconnect/reconnect notifications
+    pub async fn subscribe_health_events(
+        &self,
+    ) -> EstimatorResult<broadcast::Receiver<CodexHealthEvent>> {
+        let pool = self.pool().await?;
+        Ok(pool.health_tx.subscribe())
+    }
+
     pub async fn fetch_token_metadata(
         &self,
         tokens: &[TokenId],
@@ -223,16 +285,20 @@ const PRICE_EVENTS_BUFFER: usize = 32768; // 2^15
 #[derive(Debug)]
 struct CodexConnectionPool {
     api_key: String,
+    config: CodexConnectionConfig,
     http_client: HttpClient,
     clients: RwLock<Vec<Arc<CodexWsClient>>>,
+    next_client_id: AtomicU64,
     // Event bus for price updates
     event_tx: broadcast::Sender<PriceEvent>,
+    // Event bus for connection gaps
+    health_tx: broadcast::Sender<CodexHealthEvent>,
     // Anchor subscriptions to keep WS alive until explicit unsubscribe
     held_subscriptions: RwLock<HashMap<TokenId, (usize, CodexSubscription)>>,
 }
 
 impl CodexConnectionPool {
-    fn new(api_key: String) -> EstimatorResult<Self> {
+    fn new(api_key: String, config: CodexConnectionConfig) -> EstimatorResult<Self> {
         let mut headers = HeaderMap::new();
         headers.insert(
             AUTHORIZATION,
@@ -248,16 +314,66 @@ impl CodexConnectionPool {
             .attach_printable("Failed to build Codex HTTP client")?;
 
         let (event_tx, _event_rx) = broadcast::channel(PRICE_EVENTS_BUFFER);
+        let (health_tx, _health_rx) = broadcast::channel(HEALTH_EVENTS_BUFFER);
 
         Ok(Self {
             api_key,
+            config,
             http_client,
             clients: RwLock::new(Vec::new()),
+            next_client_id: AtomicU64::new(0),
             event_tx,
+            health_tx,
             held_subscriptions: RwLock::new(HashMap::new()),
         })
     }
 
+    // Refresh prices over HTTP for every token that was resubscribed after a connection gap,
+    // so consumers don't keep acting on prices from before the outage
+    fn spawn_backfill_task(self: &Arc<Self>) {
+        let pool = Arc::downgrade(self);
+        let mut health_rx = self.health_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match health_rx.recv().await {
+                    Ok(CodexHealthEvent::Reconnected { tokens, .. }) => {
+                        let Some(pool) = pool.upgrade() else {
+                            break;
+                        };
+                        pool.backfill_prices(&tokens).await;
+                    }
+                    Ok(CodexHealthEvent::Disconnected { .. }) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Codex backfill task lagged by {} health events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    async fn backfill_prices(&self, tokens: &[TokenId]) {
+        if tokens.is_empty() {
+            return;
+        }
+        let prices = match self.fetch_prices(tokens).await {
+            Ok(prices) => prices,
+            Err(error) => {
+                tracing::error!(
+                    "Failed to backfill Codex prices after reconnect: {:?}",
+                    error
+                );
+                return;
+            }
+        };
+        for (token, price) in prices.into_iter() {
+            let key = subscription_id(&token);
+            if let Some(client) = self.client_with_subscription(&key).await {
+                client.apply_backfill_price(&key, price).await;
+            }
+        }
+    }
+
     // Allow external components to subscribe to the global stream of events
     fn get_events_subscriber(&self) -> broadcast::Receiver<PriceEvent> {
         self.event_tx.subscribe()
@@ -343,12 +459,19 @@ impl CodexConnectionPool {
         Ok(())
     }
 
+    /// Releases one internal holder of `token`; the anchor is only dropped once no holder is left,
+    /// so the monitor and the discovery watchlist can share a subscription
     async fn unsubscribe_internal(&self, token: &TokenId) -> EstimatorResult<bool> {
         let to_drop = {
             let mut held = self.held_subscriptions.write().await;
-
-            let (_rc, anchor_owned) = held.remove(token).expect("entry must exist");
-            anchor_owned
+            let Some((rc, _anchor)) = held.get_mut(token) else {
+                return Ok(false);
+            };
+            *rc = rc.saturating_sub(1);
+            if *rc > 0 {
+                return Ok(false);
+            }
+            held.remove(token).map(|(_rc, anchor_owned)| anchor_owned)
         };
         drop(to_drop);
         Ok(true)
@@ -362,33 +485,18 @@ impl CodexConnectionPool {
         None
     }
 
-    async fn fetch_trending_tokens(
+    async fn filter_tokens(
         &self,
-        min_liquidity: f64,
-        min_market_cap: f64,
-        network: ChainId,
-        min_volume_24: f64,
-        limit: u32,
-        offset: u32,
-    ) -> EstimatorResult<Vec<TrendingTokenData>> {
+        query: &TokenDiscoveryQuery,
+    ) -> EstimatorResult<(Vec<TrendingTokenData>, Option<u64>)> {
         let response = self
             .http_client
-            .post(CODEX_HTTP_URL)
-            .json(&serde_json::json!({
-                "query": TRENDING_TOKENS_QUERY,
-                "variables": {
-                    "minLiquidity": min_liquidity,
-                    "minMarketCap": min_market_cap,
-                    "network": network.to_codex_chain_number(),
-                    "minVolume24": min_volume_24,
-                    "limit": limit,
-                    "offset": offset
-                }
-            }))
+            .post(&self.config.http_url)
+            .json(&query.to_graphql_body())
             .send()
             .await
             .change_context(Error::ResponseError)
-            .attach_printable("Failed to send Codex HTTP price request")?;
+            .attach_printable("Failed to send Codex HTTP filter tokens request")?;
 
         let status = response.status();
         if !status.is_success() {
@@ -396,9 +504,9 @@ impl CodexConnectionPool {
                 .text()
                 .await
                 .change_context(Error::ResponseError)
-                .attach_printable("Failed to read Codex HTTP error response")?;
+                .attach_printable("Failed to read Codex HTTP filter tokens error response")?;
             return Err(report!(Error::ResponseError).attach_printable(format!(
-                "Codex HTTP price request failed with status {}: {}",
+                "Codex HTTP filter tokens request failed with status {}: {}",
                 status.as_u16(),
                 body
             )));
@@ -408,31 +516,31 @@ impl CodexConnectionPool {
             .json()
             .await
             .change_context(Error::ResponseError)
-            .attach_printable("Failed to deserialize Codex HTTP price response")?;
+            .attach_printable("Failed to deserialize Codex HTTP filter tokens response")?;
 
-        tracing::debug!("Codex HTTP price response payload: {:#?}", payload);
+        tracing::debug!("Codex HTTP filter tokens response payload: {:#?}", payload);
 
         let payload =
             serde_json::from_value::<CodexGraphqlResponse<CodexGetTrendingTokensData>>(payload)
                 .change_context(Error::SerdeDeserialize(
-                    "Failed to deserialize Codex HTTP price GraphQL response".to_string(),
+                    "Failed to deserialize Codex HTTP filter tokens GraphQL response".to_string(),
                 ))?;
 
-        if let Some(errors) = payload.errors.as_ref() {
-            if !errors.is_empty() {
-                tracing::warn!(
-                    "Codex HTTP price batch response contained errors: {:?}",
-                    errors
-                );
-            }
+        if let Some(errors) = payload.errors.as_ref()
+            && !errors.is_empty()
+        {
+            tracing::warn!(
+                "Codex HTTP filter tokens response contained errors: {:?}",
+                errors
+            );
         }
 
         let Some(data) = payload.data else {
             return Err(report!(Error::ResponseError)
-                .attach_printable(format!("No data found in Codex HTTP price response")));
+                .attach_printable("No data found in Codex HTTP filter tokens response"));
         };
 
-        Ok(data.filter_tokens.results)
+        Ok((data.filter_tokens.results, data.filter_tokens.count))
     }
 
     async fn fetch_prices(
@@ -447,7 +555,7 @@ impl CodexConnectionPool {
 
         let response = self
             .http_client
-            .post(CODEX_HTTP_URL)
+            .post(&self.config.http_url)
             .json(&body)
             .send()
             .await
@@ -508,7 +616,7 @@ impl CodexConnectionPool {
 
         let response = self
             .http_client
-            .post(CODEX_HTTP_URL)
+            .post(&self.config.http_url)
             .json(&body)
             .send()
             .await
@@ -572,7 +680,7 @@ impl CodexConnectionPool {
 
         let response = self
             .http_client
-            .post(CODEX_HTTP_URL)
+            .post(&self.config.http_url)
             .json(&body)
             .send()
             .await
@@ -634,7 +742,7 @@ impl CodexConnectionPool {
 
         let response = self
             .http_client
-            .post(CODEX_HTTP_URL)
+            .post(&self.config.http_url)
             .json(&body)
             .send()
             .await
@@ -711,7 +819,14 @@ impl CodexConnectionPool {
             }
         }
 
-        let client = CodexWsClient::connect(self.api_key.clone(), self.event_tx.clone()).await?;
+        let client = CodexWsClient::connect(
+            self.next_client_id.fetch_add(1, Ordering::Relaxed),
+            self.api_key.clone(),
+            self.config.clone(),
+            self.event_tx.clone(),
+            self.health_tx.clone(),
+        )
+        .await?;
 
         let mut clients = self.clients.write().await;
         if clients.len() >= MAX_CONNECTIONS {
@@ -732,6 +847,10 @@ impl CodexConnectionPool {
 
 #[async_trait::async_trait]
 impl PriceProvider for CodexProvider {
+    fn source(&self) -> PriceSource {
+        PriceSource::Codex
+    }
+
     async fn get_tokens_price(
         &self,
         tokens: &[TokenId],
@@ -790,24 +909,71 @@ impl PriceProvider for CodexProvider {
     async fn unsubscribe_from_token(&self, token: TokenId) -> EstimatorResult<bool> {
         self.unsubscribe_internal(&token).await
     }
+
+    async fn get_tokens_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+        self.fetch_token_metadata(tokens).await
+    }
+
+    async fn get_tokens_price_and_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenPrice>> {
+        self.fetch_initial_prices(tokens).await
+    }
 }
 
 #[derive(Debug)]
 struct CodexWsClient {
-    sender: tokio::sync::mpsc::UnboundedSender<Message>,
+    id: u64,
+    sender: mpsc::UnboundedSender<Message>,
     subscriptions: RwLock<HashMap<String, TokenSubscription>>,
-    connected: AtomicBool,
-    connected_notify: Notify,
     // Event bus for price updates
     event_tx: broadcast::Sender<PriceEvent>,
+    // Event bus for connection gaps
+    health_tx: broadcast::Sender<CodexHealthEvent>,
 }
 
 impl CodexWsClient {
     async fn connect(
+        id: u64,
         api_key: String,
+        config: CodexConnectionConfig,
         event_tx: broadcast::Sender<PriceEvent>,
+        health_tx: broadcast::Sender<CodexHealthEvent>,
     ) -> EstimatorResult<Arc<Self>> {
-        let mut request = CODEX_WS_URL
+        let stream = Self::open_socket(&api_key, &config).await?;
+
+        let (send_tx, send_rx) = mpsc::unbounded_channel::<Message>();
+        let client = Arc::new(Self {
+            id,
+            sender: send_tx,
+            subscriptions: RwLock::new(HashMap::new()),
+            event_tx,
+            health_tx,
+        });
+
+        tokio::spawn(Self::supervise(
+            Arc::downgrade(&client),
+            stream,
+            send_rx,
+            api_key,
+            config,
+        ));
+
+        Ok(client)
+    }
+
+    /// Opens a websocket and completes the graphql-transport-ws handshake on it
+    async fn open_socket(
+        api_key: &str,
+        config: &CodexConnectionConfig,
+    ) -> EstimatorResult<CodexWsStream> {
+        let mut request = config
+            .ws_url
+            .as_str()
             .into_client_request()
             .change_context(Error::ResponseError)
             .attach_printable("Failed to construct Codex websocket request")?;
@@ -818,77 +984,250 @@ impl CodexWsClient {
         );
         request.headers_mut().insert(
             "Authorization",
-            ReqwestHeaderValue::from_str(&api_key)
+            ReqwestHeaderValue::from_str(api_key)
                 .change_context(Error::ResponseError)
                 .attach_printable("Invalid characters in CODEX_API_KEY")?,
         );
 
-        let (stream, _response) = connect_async(request)
+        let (mut stream, _response) = connect_async(request)
             .await
             .change_context(Error::ResponseError)
             .attach_printable("Failed to connect to Codex websocket")?;
 
-        let (write, mut read) = stream.split();
-
-        let (send_tx, mut send_rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
-        let write = Arc::new(Mutex::new(write));
-        let write_clone = write.clone();
+        stream
+            .send(Message::Text(
+                serde_json::json!({
+                    "type": "connection_init",
+                    "payload": { "Authorization": api_key }
+                })
+                .to_string(),
+            ))
+            .await
+            .change_context(Error::ResponseError)
+            .attach_printable("Failed to send Codex websocket connection_init")?;
 
-        tokio::spawn(async move {
-            while let Some(message) = send_rx.recv().await {
-                if let Err(error) = write_clone.lock().await.send(message).await {
-                    tracing::error!("Codex websocket send error: {:?}", error);
-                    break;
+        time::timeout(CONNECTION_ACK_TIMEOUT, async {
+            while let Some(message) = stream.next().await {
+                let message = message
+                    .change_context(Error::ResponseError)
+                    .attach_printable("Codex websocket closed during handshake")?;
+                match message {
+                    Message::Text(text) => {
+                        let message: GraphqlWsMessage = serde_json::from_str(&text)
+                            .change_context(Error::SerdeDeserialize(
+                                "Failed to parse Codex websocket message".to_string(),
+                            ))?;
+                        match message.message_type.as_str() {
+                            "connection_ack" => return Ok(()),
+                            "ping" => {
+                                stream
+                                    .send(Message::Text(
+                                        serde_json::json!({"type": "pong"}).to_string(),
+                                    ))
+                                    .await
+                                    .change_context(Error::ResponseError)?;
+                            }
+                            _ => {}
+                        }
+                    }
+                    Message::Ping(payload) => {
+                        stream
+                            .send(Message::Pong(payload))
+                            .await
+                            .change_context(Error::ResponseError)?;
+                    }
+                    Message::Close(frame) => {
+                        return Err(report!(Error::ResponseError).attach_printable(format!(
+                            "Codex websocket closed during handshake: {frame:?}"
+                        )));
+                    }
+                    _ => {}
                 }
             }
-        });
+            Err(report!(Error::ResponseError)
+                .attach_printable("Codex websocket closed before connection_ack"))
+        })
+        .await
+        .change_context(Error::ResponseError)
+        .attach_printable("Timed out waiting for Codex websocket connection_ack")??;
 
-        let client = Arc::new(Self {
-            sender: send_tx,
-            subscriptions: RwLock::new(HashMap::new()),
-            connected: AtomicBool::new(false),
-            connected_notify: Notify::new(),
-            event_tx,
-        });
+        Ok(stream)
+    }
 
-        client.send_message(Message::Text(
-            serde_json::json!({
-                "type": "connection_init",
-                "payload": { "Authorization": api_key }
-            })
-            .to_string(),
-        ))?;
+    /// Drives the socket for as long as the client is alive, reconnecting with
+    /// exponential backoff whenever it drops
+    async fn supervise(
+        client: Weak<Self>,
+        mut stream: CodexWsStream,
+        mut outgoing: mpsc::UnboundedReceiver<Message>,
+        api_key: String,
+        config: CodexConnectionConfig,
+    ) {
+        loop {
+            if !Self::pump(&client, &mut stream, &mut outgoing).await {
+                return;
+            }
 
-        let client_clone = client.clone();
-        tokio::spawn(async move {
-            while let Some(message) = read.next().await {
-                match message {
-                    Ok(Message::Text(text)) => {
-                        if let Err(error) = client_clone.handle_text_message(&text).await {
-                            tracing::error!("Codex websocket handler error: {:?}", error);
-                        }
-                    }
-                    Ok(Message::Ping(payload)) => {
-                        if let Err(error) = client_clone.send_message(Message::Pong(payload)) {
-                            tracing::error!("Codex websocket pong send error: {:?}", error);
-                        }
+            let Some(strong) = client.upgrade() else {
+                return;
+            };
+            let disconnected_at = get_timestamp();
+            let gap_started = Instant::now();
+            let tokens = strong.active_tokens().await;
+            tracing::warn!(
+                "Codex websocket connection {} dropped with {} active subscriptions",
+                strong.id,
+                tokens.len()
+            );
+            strong.emit_health(CodexHealthEvent::Disconnected {
+                connection_id: strong.id,
+                disconnected_at,
+                tokens,
+            });
+            drop(strong);
+
+            let mut backoff = config.initial_reconnect_backoff;
+            let mut attempts = 0u32;
+            let (new_stream, tokens) = loop {
+                attempts = attempts.saturating_add(1);
+                let Some(strong) = client.upgrade() else {
+                    return;
+                };
+                let attempt = match Self::open_socket(&api_key, &config).await {
+                    Ok(mut new_stream) => strong
+                        .replay_subscriptions(&mut new_stream, &mut outgoing)
+                        .await
+                        .map(|tokens| (new_stream, tokens)),
+                    Err(error) => Err(error),
+                };
+                drop(strong);
+                match attempt {
+                    Ok(reconnected) => break reconnected,
+                    Err(error) => {
+                        tracing::warn!(
+                            "Codex websocket reconnect attempt {} failed, retrying in {:?}: {:?}",
+                            attempts,
+                            backoff,
+                            error
+                        );
+                        time::sleep(backoff).await;
+                        backoff = backoff.saturating_mul(2).min(config.max_reconnect_backoff);
                     }
-                    Ok(Message::Close(frame)) => {
-                        tracing::warn!("Codex websocket closed by server: {:?}", frame);
-                        break;
+                }
+            };
+            stream = new_stream;
+
+            let Some(strong) = client.upgrade() else {
+                return;
+            };
+            tracing::info!(
+                "Codex websocket connection {} restored after {} attempts, replayed {} subscriptions",
+                strong.id,
+                attempts,
+                tokens.len()
+            );
+            strong.emit_health(CodexHealthEvent::Reconnected {
+                connection_id: strong.id,
+                disconnected_at,
+                reconnected_at: get_timestamp(),
+                downtime: gap_started.elapsed(),
+                attempts,
+                tokens,
+            });
+        }
+    }
+
+    /// Forwards outgoing messages and dispatches incoming ones until the socket drops.
+    /// Returns `false` once the client itself is gone and the connection should not be restored.
+    async fn pump(
+        client: &Weak<Self>,
+        stream: &mut CodexWsStream,
+        outgoing: &mut mpsc::UnboundedReceiver<Message>,
+    ) -> bool {
+        loop {
+            tokio::select! {
+                message = outgoing.recv() => {
+                    let Some(message) = message else {
+                        return false;
+                    };
+                    if let Err(error) = stream.send(message).await {
+                        tracing::error!("Codex websocket send error: {:?}", error);
+                        return true;
                     }
-                    Ok(_) => {}
-                    Err(error) => {
-                        tracing::error!("Codex websocket receive error: {:?}", error);
-                        break;
+                }
+                message = stream.next() => {
+                    let Some(strong) = client.upgrade() else {
+                        return false;
+                    };
+                    match message {
+                        Some(Ok(Message::Text(text))) => {
+                            if let Err(error) = strong.handle_text_message(&text).await {
+                                tracing::error!("Codex websocket handler error: {:?}", error);
+                            }
+                        }
+                        Some(Ok(Message::Ping(payload))) => {
+                            if let Err(error) = stream.send(Message::Pong(payload)).await {
+                                tracing::error!("Codex websocket pong send error: {:?}", error);
+                                return true;
+                            }
+                        }
+                        Some(Ok(Message::Close(frame))) => {
+                            tracing::warn!("Codex websocket closed by server: {:?}", frame);
+                            return true;
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(error)) => {
+                            tracing::error!("Codex websocket receive error: {:?}", error);
+                            return true;
+                        }
+                        None => {
+                            tracing::warn!("Codex websocket stream ended");
+                            return true;
+                        }
                     }
                 }
             }
-        });
+        }
+    }
 
-        client.wait_for_connection(Duration::from_secs(5)).await?;
+    /// Re-sends every active subscription on a freshly opened socket.
+    /// Messages queued while disconnected are discarded: subscription changes are
+    /// always queued under the subscriptions lock, so the map already reflects them.
+    async fn replay_subscriptions(
+        &self,
+        stream: &mut CodexWsStream,
+        outgoing: &mut mpsc::UnboundedReceiver<Message>,
+    ) -> EstimatorResult<Vec<TokenId>> {
+        let subscriptions = self.subscriptions.read().await;
+        while outgoing.try_recv().is_ok() {}
+
+        let mut tokens = Vec::with_capacity(subscriptions.len());
+        for (key, subscription) in subscriptions.iter() {
+            stream
+                .send(Message::Text(
+                    subscribe_message(key, &subscription.token).to_string(),
+                ))
+                .await
+                .change_context(Error::ResponseError)
+                .attach_printable("Failed to replay Codex subscription")?;
+            tokens.push(subscription.token.clone());
+        }
 
-        Ok(client)
+        Ok(tokens)
+    }
+
+    async fn active_tokens(&self) -> Vec<TokenId> {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions
+            .values()
+            .map(|subscription| subscription.token.clone())
+            .collect()
+    }
+
+    fn emit_health(&self, event: CodexHealthEvent) {
+        if let Err(err) = self.health_tx.send(event) {
+            tracing::trace!("No listeners for Codex health event: {:?}", err);
+        }
     }
 
     async fn handle_text_message(&self, text: &str) -> EstimatorResult<()> {
@@ -897,10 +1236,6 @@ impl CodexWsClient {
         )?;
 
         match message.message_type.as_str() {
-            "connection_ack" => {
-                self.connected.store(true, Ordering::Release);
-                self.connected_notify.notify_waiters();
-            }
             "ping" => {
                 self.send_message(Message::Text(
                     serde_json::json!({"type": "pong"}).to_string(),
@@ -947,34 +1282,13 @@ impl CodexWsClient {
 
         if let Some(data) = next_payload.data {
             if let Some(update) = data.on_price_updated {
-                let decimals = default_decimals(subscription.token.chain);
-                let new_price = TokenPrice {
-                    price: update.price_usd,
-                    decimals,
-                };
-
-                if let Err(error) = subscription.updates_tx.send(Some(TokenPrice {
-                    price: update.price_usd,
-                    decimals,
-                })) {
-                    tracing::error!(
-                        "Failed to send Codex price update for {}: {:?}",
-                        subscription.token.address,
-                        error
-                    );
-                }
-
-                // Emit global event
-                if let Err(err) = self.event_tx.send(PriceEvent {
-                    token: subscription.token.clone(),
-                    price: new_price,
-                }) {
-                    // If there are no subscribers or receivers lagged, just log and continue
-                    tracing::trace!(
-                        "No listeners for price event or lagging receivers: {:?}",
-                        err
-                    );
-                }
+                self.publish_price(
+                    &subscription,
+                    TokenPrice {
+                        price: update.price_usd,
+                        decimals: default_decimals(subscription.token.chain),
+                    },
+                );
             }
 
             if let Some(errors) = next_payload.errors {
@@ -989,6 +1303,38 @@ impl CodexWsClient {
         Ok(())
     }
 
+    fn publish_price(&self, subscription: &TokenSubscription, price: TokenPrice) {
+        if let Err(error) = subscription.updates_tx.send(Some(price)) {
+            tracing::error!(
+                "Failed to send Codex price update for {}: {:?}",
+                subscription.token.address,
+                error
+            );
+        }
+
+        // Emit global event
+        if let Err(err) = self.event_tx.send(PriceEvent {
+            token: subscription.token.clone(),
+            price,
+        }) {
+            // If there are no subscribers or receivers lagged, just log and continue
+            tracing::trace!(
+                "No listeners for price event or lagging receivers: {:?}",
+                err
+            );
+        }
+    }
+
+    async fn apply_backfill_price(&self, key: &str, price: TokenPrice) {
+        let subscription = {
+            let subscriptions = self.subscriptions.read().await;
+            subscriptions.get(key).cloned()
+        };
+        if let Some(subscription) = subscription {
+            self.publish_price(&subscription, price);
+        }
+    }
+
     async fn handle_complete(&self, id: &str) {
         let mut subscriptions = self.subscriptions.write().await;
         subscriptions.remove(id);
@@ -1000,28 +1346,16 @@ impl CodexWsClient {
             .map_err(|error| report!(Error::ResponseError).attach_printable(format!("{error:?}")))
     }
 
-    async fn wait_for_connection(&self, timeout: Duration) -> EstimatorResult<()> {
-        if self.connected.load(Ordering::Acquire) {
-            return Ok(());
-        }
-
-        time::timeout(timeout, self.connected_notify.notified())
-            .await
-            .change_context(Error::ResponseError)
-            .attach_printable("Timed out waiting for Codex websocket connection_ack")?;
-
-        Ok(())
-    }
-
     async fn subscribe(self: &Arc<Self>, token: TokenId) -> EstimatorResult<CodexSubscription> {
         tracing::debug!("Subscribing in CodexWsClient to Codex token: {:?}", token);
         let key = subscription_id(&token);
 
-        let (receiver, needs_subscribe) = {
+        // Outgoing messages are queued under the lock so a reconnect replay never races them
+        let receiver = {
             let mut subscriptions = self.subscriptions.write().await;
             if let Some(entry) = subscriptions.get_mut(&key) {
                 entry.ref_count += 1;
-                (entry.updates_tx.subscribe(), false)
+                entry.updates_tx.subscribe()
             } else {
                 if subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
                     return Err(
@@ -1039,25 +1373,11 @@ impl CodexWsClient {
                         ref_count: 1,
                     },
                 );
-                (rx, true)
+                self.send_message(Message::Text(subscribe_message(&key, &token).to_string()))?;
+                rx
             }
         };
 
-        if needs_subscribe {
-            let message = serde_json::json!({
-                "id": key,
-                "type": "subscribe",
-                "payload": {
-                    "query": GRAPHQL_SUBSCRIPTION,
-                    "variables": {
-                        "address": token.address,
-                        "networkId": token.chain.to_codex_chain_number()
-                    }
-                }
-            });
-            self.send_message(Message::Text(message.to_string()))?;
-        }
-
         Ok(CodexSubscription::new(self.clone(), key, receiver))
     }
 
@@ -1067,27 +1387,17 @@ impl CodexWsClient {
     }
 
     async fn release_subscription(&self, key: String) -> EstimatorResult<()> {
-        let should_unsubscribe = {
-            let mut subscriptions = self.subscriptions.write().await;
-            if let Some(entry) = subscriptions.get_mut(&key) {
-                entry.ref_count = entry.ref_count.saturating_sub(1);
-                if entry.ref_count == 0 {
-                    subscriptions.remove(&key);
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
+        let mut subscriptions = self.subscriptions.write().await;
+        if let Some(entry) = subscriptions.get_mut(&key) {
+            entry.ref_count = entry.ref_count.saturating_sub(1);
+            if entry.ref_count == 0 {
+                subscriptions.remove(&key);
+                let message = serde_json::json!({
+                    "id": key,
+                    "type": "complete"
+                });
+                self.send_message(Message::Text(message.to_string()))?;
             }
-        };
-
-        if should_unsubscribe {
-            let message = serde_json::json!({
-                "id": key,
-                "type": "complete"
-            });
-            self.send_message(Message::Text(message.to_string()))?;
         }
 
         Ok(())
@@ -1125,6 +1435,20 @@ impl CodexWsClient {
     }
 }
 
+fn subscribe_message(key: &str, token: &TokenId) -> serde_json::Value {
+    serde_json::json!({
+        "id": key,
+        "type": "subscribe",
+        "payload": {
+            "query": GRAPHQL_SUBSCRIPTION,
+            "variables": {
+                "address": token.address,
+                "networkId": token.chain.to_codex_chain_number()
+            }
+        }
+    })
+}
+
 #[derive(Debug)]
 pub struct CodexSubscription {
     client: Arc<CodexWsClient>,
@@ -1203,6 +1527,255 @@ mod tests {
     use super::*;
     use crate::tests::init_tracing_in_tests;
     use intents_models::constants::chains::NATIVE_TOKEN_SUI_ADDRESS;
+    use tokio::{
+        io::{AsyncReadExt as _, AsyncWriteExt as _},
+        net::TcpListener,
+        sync::Notify,
+    };
+    use tokio_tungstenite::{
+        accept_hdr_async,
+        tungstenite::handshake::server::{ErrorResponse, Request, Response},
+    };
+
+    // The client requests the graphql-transport-ws subprotocol and rejects handshakes without it
+    #[allow(clippy::result_large_err)]
+    fn accept_graphql_ws(
+        _request: &Request,
+        mut response: Response,
+    ) -> Result<Response, ErrorResponse> {
+        response.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            ReqwestHeaderValue::from_static("graphql-transport-ws"),
+        );
+        Ok(response)
+    }
+
+    /// Minimal graphql-transport-ws server: acks every connection, answers each `subscribe`
+    /// with one `next` priced at `connection index + 1` and reports it on `subscribed_rx`
+    struct GraphqlWsStandIn {
+        url: String,
+        drop_connections: Arc<Notify>,
+        subscribed_rx: mpsc::UnboundedReceiver<(usize, String)>,
+    }
+
+    impl GraphqlWsStandIn {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}", listener.local_addr().unwrap());
+            let drop_connections = Arc::new(Notify::new());
+            let (subscribed_tx, subscribed_rx) = mpsc::unbounded_channel();
+
+            let drop_notify = drop_connections.clone();
+            tokio::spawn(async move {
+                let mut connection_index = 0;
+                while let Ok((socket, _)) = listener.accept().await {
+                    let drop_notify = drop_notify.clone();
+                    let subscribed_tx = subscribed_tx.clone();
+                    tokio::spawn(Self::serve(
+                        socket,
+                        connection_index,
+                        drop_notify,
+                        subscribed_tx,
+                    ));
+                    connection_index += 1;
+                }
+            });
+
+            Self {
+                url,
+                drop_connections,
+                subscribed_rx,
+            }
+        }
+
+        async fn serve(
+            socket: TcpStream,
+            connection_index: usize,
+            drop_notify: Arc<Notify>,
+            subscribed_tx: mpsc::UnboundedSender<(usize, String)>,
+        ) {
+            let mut ws = accept_hdr_async(socket, accept_graphql_ws).await.unwrap();
+
+            loop {
+                tokio::select! {
+                    _ = drop_notify.notified() => return,
+                    message = ws.next() => {
+                        let Some(Ok(Message::Text(text))) = message else {
+                            return;
+                        };
+                        let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+                        match message["type"].as_str() {
+                            Some("connection_init") => {
+                                let ack = serde_json::json!({"type": "connection_ack"});
+                                ws.send(Message::Text(ack.to_string())).await.unwrap();
+                            }
+                            Some("subscribe") => {
+                                let id = message["id"].as_str().unwrap().to_string();
+                                let next = serde_json::json!({
+                                    "id": id,
+                                    "type": "next",
+                                    "payload": {"data": {"onPriceUpdated": {
+                                        "priceUsd": (connection_index + 1) as f64
+                                    }}}
+                                });
+                                ws.send(Message::Text(next.to_string())).await.unwrap();
+                                subscribed_tx.send((connection_index, id)).unwrap();
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// HTTP stand-in answering every `getTokenPrices` batch with the same price
+    async fn start_http_prices_stand_in(price_usd: f64) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                let body = loop {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    let Some(header_end) = text.find("\r\n\r\n") else {
+                        continue;
+                    };
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        break request[header_end + 4..header_end + 4 + content_length].to_vec();
+                    }
+                };
+
+                let query: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let mut data = serde_json::Map::new();
+                for (key, inputs) in query["variables"].as_object().unwrap() {
+                    let prices = inputs
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|input| {
+                            serde_json::json!({
+                                "address": input["address"],
+                                "networkId": input["networkId"],
+                                "priceUsd": price_usd,
+                                "timestamp": 0
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    data.insert(key.replace("inputs", "prices"), prices.into());
+                }
+                let response = serde_json::json!({ "data": data }).to_string();
+                socket
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            response.len(),
+                            response
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_codex_ws_reconnects_replays_and_backfills() {
+        init_tracing_in_tests();
+
+        let mut ws_server = GraphqlWsStandIn::start().await;
+        let http_url = start_http_prices_stand_in(1.5).await;
+        let codex_provider = CodexProvider::new_with_config(
+            "test-api-key".to_string(),
+            CodexConnectionConfig {
+                ws_url: ws_server.url.clone(),
+                http_url,
+                initial_reconnect_backoff: Duration::from_millis(20),
+                max_reconnect_backoff: Duration::from_millis(200),
+            },
+        );
+        let token = TokenId {
+            chain: ChainId::Solana,
+            address: "So11111111111111111111111111111111111111112".to_string(),
+        };
+
+        let mut health_rx = codex_provider.subscribe_health_events().await.unwrap();
+        let mut events_rx = codex_provider.subscribe_events().await.unwrap();
+        codex_provider
+            .subscribe_to_token(token.clone())
+            .await
+            .expect("subscribe_to_token failed");
+
+        let timeout = Duration::from_secs(5);
+        let (connection, subscription_key) = time::timeout(timeout, ws_server.subscribed_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(connection, 0);
+        assert_eq!(subscription_key, subscription_id(&token));
+        let event = time::timeout(timeout, events_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.token, token);
+        assert_eq!(event.price.price, 1.0);
+
+        ws_server.drop_connections.notify_waiters();
+
+        let disconnected = time::timeout(timeout, health_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let CodexHealthEvent::Disconnected { tokens, .. } = disconnected else {
+            panic!("Expected Disconnected event, got {disconnected:?}");
+        };
+        assert_eq!(tokens, vec![token.clone()]);
+
+        let reconnected = time::timeout(timeout, health_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let CodexHealthEvent::Reconnected {
+            tokens, attempts, ..
+        } = reconnected
+        else {
+            panic!("Expected Reconnected event, got {reconnected:?}");
+        };
+        assert_eq!(tokens, vec![token.clone()]);
+        assert!(attempts >= 1);
+
+        // The subscription is replayed on the new connection under the same id
+        let (connection, replayed_key) = time::timeout(timeout, ws_server.subscribed_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(connection, 1);
+        assert_eq!(replayed_key, subscription_key);
+
+        // Both the replayed stream and the HTTP backfill publish price events
+        let mut seen_prices = Vec::new();
+        while !(seen_prices.contains(&2.0) && seen_prices.contains(&1.5)) {
+            let event = time::timeout(timeout, events_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.token, token);
+            seen_prices.push(event.price.price);
+        }
+    }
 
     #[tokio::test]
     async fn test_trending_tokens_fetch() {
@@ -1,16 +1,16 @@
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
-use error_stack::report;
 use intents_models::constants::chains::ChainId;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
 
 use crate::{
-    error::{Error, EstimatorResult},
+    error::EstimatorResult,
     prices::{
         TokenId, TokenPrice, TokensPriceData, codex::pricing::CodexProvider,
-        gecko_terminal::pricing::GeckoTerminalProvider,
+        gecko_terminal::pricing::GeckoTerminalProvider, valuation::convert_amount,
     },
-    utils::number_conversion::{f64_to_u128, u128_to_f64},
 };
 
 pub static GECKO_TERMINAL_PROVIDER: Lazy<GeckoTerminalProvider> =
@@ -23,13 +23,15 @@ pub static CODEX_PROVIDER: Lazy<Option<CodexProvider>> = Lazy::new(|| {
     Some(CodexProvider::new(api_key))
 });
 
-#[derive(Debug, Clone)]
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderEstimationData {
     pub order_id: String,
     pub src_chain: ChainId,
     pub dst_chain: ChainId,
     pub token_in: String,
     pub token_out: String,
+    #[serde_as(as = "DisplayFromStr")]
     pub amount_in: u128,
 }
 
@@ -47,15 +49,7 @@ pub fn estimate_order_amount_out(
     ));
 
     if let (Some(src_data), Some(dst_data)) = (src_token_data, dst_token_data) {
-        let src_price = src_data.price;
-        let dst_price = dst_data.price;
-        if dst_price == 0.0 {
-            return Err(report!(Error::ZeroPriceError));
-        }
-
-        let amount_in_decimal = u128_to_f64(order_data.amount_in, src_data.decimals);
-        let amount_out_decimal = amount_in_decimal * (src_price / dst_price);
-        let amount_out = f64_to_u128(amount_out_decimal, dst_data.decimals)?;
+        let amount_out = convert_amount(order_data.amount_in, src_data, dst_data)?;
         Ok(Some(amount_out))
     } else {
         Ok(None)
@@ -3,20 +3,22 @@ use crate::prices::gecko_terminal::GeckoTerminalChain;
 use crate::{
     error::{Error, EstimatorResult},
     prices::{
-        PriceProvider, TokenId, TokenPrice,
+        PriceProvider, TokenId, TokenMetadata, TokenPrice,
         gecko_terminal::{
             GECKO_TERMINAL_API_URL,
             responses::{
                 GeckoTerminalOkResponseType, GeckoTerminalResponse, GeckoTerminalTokensInfo,
             },
         },
+        metadata_store::TokenMetadataCache,
+        valuation::PriceSource,
     },
 };
 use dashmap::{DashMap, Entry};
 use error_stack::{ResultExt as _, report};
 use intents_models::{constants::chains::ChainId, network::http::handle_reqwest_response};
 use reqwest::Client;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
@@ -36,32 +38,71 @@ pub struct GeckoTerminalProvider {
     // Event bus for price updates
     event_tx: broadcast::Sender<PriceEvent>,
     subscriptions: Arc<DashMap<TokenId, GtSubscriptionEntry>>,
+    // Token metadata seen in GeckoTerminal responses
+    metadata: TokenMetadataCache,
 }
 
 impl GeckoTerminalProvider {
     pub fn new() -> Self {
-        let (event_tx, _event_rx) = broadcast::channel(PRICE_EVENTS_BUFFER);
-
-        Self {
-            client: Client::new(),
-            event_tx,
-            subscriptions: Arc::new(DashMap::new()),
-        }
+        Self::new_with_metadata_cache(TokenMetadataCache::in_memory(), None)
     }
 
     pub fn new_with_subscriptions(refresh_secs: u64) -> Self {
+        Self::new_with_metadata_cache(TokenMetadataCache::in_memory(), Some(refresh_secs))
+    }
+
+    /// Creates a provider that reads and records token metadata through `metadata`.
+    /// If `refresh_secs` is set, subscribed tokens are refreshed in background at that interval.
+    pub fn new_with_metadata_cache(
+        metadata: TokenMetadataCache,
+        refresh_secs: Option<u64>,
+    ) -> Self {
         let (event_tx, _event_rx) = broadcast::channel(PRICE_EVENTS_BUFFER);
 
         let provider = Self {
             client: Client::new(),
             event_tx,
             subscriptions: Arc::new(DashMap::new()),
+            metadata,
         };
 
-        provider.spawn_refresh_task(Duration::from_secs(refresh_secs));
+        if let Some(refresh_secs) = refresh_secs {
+            provider.spawn_refresh_task(Duration::from_secs(refresh_secs));
+        }
         provider
     }
 
+    /// Get metadata of tokens, only querying GeckoTerminal for tokens unknown to (or stale in) the metadata cache
+    pub async fn get_tokens_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+        let tokens: HashSet<TokenId> = tokens.iter().cloned().collect();
+        self.metadata
+            .get_or_fetch(tokens, |missing| async move {
+                let mut tokens_by_chain: HashMap<ChainId, Vec<String>> = HashMap::new();
+                for token in missing.into_iter() {
+                    tokens_by_chain
+                        .entry(token.chain)
+                        .or_default()
+                        .push(token.address);
+                }
+                let mut result = HashMap::new();
+                for (chain, addresses) in tokens_by_chain.into_iter() {
+                    let infos = gecko_terminal_get_tokens_info(
+                        &self.client,
+                        &self.metadata,
+                        chain,
+                        addresses,
+                    )
+                    .await?;
+                    result.extend(tokens_info_metadata(chain, &infos));
+                }
+                Ok(result)
+            })
+            .await
+    }
+
     // Public method to subscribe to the global price event stream
     pub fn subscribe_events(&self) -> broadcast::Receiver<PriceEvent> {
         self.event_tx.subscribe()
@@ -71,6 +112,7 @@ impl GeckoTerminalProvider {
         let client = self.client.clone();
         let event_tx = self.event_tx.clone();
         let subscriptions = self.subscriptions.clone();
+        let metadata = self.metadata.clone();
 
         tokio::spawn(async move {
             let mut ticker = time::interval(interval);
@@ -97,7 +139,8 @@ impl GeckoTerminalProvider {
 
                 // Fetch and publish updates per chain
                 for (chain, addresses) in by_chain.into_iter() {
-                    match gecko_terminal_get_tokens_info(&client, chain, addresses).await {
+                    match gecko_terminal_get_tokens_info(&client, &metadata, chain, addresses).await
+                    {
                         Ok(infos) => {
                             for info in infos {
                                 let token_id = TokenId::new(chain, info.attributes.address);
@@ -170,6 +213,10 @@ impl GeckoTerminalProvider {
 
 #[async_trait::async_trait]
 impl PriceProvider for GeckoTerminalProvider {
+    fn source(&self) -> PriceSource {
+        PriceSource::GeckoTerminal
+    }
+
     async fn get_tokens_price(
         &self,
         tokens: &[TokenId],
@@ -210,8 +257,13 @@ impl PriceProvider for GeckoTerminalProvider {
                     }
                     None => {
                         // Not found in subscriptions, will need to fetch all via HTTP
-                        match gecko_terminal_get_tokens_info(&self.client, chain, addresses.clone())
-                            .await
+                        match gecko_terminal_get_tokens_info(
+                            &self.client,
+                            &self.metadata,
+                            chain,
+                            addresses.clone(),
+                        )
+                        .await
                         {
                             Ok(infos) => {
                                 for info in infos {
@@ -305,10 +357,21 @@ impl PriceProvider for GeckoTerminalProvider {
 
         Ok(dropped)
     }
+
+    async fn get_tokens_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+        GeckoTerminalProvider::get_tokens_metadata(self, tokens).await
+    }
 }
 
+/// Fetches token info (prices included) from GeckoTerminal and records the token metadata
+/// found in the response into `metadata`. Prices are never cached, metadata-only lookups
+/// should use `GeckoTerminalProvider::get_tokens_metadata` to be served from `metadata`.
 pub async fn gecko_terminal_get_tokens_info(
     client: &Client,
+    metadata: &TokenMetadataCache,
     chain_id: ChainId,
     tokens_address: Vec<String>,
 ) -> EstimatorResult<Vec<GeckoTerminalTokensInfo>> {
@@ -333,7 +396,7 @@ pub async fn gecko_terminal_get_tokens_info(
     if let GeckoTerminalOkResponseType::TokensInfo(tokens_info) =
         handle_gecko_terminal_response(tokens_response)?
     {
-        Ok(tokens_info
+        let infos: Vec<GeckoTerminalTokensInfo> = tokens_info
             .into_iter()
             .filter_map(
                 |v| match serde_json::from_value::<GeckoTerminalTokensInfo>(v) {
@@ -344,7 +407,14 @@ pub async fn gecko_terminal_get_tokens_info(
                     }
                 },
             )
-            .collect())
+            .collect();
+        if let Err(error) = metadata
+            .record(tokens_info_metadata(chain_id, &infos))
+            .await
+        {
+            tracing::warn!("Failed to record GeckoTerminal token metadata: {:?}", error);
+        }
+        Ok(infos)
     } else {
         tracing::error!("Unexpected response in gecko terminal request");
         Err(report!(Error::ResponseError)
@@ -352,6 +422,25 @@ pub async fn gecko_terminal_get_tokens_info(
     }
 }
 
+fn tokens_info_metadata(
+    chain: ChainId,
+    infos: &[GeckoTerminalTokensInfo],
+) -> HashMap<TokenId, TokenMetadata> {
+    infos
+        .iter()
+        .map(|info| {
+            (
+                TokenId::new(chain, info.attributes.address.clone()),
+                TokenMetadata {
+                    name: info.attributes.name.clone(),
+                    symbol: info.attributes.symbol.clone(),
+                    decimals: info.attributes.decimals,
+                },
+            )
+        })
+        .collect()
+}
+
 fn handle_gecko_terminal_response(
     response: GeckoTerminalResponse,
 ) -> EstimatorResult<GeckoTerminalOkResponseType> {
@@ -0,0 +1,704 @@
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use error_stack::{ResultExt as _, report};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    error::{Error, EstimatorResult},
+    prices::{TokenId, TokenMetadata},
+    utils::{fs::write_atomically, get_timestamp},
+};
+
+// Decimals, names and symbols practically never change, so a week is plenty
+pub const DEFAULT_METADATA_REFRESH_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredTokenMetadata {
+    pub token: TokenId,
+    pub metadata: TokenMetadata,
+    /// Timestamp (in seconds) when the token was first stored
+    pub first_seen: u64,
+    /// Timestamp (in seconds) of the last successful fetch from the provider
+    pub refreshed_at: u64,
+}
+
+/// Persistence backend for token metadata, keyed by `TokenId`
+#[async_trait::async_trait]
+pub trait TokenMetadataStore: std::fmt::Debug + Send + Sync {
+    async fn get_many(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, StoredTokenMetadata>>;
+
+    async fn put_many(&self, entries: Vec<StoredTokenMetadata>) -> EstimatorResult<()>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryTokenMetadataStore {
+    entries: RwLock<HashMap<TokenId, StoredTokenMetadata>>,
+}
+
+impl InMemoryTokenMetadataStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenMetadataStore for InMemoryTokenMetadataStore {
+    async fn get_many(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, StoredTokenMetadata>> {
+        let entries = self.entries.read().await;
+        Ok(tokens
+            .iter()
+            .filter_map(|token| entries.get(token).map(|e| (token.clone(), e.clone())))
+            .collect())
+    }
+
+    async fn put_many(&self, new_entries: Vec<StoredTokenMetadata>) -> EstimatorResult<()> {
+        let mut entries = self.entries.write().await;
+        for entry in new_entries.into_iter() {
+            entries.insert(entry.token.clone(), entry);
+        }
+        Ok(())
+    }
+}
+
+/// Keeps every entry in memory and rewrites the whole JSON file on each `put_many`
+/// with `write_atomically`, so a crash never leaves a truncated cache behind.
+#[derive(Debug)]
+pub struct JsonFileTokenMetadataStore {
+    path: PathBuf,
+    entries: RwLock<HashMap<TokenId, StoredTokenMetadata>>,
+}
+
+impl JsonFileTokenMetadataStore {
+    pub async fn open(path: impl Into<PathBuf>) -> EstimatorResult<Self> {
+        let path = path.into();
+        let entries = match tokio::fs::read(&path).await {
+            Ok(bytes) => {
+                let stored: Vec<StoredTokenMetadata> =
+                    serde_json::from_slice(&bytes).change_context(Error::SerdeDeserialize(
+                        format!("Failed to parse token metadata file {}", path.display()),
+                    ))?;
+                stored
+                    .into_iter()
+                    .map(|entry| (entry.token.clone(), entry))
+                    .collect()
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => {
+                return Err(report!(Error::StorageError(format!(
+                    "Failed to read token metadata file {}: {error}",
+                    path.display()
+                ))));
+            }
+        };
+        tracing::debug!(
+            "Loaded {} token metadata entries from {}",
+            entries.len(),
+            path.display()
+        );
+
+        Ok(Self {
+            path,
+            entries: RwLock::new(entries),
+        })
+    }
+
+    async fn persist(
+        &self,
+        entries: &HashMap<TokenId, StoredTokenMetadata>,
+    ) -> EstimatorResult<()> {
+        let mut stored: Vec<&StoredTokenMetadata> = entries.values().collect();
+        // Stable output keeps diffs of the file readable
+        stored.sort_by(|a, b| {
+            (a.token.chain as u32, &a.token.address).cmp(&(b.token.chain as u32, &b.token.address))
+        });
+        let bytes = serde_json::to_vec_pretty(&stored).change_context(Error::SerdeSerialize(
+            "Failed to serialize token metadata".to_string(),
+        ))?;
+
+        write_atomically(&self.path, bytes).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenMetadataStore for JsonFileTokenMetadataStore {
+    async fn get_many(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, StoredTokenMetadata>> {
+        let entries = self.entries.read().await;
+        Ok(tokens
+            .iter()
+            .filter_map(|token| entries.get(token).map(|e| (token.clone(), e.clone())))
+            .collect())
+    }
+
+    async fn put_many(&self, new_entries: Vec<StoredTokenMetadata>) -> EstimatorResult<()> {
+        if new_entries.is_empty() {
+            return Ok(());
+        }
+        // Hold the write lock while persisting so concurrent writers can't reorder file contents
+        let mut entries = self.entries.write().await;
+        for entry in new_entries.into_iter() {
+            entries.insert(entry.token.clone(), entry);
+        }
+        self.persist(&entries).await
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteTokenMetadataStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::{
+        collections::HashMap,
+        path::Path,
+        sync::{Arc, Mutex},
+    };
+
+    use error_stack::{ResultExt as _, report};
+    use rusqlite::{Connection, params};
+
+    use super::{StoredTokenMetadata, TokenMetadataStore};
+    use crate::{
+        error::{Error, EstimatorResult},
+        prices::{TokenId, TokenMetadata},
+    };
+
+    #[derive(Debug, Clone)]
+    pub struct SqliteTokenMetadataStore {
+        connection: Arc<Mutex<Connection>>,
+    }
+
+    impl SqliteTokenMetadataStore {
+        pub fn open(path: impl AsRef<Path>) -> EstimatorResult<Self> {
+            let connection = Connection::open(path.as_ref())
+                .change_context(Error::StorageError(
+                    "Failed to open SQLite database".to_string(),
+                ))
+                .attach_printable_lazy(|| format!("Path: {}", path.as_ref().display()))?;
+            Self::init(connection)
+        }
+
+        pub fn open_in_memory() -> EstimatorResult<Self> {
+            let connection = Connection::open_in_memory().change_context(Error::StorageError(
+                "Failed to open in-memory SQLite database".to_string(),
+            ))?;
+            Self::init(connection)
+        }
+
+        fn init(connection: Connection) -> EstimatorResult<Self> {
+            connection
+                .execute_batch(
+                    "CREATE TABLE IF NOT EXISTS token_metadata (
+                        chain_id     INTEGER NOT NULL,
+                        address      TEXT    NOT NULL,
+                        name         TEXT    NOT NULL,
+                        symbol       TEXT    NOT NULL,
+                        decimals     INTEGER NOT NULL,
+                        first_seen   INTEGER NOT NULL,
+                        refreshed_at INTEGER NOT NULL,
+                        PRIMARY KEY (chain_id, address)
+                    );",
+                )
+                .change_context(Error::StorageError(
+                    "Failed to create token_metadata table".to_string(),
+                ))?;
+            Ok(Self {
+                connection: Arc::new(Mutex::new(connection)),
+            })
+        }
+
+        async fn with_connection<T, F>(&self, f: F) -> EstimatorResult<T>
+        where
+            T: Send + 'static,
+            F: FnOnce(&mut Connection) -> EstimatorResult<T> + Send + 'static,
+        {
+            let connection = self.connection.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut connection = connection.lock().map_err(|_| {
+                    report!(Error::StorageError(
+                        "SQLite connection mutex poisoned".to_string()
+                    ))
+                })?;
+                f(&mut connection)
+            })
+            .await
+            .change_context(Error::StorageError("SQLite task panicked".to_string()))?
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenMetadataStore for SqliteTokenMetadataStore {
+        async fn get_many(
+            &self,
+            tokens: &[TokenId],
+        ) -> EstimatorResult<HashMap<TokenId, StoredTokenMetadata>> {
+            let tokens = tokens.to_vec();
+            self.with_connection(move |connection| {
+                let mut statement = connection
+                    .prepare_cached(
+                        "SELECT name, symbol, decimals, first_seen, refreshed_at
+                         FROM token_metadata WHERE chain_id = ?1 AND address = ?2",
+                    )
+                    .change_context(Error::StorageError(
+                        "Failed to prepare SQLite select".to_string(),
+                    ))?;
+                let mut result = HashMap::new();
+                for token in tokens.into_iter() {
+                    let row =
+                        statement.query_row(params![token.chain as u32, token.address], |row| {
+                            Ok((
+                                row.get::<_, String>(0)?,
+                                row.get::<_, String>(1)?,
+                                row.get::<_, u8>(2)?,
+                                row.get::<_, u64>(3)?,
+                                row.get::<_, u64>(4)?,
+                            ))
+                        });
+                    match row {
+                        Ok((name, symbol, decimals, first_seen, refreshed_at)) => {
+                            result.insert(
+                                token.clone(),
+                                StoredTokenMetadata {
+                                    token,
+                                    metadata: TokenMetadata {
+                                        name,
+                                        symbol,
+                                        decimals,
+                                    },
+                                    first_seen,
+                                    refreshed_at,
+                                },
+                            );
+                        }
+                        Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                        Err(error) => {
+                            return Err(report!(Error::StorageError(format!(
+                                "Failed to read token metadata: {error}"
+                            ))));
+                        }
+                    }
+                }
+                Ok(result)
+            })
+            .await
+        }
+
+        async fn put_many(&self, entries: Vec<StoredTokenMetadata>) -> EstimatorResult<()> {
+            if entries.is_empty() {
+                return Ok(());
+            }
+            self.with_connection(move |connection| {
+                let tx = connection
+                    .transaction()
+                    .change_context(Error::StorageError(
+                        "Failed to start SQLite transaction".to_string(),
+                    ))?;
+                {
+                    let mut statement = tx
+                        .prepare_cached(
+                            "INSERT OR REPLACE INTO token_metadata
+                             (chain_id, address, name, symbol, decimals, first_seen, refreshed_at)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        )
+                        .change_context(Error::StorageError(
+                            "Failed to prepare SQLite insert".to_string(),
+                        ))?;
+                    for entry in entries.iter() {
+                        statement
+                            .execute(params![
+                                entry.token.chain as u32,
+                                entry.token.address,
+                                entry.metadata.name,
+                                entry.metadata.symbol,
+                                entry.metadata.decimals,
+                                entry.first_seen,
+                                entry.refreshed_at,
+                            ])
+                            .change_context(Error::StorageError(
+                                "Failed to write token metadata".to_string(),
+                            ))?;
+                    }
+                }
+                tx.commit().change_context(Error::StorageError(
+                    "Failed to commit SQLite transaction".to_string(),
+                ))
+            })
+            .await
+        }
+    }
+}
+
+/// Read-through cache in front of a `TokenMetadataStore`.
+///
+/// Entries older than `refresh_after` are refetched lazily on the next read. If the refetch
+/// fails, the stale entry is served instead of failing the whole lookup.
+#[derive(Debug, Clone)]
+pub struct TokenMetadataCache {
+    store: Arc<dyn TokenMetadataStore>,
+    refresh_after: Duration,
+}
+
+impl TokenMetadataCache {
+    pub fn new(store: Arc<dyn TokenMetadataStore>, refresh_after: Duration) -> Self {
+        Self {
+            store,
+            refresh_after,
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self::new(
+            Arc::new(InMemoryTokenMetadataStore::new()),
+            Duration::from_secs(DEFAULT_METADATA_REFRESH_SECS),
+        )
+    }
+
+    fn is_fresh(&self, entry: &StoredTokenMetadata, now: u64) -> bool {
+        now < entry
+            .refreshed_at
+            .saturating_add(self.refresh_after.as_secs())
+    }
+
+    /// Returns stored entries without hitting the provider, stale ones included.
+    pub async fn get_cached(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>> {
+        Ok(self
+            .store
+            .get_many(tokens)
+            .await?
+            .into_iter()
+            .map(|(token, entry)| (token, entry.metadata))
+            .collect())
+    }
+
+    /// Returns metadata for `tokens`, calling `fetch` only for tokens that are missing or stale.
+    ///
+    /// Tokens the provider does not know about are absent from the result.
+    pub async fn get_or_fetch<F, Fut>(
+        &self,
+        tokens: HashSet<TokenId>,
+        fetch: F,
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>>
+    where
+        F: FnOnce(HashSet<TokenId>) -> Fut,
+        Fut: Future<Output = EstimatorResult<HashMap<TokenId, TokenMetadata>>>,
+    {
+        let requested: Vec<TokenId> = tokens.into_iter().collect();
+        let stored = self.store.get_many(&requested).await?;
+        let now = get_timestamp();
+
+        let mut result = HashMap::new();
+        let mut missing = HashSet::new();
+        let mut stale = HashSet::new();
+        for token in requested.into_iter() {
+            match stored.get(&token) {
+                Some(entry) if self.is_fresh(entry, now) => {
+                    result.insert(token, entry.metadata.clone());
+                }
+                Some(_) => {
+                    stale.insert(token);
+                }
+                None => {
+                    missing.insert(token);
+                }
+            }
+        }
+
+        if missing.is_empty() && stale.is_empty() {
+            return Ok(result);
+        }
+
+        let to_fetch: HashSet<TokenId> = missing.union(&stale).cloned().collect();
+        tracing::debug!(
+            "Token metadata cache: {} missing, {} stale; fetching from provider",
+            missing.len(),
+            stale.len()
+        );
+
+        match fetch(to_fetch).await {
+            Ok(fetched) => {
+                self.write_entries(&fetched, &stored, now).await?;
+                result.extend(fetched);
+                // Keep serving stale entries the provider no longer returned
+                for token in stale.into_iter() {
+                    if !result.contains_key(&token)
+                        && let Some(entry) = stored.get(&token)
+                    {
+                        result.insert(token, entry.metadata.clone());
+                    }
+                }
+                Ok(result)
+            }
+            Err(error) if missing.is_empty() => {
+                tracing::warn!(
+                    "Failed to refresh stale token metadata, serving cached values: {:?}",
+                    error
+                );
+                for token in stale.into_iter() {
+                    if let Some(entry) = stored.get(&token) {
+                        result.insert(token, entry.metadata.clone());
+                    }
+                }
+                Ok(result)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Stores metadata obtained as a side effect of another request (e.g. a price lookup
+    /// that also returns decimals). Only new, stale or changed entries reach the store.
+    pub async fn record(&self, fetched: HashMap<TokenId, TokenMetadata>) -> EstimatorResult<()> {
+        if fetched.is_empty() {
+            return Ok(());
+        }
+        let tokens: Vec<TokenId> = fetched.keys().cloned().collect();
+        let stored = self.store.get_many(&tokens).await?;
+        let now = get_timestamp();
+        let changed: HashMap<TokenId, TokenMetadata> = fetched
+            .into_iter()
+            .filter(|(token, metadata)| match stored.get(token) {
+                Some(entry) => entry.metadata != *metadata || !self.is_fresh(entry, now),
+                None => true,
+            })
+            .collect();
+        self.write_entries(&changed, &stored, now).await
+    }
+
+    async fn write_entries(
+        &self,
+        fetched: &HashMap<TokenId, TokenMetadata>,
+        stored: &HashMap<TokenId, StoredTokenMetadata>,
+        now: u64,
+    ) -> EstimatorResult<()> {
+        if fetched.is_empty() {
+            return Ok(());
+        }
+        let entries = fetched
+            .iter()
+            .map(|(token, metadata)| StoredTokenMetadata {
+                token: token.clone(),
+                metadata: metadata.clone(),
+                first_seen: stored.get(token).map(|e| e.first_seen).unwrap_or(now),
+                refreshed_at: now,
+            })
+            .collect();
+        self.store.put_many(entries).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intents_models::constants::chains::ChainId;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn token(address: &str) -> TokenId {
+        TokenId::new(ChainId::Base, address.to_string())
+    }
+
+    fn metadata(symbol: &str, decimals: u8) -> TokenMetadata {
+        TokenMetadata {
+            name: format!("{symbol} token"),
+            symbol: symbol.to_string(),
+            decimals,
+        }
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "{name}_{}_{}.json",
+            std::process::id(),
+            get_timestamp()
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_only_fetches_missing_tokens() {
+        let cache = TokenMetadataCache::in_memory();
+        let calls = AtomicUsize::new(0);
+
+        let result = cache
+            .get_or_fetch(HashSet::from([token("0xa"), token("0xb")]), |missing| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                assert_eq!(missing.len(), 2);
+                async move {
+                    Ok(missing
+                        .into_iter()
+                        .map(|t| (t, metadata("TKN", 6)))
+                        .collect())
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+
+        let result = cache
+            .get_or_fetch(HashSet::from([token("0xa"), token("0xc")]), |missing| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                assert_eq!(missing, HashSet::from([token("0xc")]));
+                async move { Ok(HashMap::from([(token("0xc"), metadata("C", 18))])) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[&token("0xc")].decimals, 18);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Everything cached now, fetch must not be called
+        let result = cache
+            .get_or_fetch(HashSet::from([token("0xb"), token("0xc")]), |_| async {
+                panic!("Fetch should not be called for cached tokens")
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_entries_are_refreshed_and_keep_first_seen() {
+        let store = Arc::new(InMemoryTokenMetadataStore::new());
+        store
+            .put_many(vec![StoredTokenMetadata {
+                token: token("0xa"),
+                metadata: metadata("OLD", 6),
+                first_seen: 100,
+                refreshed_at: 100,
+            }])
+            .await
+            .unwrap();
+        let cache = TokenMetadataCache::new(store.clone(), Duration::from_secs(60));
+
+        let result = cache
+            .get_or_fetch(HashSet::from([token("0xa")]), |_| async {
+                Ok(HashMap::from([(token("0xa"), metadata("NEW", 6))]))
+            })
+            .await
+            .unwrap();
+        assert_eq!(result[&token("0xa")].symbol, "NEW");
+
+        let stored = store.get_many(&[token("0xa")]).await.unwrap();
+        assert_eq!(stored[&token("0xa")].first_seen, 100);
+        assert!(stored[&token("0xa")].refreshed_at > 100);
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_serves_stale_entries() {
+        let store = Arc::new(InMemoryTokenMetadataStore::new());
+        store
+            .put_many(vec![StoredTokenMetadata {
+                token: token("0xa"),
+                metadata: metadata("OLD", 6),
+                first_seen: 100,
+                refreshed_at: 100,
+            }])
+            .await
+            .unwrap();
+        let cache = TokenMetadataCache::new(store, Duration::from_secs(60));
+
+        let result = cache
+            .get_or_fetch(HashSet::from([token("0xa")]), |_| async {
+                Err(report!(Error::ResponseError))
+            })
+            .await
+            .unwrap();
+        assert_eq!(result[&token("0xa")].symbol, "OLD");
+
+        // A missing token can't be served from anywhere, so the error is propagated
+        let result = cache
+            .get_or_fetch(HashSet::from([token("0xa"), token("0xb")]), |_| async {
+                Err(report!(Error::ResponseError))
+            })
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_record_skips_unchanged_fresh_entries() {
+        let store = Arc::new(InMemoryTokenMetadataStore::new());
+        let cache = TokenMetadataCache::new(store.clone(), Duration::from_secs(3600));
+
+        cache
+            .record(HashMap::from([(token("0xa"), metadata("A", 6))]))
+            .await
+            .unwrap();
+        let first = store.get_many(&[token("0xa")]).await.unwrap()[&token("0xa")].clone();
+
+        cache
+            .record(HashMap::from([(token("0xa"), metadata("A", 6))]))
+            .await
+            .unwrap();
+        let second = store.get_many(&[token("0xa")]).await.unwrap()[&token("0xa")].clone();
+        assert_eq!(first, second);
+
+        cache
+            .record(HashMap::from([(token("0xa"), metadata("A", 8))]))
+            .await
+            .unwrap();
+        let third = store.get_many(&[token("0xa")]).await.unwrap()[&token("0xa")].clone();
+        assert_eq!(third.metadata.decimals, 8);
+        assert_eq!(third.first_seen, first.first_seen);
+    }
+
+    #[tokio::test]
+    async fn test_json_file_store_survives_reopen() {
+        let path = temp_file("token_metadata_store");
+
+        let store = JsonFileTokenMetadataStore::open(&path).await.unwrap();
+        store
+            .put_many(vec![StoredTokenMetadata {
+                token: token("0xa"),
+                metadata: metadata("A", 6),
+                first_seen: 1,
+                refreshed_at: 2,
+            }])
+            .await
+            .unwrap();
+        drop(store);
+
+        let reopened = JsonFileTokenMetadataStore::open(&path).await.unwrap();
+        let stored = reopened
+            .get_many(&[token("0xa"), token("0xb")])
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[&token("0xa")].metadata, metadata("A", 6));
+        assert_eq!(stored[&token("0xa")].first_seen, 1);
+
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_store_round_trip() {
+        let store = SqliteTokenMetadataStore::open_in_memory().unwrap();
+        store
+            .put_many(vec![StoredTokenMetadata {
+                token: token("0xa"),
+                metadata: metadata("A", 6),
+                first_seen: 1,
+                refreshed_at: 2,
+            }])
+            .await
+            .unwrap();
+        let stored = store.get_many(&[token("0xa"), token("0xb")]).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[&token("0xa")].refreshed_at, 2);
+    }
+}
@@ -3,12 +3,17 @@ use std::collections::HashMap;
 use intents_models::constants::chains::{ChainId, ChainType};
 use serde::{Deserialize, Serialize};
 
-use crate::{error::EstimatorResult, prices::codex::CodexChain as _};
+use crate::{
+    error::EstimatorResult,
+    prices::{codex::CodexChain as _, valuation::PriceSource},
+};
 
 pub mod codex;
 pub mod defillama;
 pub mod estimating;
 pub mod gecko_terminal;
+pub mod metadata_store;
+pub mod valuation;
 
 pub type TokensPriceData = HashMap<TokenId, TokenPrice>;
 
@@ -43,14 +48,14 @@ pub struct PriceEvent {
     pub price: TokenPrice,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct TokenMetadata {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct TokenPrice {
     pub price: f64,
     pub decimals: u8,
@@ -67,6 +72,9 @@ impl TokenPrice {
 
 #[async_trait::async_trait]
 pub trait PriceProvider {
+    /// Where the prices returned by this provider come from
+    fn source(&self) -> PriceSource;
+
     async fn get_tokens_price(
         &self,
         tokens: &[TokenId],
@@ -80,4 +88,29 @@ pub trait PriceProvider {
     async fn subscribe_to_token(&self, token: TokenId) -> EstimatorResult<()>;
 
     async fn unsubscribe_from_token(&self, token: TokenId) -> EstimatorResult<bool>;
+
+    /// Tokens unknown to the provider are left out of the result
+    async fn get_tokens_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenMetadata>>;
+
+    /// Prices with decimals taken from the token metadata. Providers able to fetch both in a
+    /// single request should override it.
+    async fn get_tokens_price_and_metadata(
+        &self,
+        tokens: &[TokenId],
+    ) -> EstimatorResult<HashMap<TokenId, TokenPrice>>
+    where
+        Self: Sync,
+    {
+        let mut prices = self.get_tokens_price(tokens, false).await?;
+        let metadata = self.get_tokens_metadata(tokens).await?;
+        for (token, price) in prices.iter_mut() {
+            if let Some(metadata) = metadata.get(token) {
+                price.decimals = metadata.decimals;
+            }
+        }
+        Ok(prices)
+    }
 }
@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use error_stack::report;
+use rust_decimal::{
+    Decimal,
+    prelude::{FromPrimitive as _, MathematicalOps as _, ToPrimitive as _},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, EstimatorResult},
+    prices::{TokenId, TokenPrice},
+};
+
+/// Highest scale supported by `rust_decimal`
+const MAX_DECIMALS: u8 = 28;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriceSource {
+    Codex,
+    GeckoTerminal,
+    DefiLlama,
+    /// Recorded prices replayed by backtests and test providers
+    Replay,
+}
+
+/// A token price together with where and when it was observed
+#[derive(Debug, Clone, Copy)]
+pub struct PriceQuote {
+    pub price: TokenPrice,
+    pub source: PriceSource,
+    /// Unix timestamp (seconds) of the observation, if the source tracks it
+    pub observed_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricedValue {
+    pub price_usd: Decimal,
+    pub value_usd: Decimal,
+    pub source: PriceSource,
+    pub observed_at: Option<u64>,
+    pub age_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemValuation {
+    pub token: TokenId,
+    pub amount: u128,
+    /// `None` when no usable price was available for the token
+    pub value: Option<PricedValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasketValuation {
+    /// One entry per basket item, in basket order
+    pub items: Vec<ItemValuation>,
+    /// Sum of the priced items only
+    pub total_usd: Decimal,
+    /// Tokens without a usable price, deduplicated, in basket order
+    pub missing: Vec<TokenId>,
+}
+
+impl BasketValuation {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Values every `(token, raw amount)` item of `basket` in USD.
+/// Tokens with no quote, or with a zero/negative/non-finite price, are reported in `missing`
+/// instead of failing the whole basket.
+pub fn value_basket(
+    basket: &[(TokenId, u128)],
+    quotes: &HashMap<TokenId, PriceQuote>,
+    now: u64,
+) -> EstimatorResult<BasketValuation> {
+    let mut items = Vec::with_capacity(basket.len());
+    let mut total_usd = Decimal::ZERO;
+    let mut missing: Vec<TokenId> = Vec::new();
+
+    for (token, amount) in basket.iter() {
+        let quote = quotes
+            .get(token)
+            .filter(|quote| quote.price.price.is_finite() && quote.price.price > 0.0);
+        let Some(quote) = quote else {
+            if !missing.contains(token) {
+                missing.push(token.clone());
+            }
+            items.push(ItemValuation {
+                token: token.clone(),
+                amount: *amount,
+                value: None,
+            });
+            continue;
+        };
+
+        let price_usd = price_to_decimal(quote.price.price)?;
+        let value_usd = checked_mul(amount_to_decimal(*amount, quote.price.decimals)?, price_usd)?;
+        total_usd = checked_add(total_usd, value_usd)?;
+        items.push(ItemValuation {
+            token: token.clone(),
+            amount: *amount,
+            value: Some(PricedValue {
+                price_usd,
+                value_usd,
+                source: quote.source,
+                observed_at: quote.observed_at,
+                age_secs: quote
+                    .observed_at
+                    .map(|observed_at| now.saturating_sub(observed_at)),
+            }),
+        });
+    }
+
+    Ok(BasketValuation {
+        items,
+        total_usd,
+        missing,
+    })
+}
+
+/// USD value of a raw token amount
+pub fn usd_value(amount: u128, price: &TokenPrice) -> EstimatorResult<Decimal> {
+    checked_mul(
+        amount_to_decimal(amount, price.decimals)?,
+        price_to_decimal(price.price)?,
+    )
+}
+
+/// Converts a raw `amount_in` of the `src` token into raw units of the `dst` token
+pub fn convert_amount(
+    amount_in: u128,
+    src: &TokenPrice,
+    dst: &TokenPrice,
+) -> EstimatorResult<u128> {
+    validate_decimals(dst.decimals)?;
+    let value_usd = usd_value(amount_in, src)?;
+    let dst_price = price_to_decimal(dst.price)?;
+    let amount_out = checked_div(value_usd, dst_price)?;
+    decimal_to_raw(amount_out, dst.decimals as i64)
+}
+
+/// Converts a raw `u128` amount with `decimals` into an exact `Decimal`
+pub fn amount_to_decimal(amount: u128, decimals: u8) -> EstimatorResult<Decimal> {
+    validate_decimals(decimals)?;
+    let Some(amount_dec) = Decimal::from_u128(amount) else {
+        return Err(
+            report!(Error::ParseError).attach_printable("Failed to convert u128 amount to Decimal")
+        );
+    };
+    let factor = Decimal::from(10u128).powi(-(decimals as i64));
+    Ok(amount_dec * factor)
+}
+
+/// Converts a price into `Decimal`, rejecting non-finite and non-positive prices
+pub fn price_to_decimal(price: f64) -> EstimatorResult<Decimal> {
+    if !price.is_finite() {
+        return Err(report!(Error::ParseError).attach_printable("Price is not finite"));
+    }
+    let price = Decimal::from_f64(price).ok_or(Error::ParseError)?;
+    if price.is_sign_negative() || price.is_zero() {
+        return Err(report!(Error::ZeroPriceError));
+    }
+    Ok(price)
+}
+
+pub fn decimal_to_raw(amount: Decimal, decimals: i64) -> EstimatorResult<u128> {
+    if amount < Decimal::ZERO {
+        return Err(report!(Error::ParseError)
+            .attach_printable("Cannot convert negative decimal amount to raw u128"));
+    }
+    // 10^decimals
+    let factor = Decimal::from(10u128)
+        .checked_powi(decimals)
+        .ok_or_else(|| overflow("Decimals scale factor overflowed"))?;
+    // amount * 10^decimals
+    let scaled = checked_mul(amount, factor)?;
+
+    let scaled_int = scaled.trunc();
+
+    let raw = scaled_int.to_u128().ok_or(Error::ParseError)?;
+    Ok(raw)
+}
+
+pub(crate) fn checked_mul(a: Decimal, b: Decimal) -> EstimatorResult<Decimal> {
+    a.checked_mul(b)
+        .ok_or_else(|| overflow("Decimal multiplication overflowed"))
+}
+
+pub(crate) fn checked_add(a: Decimal, b: Decimal) -> EstimatorResult<Decimal> {
+    a.checked_add(b)
+        .ok_or_else(|| overflow("Decimal addition overflowed"))
+}
+
+pub(crate) fn checked_sub(a: Decimal, b: Decimal) -> EstimatorResult<Decimal> {
+    a.checked_sub(b)
+        .ok_or_else(|| overflow("Decimal subtraction overflowed"))
+}
+
+pub(crate) fn checked_div(a: Decimal, b: Decimal) -> EstimatorResult<Decimal> {
+    a.checked_div(b)
+        .ok_or_else(|| overflow("Decimal division overflowed"))
+}
+
+fn overflow(message: &'static str) -> error_stack::Report<Error> {
+    report!(Error::ParseError).attach_printable(message)
+}
+
+// Fail-fast validation for decimals scale supported by rust_decimal
+pub fn validate_decimals(decimals: u8) -> EstimatorResult<()> {
+    if decimals > MAX_DECIMALS {
+        return Err(report!(Error::ParseError).attach_printable(format!(
+            "Token decimals {decimals} exceed the supported maximum of {MAX_DECIMALS}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intents_models::constants::chains::ChainId;
+    use std::str::FromStr as _;
+
+    fn usdc() -> TokenId {
+        TokenId::new(
+            ChainId::Base,
+            "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string(),
+        )
+    }
+
+    fn sol() -> TokenId {
+        TokenId::new(
+            ChainId::Solana,
+            "So11111111111111111111111111111111111111112".to_string(),
+        )
+    }
+
+    fn quote(price: f64, decimals: u8, observed_at: Option<u64>) -> PriceQuote {
+        PriceQuote {
+            price: TokenPrice { price, decimals },
+            source: PriceSource::Codex,
+            observed_at,
+        }
+    }
+
+    #[test]
+    fn test_value_basket_exact_totals() {
+        let quotes = HashMap::from([
+            (usdc(), quote(1.0, 6, Some(1_000))),
+            (sol(), quote(123.45, 9, None)),
+        ]);
+        let basket = vec![
+            (usdc(), 1_100_000),
+            (sol(), 2_000_000_000),
+            (usdc(), 200_000),
+        ];
+
+        let valuation = value_basket(&basket, &quotes, 1_030).unwrap();
+
+        assert!(valuation.is_complete());
+        let values = valuation
+            .items
+            .iter()
+            .map(|item| item.value.as_ref().unwrap().value_usd)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Decimal::from_str("1.1").unwrap(),
+                Decimal::from_str("246.9").unwrap(),
+                Decimal::from_str("0.2").unwrap(),
+            ]
+        );
+        assert_eq!(valuation.total_usd, Decimal::from_str("248.2").unwrap());
+        let first = valuation.items[0].value.as_ref().unwrap();
+        assert_eq!(first.source, PriceSource::Codex);
+        assert_eq!(first.age_secs, Some(30));
+        assert_eq!(valuation.items[1].value.as_ref().unwrap().age_secs, None);
+    }
+
+    #[test]
+    fn test_value_basket_reports_missing_prices() {
+        let unknown = TokenId::new(ChainId::Ethereum, "0xunknown".to_string());
+        let quotes = HashMap::from([(usdc(), quote(1.0, 6, None)), (sol(), quote(0.0, 9, None))]);
+        let basket = vec![
+            (usdc(), 5_000_000),
+            (sol(), 1_000_000_000),
+            (unknown.clone(), 1),
+            (unknown.clone(), 2),
+        ];
+
+        let valuation = value_basket(&basket, &quotes, 0).unwrap();
+
+        assert!(!valuation.is_complete());
+        assert_eq!(valuation.missing, vec![sol(), unknown]);
+        assert_eq!(valuation.items.len(), 4);
+        assert!(valuation.items[1].value.is_none());
+        assert!(valuation.items[3].value.is_none());
+        assert_eq!(valuation.total_usd, Decimal::from(5));
+    }
+
+    #[test]
+    fn test_convert_amount_is_exact() {
+        let usdc_price = TokenPrice {
+            price: 1.0,
+            decimals: 6,
+        };
+        let sui_price = TokenPrice {
+            price: 1.5,
+            decimals: 9,
+        };
+        assert_eq!(
+            convert_amount(3_000_000, &usdc_price, &sui_price).unwrap(),
+            2_000_000_000
+        );
+        assert_eq!(convert_amount(0, &usdc_price, &sui_price).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_conversion_errors() {
+        let zero = TokenPrice {
+            price: 0.0,
+            decimals: 6,
+        };
+        let one = TokenPrice {
+            price: 1.0,
+            decimals: 6,
+        };
+        assert!(convert_amount(1, &one, &zero).is_err());
+        assert!(price_to_decimal(f64::NAN).is_err());
+        assert!(price_to_decimal(-1.0).is_err());
+        assert!(amount_to_decimal(1, 29).is_err());
+        assert!(amount_to_decimal(u128::MAX, 0).is_err());
+        assert!(decimal_to_raw(Decimal::from(-1), 6).is_err());
+        assert!(decimal_to_raw(Decimal::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_convert_amount_overflow_is_an_error() {
+        // 10^10 tokens with 18 decimals worth $1 each, into a token worth $10^-9 with 18 decimals:
+        // 10^37 raw units, far beyond what `Decimal` can hold
+        let src = TokenPrice {
+            price: 1.0,
+            decimals: 18,
+        };
+        let dst = TokenPrice {
+            price: 1e-9,
+            decimals: 18,
+        };
+        assert!(convert_amount(10u128.pow(28), &src, &dst).is_err());
+        let expensive = TokenPrice {
+            price: 1e18,
+            decimals: 0,
+        };
+        assert!(usd_value(10u128.pow(28), &expensive).is_err());
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use error_stack::ResultExt as _;
+
+use crate::error::{Error, EstimatorResult};
+
+/// Replaces the file at `path` with `bytes`. The bytes are written to a temporary sibling first
+/// and renamed over `path`, so a crash mid-write never leaves a truncated file behind.
+pub async fn write_atomically(path: &Path, bytes: Vec<u8>) -> EstimatorResult<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, bytes)
+        .await
+        .change_context(Error::StorageError(format!(
+            "Failed to write {}",
+            tmp_path.display()
+        )))?;
+    tokio::fs::rename(&tmp_path, path)
+        .await
+        .change_context(Error::StorageError(format!(
+            "Failed to replace {}",
+            path.display()
+        )))
+}